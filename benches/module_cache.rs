@@ -0,0 +1,85 @@
+//! Indexes a block of calls into one contract with the module cache off and
+//! on, and prints how long the block took each way.
+//!
+//! cargo bench --bench module_cache --features native,test-utils
+
+use alkanes::native::NativeIndexer;
+use alkanes::precompiled::alkanes_std_owned_token_build;
+use alkanes::tests::helpers::{
+    init_with_cellpack_pairs, init_with_multiple_cellpacks_with_tx_w_input, BinaryAndCellpack,
+};
+use alkanes::vm::module_cache;
+use alkanes_support::cellpack::Cellpack;
+use alkanes_support::id::AlkaneId;
+use anyhow::Result;
+use bitcoin::{Block, OutPoint};
+use metashrew_native::MemoryStore;
+use protorune::test_helpers::create_block_with_coinbase_tx;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+const CALLS: usize = 200;
+const RUNS: usize = 5;
+
+/// Genesis, then the owned token deployed at `2:1`, then `CALLS`
+/// transactions each calling its `get_name` (opcode 99).
+fn fixture() -> Vec<Block> {
+    let deploy = init_with_cellpack_pairs(vec![BinaryAndCellpack::new(
+        alkanes_std_owned_token_build::get_bytes(),
+        Cellpack {
+            target: AlkaneId::new(1, 0),
+            inputs: vec![99],
+        },
+    )]);
+    let calls = init_with_multiple_cellpacks_with_tx_w_input(
+        vec![vec![]; CALLS],
+        vec![
+            Cellpack {
+                target: AlkaneId::new(2, 1),
+                inputs: vec![99],
+            };
+            CALLS
+        ],
+        Some(OutPoint {
+            txid: deploy.txdata.last().unwrap().compute_txid(),
+            vout: 0,
+        }),
+    );
+    vec![create_block_with_coinbase_tx(0), deploy, calls]
+}
+
+/// Time spent indexing the last block of `blocks` on a fresh store.
+fn index_last(blocks: &[Block], cache: bool) -> Result<Duration> {
+    module_cache::set_enabled(cache);
+    let indexer = NativeIndexer::new(Arc::new(MemoryStore::new()));
+    let (last, setup) = blocks.split_last().unwrap();
+    for (height, block) in setup.iter().enumerate() {
+        indexer.index_block(block, height as u32)?;
+    }
+    let start = Instant::now();
+    indexer.index_block(last, setup.len() as u32)?;
+    Ok(start.elapsed())
+}
+
+fn median(mut runs: Vec<Duration>) -> Duration {
+    runs.sort();
+    runs[runs.len() / 2]
+}
+
+fn main() -> Result<()> {
+    let blocks = fixture();
+    for (label, cache) in [("uncached", false), ("cached", true)] {
+        let runs = (0..RUNS)
+            .map(|_| index_last(&blocks, cache))
+            .collect::<Result<Vec<_>>>()?;
+        let block = median(runs);
+        println!(
+            "{:<8} {} calls in {:?} ({:?}/call)",
+            label,
+            CALLS,
+            block,
+            block / CALLS as u32
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,524 @@
+//! Typed model of the ABI a contract exports through `__meta`.
+//!
+//! The `MessageDispatch` derive in `alkanes-macros` emits
+//! `{"contract": .., "methods": [{"name", "opcode", "params": [{"type", "name"}], "returns"}]}`
+//! where types are the Rust type names of the message enum's fields
+//! (`u128`, `String`, `AlkaneId`, `Vec<T>`) and `returns` is the raw
+//! `#[returns(..)]` token string, or `"void"`.
+//!
+//! [`AbiMethod::encode_call`] turns `name=value` arguments into cellpack
+//! inputs the way the derive's `from_opcode` reads them back, and
+//! [`AbiMethod::decode_return`] renders `CallResponse.data` as the declared
+//! return type.
+
+use crate::{AlkanesError, Result};
+use serde::Deserialize;
+use serde_json::{json, Value as JsonValue};
+
+#[cfg(not(feature = "std"))]
+use alloc::{
+    boxed::Box,
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+#[cfg(feature = "std")]
+use std::vec::Vec;
+
+/// A parameter or return type named in the ABI.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AbiType {
+    U128,
+    String,
+    AlkaneId,
+    /// `Vec<u8>`: opaque bytes
+    Bytes,
+    Vec(Box<AbiType>),
+    Void,
+    /// Anything else, kept verbatim
+    Other(String),
+}
+
+impl AbiType {
+    /// Parse a type as the derive writes it. `#[returns(..)]` tokens come
+    /// through `TokenStream::to_string`, so whitespace and paths are ignored.
+    pub fn parse(s: &str) -> Self {
+        let compact: String = s.chars().filter(|c| !c.is_whitespace()).collect();
+        if let Some(inner) = compact
+            .strip_prefix("Vec<")
+            .and_then(|r| r.strip_suffix('>'))
+        {
+            return match AbiType::parse(inner) {
+                AbiType::Other(t) if t == "u8" => AbiType::Bytes,
+                inner => AbiType::Vec(Box::new(inner)),
+            };
+        }
+        let name = compact.rsplit("::").next().unwrap_or_default();
+        match name {
+            "u128" => AbiType::U128,
+            "String" => AbiType::String,
+            "AlkaneId" => AbiType::AlkaneId,
+            "void" | "" | "()" => AbiType::Void,
+            other => AbiType::Other(other.to_string()),
+        }
+    }
+
+    /// Append `value` to `inputs` in the layout `from_opcode` expects.
+    ///
+    /// - `u128`: decimal or `0x` hex
+    /// - `String`: UTF-8 bytes packed little-endian into u128s, NUL-terminated
+    /// - `AlkaneId`: `block:tx`
+    /// - `Vec<T>`: a JSON array or comma-separated list, written as its length
+    ///   followed by each element
+    pub fn encode(&self, value: &str, inputs: &mut Vec<u128>) -> Result<()> {
+        match self {
+            AbiType::U128 => inputs.push(parse_u128(value)?),
+            AbiType::String => inputs.extend(pack_string(value)),
+            AbiType::AlkaneId => {
+                let (block, tx) = value.split_once(':').ok_or_else(|| {
+                    AlkanesError::InvalidParameters(format!(
+                        "expected an AlkaneId as block:tx, got '{}'",
+                        value
+                    ))
+                })?;
+                inputs.push(parse_u128(block)?);
+                inputs.push(parse_u128(tx)?);
+            }
+            AbiType::Vec(inner) => {
+                let items = split_list(value)?;
+                inputs.push(items.len() as u128);
+                for item in items {
+                    inner.encode(&item, inputs)?;
+                }
+            }
+            AbiType::Bytes | AbiType::Void | AbiType::Other(_) => {
+                return Err(AlkanesError::InvalidParameters(format!(
+                    "cannot encode a {} parameter",
+                    self
+                )))
+            }
+        }
+        Ok(())
+    }
+
+    /// Render `data` as this type. u128s are JSON strings so they survive
+    /// round-trips through JavaScript; anything that does not fit the type
+    /// falls back to hex.
+    pub fn decode(&self, data: &[u8]) -> JsonValue {
+        match self {
+            AbiType::Void if data.is_empty() => JsonValue::Null,
+            AbiType::U128 if data.len() == 16 => json!(read_u128(data).to_string()),
+            AbiType::String => match core::str::from_utf8(data) {
+                Ok(s) => json!(s),
+                Err(_) => hex_value(data),
+            },
+            AbiType::AlkaneId if data.len() == 32 => alkane_id_value(data),
+            AbiType::Vec(inner) => match inner.as_ref() {
+                AbiType::U128 if data.len() % 16 == 0 => JsonValue::Array(
+                    data.chunks(16)
+                        .map(|c| json!(read_u128(c).to_string()))
+                        .collect(),
+                ),
+                AbiType::AlkaneId if data.len() % 32 == 0 => {
+                    JsonValue::Array(data.chunks(32).map(alkane_id_value).collect())
+                }
+                _ => hex_value(data),
+            },
+            _ => hex_value(data),
+        }
+    }
+}
+
+impl core::fmt::Display for AbiType {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            AbiType::U128 => write!(f, "u128"),
+            AbiType::String => write!(f, "String"),
+            AbiType::AlkaneId => write!(f, "AlkaneId"),
+            AbiType::Bytes => write!(f, "Vec<u8>"),
+            AbiType::Vec(inner) => write!(f, "Vec<{}>", inner),
+            AbiType::Void => write!(f, "void"),
+            AbiType::Other(name) => write!(f, "{}", name),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AbiParam {
+    pub name: String,
+    pub ty: AbiType,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AbiMethod {
+    pub name: String,
+    pub opcode: u128,
+    pub params: Vec<AbiParam>,
+    pub returns: AbiType,
+}
+
+impl AbiMethod {
+    /// Cellpack inputs for this method: the opcode followed by each
+    /// parameter, taken by name from `args` (`name=value` pairs).
+    pub fn encode_call(&self, args: &[(String, String)]) -> Result<Vec<u128>> {
+        if let Some((unknown, _)) = args
+            .iter()
+            .find(|(name, _)| !self.params.iter().any(|p| &p.name == name))
+        {
+            return Err(AlkanesError::InvalidParameters(format!(
+                "{} has no parameter '{}' (expected {})",
+                self.name,
+                unknown,
+                self.signature()
+            )));
+        }
+        let mut inputs = vec![self.opcode];
+        for param in &self.params {
+            let (_, value) = args
+                .iter()
+                .find(|(name, _)| name == &param.name)
+                .ok_or_else(|| {
+                    AlkanesError::InvalidParameters(format!(
+                        "missing argument '{}' for {}",
+                        param.name,
+                        self.signature()
+                    ))
+                })?;
+            param.ty.encode(value, &mut inputs).map_err(|e| match e {
+                AlkanesError::InvalidParameters(msg) => {
+                    AlkanesError::InvalidParameters(format!("argument '{}': {}", param.name, msg))
+                }
+                other => other,
+            })?;
+        }
+        Ok(inputs)
+    }
+
+    /// Decode `CallResponse.data` as the declared return type.
+    pub fn decode_return(&self, data: &[u8]) -> JsonValue {
+        self.returns.decode(data)
+    }
+
+    /// `name(param: Type, ..) -> Returns`
+    pub fn signature(&self) -> String {
+        let params: Vec<String> = self
+            .params
+            .iter()
+            .map(|p| format!("{}: {}", p.name, p.ty))
+            .collect();
+        format!("{}({}) -> {}", self.name, params.join(", "), self.returns)
+    }
+}
+
+/// The ABI of one contract, as exported through `__meta`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContractAbi {
+    pub contract: String,
+    pub methods: Vec<AbiMethod>,
+}
+
+#[derive(Deserialize)]
+struct RawAbi {
+    #[serde(default)]
+    contract: String,
+    #[serde(default)]
+    methods: Vec<RawMethod>,
+}
+
+#[derive(Deserialize)]
+struct RawMethod {
+    name: String,
+    opcode: u128,
+    #[serde(default)]
+    params: Vec<RawParam>,
+    #[serde(default)]
+    returns: String,
+}
+
+#[derive(Deserialize)]
+struct RawParam {
+    #[serde(rename = "type")]
+    ty: String,
+    name: String,
+}
+
+impl ContractAbi {
+    /// Parse the bytes returned by the `meta` view.
+    pub fn from_meta(meta: &[u8]) -> Result<Self> {
+        if meta.is_empty() {
+            return Err(AlkanesError::Alkanes(
+                "contract exports no ABI (empty __meta)".to_string(),
+            ));
+        }
+        let raw: RawAbi = serde_json::from_slice(meta)
+            .map_err(|e| AlkanesError::Serialization(format!("__meta is not a JSON ABI: {}", e)))?;
+        Ok(ContractAbi {
+            contract: raw.contract,
+            methods: raw
+                .methods
+                .into_iter()
+                .map(|m| AbiMethod {
+                    name: m.name,
+                    opcode: m.opcode,
+                    params: m
+                        .params
+                        .into_iter()
+                        .map(|p| AbiParam {
+                            name: p.name,
+                            ty: AbiType::parse(&p.ty),
+                        })
+                        .collect(),
+                    returns: AbiType::parse(&m.returns),
+                })
+                .collect(),
+        })
+    }
+
+    /// Look a method up by its snake_case name, ignoring case and
+    /// underscores (so `get_name`, `getName` and `GetName` all match), or by
+    /// opcode.
+    pub fn method(&self, name: &str) -> Result<&AbiMethod> {
+        let key = normalize_name(name);
+        let opcode = parse_u128(name).ok();
+        self.methods
+            .iter()
+            .find(|m| normalize_name(&m.name) == key || Some(m.opcode) == opcode)
+            .ok_or_else(|| {
+                let names: Vec<&str> = self.methods.iter().map(|m| m.name.as_str()).collect();
+                AlkanesError::InvalidParameters(format!(
+                    "{} has no method '{}' (available: {})",
+                    self.contract,
+                    name,
+                    names.join(", ")
+                ))
+            })
+    }
+}
+
+/// Split `name=value` as passed to `--arg`.
+pub fn parse_named_arg(arg: &str) -> Result<(String, String)> {
+    arg.split_once('=')
+        .map(|(name, value)| (name.trim().to_string(), value.to_string()))
+        .ok_or_else(|| {
+            AlkanesError::InvalidParameters(format!("expected name=value, got '{}'", arg))
+        })
+}
+
+fn normalize_name(name: &str) -> String {
+    name.chars()
+        .filter(|c| *c != '_')
+        .flat_map(|c| c.to_lowercase())
+        .collect()
+}
+
+fn parse_u128(s: &str) -> Result<u128> {
+    let s = s.trim();
+    let parsed = match s.strip_prefix("0x") {
+        Some(hex) => u128::from_str_radix(hex, 16),
+        None => s.parse(),
+    };
+    parsed.map_err(|_| AlkanesError::InvalidParameters(format!("'{}' is not a u128", s)))
+}
+
+/// Pack a string the way the derive's string extraction unpacks it: bytes
+/// in little-endian u128 words, read until the first NUL byte.
+fn pack_string(s: &str) -> Vec<u128> {
+    let mut bytes = s.as_bytes().to_vec();
+    bytes.push(0);
+    bytes
+        .chunks(16)
+        .map(|chunk| {
+            let mut word = [0u8; 16];
+            word[..chunk.len()].copy_from_slice(chunk);
+            u128::from_le_bytes(word)
+        })
+        .collect()
+}
+
+/// Elements of a `Vec<T>` argument: a JSON array (of strings or numbers) or
+/// a comma-separated list.
+fn split_list(value: &str) -> Result<Vec<String>> {
+    let value = value.trim();
+    if value.starts_with('[') {
+        let items: Vec<JsonValue> = serde_json::from_str(value).map_err(|e| {
+            AlkanesError::InvalidParameters(format!("invalid list '{}': {}", value, e))
+        })?;
+        return Ok(items
+            .into_iter()
+            .map(|v| match v {
+                JsonValue::String(s) => s,
+                other => other.to_string(),
+            })
+            .collect());
+    }
+    if value.is_empty() {
+        return Ok(Vec::new());
+    }
+    Ok(value.split(',').map(|s| s.trim().to_string()).collect())
+}
+
+fn read_u128(bytes: &[u8]) -> u128 {
+    let mut word = [0u8; 16];
+    word.copy_from_slice(&bytes[..16]);
+    u128::from_le_bytes(word)
+}
+
+fn alkane_id_value(bytes: &[u8]) -> JsonValue {
+    json!({
+        "block": read_u128(&bytes[..16]).to_string(),
+        "tx": read_u128(&bytes[16..32]).to_string(),
+    })
+}
+
+fn hex_value(data: &[u8]) -> JsonValue {
+    json!(format!("0x{}", hex::encode(data)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const META: &str = r#"{ "contract": "OwnedToken", "methods": [
+        { "name": "initialize", "opcode": 0, "params": [{ "type": "u128", "name": "auth_token_units" }, { "type": "u128", "name": "token_units" }], "returns": "void" },
+        { "name": "set_name_and_symbol", "opcode": 3, "params": [{ "type": "String", "name": "name" }, { "type": "String", "name": "symbol" }], "returns": "void" },
+        { "name": "airdrop", "opcode": 9, "params": [{ "type": "Vec<AlkaneId>", "name": "targets" }, { "type": "Vec<u128>", "name": "amounts" }], "returns": "void" },
+        { "name": "get_name", "opcode": 99, "params": [], "returns": "String" },
+        { "name": "get_total_supply", "opcode": 101, "params": [], "returns": "u128" },
+        { "name": "get_data", "opcode": 1000, "params": [], "returns": "Vec < u8 >" }
+    ] }"#;
+
+    fn args(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|(n, v)| (n.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn parses_meta_and_types() {
+        let abi = ContractAbi::from_meta(META.as_bytes()).unwrap();
+        assert_eq!(abi.contract, "OwnedToken");
+        assert_eq!(abi.methods.len(), 6);
+        let airdrop = abi.method("airdrop").unwrap();
+        assert_eq!(
+            airdrop.params[0].ty,
+            AbiType::Vec(Box::new(AbiType::AlkaneId))
+        );
+        assert_eq!(abi.method("get_data").unwrap().returns, AbiType::Bytes);
+        assert_eq!(
+            airdrop.signature(),
+            "airdrop(targets: Vec<AlkaneId>, amounts: Vec<u128>) -> void"
+        );
+        assert_eq!(
+            AbiType::parse("alkanes_support :: id :: AlkaneId"),
+            AbiType::AlkaneId
+        );
+    }
+
+    #[test]
+    fn method_lookup_by_name_style_or_opcode() {
+        let abi = ContractAbi::from_meta(META.as_bytes()).unwrap();
+        assert_eq!(abi.method("getTotalSupply").unwrap().opcode, 101);
+        assert_eq!(abi.method("GetName").unwrap().opcode, 99);
+        assert_eq!(abi.method("3").unwrap().name, "set_name_and_symbol");
+        assert!(abi.method("burn").is_err());
+        assert!(ContractAbi::from_meta(b"").is_err());
+    }
+
+    #[test]
+    fn encodes_u128_and_strings_like_from_opcode_reads_them() {
+        let abi = ContractAbi::from_meta(META.as_bytes()).unwrap();
+        let init = abi.method("initialize").unwrap();
+        assert_eq!(
+            init.encode_call(&args(&[("token_units", "0x10"), ("auth_token_units", "1")]))
+                .unwrap(),
+            vec![0, 1, 16]
+        );
+
+        let set = abi.method("set_name_and_symbol").unwrap();
+        let inputs = set
+            .encode_call(&args(&[("name", "sixteen-byte-str"), ("symbol", "AB")]))
+            .unwrap();
+        // A 16-byte name fills a word, so its NUL terminator takes another
+        assert_eq!(inputs.len(), 1 + 2 + 1);
+        assert_eq!(inputs[1].to_le_bytes(), *b"sixteen-byte-str");
+        assert_eq!(inputs[2], 0);
+        assert_eq!(
+            inputs[3],
+            u128::from_le_bytes(*b"AB\0\0\0\0\0\0\0\0\0\0\0\0\0\0")
+        );
+    }
+
+    #[test]
+    fn encodes_vectors_with_length_prefix() {
+        let abi = ContractAbi::from_meta(META.as_bytes()).unwrap();
+        let airdrop = abi.method("airdrop").unwrap();
+        let inputs = airdrop
+            .encode_call(&args(&[
+                ("targets", r#"["2:1", "2:7"]"#),
+                ("amounts", "5, 6"),
+            ]))
+            .unwrap();
+        assert_eq!(inputs, vec![9, 2, 2, 1, 2, 7, 2, 5, 6]);
+    }
+
+    #[test]
+    fn rejects_missing_unknown_and_malformed_args() {
+        let abi = ContractAbi::from_meta(META.as_bytes()).unwrap();
+        let init = abi.method("initialize").unwrap();
+        assert!(init.encode_call(&args(&[("token_units", "1")])).is_err());
+        assert!(init
+            .encode_call(&args(&[
+                ("token_units", "1"),
+                ("auth_token_units", "1"),
+                ("x", "1")
+            ]))
+            .is_err());
+        assert!(init
+            .encode_call(&args(&[("token_units", "ten"), ("auth_token_units", "1")]))
+            .is_err());
+        assert!(parse_named_arg("novalue").is_err());
+        assert_eq!(
+            parse_named_arg("name=a=b").unwrap(),
+            ("name".to_string(), "a=b".to_string())
+        );
+    }
+
+    #[test]
+    fn decodes_declared_return_types() {
+        let abi = ContractAbi::from_meta(META.as_bytes()).unwrap();
+        assert_eq!(
+            abi.method("get_name").unwrap().decode_return(b"DIESEL"),
+            json!("DIESEL")
+        );
+        assert_eq!(
+            abi.method("get_total_supply")
+                .unwrap()
+                .decode_return(&1000u128.to_le_bytes()),
+            json!("1000")
+        );
+        assert_eq!(
+            abi.method("get_data").unwrap().decode_return(&[0xde, 0xad]),
+            json!("0xdead")
+        );
+        assert_eq!(
+            abi.method("initialize").unwrap().decode_return(&[]),
+            JsonValue::Null
+        );
+        // Data that does not fit the declared type is shown as hex
+        assert_eq!(
+            abi.method("get_total_supply")
+                .unwrap()
+                .decode_return(&[1, 2]),
+            json!("0x0102")
+        );
+
+        let mut ids = 2u128.to_le_bytes().to_vec();
+        ids.extend(7u128.to_le_bytes());
+        assert_eq!(
+            AbiType::parse("Vec<AlkaneId>").decode(&ids),
+            json!([{ "block": "2", "tx": "7" }])
+        );
+    }
+}
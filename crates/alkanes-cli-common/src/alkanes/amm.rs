@@ -546,9 +546,13 @@ const POOL_OPCODE_POOL_DETAILS: u64 = 999;
 
 /// Factory operation codes
 const FACTORY_OPCODE_INIT_POOL: u64 = 0;
-const FACTORY_OPCODE_CREATE_NEW_POOL: u64 = 1;
+pub const FACTORY_OPCODE_CREATE_NEW_POOL: u64 = 1;
 const FACTORY_OPCODE_FIND_EXISTING_POOL_ID: u64 = 2;
-const FACTORY_OPCODE_GET_ALL_POOLS: u64 = 3;
+pub const FACTORY_OPCODE_GET_ALL_POOLS: u64 = 3;
+/// Router opcodes; both take `pathLen,path...` followed by the amount, the
+/// limit and the expiry block
+pub const FACTORY_OPCODE_SWAP_EXACT_TOKENS_FOR_TOKENS: u64 = 13;
+pub const FACTORY_OPCODE_SWAP_TOKENS_FOR_EXACT_TOKENS: u64 = 14;
 
 /// Result of a pool details query
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
@@ -5,6 +5,11 @@ use log::info;
 use super::types::AlkaneId;
 use super::execute::{EnhancedAlkanesExecutor, EnhancedExecuteParams};
 use super::parsing::parse_protostones;
+use super::amm::{
+    FACTORY_OPCODE_CREATE_NEW_POOL, FACTORY_OPCODE_SWAP_EXACT_TOKENS_FOR_TOKENS,
+    FACTORY_OPCODE_SWAP_TOKENS_FOR_EXACT_TOKENS,
+};
+use crate::{format, vec, String, ToString};
 
 /// Parameters for initializing a new pool
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
@@ -43,6 +48,94 @@ pub struct SwapExecuteParams {
     pub ordinals_strategy: super::types::OrdinalsStrategy,
 }
 
+/// Protostone for creating a pool through the factory, seeded with
+/// `amount0` and `amount1`. Pointer and refund both go to output 0.
+/// Format: [factoryBlock,factoryTx,1,token0Block,token0Tx,token1Block,token1Tx,amount0,amount1]:v0:v0
+pub fn init_pool_calldata(
+    factory_id: &AlkaneId,
+    token0: &AlkaneId,
+    token1: &AlkaneId,
+    amount0: u128,
+    amount1: u128,
+) -> String {
+    format!(
+        "[{},{},{},{},{},{},{},{},{}]:v0:v0",
+        factory_id.block,
+        factory_id.tx,
+        FACTORY_OPCODE_CREATE_NEW_POOL,
+        token0.block,
+        token0.tx,
+        token1.block,
+        token1.tx,
+        amount0,
+        amount1
+    )
+}
+
+fn swap_calldata_with_opcode(
+    factory_id: &AlkaneId,
+    opcode: u64,
+    path: &[AlkaneId],
+    amount: u128,
+    limit: u128,
+    expires: u64,
+) -> String {
+    let mut inputs = vec![
+        factory_id.block.to_string(),
+        factory_id.tx.to_string(),
+        opcode.to_string(),
+        path.len().to_string(),
+    ];
+    for id in path {
+        inputs.push(id.block.to_string());
+        inputs.push(id.tx.to_string());
+    }
+    inputs.push(amount.to_string());
+    inputs.push(limit.to_string());
+    inputs.push(expires.to_string());
+    format!("[{}]:v0:v0", inputs.join(","))
+}
+
+/// Protostone for selling exactly `input_amount` of `path[0]` for at least
+/// `minimum_output` of the last token in `path`, through the factory router.
+/// Format: [factoryBlock,factoryTx,13,pathLen,path...,inputAmount,minimumOutput,expiryBlock]:v0:v0
+pub fn swap_calldata(
+    factory_id: &AlkaneId,
+    path: &[AlkaneId],
+    input_amount: u128,
+    minimum_output: u128,
+    expires: u64,
+) -> String {
+    swap_calldata_with_opcode(
+        factory_id,
+        FACTORY_OPCODE_SWAP_EXACT_TOKENS_FOR_TOKENS,
+        path,
+        input_amount,
+        minimum_output,
+        expires,
+    )
+}
+
+/// Protostone for buying exactly `output_amount` of the last token in
+/// `path`, spending at most `maximum_input` of `path[0]`.
+/// Format: [factoryBlock,factoryTx,14,pathLen,path...,outputAmount,maximumInput,expiryBlock]:v0:v0
+pub fn swap_exact_out_calldata(
+    factory_id: &AlkaneId,
+    path: &[AlkaneId],
+    output_amount: u128,
+    maximum_input: u128,
+    expires: u64,
+) -> String {
+    swap_calldata_with_opcode(
+        factory_id,
+        FACTORY_OPCODE_SWAP_TOKENS_FOR_EXACT_TOKENS,
+        path,
+        output_amount,
+        maximum_input,
+        expires,
+    )
+}
+
 /// Add liquidity to a pool (opcode 1)
 /// Calldata format: [factory, 1, token0Block, token0Tx, token1Block, token1Tx, amount0, amount1]:v0:v0
 #[cfg(feature = "std")]
@@ -69,16 +162,12 @@ pub async fn init_pool(
     // The tokens will come from transaction inputs (via UTXOs) automatically
     // If auto-change is needed, it will be inserted as protostone #0 and will send tokens to p1 (this protostone)
     // Format: [factoryBlock,factoryTx,1,token0Block,token0Tx,token1Block,token1Tx,amount0,amount1,...]:v0:v0
-    let calldata = format!(
-        "[{},{},1,{},{},{},{},{},{}]:v0:v0",
-        params.factory_id.block,
-        params.factory_id.tx,
-        params.token0.block,
-        params.token0.tx,
-        params.token1.block,
-        params.token1.tx,
+    let calldata = init_pool_calldata(
+        &params.factory_id,
+        &params.token0,
+        &params.token1,
         params.amount0,
-        params.amount1
+        params.amount1,
     );
     
     info!("Calldata: {}", calldata);
@@ -131,6 +220,8 @@ pub async fn init_pool(
         skip_diesel_mint: false,
         max_indexed_height,
         utxo_source: Default::default(),
+        skip_fuel_estimate: false,
+        fuel_padding_vbytes: 0,
     };
 
     // Execute
@@ -155,8 +246,8 @@ pub async fn init_pool(
     Ok(txid)
 }
 
-/// Execute a token swap (opcode 3)
-/// Calldata format: [factory, 3, poolBlock, poolTx]:inputAmount:minimumOutput:expiryBlock
+/// Execute a token swap through the factory router (opcode 13)
+/// Calldata format: see [`swap_calldata`]
 #[cfg(feature = "std")]
 pub async fn execute_swap(
     provider: &mut dyn DeezelProvider,
@@ -170,34 +261,20 @@ pub async fn execute_swap(
           format!("{}:{}", params.path[0].block, params.path[0].tx),
           format!("{}:{}", params.path[params.path.len() - 1].block, params.path[params.path.len() - 1].tx));
     
-    // For now, only support direct swaps (single hop)
-    if params.path.len() > 2 {
-        return Err(AlkanesError::Validation("Multi-hop swaps not yet supported. Use a direct pair.".to_string()));
-    }
-    
     let input_token = &params.path[0];
-    let output_token = &params.path[1];
+    let output_token = &params.path[params.path.len() - 1];
     
     info!("Swap details: {} {} → min {} {}", 
           params.input_amount, format!("{}:{}", input_token.block, input_token.tx),
           params.minimum_output, format!("{}:{}", output_token.block, output_token.tx));
     
-    // Need to find the pool for this pair
-    // For now, we'll need the user to provide the pool ID
-    // TODO: Add pool lookup from factory
-    
-    // Build calldata: [factory, 3, poolBlock, poolTx]:inputAmount:minimumOutput:expiryBlock
-    // For direct swap, we need to query which pool contains this pair
-    // This would require calling the factory contract to find the pool
-    
-    // Temporary: construct calldata assuming pool discovery
-    let calldata = format!(
-        "[{},{},3]:{}:{}:{}",
-        params.factory_id.block,
-        params.factory_id.tx,
+    // The factory routes the swap through the pool of each hop in the path
+    let calldata = swap_calldata(
+        &params.factory_id,
+        &params.path,
         params.input_amount,
         params.minimum_output,
-        params.expires
+        params.expires,
     );
     
     info!("Swap calldata: {}", calldata);
@@ -241,6 +318,8 @@ pub async fn execute_swap(
         skip_diesel_mint: false,
         max_indexed_height,
         utxo_source: Default::default(),
+        skip_fuel_estimate: false,
+        fuel_padding_vbytes: 0,
     };
     
     // Execute
@@ -265,3 +344,33 @@ pub async fn execute_swap(
     
     Ok(txid)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alkanes_support::cellpack::Cellpack;
+
+    fn id(block: u64, tx: u64) -> AlkaneId {
+        AlkaneId { block, tx }
+    }
+
+    fn cellpack(calldata: &str) -> Cellpack {
+        parse_protostones(calldata).unwrap().remove(0).cellpack.unwrap()
+    }
+
+    #[test]
+    fn test_swap_calldata_routes_through_factory() {
+        let path = [id(2, 1), id(2, 3), id(2, 5)];
+        let cellpack = cellpack(&swap_calldata(&id(4, 65522), &path, 1000, 900, 840_000));
+        assert_eq!(cellpack.target.block, 4);
+        assert_eq!(cellpack.target.tx, 65522);
+        assert_eq!(cellpack.inputs, vec![13, 3, 2, 1, 2, 3, 2, 5, 1000, 900, 840_000]);
+    }
+
+    #[test]
+    fn test_swap_exact_out_calldata() {
+        let path = [id(2, 1), id(2, 3)];
+        let cellpack = cellpack(&swap_exact_out_calldata(&id(4, 65522), &path, 500, 600, 840_000));
+        assert_eq!(cellpack.inputs, vec![14, 2, 2, 1, 2, 3, 500, 600, 840_000]);
+    }
+}
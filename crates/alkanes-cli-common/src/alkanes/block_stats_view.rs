@@ -0,0 +1,267 @@
+//! Native mirror + JSON-RPC helpers for the `blockstats` view function,
+//! which reports the indexing workload the indexer recorded for each block:
+//! protostones, messages, reverts by class, fuel, storage bytes written,
+//! alkanes created and extcall depth.
+//!
+//! [`get_block_stats`] pages through a height range (the view caps one call
+//! at [`MAX_RANGE`] blocks); [`BlockStatsTotals`] aggregates the result and
+//! [`to_csv`] renders it one row per block.
+
+use crate::traits::MetashrewRpcProvider;
+use crate::{AlkanesError, Result};
+use alkanes_support::proto::alkanes as pb;
+use prost::Message;
+use serde::{Deserialize, Serialize};
+
+#[cfg(not(feature = "std"))]
+use alloc::{
+    collections::BTreeMap,
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+#[cfg(feature = "std")]
+use std::collections::BTreeMap;
+
+/// View-function name used in `metashrew_view`.
+pub const VIEW_BLOCK_STATS: &str = "blockstats";
+
+/// Heights the indexer covers per `blockstats` call.
+pub const MAX_RANGE: u64 = 1000;
+
+/// Revert classes the indexer reports, in CSV column order.
+pub const REVERT_CLASSES: [&str; 5] = ["out_of_fuel", "balance", "deployment", "extcall", "revert"];
+
+/// Native form of `pb::BlockStats`.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct BlockStats {
+    pub height: u64,
+    pub protostones: u64,
+    pub messages: u64,
+    /// Reverted messages by class.
+    pub reverts: BTreeMap<String, u64>,
+    pub fuel_consumed: u64,
+    pub block_fuel: u64,
+    pub storage_bytes: u64,
+    pub alkanes_created: u64,
+    /// Messages by the depth of their deepest call frame.
+    pub call_depths: BTreeMap<u32, u64>,
+}
+
+impl BlockStats {
+    pub fn total_reverts(&self) -> u64 {
+        self.reverts.values().sum()
+    }
+
+    pub fn max_call_depth(&self) -> u32 {
+        self.call_depths.keys().next_back().copied().unwrap_or(0)
+    }
+}
+
+impl From<pb::BlockStats> for BlockStats {
+    fn from(s: pb::BlockStats) -> Self {
+        BlockStats {
+            height: s.height,
+            protostones: s.protostones,
+            messages: s.messages,
+            reverts: s.reverts.into_iter().map(|r| (r.class, r.count)).collect(),
+            fuel_consumed: s.fuel_consumed,
+            block_fuel: s.block_fuel,
+            storage_bytes: s.storage_bytes,
+            alkanes_created: s.alkanes_created,
+            call_depths: s.call_depths.into_iter().map(|d| (d.depth, d.count)).collect(),
+        }
+    }
+}
+
+/// Sums over a range of [`BlockStats`]. `fuel_utilization` is
+/// `fuel_consumed / block_fuel` across the blocks that have stats.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct BlockStatsTotals {
+    pub blocks: u64,
+    pub protostones: u64,
+    pub messages: u64,
+    pub reverts: BTreeMap<String, u64>,
+    pub fuel_consumed: u64,
+    pub block_fuel: u64,
+    pub fuel_utilization: f64,
+    pub storage_bytes: u64,
+    pub alkanes_created: u64,
+    pub call_depths: BTreeMap<u32, u64>,
+}
+
+impl BlockStatsTotals {
+    pub fn aggregate(blocks: &[BlockStats]) -> Self {
+        let mut totals = BlockStatsTotals::default();
+        for block in blocks {
+            totals.blocks += 1;
+            totals.protostones += block.protostones;
+            totals.messages += block.messages;
+            for (class, count) in &block.reverts {
+                *totals.reverts.entry(class.clone()).or_default() += count;
+            }
+            totals.fuel_consumed = totals.fuel_consumed.saturating_add(block.fuel_consumed);
+            totals.block_fuel = totals.block_fuel.saturating_add(block.block_fuel);
+            totals.storage_bytes += block.storage_bytes;
+            totals.alkanes_created += block.alkanes_created;
+            for (depth, count) in &block.call_depths {
+                *totals.call_depths.entry(*depth).or_default() += count;
+            }
+        }
+        if totals.block_fuel > 0 {
+            totals.fuel_utilization = totals.fuel_consumed as f64 / totals.block_fuel as f64;
+        }
+        totals
+    }
+}
+
+/// Parses `N`, `A..B` or `A-B` (inclusive) into a height range.
+pub fn parse_height_range(range: &str) -> Result<(u64, u64)> {
+    let parse = |s: &str| {
+        s.trim()
+            .parse::<u64>()
+            .map_err(|_| AlkanesError::Parse(format!("invalid height '{}' in range '{}'", s, range)))
+    };
+    let (start, end) = match range.split_once("..").or_else(|| range.split_once('-')) {
+        Some((start, end)) => (parse(start)?, parse(end)?),
+        None => {
+            let height = parse(range)?;
+            (height, height)
+        }
+    };
+    if end < start {
+        return Err(AlkanesError::Parse(format!(
+            "range '{}' ends before it starts",
+            range
+        )));
+    }
+    Ok((start, end))
+}
+
+/// Call `metashrew_view "blockstats"` once for `start..=end`, which the
+/// indexer cuts short at [`MAX_RANGE`] blocks.
+pub async fn get_block_stats_page<P: MetashrewRpcProvider + ?Sized>(
+    provider: &P,
+    start: u64,
+    end: u64,
+    block_tag: Option<&str>,
+) -> Result<Vec<BlockStats>> {
+    let req = pb::BlockStatsRequest {
+        start_height: start,
+        end_height: end,
+    };
+    let params_hex = format!("0x{}", hex::encode(req.encode_to_vec()));
+    let bytes = provider
+        .metashrew_view_call(VIEW_BLOCK_STATS, &params_hex, block_tag.unwrap_or("latest"))
+        .await?;
+    let resp = pb::BlockStatsResponse::decode(bytes.as_slice()).map_err(|e| {
+        AlkanesError::Other(format!(
+            "failed to decode BlockStatsResponse: {} ({} bytes)",
+            e,
+            bytes.len()
+        ))
+    })?;
+    Ok(resp.blocks.into_iter().map(Into::into).collect())
+}
+
+/// Stats of every block in `start..=end` that has any, oldest first.
+pub async fn get_block_stats<P: MetashrewRpcProvider + ?Sized>(
+    provider: &P,
+    start: u64,
+    end: u64,
+    block_tag: Option<&str>,
+) -> Result<Vec<BlockStats>> {
+    let mut blocks = Vec::new();
+    let mut from = start;
+    while from <= end {
+        let to = end.min(from.saturating_add(MAX_RANGE - 1));
+        blocks.extend(get_block_stats_page(provider, from, to, block_tag).await?);
+        if to == u64::MAX {
+            break;
+        }
+        from = to + 1;
+    }
+    Ok(blocks)
+}
+
+/// `blocks` as CSV, one row per block, with a column per revert class and
+/// the deepest call frame of the block.
+pub fn to_csv(blocks: &[BlockStats]) -> String {
+    let mut csv = String::from(
+        "height,protostones,messages,reverts,fuel_consumed,block_fuel,storage_bytes,alkanes_created,max_call_depth",
+    );
+    for class in REVERT_CLASSES {
+        csv.push_str(",reverts_");
+        csv.push_str(class);
+    }
+    csv.push('\n');
+    for b in blocks {
+        csv.push_str(&format!(
+            "{},{},{},{},{},{},{},{},{}",
+            b.height,
+            b.protostones,
+            b.messages,
+            b.total_reverts(),
+            b.fuel_consumed,
+            b.block_fuel,
+            b.storage_bytes,
+            b.alkanes_created,
+            b.max_call_depth()
+        ));
+        for class in REVERT_CLASSES {
+            csv.push(',');
+            csv.push_str(&b.reverts.get(class).copied().unwrap_or(0).to_string());
+        }
+        csv.push('\n');
+    }
+    csv
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn block(height: u64, fuel_consumed: u64, reverts: &[(&str, u64)]) -> BlockStats {
+        BlockStats {
+            height,
+            messages: 2,
+            fuel_consumed,
+            block_fuel: 1000,
+            reverts: reverts.iter().map(|(c, n)| (c.to_string(), *n)).collect(),
+            call_depths: [(1, 1), (3, 1)].into_iter().collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn parses_height_ranges() {
+        assert_eq!(parse_height_range("840000").unwrap(), (840000, 840000));
+        assert_eq!(parse_height_range("10..20").unwrap(), (10, 20));
+        assert_eq!(parse_height_range("10-20").unwrap(), (10, 20));
+        assert!(parse_height_range("20..10").is_err());
+        assert!(parse_height_range("ten").is_err());
+    }
+
+    #[test]
+    fn aggregates_blocks() {
+        let totals = BlockStatsTotals::aggregate(&[
+            block(1, 250, &[("out_of_fuel", 1)]),
+            block(2, 750, &[("out_of_fuel", 1), ("revert", 2)]),
+        ]);
+        assert_eq!(totals.blocks, 2);
+        assert_eq!(totals.messages, 4);
+        assert_eq!(totals.reverts.get("out_of_fuel"), Some(&2));
+        assert_eq!(totals.reverts.get("revert"), Some(&2));
+        assert_eq!(totals.call_depths.get(&3), Some(&2));
+        assert_eq!(totals.fuel_utilization, 0.5);
+    }
+
+    #[test]
+    fn csv_has_a_row_per_block() {
+        let csv = to_csv(&[block(7, 100, &[("extcall", 1)])]);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].starts_with("height,protostones,"));
+        assert_eq!(lines[1], "7,0,2,1,100,1000,0,0,3,0,0,0,1,0");
+    }
+}
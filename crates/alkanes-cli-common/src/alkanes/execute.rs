@@ -54,6 +54,22 @@ const DUST_LIMIT: u64 = 546;
 /// target, and under-charging is the failure mode being fixed.
 const MARGINAL_INPUT_VBYTES: u64 = 58;
 
+/// Ceiling on the runestone padding the fuel check adds. Past it a wrap +
+/// execute request is split into two transactions, each of which gets its
+/// own allocation; any other tx is kept as built with a warning.
+const MAX_FUEL_PADDING_VBYTES: u64 = 10_000;
+
+/// What the `estimatefuel` check asks of a built single transaction.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum FuelAdjustment {
+    /// Its allocation covers its protostones, or nothing would help.
+    Keep,
+    /// Rebuild with this many more vbytes of runestone padding.
+    Pad(u64),
+    /// Rebuild as a wrap → execute split.
+    Split,
+}
+
 /// Ceiling on how many EXTRA inputs coin selection may pull purely to lift the
 /// BTC change output out of the dust band (see [`BtcHeadroom`]). Without a cap
 /// a wallet of sub-marginal UTXOs (value < the fee that spending them costs)
@@ -758,7 +774,20 @@ impl<'a> EnhancedAlkanesExecutor<'a> {
             self.build_commit_reveal_pattern(params, &envelope).await
         } else {
             log::info!("CONTRACT EXECUTION: Single transaction without envelope");
-            self.build_single_transaction(&params).await
+            let state = self.build_single_transaction(&params).await?;
+            match self.check_fuel(&state, &params).await {
+                FuelAdjustment::Keep => Ok(state),
+                FuelAdjustment::Pad(vbytes) => {
+                    let mut padded = params.clone();
+                    padded.fuel_padding_vbytes += vbytes;
+                    self.build_single_transaction(&padded).await
+                }
+                FuelAdjustment::Split => {
+                    let mut split = params.clone();
+                    split.split_transactions = true;
+                    self.build_split_transaction_psbts(split).await
+                }
+            }
         }
     }
 
@@ -803,7 +832,21 @@ impl<'a> EnhancedAlkanesExecutor<'a> {
             log::info!("CONTRACT EXECUTION: Single transaction without envelope");
 
             // Build transaction
-            let sign_state = match self.build_single_transaction(&params).await? {
+            let mut state = self.build_single_transaction(&params).await?;
+            match self.check_fuel(&state, &params).await {
+                FuelAdjustment::Keep => {}
+                FuelAdjustment::Pad(vbytes) => {
+                    let mut padded = params.clone();
+                    padded.fuel_padding_vbytes += vbytes;
+                    state = self.build_single_transaction(&padded).await?;
+                }
+                FuelAdjustment::Split => {
+                    let mut split = params.clone();
+                    split.split_transactions = true;
+                    return self.execute_split(split).await;
+                }
+            }
+            let sign_state = match state {
                 ExecutionState::ReadyToSign(state) => state,
                 other => return Err(AlkanesError::Other(format!("Unexpected state after build: {:?}", other))),
             };
@@ -1467,6 +1510,92 @@ impl<'a> EnhancedAlkanesExecutor<'a> {
         Ok(commit_address)
     }
 
+    /// Simulates a built single transaction through the `estimatefuel` view
+    /// and decides whether its fuel allocation needs help. The allocation
+    /// is the tx's vfsize share of its block's fuel (floored at the minimum),
+    /// so a tx that needs more can be padded up to a size that gets it —
+    /// unless that takes more than `MAX_FUEL_PADDING_VBYTES`, in which case a
+    /// wrap is split off into its own tx when there is one. Anything that
+    /// cannot be fixed here, including padding over the cap, is logged as a
+    /// warning before signing.
+    ///
+    /// Fails open: when the view is unavailable the tx is kept as built.
+    async fn check_fuel(&mut self, state: &ExecutionState, params: &EnhancedExecuteParams) -> FuelAdjustment {
+        let ExecutionState::ReadyToSign(ready) = state else {
+            return FuelAdjustment::Keep;
+        };
+        if params.skip_fuel_estimate || params.protostones.is_empty() {
+            return FuelAdjustment::Keep;
+        }
+        let height = match self.provider.get_metashrew_height().await {
+            Ok(height) => height,
+            Err(e) => {
+                log::warn!("⛽ Skipping fuel check: could not read the indexer height: {}", e);
+                return FuelAdjustment::Keep;
+            }
+        };
+        let input = super::fuel_estimate::EstimateFuelInput {
+            // The tx lands in the block after the indexer tip.
+            height: height + 1,
+            transaction: bitcoin::consensus::serialize(&ready.psbt.unsigned_tx),
+            block_vfsize: 0,
+        };
+        let estimate = match super::fuel_estimate::estimate_fuel(&*self.provider, &input, None).await {
+            Ok(estimate) if estimate.error.is_empty() => estimate,
+            Ok(estimate) => {
+                log::warn!("⛽ Skipping fuel check: estimatefuel failed: {}", estimate.error);
+                return FuelAdjustment::Keep;
+            }
+            Err(e) => {
+                log::warn!("⛽ Skipping fuel check: estimatefuel unavailable: {}", e);
+                return FuelAdjustment::Keep;
+            }
+        };
+        if let Some((index, reason)) = estimate.first_revert() {
+            log::warn!("⛽ Protostone {} reverts in simulation: {}", index, reason);
+        }
+
+        // The unsigned tx has no witnesses; the builder's estimate does.
+        let vsize = (ready.estimated_vsize as u64).max(estimate.transaction_vfsize);
+        let can_split = !params.split_transactions
+            && params.envelope_data.is_none()
+            && params.protostones.len() >= 2
+            && is_wrap_protostone(&params.protostones[0]);
+        log::info!(
+            "⛽ Fuel estimate: {} used, minimum {} (block of {} vB besides this tx, needs ≥ {} vB, tx is ~{} vB)",
+            estimate.fuel_used, estimate.minimum_fuel, estimate.block_vfsize, estimate.minimum_vfsize, vsize
+        );
+        match estimate.padding_for(vsize) {
+            Some(0) => FuelAdjustment::Keep,
+            Some(padding) if padding <= MAX_FUEL_PADDING_VBYTES => {
+                log::info!("⛽ Padding the runestone by {} vB so the tx is allocated {} fuel", padding, estimate.fuel_used);
+                FuelAdjustment::Pad(padding)
+            }
+            _ if can_split => {
+                log::info!("⛽ Splitting wrap → execute so each tx gets its own fuel allocation");
+                FuelAdjustment::Split
+            }
+            Some(padding) => {
+                log::warn!(
+                    "⛽ This transaction will run out of fuel: it needs {} vB of runestone padding to be allocated {}, over the {} vB cap",
+                    padding,
+                    estimate.fuel_used,
+                    MAX_FUEL_PADDING_VBYTES
+                );
+                FuelAdjustment::Keep
+            }
+            _ => {
+                log::warn!(
+                    "⛽ This transaction will run out of fuel: it uses {} but no size gets more than {} at height {}",
+                    estimate.fuel_used,
+                    estimate.allocated_fuel.max(estimate.minimum_fuel),
+                    input.height
+                );
+                FuelAdjustment::Keep
+            }
+        }
+    }
+
     /// Execute single transaction (no envelope)
     async fn build_single_transaction(&mut self, params: &EnhancedExecuteParams) -> Result<ExecutionState> {
         log::info!("Building single transaction (no envelope)");
@@ -1534,7 +1663,8 @@ impl<'a> EnhancedAlkanesExecutor<'a> {
         let estimated_outputs = outputs.len() + 1; // +1 for OP_RETURN
         let has_runestone = !params.protostones.is_empty();
         
-        let estimated_vsize = Self::estimate_transaction_vsize(estimated_inputs, estimated_outputs, false, has_runestone);
+        let estimated_vsize = Self::estimate_transaction_vsize(estimated_inputs, estimated_outputs, false, has_runestone)
+            + params.fuel_padding_vbytes as usize;
         let estimated_fee = (fee_rate_sat_vb * estimated_vsize as f32).ceil() as u64;
         
         // Add 50% buffer to fee to account for variations in actual transaction size
@@ -1820,7 +1950,10 @@ impl<'a> EnhancedAlkanesExecutor<'a> {
         // Use final_funding_outpoints which may have inscribed UTXOs replaced with clean ones from split
         // When alkane inputs are specified, route them to the first protomessage (not output 0)
         let has_alkane_inputs = params.input_requirements.iter().any(|r| matches!(r, InputRequirement::Alkanes { .. }));
-        let runestone_script = self.construct_runestone_script_with_alkane_routing(&final_protostones, outputs.len(), has_alkane_inputs, params.skip_diesel_mint)?;
+        let mut runestone_script = self.construct_runestone_script_with_alkane_routing(&final_protostones, outputs.len(), has_alkane_inputs, params.skip_diesel_mint)?;
+        if params.fuel_padding_vbytes > 0 {
+            runestone_script = super::fuel_estimate::pad_runestone_script(&runestone_script, params.fuel_padding_vbytes);
+        }
         let prefetched_for_build = build_effective_txouts_map(params, &utxo_selection.txouts)?;
         let (psbt, fee, estimated_vsize) = self.build_psbt_and_fee(final_funding_outpoints.clone(), outputs, Some(runestone_script), params.fee_rate, None, None, prefetched_for_build.as_ref(), droppable_change_index).await?;
 
@@ -0,0 +1,254 @@
+//! Native mirror + JSON-RPC helper for the `estimatefuel` view function,
+//! which simulates a transaction with its block's whole fuel available and
+//! reports the fuel its protostones used next to what the indexer's
+//! `FuelTank` would allocate it.
+//!
+//! A transaction's allocation is its vfsize share of the block's fuel,
+//! floored at the minimum, so a transaction that uses more than the minimum
+//! can be made to fit by growing it. [`FuelEstimate::padding_for`] says by
+//! how much, and [`pad_runestone_script`] grows a runestone by that many
+//! vbytes with `Nop` fields, which rune and protorune decoders ignore.
+
+use crate::alkanes::simulate_view::hex_bytes;
+use crate::traits::MetashrewRpcProvider;
+use crate::{AlkanesError, Result};
+use alkanes_support::proto::alkanes as pb;
+use bitcoin::script::PushBytesBuf;
+use bitcoin::ScriptBuf;
+use prost::Message;
+use serde::{Deserialize, Serialize};
+
+#[cfg(not(feature = "std"))]
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+#[cfg(feature = "std")]
+use std::vec::Vec;
+
+/// View-function name used in `metashrew_view`.
+pub const VIEW_ESTIMATE_FUEL: &str = "estimatefuel";
+
+/// Runestone tag reserved for no-op fields (odd, so decoders skip it).
+const TAG_NOP: u8 = 127;
+
+/// Largest data push allowed in a standard script.
+const MAX_PUSH_BYTES: usize = 520;
+
+/// Selector the indexer prefixes revert messages with.
+const REVERT_SELECTOR: [u8; 4] = [0x08, 0xc3, 0x79, 0xa0];
+
+/// Input for [`estimate_fuel`]. `transaction` is a raw transaction or a
+/// PSBT; `block_vfsize == 0` assumes the rest of the landing block is as
+/// full as the block before `height`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EstimateFuelInput {
+    pub height: u64,
+    #[serde(with = "hex_bytes")]
+    pub transaction: Vec<u8>,
+    #[serde(default)]
+    pub block_vfsize: u64,
+}
+
+/// Fuel one protostone's message was charged. `fuel_used` is 0 when it
+/// reverted.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct ProtostoneFuel {
+    pub fuel_used: u64,
+    pub reverted: bool,
+    #[serde(with = "hex_bytes")]
+    pub revert_data: Vec<u8>,
+}
+
+/// Native form of `pb::EstimateFuelResponse`.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct FuelEstimate {
+    pub height: u64,
+    pub protostones: Vec<ProtostoneFuel>,
+    pub fuel_used: u64,
+    pub minimum_fuel: u64,
+    pub block_fuel: u64,
+    pub block_vfsize: u64,
+    pub transaction_vfsize: u64,
+    pub allocated_fuel: u64,
+    pub minimum_vfsize: u64,
+    pub satisfiable: bool,
+    pub error: String,
+}
+
+impl FuelEstimate {
+    /// Extra vbytes a transaction of `vsize` vbytes needs to be allocated
+    /// `fuel_used`: `Some(0)` when it already is, `None` when no size is.
+    pub fn padding_for(&self, vsize: u64) -> Option<u64> {
+        self.satisfiable
+            .then(|| self.minimum_vfsize.saturating_sub(vsize))
+    }
+
+    /// The first protostone that reverted in the simulation, with its
+    /// revert message.
+    pub fn first_revert(&self) -> Option<(usize, String)> {
+        self.protostones
+            .iter()
+            .position(|p| p.reverted)
+            .map(|i| (i, revert_message(&self.protostones[i].revert_data)))
+    }
+}
+
+fn revert_message(data: &[u8]) -> String {
+    String::from_utf8_lossy(data.strip_prefix(&REVERT_SELECTOR[..]).unwrap_or(data)).to_string()
+}
+
+impl From<&EstimateFuelInput> for pb::EstimateFuelRequest {
+    fn from(i: &EstimateFuelInput) -> Self {
+        pb::EstimateFuelRequest {
+            height: i.height,
+            transaction: i.transaction.clone(),
+            block_vfsize: i.block_vfsize,
+            ..Default::default()
+        }
+    }
+}
+
+impl From<pb::ProtostoneFuel> for ProtostoneFuel {
+    fn from(p: pb::ProtostoneFuel) -> Self {
+        ProtostoneFuel {
+            fuel_used: p.fuel_used,
+            reverted: p.reverted,
+            revert_data: p.revert_data,
+        }
+    }
+}
+
+impl From<pb::EstimateFuelResponse> for FuelEstimate {
+    fn from(r: pb::EstimateFuelResponse) -> Self {
+        FuelEstimate {
+            height: r.height,
+            protostones: r.protostones.into_iter().map(Into::into).collect(),
+            fuel_used: r.fuel_used,
+            minimum_fuel: r.minimum_fuel,
+            block_fuel: r.block_fuel,
+            block_vfsize: r.block_vfsize,
+            transaction_vfsize: r.transaction_vfsize,
+            allocated_fuel: r.allocated_fuel,
+            minimum_vfsize: r.minimum_vfsize,
+            satisfiable: r.satisfiable,
+            error: r.error,
+        }
+    }
+}
+
+/// Call `metashrew_view "estimatefuel"` and decode the response.
+pub async fn estimate_fuel<P: MetashrewRpcProvider + ?Sized>(
+    provider: &P,
+    input: &EstimateFuelInput,
+    block_tag: Option<&str>,
+) -> Result<FuelEstimate> {
+    let req: pb::EstimateFuelRequest = input.into();
+    let params_hex = format!("0x{}", hex::encode(req.encode_to_vec()));
+    let bytes = provider
+        .metashrew_view_call(VIEW_ESTIMATE_FUEL, &params_hex, block_tag.unwrap_or("latest"))
+        .await?;
+    let resp = pb::EstimateFuelResponse::decode(bytes.as_slice()).map_err(|e| {
+        AlkanesError::Other(format!(
+            "failed to decode EstimateFuelResponse: {} ({} bytes)",
+            e,
+            bytes.len()
+        ))
+    })?;
+    Ok(resp.into())
+}
+
+/// `script` with at least `vbytes` of `Nop` fields appended to its
+/// runestone payload. Only valid for runestones without edicts, whose
+/// payload ends in tag/value pairs; the executor never builds edicts.
+pub fn pad_runestone_script(script: &ScriptBuf, vbytes: u64) -> ScriptBuf {
+    let pairs = (vbytes as usize).div_ceil(2);
+    let padding: Vec<u8> = vec![[TAG_NOP, 0]; pairs].concat();
+    let mut padded = script.clone();
+    for chunk in padding.chunks(MAX_PUSH_BYTES) {
+        let push = PushBytesBuf::try_from(chunk.to_vec()).expect("chunk within push limit");
+        padded.push_slice(push);
+    }
+    padded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bitcoin::{transaction::Version, Amount, Transaction, TxOut};
+    use ordinals::{Artifact, Runestone};
+
+    #[test]
+    fn padding_covers_minimum_vfsize() {
+        let estimate = FuelEstimate {
+            minimum_vfsize: 900,
+            satisfiable: true,
+            ..Default::default()
+        };
+        assert_eq!(estimate.padding_for(250), Some(650));
+        assert_eq!(estimate.padding_for(1200), Some(0));
+        let unsatisfiable = FuelEstimate::default();
+        assert_eq!(unsatisfiable.padding_for(250), None);
+    }
+
+    #[test]
+    fn padded_runestone_deciphers_unchanged() {
+        let runestone = Runestone {
+            protocol: Some(vec![1, 2, 3, 4]),
+            pointer: Some(0),
+            ..Default::default()
+        };
+        let script = runestone.encipher();
+        let padded = pad_runestone_script(&script, 1201);
+        assert!(padded.len() >= script.len() + 1201);
+        let tx = Transaction {
+            version: Version::TWO,
+            lock_time: bitcoin::absolute::LockTime::ZERO,
+            input: vec![],
+            output: vec![
+                TxOut {
+                    value: Amount::from_sat(546),
+                    script_pubkey: ScriptBuf::new(),
+                },
+                TxOut {
+                    value: Amount::ZERO,
+                    script_pubkey: padded,
+                },
+            ],
+        };
+        match Runestone::decipher(&tx) {
+            Some(Artifact::Runestone(deciphered)) => {
+                assert_eq!(deciphered.protocol, runestone.protocol);
+                assert_eq!(deciphered.pointer, runestone.pointer);
+            }
+            other => panic!("expected a runestone, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn first_revert_strips_selector() {
+        let mut revert_data = REVERT_SELECTOR.to_vec();
+        revert_data.extend_from_slice(b"ALKANES: revert: out of stock");
+        let estimate: FuelEstimate = pb::EstimateFuelResponse {
+            protostones: vec![
+                pb::ProtostoneFuel {
+                    fuel_used: 40_000,
+                    ..Default::default()
+                },
+                pb::ProtostoneFuel {
+                    reverted: true,
+                    revert_data,
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        }
+        .into();
+        assert_eq!(
+            estimate.first_revert(),
+            Some((1, "ALKANES: revert: out of stock".to_string()))
+        );
+    }
+}
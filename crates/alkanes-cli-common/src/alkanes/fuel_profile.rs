@@ -0,0 +1,321 @@
+//! Native mirror + JSON-RPC helper for the `simulateprofile` view function,
+//! which runs a `simulate` with the indexer's fuel profiler enabled and
+//! returns a per-frame fuel tree next to the usual response.
+//!
+//! [`format_fuel_profile`] renders a tree flame-graph style: one line per
+//! frame and per fuel category, each with a bar proportional to the share
+//! of the top-level frame's fuel it accounts for.
+
+use crate::alkanes::simulate_view::{hex_bytes, AlkaneId};
+use crate::traits::MetashrewRpcProvider;
+use crate::{AlkanesError, Result};
+use alkanes_support::proto::alkanes as pb;
+use prost::Message;
+use serde::{Deserialize, Serialize};
+
+#[cfg(not(feature = "std"))]
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+#[cfg(feature = "std")]
+use std::vec::Vec;
+
+/// View-function name used in `metashrew_view`.
+pub const VIEW_SIMULATE_PROFILE: &str = "simulateprofile";
+
+/// Width, in characters, of the bar drawn for the top-level frame.
+const BAR_WIDTH: usize = 20;
+
+/// Fuel attributed to one storage key within a frame.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StorageFuel {
+    #[serde(with = "hex_bytes")]
+    pub key: Vec<u8>,
+    pub bytes: u64,
+    pub fuel: u64,
+}
+
+/// Native form of `pb::FuelProfileFrame`. `instruction_fuel` is what the
+/// frame spent in wasm after host-call charges and its children are taken
+/// out; a reverted child is charged its whole `start_fuel`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FuelFrame {
+    pub alkane: AlkaneId,
+    pub kind: String,
+    pub opcode: u128,
+    pub start_fuel: u64,
+    pub fuel_used: u64,
+    pub instruction_fuel: u64,
+    pub host_fuel: u64,
+    pub storage_reads: Vec<StorageFuel>,
+    pub storage_writes: Vec<StorageFuel>,
+    pub extcall_fuel: u64,
+    pub transferred_bytes: u64,
+    pub reverted: bool,
+    pub children: Vec<FuelFrame>,
+}
+
+/// Result of [`simulate_profile`]: the `SimulateResponse` fields plus the
+/// fuel tree of the simulated call.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProfiledSimulation {
+    pub gas_used: u64,
+    #[serde(with = "hex_bytes")]
+    pub data: Vec<u8>,
+    pub error: String,
+    pub profile: Option<FuelFrame>,
+}
+
+impl From<pb::FuelStorageAccess> for StorageFuel {
+    fn from(v: pb::FuelStorageAccess) -> Self {
+        StorageFuel {
+            key: v.key,
+            bytes: v.bytes,
+            fuel: v.fuel,
+        }
+    }
+}
+
+impl From<pb::FuelProfileFrame> for FuelFrame {
+    fn from(v: pb::FuelProfileFrame) -> Self {
+        FuelFrame {
+            alkane: v.alkane.map(Into::into).unwrap_or_default(),
+            kind: v.kind,
+            opcode: v
+                .opcode
+                .map(|o| ((o.hi as u128) << 64) | (o.lo as u128))
+                .unwrap_or_default(),
+            start_fuel: v.start_fuel,
+            fuel_used: v.fuel_used,
+            instruction_fuel: v.instruction_fuel,
+            host_fuel: v.host_fuel,
+            storage_reads: v.storage_reads.into_iter().map(Into::into).collect(),
+            storage_writes: v.storage_writes.into_iter().map(Into::into).collect(),
+            extcall_fuel: v.extcall_fuel,
+            transferred_bytes: v.transferred_bytes,
+            reverted: v.reverted,
+            children: v.children.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl From<pb::SimulateResponse> for ProfiledSimulation {
+    fn from(r: pb::SimulateResponse) -> Self {
+        ProfiledSimulation {
+            gas_used: r.gas_used,
+            data: r.execution.map(|e| e.data).unwrap_or_default(),
+            error: r.error,
+            profile: r.profile.map(Into::into),
+        }
+    }
+}
+
+/// Call `metashrew_view "simulateprofile"` with an encoded
+/// `MessageContextParcel` — the same request body `simulate` takes.
+pub async fn simulate_profile<P: MetashrewRpcProvider + ?Sized, M: Message>(
+    provider: &P,
+    parcel: &M,
+    block_tag: Option<&str>,
+) -> Result<ProfiledSimulation> {
+    let params_hex = format!("0x{}", hex::encode(parcel.encode_to_vec()));
+    let bytes = provider
+        .metashrew_view_call(VIEW_SIMULATE_PROFILE, &params_hex, block_tag.unwrap_or("latest"))
+        .await?;
+    let resp = pb::SimulateResponse::decode(bytes.as_slice()).map_err(|e| {
+        AlkanesError::Other(format!(
+            "failed to decode SimulateResponse: {} ({} bytes)",
+            e,
+            bytes.len()
+        ))
+    })?;
+    Ok(resp.into())
+}
+
+fn bar(fuel: u64, total: u64) -> String {
+    let filled = ((fuel as u128 * BAR_WIDTH as u128) / total.max(1) as u128) as usize;
+    let filled = filled.min(BAR_WIDTH);
+    let mut s = "█".repeat(filled);
+    s.push_str(&"░".repeat(BAR_WIDTH - filled));
+    s
+}
+
+fn percent(fuel: u64, total: u64) -> f64 {
+    fuel as f64 * 100.0 / total.max(1) as f64
+}
+
+fn display_key(key: &[u8]) -> String {
+    match core::str::from_utf8(key) {
+        Ok(s) if !s.is_empty() && s.chars().all(|c| c.is_ascii_graphic() || c == ' ') => {
+            s.to_string()
+        }
+        _ => format!("0x{}", hex::encode(key)),
+    }
+}
+
+enum Line<'a> {
+    Category(&'static str, u64),
+    Key(&'static str, &'a StorageFuel),
+    Frame(&'a FuelFrame),
+}
+
+fn render_frame(frame: &FuelFrame, total: u64, prefix: &str, out: &mut String) {
+    let mut lines = Vec::new();
+    if frame.instruction_fuel > 0 {
+        lines.push(Line::Category("instructions", frame.instruction_fuel));
+    }
+    if frame.host_fuel > 0 {
+        lines.push(Line::Category("host calls", frame.host_fuel));
+    }
+    for read in &frame.storage_reads {
+        lines.push(Line::Key("read", read));
+    }
+    for write in &frame.storage_writes {
+        lines.push(Line::Key("write", write));
+    }
+    for child in &frame.children {
+        lines.push(Line::Frame(child));
+    }
+    let count = lines.len();
+    for (i, line) in lines.into_iter().enumerate() {
+        let last = i + 1 == count;
+        let branch = if last { "└─ " } else { "├─ " };
+        match line {
+            Line::Category(label, fuel) => out.push_str(&format!(
+                "{}{}{} {} {} ({:.1}%)\n",
+                prefix,
+                branch,
+                bar(fuel, total),
+                label,
+                fuel,
+                percent(fuel, total)
+            )),
+            Line::Key(label, access) => out.push_str(&format!(
+                "{}{}{} {} {} [{} B] {} ({:.1}%)\n",
+                prefix,
+                branch,
+                bar(access.fuel, total),
+                label,
+                display_key(&access.key),
+                access.bytes,
+                access.fuel,
+                percent(access.fuel, total)
+            )),
+            Line::Frame(child) => {
+                out.push_str(&format!(
+                    "{}{}{} {}\n",
+                    prefix,
+                    branch,
+                    bar(child.fuel_used, total),
+                    frame_header(child, total)
+                ));
+                let next = format!("{}{}", prefix, if last { "   " } else { "│  " });
+                render_frame(child, total, &next, out);
+            }
+        }
+    }
+}
+
+fn frame_header(frame: &FuelFrame, total: u64) -> String {
+    let mut header = format!(
+        "{}:{} {} opcode {} — {} fuel ({:.1}%)",
+        frame.alkane.block,
+        frame.alkane.tx,
+        frame.kind,
+        frame.opcode,
+        frame.fuel_used,
+        percent(frame.fuel_used, total)
+    );
+    if frame.extcall_fuel > 0 || frame.transferred_bytes > 0 {
+        header.push_str(&format!(
+            ", +{} extcall overhead, {} B flushed",
+            frame.extcall_fuel, frame.transferred_bytes
+        ));
+    }
+    if frame.reverted {
+        header.push_str(" [reverted]");
+    }
+    header
+}
+
+/// Renders `root` as an indented tree, one bar per frame and per fuel
+/// category, scaled to `root.fuel_used`.
+pub fn format_fuel_profile(root: &FuelFrame) -> String {
+    let total = root.fuel_used;
+    let mut out = format!("{} {}\n", bar(total, total), frame_header(root, total));
+    render_frame(root, total, "", &mut out);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(opcode: u128, fuel_used: u64, instruction_fuel: u64) -> FuelFrame {
+        FuelFrame {
+            alkane: AlkaneId { block: 2, tx: 1 },
+            kind: "call".to_string(),
+            opcode,
+            start_fuel: 1_000_000,
+            fuel_used,
+            instruction_fuel,
+            host_fuel: 0,
+            storage_reads: vec![],
+            storage_writes: vec![],
+            extcall_fuel: 0,
+            transferred_bytes: 0,
+            reverted: false,
+            children: vec![],
+        }
+    }
+
+    #[test]
+    fn fuel_frame_from_proto() {
+        let pb_frame = pb::FuelProfileFrame {
+            alkane: Some((&AlkaneId { block: 2, tx: 1 }).into()),
+            kind: "staticcall".to_string(),
+            opcode: Some(pb::Uint128 { lo: 99, hi: 0 }),
+            fuel_used: 10,
+            storage_reads: vec![pb::FuelStorageAccess {
+                key: b"/k".to_vec(),
+                bytes: 3,
+                fuel: 6,
+            }],
+            reverted: true,
+            ..Default::default()
+        };
+        let f: FuelFrame = pb_frame.into();
+        assert_eq!(f.alkane, AlkaneId { block: 2, tx: 1 });
+        assert_eq!(f.kind, "staticcall");
+        assert_eq!(f.opcode, 99);
+        assert_eq!(f.storage_reads[0].key, b"/k".to_vec());
+        assert!(f.reverted);
+    }
+
+    #[test]
+    fn format_fuel_profile_draws_tree() {
+        let mut child = frame(104, 400, 100);
+        child.extcall_fuel = 500;
+        child.transferred_bytes = 4;
+        child.storage_writes.push(StorageFuel {
+            key: b"/claimablefees".to_vec(),
+            bytes: 38,
+            fuel: 300,
+        });
+        let mut root = frame(31, 1000, 100);
+        root.host_fuel = 500;
+        root.children.push(child);
+
+        let rendered = format_fuel_profile(&root);
+        let lines: Vec<&str> = rendered.lines().collect();
+        assert_eq!(lines.len(), 6);
+        assert!(lines[0].starts_with(&"█".repeat(BAR_WIDTH)));
+        assert!(lines[0].contains("2:1 call opcode 31 — 1000 fuel (100.0%)"));
+        assert!(lines[1].starts_with("├─ ") && lines[1].contains("instructions 100 (10.0%)"));
+        assert!(lines[2].contains("host calls 500"));
+        assert!(lines[3].starts_with("└─ ") && lines[3].contains("+500 extcall overhead, 4 B flushed"));
+        assert!(lines[4].starts_with("   ├─ "));
+        assert!(lines[5].contains("write /claimablefees [38 B] 300 (30.0%)"));
+    }
+}
@@ -0,0 +1,147 @@
+//! Native mirror + JSON-RPC helpers for the `holdersofalkane` view function,
+//! which lists the alkanes (contracts) holding a balance of a given alkane.
+//!
+//! [`get_holders`] fetches a single page; [`get_all_holders`] follows
+//! `next_cursor` until the indexer reports the holder list exhausted.
+//! Balances held by outpoints are not included, so the sum over every
+//! holder is the contract-locked part of the supply. Contracts that have
+//! not been credited the alkane since the indexer started tracking holders
+//! are missing too, so that sum is a lower bound.
+
+use crate::alkanes::simulate_view::AlkaneId;
+use crate::traits::MetashrewRpcProvider;
+use crate::{AlkanesError, Result};
+use alkanes_support::proto::alkanes as pb;
+use prost::Message;
+use serde::{Deserialize, Serialize};
+
+#[cfg(not(feature = "std"))]
+use alloc::{format, vec::Vec};
+#[cfg(feature = "std")]
+use std::vec::Vec;
+
+/// View-function name used in `metashrew_view`.
+pub const VIEW_HOLDERS_OF_ALKANE: &str = "holdersofalkane";
+
+/// Input for [`get_holders`]. `limit == 0` selects the indexer's default
+/// page size.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HoldersInput {
+    pub alkane: AlkaneId,
+    #[serde(default)]
+    pub cursor: u64,
+    #[serde(default)]
+    pub limit: u32,
+}
+
+/// One contract holding the queried alkane.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Holder {
+    pub holder: AlkaneId,
+    pub balance: u128,
+}
+
+/// Native form of `pb::HoldersOfAlkaneResponse`. `next_cursor == 0` means
+/// there are no further pages.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct HoldersPage {
+    pub holders: Vec<Holder>,
+    pub next_cursor: u64,
+}
+
+impl From<&HoldersInput> for pb::HoldersOfAlkaneRequest {
+    fn from(i: &HoldersInput) -> Self {
+        pb::HoldersOfAlkaneRequest {
+            id: Some((&i.alkane).into()),
+            cursor: i.cursor,
+            limit: i.limit,
+        }
+    }
+}
+
+impl From<pb::AlkaneHolder> for Holder {
+    fn from(h: pb::AlkaneHolder) -> Self {
+        Holder {
+            holder: h.holder.map(Into::into).unwrap_or_default(),
+            balance: h.balance.map(Into::into).unwrap_or_default(),
+        }
+    }
+}
+
+impl From<pb::HoldersOfAlkaneResponse> for HoldersPage {
+    fn from(r: pb::HoldersOfAlkaneResponse) -> Self {
+        HoldersPage {
+            holders: r.holders.into_iter().map(Into::into).collect(),
+            next_cursor: r.next_cursor,
+        }
+    }
+}
+
+/// Call `metashrew_view "holdersofalkane"` for one page and decode it.
+pub async fn get_holders<P: MetashrewRpcProvider + ?Sized>(
+    provider: &P,
+    input: &HoldersInput,
+    block_tag: Option<&str>,
+) -> Result<HoldersPage> {
+    let req: pb::HoldersOfAlkaneRequest = input.into();
+    let params_hex = format!("0x{}", hex::encode(req.encode_to_vec()));
+    let bytes = provider
+        .metashrew_view_call(
+            VIEW_HOLDERS_OF_ALKANE,
+            &params_hex,
+            block_tag.unwrap_or("latest"),
+        )
+        .await?;
+    let resp = pb::HoldersOfAlkaneResponse::decode(bytes.as_slice()).map_err(|e| {
+        AlkanesError::Other(format!(
+            "failed to decode HoldersOfAlkaneResponse: {} ({} bytes)",
+            e,
+            bytes.len()
+        ))
+    })?;
+    Ok(resp.into())
+}
+
+/// Page through `holdersofalkane` from `input.cursor` until the holder list
+/// is exhausted, returning every holder.
+pub async fn get_all_holders<P: MetashrewRpcProvider + ?Sized>(
+    provider: &P,
+    input: &HoldersInput,
+    block_tag: Option<&str>,
+) -> Result<Vec<Holder>> {
+    let mut holders = Vec::new();
+    let mut page_input = input.clone();
+    loop {
+        let page = get_holders(provider, &page_input, block_tag).await?;
+        holders.extend(page.holders);
+        if page.next_cursor == 0 {
+            return Ok(holders);
+        }
+        page_input.cursor = page.next_cursor;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn holders_response_converts() {
+        let page: HoldersPage = pb::HoldersOfAlkaneResponse {
+            holders: vec![pb::AlkaneHolder {
+                holder: Some((&AlkaneId { block: 2, tx: 7 }).into()),
+                balance: Some(u128::MAX.into()),
+            }],
+            next_cursor: 3,
+        }
+        .into();
+        assert_eq!(page.next_cursor, 3);
+        assert_eq!(
+            page.holders,
+            vec![Holder {
+                holder: AlkaneId { block: 2, tx: 7 },
+                balance: u128::MAX,
+            }]
+        );
+    }
+}
@@ -0,0 +1,161 @@
+//! Native mirror + JSON-RPC helper for the `resolveimplementation` view
+//! function, which follows the standard upgradeable and beacon proxy layouts
+//! to the alkane whose code actually runs, and lists their upgrades.
+
+use crate::alkanes::simulate_view::AlkaneId;
+use crate::traits::MetashrewRpcProvider;
+use crate::{AlkanesError, Result};
+use alkanes_support::proto::alkanes as pb;
+use prost::Message;
+use serde::{Deserialize, Serialize};
+
+#[cfg(not(feature = "std"))]
+use alloc::{format, string::String, vec::Vec};
+#[cfg(feature = "std")]
+use std::{string::String, vec::Vec};
+
+/// View-function name used in `metashrew_view`.
+pub const VIEW_RESOLVE_IMPLEMENTATION: &str = "resolveimplementation";
+
+/// Proxy or beacon followed on the way to the implementation.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProxyHop {
+    pub alkane: AlkaneId,
+    /// `upgradeable`, `beacon-proxy` or `upgradeable-beacon`
+    pub kind: String,
+}
+
+/// One implementation change, `old_implementation` → `new_implementation`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Upgrade {
+    pub alkane: AlkaneId,
+    pub height: u64,
+    pub old_implementation: AlkaneId,
+    pub new_implementation: AlkaneId,
+}
+
+/// Native form of `pb::ResolveImplementationResponse`. `chain` is empty when
+/// the queried alkane is not a proxy, in which case `implementation` is the
+/// alkane itself.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct Resolution {
+    pub chain: Vec<ProxyHop>,
+    pub implementation: AlkaneId,
+    /// hex sha256 of the implementation's bytecode
+    pub bytecode_hash: String,
+    pub upgrades: Vec<Upgrade>,
+}
+
+fn kind_name(kind: i32) -> String {
+    match pb::ProxyKind::try_from(kind) {
+        Ok(pb::ProxyKind::Upgradeable) => "upgradeable",
+        Ok(pb::ProxyKind::BeaconProxy) => "beacon-proxy",
+        Ok(pb::ProxyKind::UpgradeableBeacon) => "upgradeable-beacon",
+        _ => "unknown",
+    }
+    .into()
+}
+
+impl From<pb::ResolveImplementationResponse> for Resolution {
+    fn from(r: pb::ResolveImplementationResponse) -> Self {
+        Resolution {
+            chain: r
+                .chain
+                .into_iter()
+                .map(|hop| ProxyHop {
+                    alkane: hop.alkane.map(Into::into).unwrap_or_default(),
+                    kind: kind_name(hop.kind),
+                })
+                .collect(),
+            implementation: r.implementation.map(Into::into).unwrap_or_default(),
+            bytecode_hash: hex::encode(&r.bytecode_hash),
+            upgrades: r
+                .upgrades
+                .into_iter()
+                .map(|u| Upgrade {
+                    alkane: u.alkane.map(Into::into).unwrap_or_default(),
+                    height: u.height,
+                    old_implementation: u.old_implementation.map(Into::into).unwrap_or_default(),
+                    new_implementation: u.new_implementation.map(Into::into).unwrap_or_default(),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Call `metashrew_view "resolveimplementation"` for `alkane` and decode it.
+/// Fails when the indexer could not resolve the alkane (no bytecode, or a
+/// proxy chain that loops).
+pub async fn resolve_implementation<P: MetashrewRpcProvider + ?Sized>(
+    provider: &P,
+    alkane: &AlkaneId,
+    block_tag: Option<&str>,
+) -> Result<Resolution> {
+    let req = pb::ResolveImplementationRequest {
+        id: Some(alkane.into()),
+    };
+    let params_hex = format!("0x{}", hex::encode(req.encode_to_vec()));
+    let bytes = provider
+        .metashrew_view_call(
+            VIEW_RESOLVE_IMPLEMENTATION,
+            &params_hex,
+            block_tag.unwrap_or("latest"),
+        )
+        .await?;
+    let resp = pb::ResolveImplementationResponse::decode(bytes.as_slice()).map_err(|e| {
+        AlkanesError::Other(format!(
+            "failed to decode ResolveImplementationResponse: {} ({} bytes)",
+            e,
+            bytes.len()
+        ))
+    })?;
+    if resp.implementation.is_none() {
+        return Err(AlkanesError::Other(format!(
+            "could not resolve the implementation of {}:{}",
+            alkane.block, alkane.tx
+        )));
+    }
+    Ok(resp.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolution_converts() {
+        let proxy = AlkaneId { block: 2, tx: 5 };
+        let beacon = AlkaneId { block: 4, tx: 9 };
+        let implementation = AlkaneId { block: 2, tx: 2 };
+        let resolution: Resolution = pb::ResolveImplementationResponse {
+            chain: vec![
+                pb::ProxyHop {
+                    alkane: Some((&proxy).into()),
+                    kind: pb::ProxyKind::BeaconProxy as i32,
+                },
+                pb::ProxyHop {
+                    alkane: Some((&beacon).into()),
+                    kind: pb::ProxyKind::UpgradeableBeacon as i32,
+                },
+            ],
+            implementation: Some((&implementation).into()),
+            bytecode_hash: vec![0xab; 32],
+            upgrades: vec![pb::UpgradeRecord {
+                alkane: Some((&beacon).into()),
+                height: 880_000,
+                old_implementation: Some((&AlkaneId { block: 2, tx: 1 }).into()),
+                new_implementation: Some((&implementation).into()),
+            }],
+        }
+        .into();
+        assert_eq!(resolution.chain[0].kind, "beacon-proxy");
+        assert_eq!(resolution.chain[1].alkane, beacon);
+        assert_eq!(resolution.implementation, implementation);
+        assert_eq!(resolution.bytecode_hash, "ab".repeat(32));
+        assert_eq!(resolution.upgrades[0].height, 880_000);
+        assert_eq!(
+            resolution.upgrades[0].old_implementation,
+            AlkaneId { block: 2, tx: 1 }
+        );
+    }
+}
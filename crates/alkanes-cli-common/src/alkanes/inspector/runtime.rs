@@ -297,6 +297,43 @@ pub(crate) fn create_host_functions(engine: &Engine) -> Linker<AlkanesState> {
         }
     }).unwrap();
 
+    // __emit_event - matches alkanes-rs signature; recorded as a host call
+    linker.func_wrap("env", "__emit_event", |caller: Caller<'_, AlkanesState>, topic: i32, data: i32| {
+        let start_time = Instant::now();
+        let read = |ptr: i32| -> Vec<u8> {
+            let addr = ptr as usize;
+            if addr < 4 {
+                return vec![];
+            }
+            if let Some(memory) = caller.get_export("memory").and_then(|e| e.into_memory()) {
+                let mut len_bytes = [0u8; 4];
+                if memory.read(&caller, addr - 4, &mut len_bytes).is_ok() {
+                    let mut bytes = vec![0u8; u32::from_le_bytes(len_bytes) as usize];
+                    if memory.read(&caller, addr, &mut bytes).is_ok() {
+                        return bytes;
+                    }
+                }
+            }
+            vec![]
+        };
+        let (topic_bytes, data_bytes) = (read(topic), read(data));
+
+        let host_call = HostCall {
+            function_name: "__emit_event".to_string(),
+            parameters: vec![
+                format!("topic: \"{}\"", String::from_utf8_lossy(&topic_bytes)),
+                format!("data: {}", hex::encode(&data_bytes)),
+            ],
+            result: "emitted".to_string(),
+            timestamp_micros: start_time.elapsed().as_micros() as u64,
+        };
+
+        {
+            let mut calls = lock_mutex!(caller.data().host_calls);
+            calls.push(host_call);
+        }
+    }).unwrap();
+
     // __balance - matches alkanes-rs signature
     linker.func_wrap("env", "__balance", |mut caller: Caller<'_, AlkanesState>, _who: i32, _what: i32, output: i32| {
         // Return zero balance
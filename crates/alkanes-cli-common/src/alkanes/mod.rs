@@ -19,6 +19,14 @@ pub mod protostone_ext;
 pub mod protoburn;
 pub mod simulation;
 pub mod simulate_view;
+pub mod storage_keys_view;
+pub mod holders_view;
+pub mod implementation_view;
+pub mod fuel_profile;
+pub mod fuel_estimate;
+pub mod block_stats_view;
+pub mod abi;
+pub mod verify;
 pub mod protostone;
 pub mod balance_sheet;
 pub mod predict;
@@ -1,18 +1,19 @@
 //! Native mirrors + JSON-RPC helpers for the RC8 view functions
 //! `simulatetransaction`, `simulateprotostones`, and `simulateblock`
-//! exposed by alkanes-rs v2.2.0-rc.8.
+//! exposed by alkanes-rs v2.2.0-rc.8, plus `simulatechain`.
 //!
 //! The prost structs ([`alkanes_support::proto::alkanes::SimulateTransactionRequest`]
 //! et al.) are the wire format; the structs in this module are ergonomic
 //! Rust mirrors with [`From`] conversions in both directions, so client
 //! code never has to peek inside `Option<Uint128>` wrappers.
 //!
-//! Three async helpers ([`simulate_transaction`], [`simulate_protostones`],
-//! [`simulate_block`]) take a `&P: MetashrewRpcProvider`, build the prost
+//! The async helpers ([`simulate_transaction`], [`simulate_protostones`],
+//! [`simulate_block`], [`simulate_chain`]) take a `&P: MetashrewRpcProvider`, build the prost
 //! request, hex-encode it, dispatch through
 //! [`metashrew_view_call`](crate::traits::MetashrewRpcProvider::metashrew_view_call),
 //! decode the response, and hand back the native form.
 
+use crate::alkanes::fuel_profile::FuelFrame;
 use crate::traits::MetashrewRpcProvider;
 use crate::{AlkanesError, Result};
 use alkanes_support::proto::alkanes as pb;
@@ -30,6 +31,8 @@ pub const VIEW_SIMULATE_TRANSACTION: &str = "simulatetransaction";
 pub const VIEW_SIMULATE_PROTOSTONES: &str = "simulateprotostones";
 /// View-function name used in `metashrew_view`.
 pub const VIEW_SIMULATE_BLOCK: &str = "simulateblock";
+/// View-function name used in `metashrew_view`.
+pub const VIEW_SIMULATE_CHAIN: &str = "simulatechain";
 
 // ---------------------------------------------------------------------------
 // Native mirrors
@@ -82,6 +85,15 @@ pub struct Outpoint {
     pub vout: u32,
 }
 
+/// Pre-execution balance override: the sandbox treats `outpoint` as
+/// holding exactly `balances`, whatever it holds on-chain. `txid` is in
+/// internal byte order, like every other [`Outpoint`] on the wire.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BalanceOverride {
+    pub outpoint: Outpoint,
+    pub balances: Vec<AlkaneTransfer>,
+}
+
 /// All alkane balances routed to one tx output after simulation.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct VoutBalances {
@@ -102,6 +114,10 @@ pub struct ProtostoneExecution {
     pub trace: Vec<u8>,
     pub fuel_used: u64,
     pub touched_storage: Vec<TouchedStorage>,
+    /// Fuel tree of the protostone's message; set when the request asked
+    /// for `profile`.
+    #[serde(default)]
+    pub profile: Option<FuelFrame>,
 }
 
 /// Native form of `pb::SimulateTransactionResponse`. Returned by all
@@ -132,8 +148,18 @@ pub struct SimulateBlockResponse {
     pub error: String,
 }
 
+/// Native form of `pb::SimulateChainResponse` — one entry per pending tx,
+/// in input order.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SimulateChainResponse {
+    pub height: u64,
+    pub txs: Vec<SimulateTransactionResponse>,
+    pub total_fuel_used: u64,
+    pub error: String,
+}
+
 /// Input for [`simulate_transaction`] — a height + raw tx (or PSBT) +
-/// optional storage overrides.
+/// optional storage and balance overrides.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct SimulateTransactionInput {
     pub height: u64,
@@ -141,6 +167,11 @@ pub struct SimulateTransactionInput {
     pub transaction: Vec<u8>,
     #[serde(default)]
     pub storage_overrides: Vec<StorageOverride>,
+    #[serde(default)]
+    pub balance_overrides: Vec<BalanceOverride>,
+    /// Ask the indexer for a per-protostone fuel profile.
+    #[serde(default)]
+    pub profile: bool,
 }
 
 /// Input for [`simulate_protostones`] — height + alkane inputs +
@@ -157,10 +188,13 @@ pub struct SimulateProtostonesInput {
     pub block: Vec<u8>,
     #[serde(default)]
     pub storage_overrides: Vec<StorageOverride>,
+    /// Ask the indexer for a per-protostone fuel profile.
+    #[serde(default)]
+    pub profile: bool,
 }
 
 /// Input for [`simulate_block`] — height + consensus-encoded block +
-/// optional shared storage overrides.
+/// optional shared storage and balance overrides.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct SimulateBlockInput {
     pub height: u64,
@@ -168,6 +202,28 @@ pub struct SimulateBlockInput {
     pub block: Vec<u8>,
     #[serde(default)]
     pub storage_overrides: Vec<StorageOverride>,
+    #[serde(default)]
+    pub balance_overrides: Vec<BalanceOverride>,
+    /// Ask the indexer for a per-protostone fuel profile.
+    #[serde(default)]
+    pub profile: bool,
+}
+
+/// Input for [`simulate_chain`] — the first faux block's height + the
+/// pending raw txs (or PSBTs) in mining order + optional shared storage
+/// and balance overrides.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SimulateChainInput {
+    pub height: u64,
+    #[serde(with = "hex_bytes_list")]
+    pub transactions: Vec<Vec<u8>>,
+    #[serde(default)]
+    pub storage_overrides: Vec<StorageOverride>,
+    #[serde(default)]
+    pub balance_overrides: Vec<BalanceOverride>,
+    /// Ask the indexer for a per-protostone fuel profile.
+    #[serde(default)]
+    pub profile: bool,
 }
 
 // ---------------------------------------------------------------------------
@@ -253,6 +309,23 @@ impl From<pb::StorageOverride> for StorageOverride {
     }
 }
 
+impl From<&BalanceOverride> for pb::BalanceOverride {
+    fn from(o: &BalanceOverride) -> Self {
+        pb::BalanceOverride {
+            outpoint: Some((&o.outpoint).into()),
+            balances: o.balances.iter().map(Into::into).collect(),
+        }
+    }
+}
+impl From<pb::BalanceOverride> for BalanceOverride {
+    fn from(o: pb::BalanceOverride) -> Self {
+        BalanceOverride {
+            outpoint: o.outpoint.map(Into::into).unwrap_or_default(),
+            balances: o.balances.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
 impl From<pb::TouchedStorage> for TouchedStorage {
     fn from(o: pb::TouchedStorage) -> Self {
         TouchedStorage {
@@ -262,6 +335,14 @@ impl From<pb::TouchedStorage> for TouchedStorage {
     }
 }
 
+impl From<&Outpoint> for pb::Outpoint {
+    fn from(o: &Outpoint) -> Self {
+        pb::Outpoint {
+            txid: o.txid.clone(),
+            vout: o.vout,
+        }
+    }
+}
 impl From<pb::Outpoint> for Outpoint {
     fn from(o: pb::Outpoint) -> Self {
         Outpoint {
@@ -293,6 +374,7 @@ impl From<pb::ProtostoneExecution> for ProtostoneExecution {
             trace: trace_bytes,
             fuel_used: p.fuel_used,
             touched_storage: p.touched_storage.into_iter().map(Into::into).collect(),
+            profile: p.profile.map(Into::into),
         }
     }
 }
@@ -329,12 +411,25 @@ impl From<pb::SimulateBlockResponse> for SimulateBlockResponse {
     }
 }
 
+impl From<pb::SimulateChainResponse> for SimulateChainResponse {
+    fn from(r: pb::SimulateChainResponse) -> Self {
+        SimulateChainResponse {
+            height: r.height,
+            txs: r.txs.into_iter().map(Into::into).collect(),
+            total_fuel_used: r.total_fuel_used,
+            error: r.error,
+        }
+    }
+}
+
 impl From<&SimulateTransactionInput> for pb::SimulateTransactionRequest {
     fn from(i: &SimulateTransactionInput) -> Self {
         pb::SimulateTransactionRequest {
             height: i.height,
             transaction: i.transaction.clone(),
             storage_overrides: i.storage_overrides.iter().map(Into::into).collect(),
+            balance_overrides: i.balance_overrides.iter().map(Into::into).collect(),
+            profile: i.profile,
         }
     }
 }
@@ -348,6 +443,7 @@ impl From<&SimulateProtostonesInput> for pb::SimulateProtostonesRequest {
             transaction: i.transaction.clone(),
             block: i.block.clone(),
             storage_overrides: i.storage_overrides.iter().map(Into::into).collect(),
+            profile: i.profile,
         }
     }
 }
@@ -358,6 +454,20 @@ impl From<&SimulateBlockInput> for pb::SimulateBlockRequest {
             height: i.height,
             block: i.block.clone(),
             storage_overrides: i.storage_overrides.iter().map(Into::into).collect(),
+            balance_overrides: i.balance_overrides.iter().map(Into::into).collect(),
+            profile: i.profile,
+        }
+    }
+}
+
+impl From<&SimulateChainInput> for pb::SimulateChainRequest {
+    fn from(i: &SimulateChainInput) -> Self {
+        pb::SimulateChainRequest {
+            height: i.height,
+            transactions: i.transactions.clone(),
+            storage_overrides: i.storage_overrides.iter().map(Into::into).collect(),
+            balance_overrides: i.balance_overrides.iter().map(Into::into).collect(),
+            profile: i.profile,
         }
     }
 }
@@ -449,11 +559,37 @@ pub async fn simulate_block<P: MetashrewRpcProvider + ?Sized>(
     Ok(resp.into())
 }
 
+/// Call `metashrew_view "simulatechain"` and decode the response.
+///
+/// Runs a chain of unconfirmed txs where later ones spend outputs of
+/// earlier ones (e.g. a wallet's pending wrap → swap → add-liquidity),
+/// each in its own faux block over one shared sandbox. `txs` aligns 1:1
+/// with `input.transactions`.
+pub async fn simulate_chain<P: MetashrewRpcProvider + ?Sized>(
+    provider: &P,
+    input: &SimulateChainInput,
+    block_tag: Option<&str>,
+) -> Result<SimulateChainResponse> {
+    let req: pb::SimulateChainRequest = input.into();
+    let params_hex = encode_proto(&req);
+    let bytes = provider
+        .metashrew_view_call(VIEW_SIMULATE_CHAIN, &params_hex, block_tag_str(block_tag))
+        .await?;
+    let resp = pb::SimulateChainResponse::decode(bytes.as_slice()).map_err(|e| {
+        AlkanesError::Other(format!(
+            "failed to decode SimulateChainResponse: {} ({} bytes)",
+            e,
+            bytes.len()
+        ))
+    })?;
+    Ok(resp.into())
+}
+
 // ---------------------------------------------------------------------------
 // Hex-bytes serde helper
 // ---------------------------------------------------------------------------
 
-mod hex_bytes {
+pub(crate) mod hex_bytes {
     use serde::{Deserialize, Deserializer, Serializer};
     #[cfg(not(feature = "std"))]
     use alloc::{string::String, vec::Vec};
@@ -471,6 +607,28 @@ mod hex_bytes {
     }
 }
 
+/// [`hex_bytes`] for a list of byte strings.
+pub(crate) mod hex_bytes_list {
+    use serde::{Deserialize, Deserializer, Serializer};
+    #[cfg(not(feature = "std"))]
+    use alloc::{string::String, vec::Vec};
+    #[cfg(feature = "std")]
+    use std::vec::Vec;
+
+    pub fn serialize<S: Serializer>(list: &[Vec<u8>], s: S) -> Result<S::Ok, S::Error> {
+        s.collect_seq(list.iter().map(hex::encode))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Vec<Vec<u8>>, D::Error> {
+        Vec::<String>::deserialize(d)?
+            .iter()
+            .map(|s| {
+                hex::decode(s.strip_prefix("0x").unwrap_or(s)).map_err(serde::de::Error::custom)
+            })
+            .collect()
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Tests
 // ---------------------------------------------------------------------------
@@ -519,12 +677,28 @@ mod tests {
         assert_eq!(so, back);
     }
 
+    #[test]
+    fn balance_override_round_trip() {
+        let bo = BalanceOverride {
+            outpoint: Outpoint { txid: vec![7; 32], vout: 1 },
+            balances: vec![AlkaneTransfer {
+                id: AlkaneId { block: 2, tx: 0 },
+                value: 1_000,
+            }],
+        };
+        let pb: pb::BalanceOverride = (&bo).into();
+        let back: BalanceOverride = pb.into();
+        assert_eq!(bo, back);
+    }
+
     #[test]
     fn simulate_transaction_input_encodes() {
         let i = SimulateTransactionInput {
             height: 955_828,
             transaction: vec![0xde, 0xad, 0xbe, 0xef],
             storage_overrides: vec![],
+            balance_overrides: vec![],
+            profile: true,
         };
         let req: pb::SimulateTransactionRequest = (&i).into();
         assert_eq!(req.height, 955_828);
@@ -535,6 +709,7 @@ mod tests {
         let bytes = hex::decode(&hex_params[2..]).unwrap();
         let decoded = pb::SimulateTransactionRequest::decode(bytes.as_slice()).unwrap();
         assert_eq!(decoded.height, 955_828);
+        assert!(decoded.profile);
     }
 
     #[test]
@@ -549,6 +724,7 @@ mod tests {
             transaction: vec![],
             block: vec![],
             storage_overrides: vec![],
+            profile: false,
         };
         let req: pb::SimulateProtostonesRequest = (&i).into();
         assert_eq!(req.height, 100);
@@ -578,6 +754,7 @@ mod tests {
                         value: b"\x01".to_vec(),
                     }],
                 }],
+                profile: None,
             }],
             final_balances_by_vout: vec![pb::VoutBalances {
                 vout: 2,
@@ -611,10 +788,29 @@ mod tests {
         assert_eq!(native.total_fuel_used, 42);
     }
 
+    #[test]
+    fn simulate_chain_input_keeps_tx_order() {
+        let i: SimulateChainInput = serde_json::from_str(
+            r#"{"height": 7, "transactions": ["0xaa", "bbcc"]}"#,
+        )
+        .unwrap();
+        assert_eq!(i.transactions, vec![vec![0xaa], vec![0xbb, 0xcc]]);
+        let req: pb::SimulateChainRequest = (&i).into();
+        let bytes = hex::decode(&encode_proto(&req)[2..]).unwrap();
+        let decoded = pb::SimulateChainRequest::decode(bytes.as_slice()).unwrap();
+        assert_eq!(decoded.height, 7);
+        assert_eq!(decoded.transactions, vec![vec![0xaa], vec![0xbb, 0xcc]]);
+        assert_eq!(
+            serde_json::to_value(&i).unwrap()["transactions"],
+            serde_json::json!(["aa", "bbcc"])
+        );
+    }
+
     #[test]
     fn view_fn_names_match_metashrew_export() {
         assert_eq!(VIEW_SIMULATE_TRANSACTION, "simulatetransaction");
         assert_eq!(VIEW_SIMULATE_PROTOSTONES, "simulateprotostones");
         assert_eq!(VIEW_SIMULATE_BLOCK, "simulateblock");
+        assert_eq!(VIEW_SIMULATE_CHAIN, "simulatechain");
     }
 }
@@ -0,0 +1,160 @@
+//! Native mirror + JSON-RPC helpers for the `getstoragekeys` view function,
+//! which lists every storage key an alkane has written together with its
+//! value and the height of its last write.
+//!
+//! [`get_storage_keys`] fetches a single page; [`get_all_storage_keys`]
+//! follows `next_cursor` until the indexer reports the key list exhausted.
+
+use crate::alkanes::simulate_view::{hex_bytes, AlkaneId};
+use crate::traits::MetashrewRpcProvider;
+use crate::{AlkanesError, Result};
+use alkanes_support::proto::alkanes as pb;
+use prost::Message;
+use serde::{Deserialize, Serialize};
+
+#[cfg(not(feature = "std"))]
+use alloc::{format, vec::Vec};
+#[cfg(feature = "std")]
+use std::vec::Vec;
+
+/// View-function name used in `metashrew_view`.
+pub const VIEW_GET_STORAGE_KEYS: &str = "getstoragekeys";
+
+/// Input for [`get_storage_keys`]. `limit == 0` selects the indexer's
+/// default page size.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StorageKeysInput {
+    pub alkane: AlkaneId,
+    #[serde(default, with = "hex_bytes")]
+    pub prefix: Vec<u8>,
+    #[serde(default)]
+    pub cursor: u64,
+    #[serde(default)]
+    pub limit: u32,
+}
+
+/// One storage slot of the queried alkane.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StorageKeyEntry {
+    #[serde(with = "hex_bytes")]
+    pub key: Vec<u8>,
+    #[serde(with = "hex_bytes")]
+    pub value: Vec<u8>,
+    pub last_modified_height: u64,
+}
+
+/// Native form of `pb::AlkaneStorageKeysResponse`. `next_cursor == 0`
+/// means there are no further pages.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct StorageKeysPage {
+    pub entries: Vec<StorageKeyEntry>,
+    pub next_cursor: u64,
+}
+
+impl From<&StorageKeysInput> for pb::AlkaneStorageKeysRequest {
+    fn from(i: &StorageKeysInput) -> Self {
+        pb::AlkaneStorageKeysRequest {
+            id: Some((&i.alkane).into()),
+            prefix: i.prefix.clone(),
+            cursor: i.cursor,
+            limit: i.limit,
+        }
+    }
+}
+
+impl From<pb::AlkaneStorageKeyEntry> for StorageKeyEntry {
+    fn from(e: pb::AlkaneStorageKeyEntry) -> Self {
+        StorageKeyEntry {
+            key: e.key,
+            value: e.value,
+            last_modified_height: e.last_modified_height,
+        }
+    }
+}
+
+impl From<pb::AlkaneStorageKeysResponse> for StorageKeysPage {
+    fn from(r: pb::AlkaneStorageKeysResponse) -> Self {
+        StorageKeysPage {
+            entries: r.entries.into_iter().map(Into::into).collect(),
+            next_cursor: r.next_cursor,
+        }
+    }
+}
+
+/// Call `metashrew_view "getstoragekeys"` for one page and decode it.
+pub async fn get_storage_keys<P: MetashrewRpcProvider + ?Sized>(
+    provider: &P,
+    input: &StorageKeysInput,
+    block_tag: Option<&str>,
+) -> Result<StorageKeysPage> {
+    let req: pb::AlkaneStorageKeysRequest = input.into();
+    let params_hex = format!("0x{}", hex::encode(req.encode_to_vec()));
+    let bytes = provider
+        .metashrew_view_call(VIEW_GET_STORAGE_KEYS, &params_hex, block_tag.unwrap_or("latest"))
+        .await?;
+    let resp = pb::AlkaneStorageKeysResponse::decode(bytes.as_slice()).map_err(|e| {
+        AlkanesError::Other(format!(
+            "failed to decode AlkaneStorageKeysResponse: {} ({} bytes)",
+            e,
+            bytes.len()
+        ))
+    })?;
+    Ok(resp.into())
+}
+
+/// Page through `getstoragekeys` from `input.cursor` until the key list is
+/// exhausted, returning every matching entry.
+pub async fn get_all_storage_keys<P: MetashrewRpcProvider + ?Sized>(
+    provider: &P,
+    input: &StorageKeysInput,
+    block_tag: Option<&str>,
+) -> Result<Vec<StorageKeyEntry>> {
+    let mut entries = Vec::new();
+    let mut page_input = input.clone();
+    loop {
+        let page = get_storage_keys(provider, &page_input, block_tag).await?;
+        entries.extend(page.entries);
+        if page.next_cursor == 0 {
+            return Ok(entries);
+        }
+        page_input.cursor = page.next_cursor;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn storage_keys_input_encodes() {
+        let i = StorageKeysInput {
+            alkane: AlkaneId { block: 2, tx: 1 },
+            prefix: b"/claim".to_vec(),
+            cursor: 7,
+            limit: 25,
+        };
+        let req: pb::AlkaneStorageKeysRequest = (&i).into();
+        let decoded = pb::AlkaneStorageKeysRequest::decode(req.encode_to_vec().as_slice()).unwrap();
+        assert_eq!(AlkaneId::from(decoded.id.unwrap()), i.alkane);
+        assert_eq!(decoded.prefix, b"/claim".to_vec());
+        assert_eq!(decoded.cursor, 7);
+        assert_eq!(decoded.limit, 25);
+    }
+
+    #[test]
+    fn storage_keys_response_converts() {
+        let page: StorageKeysPage = pb::AlkaneStorageKeysResponse {
+            entries: vec![pb::AlkaneStorageKeyEntry {
+                key: b"/totalsupply".to_vec(),
+                value: vec![0x10],
+                last_modified_height: 880_123,
+            }],
+            next_cursor: 42,
+        }
+        .into();
+        assert_eq!(page.next_cursor, 42);
+        assert_eq!(page.entries.len(), 1);
+        assert_eq!(page.entries[0].key, b"/totalsupply".to_vec());
+        assert_eq!(page.entries[0].last_modified_height, 880_123);
+    }
+}
@@ -18,6 +18,7 @@
 //! `conversion` module provides utilities for working with alkanes-support types
 //! in a more general context.
 
+use alkanes_support::error::{ErrorValue, StructuredError};
 use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
 #[cfg(not(feature = "std"))]
@@ -62,6 +63,7 @@ pub enum Event {
     Create(Create),
     ReceiveIntent(ReceiveIntentEvent),
     ValueTransfer(ValueTransferEvent),
+    EmitEvent(EmitEventData),
     Unknown,
 }
 
@@ -93,6 +95,16 @@ pub struct ValueTransferEvent {
     pub redirect_to: u32,
 }
 
+/// A structured event emitted by a contract through `emit_event`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct EmitEventData {
+    pub alkane: ContractId,
+    #[serde(with = "hex_serde")]
+    pub topic: Vec<u8>,
+    #[serde(with = "hex_serde")]
+    pub data: Vec<u8>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct TraceContext {
     pub inner: Context,
@@ -207,6 +219,7 @@ impl From<alkanes_support::proto::alkanes::alkanes_trace_event::Event> for Event
             alkanes_support::proto::alkanes::alkanes_trace_event::Event::CreateAlkane(e) => Event::Create(e.into()),
             alkanes_support::proto::alkanes::alkanes_trace_event::Event::ReceiveIntent(e) => Event::ReceiveIntent(e.into()),
             alkanes_support::proto::alkanes::alkanes_trace_event::Event::ValueTransfer(e) => Event::ValueTransfer(e.into()),
+            alkanes_support::proto::alkanes::alkanes_trace_event::Event::EmitEvent(e) => Event::EmitEvent(e.into()),
             _ => Event::Unknown,
         }
     }
@@ -246,6 +259,16 @@ impl From<alkanes_support::proto::alkanes::AlkanesValueTransfer> for ValueTransf
     }
 }
 
+impl From<alkanes_support::proto::alkanes::AlkanesEmitEvent> for EmitEventData {
+    fn from(e: alkanes_support::proto::alkanes::AlkanesEmitEvent) -> Self {
+        Self {
+            alkane: e.alkane.map(Into::into).unwrap_or_default(),
+            topic: e.topic,
+            data: e.data,
+        }
+    }
+}
+
 impl From<alkanes_support::proto::alkanes::AlkanesCreate> for Create {
     fn from(e: alkanes_support::proto::alkanes::AlkanesCreate) -> Self {
         Self {
@@ -308,6 +331,15 @@ pub fn to_raw_json(trace: &Trace) -> JsonValue {
 /// Format a trace for pretty printing with colorful emojis and YAML-like tree structure
 /// This function works with alkanes_support::trace::Trace
 pub fn format_trace_pretty(trace: &alkanes_support::trace::Trace) -> String {
+    format_trace_pretty_with_diff(trace, None)
+}
+
+/// [`format_trace_pretty`], followed by the message's state diff (from the
+/// `tracediff` view) when one is given.
+pub fn format_trace_pretty_with_diff(
+    trace: &alkanes_support::trace::Trace,
+    diff: Option<&alkanes_support::trace::StateDiff>,
+) -> String {
     let events = trace.0.lock().unwrap();
     let mut output = String::new();
     
@@ -365,6 +397,19 @@ pub fn format_trace_pretty(trace: &alkanes_support::trace::Trace) -> String {
                         output.push_str(&format!("{}    🪙 transfers: []\n", indent_prefix));
                     }
                 },
+                alkanes_support::trace::TraceEvent::EmitEvent { alkane, topic, data } => {
+                    output.push_str(&format!("{} 📣 event:\n", tree_prefix));
+                    output.push_str(&format!("{}    alkane_id:\n", indent_prefix));
+                    output.push_str(&format!("{}      block: {}\n", indent_prefix, alkane.block));
+                    output.push_str(&format!("{}      tx: {}\n", indent_prefix, alkane.tx));
+                    match core::str::from_utf8(topic) {
+                        Ok(name) if !name.is_empty() && !name.chars().any(|c| c.is_control()) => {
+                            output.push_str(&format!("{}    topic: \"{}\"\n", indent_prefix, name));
+                        }
+                        _ => output.push_str(&format!("{}    topic: 0x{}\n", indent_prefix, hex::encode(topic))),
+                    }
+                    output.push_str(&format!("{}    data: 0x{}\n", indent_prefix, hex::encode(data)));
+                },
                 alkanes_support::trace::TraceEvent::CreateAlkane(id) => {
                     output.push_str(&format!("{} 🏗️  create_alkane:\n", tree_prefix));
                     output.push_str(&format!("{}    alkane_id:\n", indent_prefix));
@@ -457,6 +502,7 @@ pub fn format_trace_pretty(trace: &alkanes_support::trace::Trace) -> String {
                                 }
                             }
                         }
+                        format_structured_error(&mut output, &resp.inner.data, indent_prefix);
                     } else {
                         output.push_str(&format!("{}    🚨 error_data: null\n", indent_prefix));
                     }
@@ -470,12 +516,156 @@ pub fn format_trace_pretty(trace: &alkanes_support::trace::Trace) -> String {
         }
     }
     
+    if let Some(diff) = diff {
+        output.push('\n');
+        output.push_str(&format_state_diff(diff));
+    }
+
     output.push_str("\n🎯 ═══════════════════════════════════════════════════════════════\n");
     output.push_str("✨                      TRACE COMPLETE                         ✨\n");
     output.push_str("🎯 ═══════════════════════════════════════════════════════════════\n");
     output
 }
 
+/// View-function name used in `metashrew_view`.
+pub const VIEW_TRACE_DIFF: &str = "tracediff";
+
+/// Call `metashrew_view "tracediff"` for the protostone at `outpoint`
+/// (`txid:vout`, the protostone's shadow vout) and decode the state diff.
+/// Reverted and unknown outpoints come back as an empty diff.
+pub async fn trace_diff<P: crate::traits::MetashrewRpcProvider + ?Sized>(
+    provider: &P,
+    outpoint: &str,
+) -> crate::Result<alkanes_support::trace::StateDiff> {
+    use bitcoin::hashes::Hash;
+    use core::str::FromStr;
+    use prost::Message;
+    let (txid, vout) = outpoint.split_once(':').ok_or_else(|| {
+        crate::AlkanesError::InvalidParameters("Invalid outpoint format. Expected 'txid:vout'".to_string())
+    })?;
+    let request = crate::proto::protorune::Outpoint {
+        txid: bitcoin::Txid::from_str(txid)?.to_byte_array().to_vec(),
+        vout: vout.parse::<u32>()?,
+    };
+    let params_hex = format!("0x{}", hex::encode(request.encode_to_vec()));
+    let bytes = provider.metashrew_view_call(VIEW_TRACE_DIFF, &params_hex, "latest").await?;
+    let diff = alkanes_support::proto::alkanes::StateDiff::decode(bytes.as_slice()).map_err(|e| {
+        crate::AlkanesError::Other(format!("failed to decode tracediff response: {}", e))
+    })?;
+    Ok(diff.into())
+}
+
+fn format_alkane_id(id: &alkanes_support::id::AlkaneId) -> String {
+    format!("{}:{}", id.block, id.tx)
+}
+
+fn format_storage_value(value: &[u8]) -> String {
+    if value.is_empty() {
+        "(empty)".to_string()
+    } else {
+        format!("0x{}", hex::encode(value))
+    }
+}
+
+/// Format a state diff in the same YAML-like style as [`format_trace_pretty`].
+pub fn format_state_diff(diff: &alkanes_support::trace::StateDiff) -> String {
+    let mut output = String::new();
+    if diff.is_empty() {
+        output.push_str("📝 state_diff: {}\n");
+        return output;
+    }
+    output.push_str("📝 state_diff:\n");
+    if !diff.created.is_empty() {
+        output.push_str("    🏗️  created:\n");
+        for id in &diff.created {
+            output.push_str(&format!("      - {}\n", format_alkane_id(id)));
+        }
+    }
+    if !diff.storage.is_empty() {
+        output.push_str("    🗄️  storage:\n");
+        for alkane in &diff.storage {
+            output.push_str(&format!("      {}:\n", format_alkane_id(&alkane.alkane)));
+            for write in &alkane.writes {
+                let key = match core::str::from_utf8(&write.key) {
+                    Ok(text) if !text.is_empty() && !text.chars().any(|c| c.is_control()) => {
+                        format!("\"{}\"", text)
+                    }
+                    _ => format!("0x{}", hex::encode(&write.key)),
+                };
+                output.push_str(&format!(
+                    "        {}: {} → {}\n",
+                    key,
+                    format_storage_value(&write.old_value),
+                    format_storage_value(&write.new_value)
+                ));
+            }
+        }
+    }
+    if !diff.inventories.is_empty() {
+        output.push_str("    💼 contract_balances:\n");
+        for inventory in &diff.inventories {
+            output.push_str(&format!("      {}:\n", format_alkane_id(&inventory.holder)));
+            for change in &inventory.changes {
+                output.push_str(&format!(
+                    "        {}: {} → {} ({})\n",
+                    format_alkane_id(&change.alkane),
+                    change.old_balance,
+                    change.new_balance,
+                    change.delta_string()
+                ));
+            }
+        }
+    }
+    if !diff.outputs.is_empty() {
+        output.push_str("    📦 outputs:\n");
+        for out in &diff.outputs {
+            output.push_str(&format!("      vout {}:\n", out.vout));
+            for transfer in &out.debited {
+                output.push_str(&format!("        {}: -{}\n", format_alkane_id(&transfer.id), transfer.value));
+            }
+            for transfer in &out.credited {
+                output.push_str(&format!("        {}: +{}\n", format_alkane_id(&transfer.id), transfer.value));
+            }
+        }
+    }
+    output
+}
+
+/// A state diff as JSON, for `--raw` output next to [`trace_to_json`].
+pub fn state_diff_to_json(diff: &alkanes_support::trace::StateDiff) -> JsonValue {
+    serde_json::json!({
+        "created": diff.created.iter().map(format_alkane_id).collect::<Vec<_>>(),
+        "storage": diff.storage.iter().map(|alkane| serde_json::json!({
+            "alkane": format_alkane_id(&alkane.alkane),
+            "writes": alkane.writes.iter().map(|w| serde_json::json!({
+                "key": hex::encode(&w.key),
+                "old_value": hex::encode(&w.old_value),
+                "new_value": hex::encode(&w.new_value),
+            })).collect::<Vec<_>>(),
+        })).collect::<Vec<_>>(),
+        "contract_balances": diff.inventories.iter().map(|inventory| serde_json::json!({
+            "holder": format_alkane_id(&inventory.holder),
+            "changes": inventory.changes.iter().map(|c| serde_json::json!({
+                "alkane": format_alkane_id(&c.alkane),
+                "old_balance": c.old_balance.to_string(),
+                "new_balance": c.new_balance.to_string(),
+                "delta": c.delta_string(),
+            })).collect::<Vec<_>>(),
+        })).collect::<Vec<_>>(),
+        "outputs": diff.outputs.iter().map(|out| serde_json::json!({
+            "vout": out.vout,
+            "credited": out.credited.iter().map(|t| serde_json::json!({
+                "alkane": format_alkane_id(&t.id),
+                "amount": t.value.to_string(),
+            })).collect::<Vec<_>>(),
+            "debited": out.debited.iter().map(|t| serde_json::json!({
+                "alkane": format_alkane_id(&t.id),
+                "amount": t.value.to_string(),
+            })).collect::<Vec<_>>(),
+        })).collect::<Vec<_>>(),
+    })
+}
+
 /// Convert a trace to JSON format for raw output
 pub fn trace_to_json(trace: &alkanes_support::trace::Trace) -> JsonValue {
     use serde_json::json;
@@ -518,6 +708,18 @@ pub fn trace_to_json(trace: &alkanes_support::trace::Trace) -> JsonValue {
                     "redirect_to": redirect_to
                 })
             },
+            alkanes_support::trace::TraceEvent::EmitEvent { alkane, topic, data } => {
+                json!({
+                    "type": "event",
+                    "alkane_id": {
+                        "block": alkane.block,
+                        "tx": alkane.tx
+                    },
+                    "topic": hex::encode(topic),
+                    "topic_utf8": String::from_utf8_lossy(topic),
+                    "data": hex::encode(data)
+                })
+            },
             alkanes_support::trace::TraceEvent::CreateAlkane(id) => {
                 json!({
                     "type": "create_alkane",
@@ -603,6 +805,9 @@ pub fn trace_to_json(trace: &alkanes_support::trace::Trace) -> JsonValue {
                         revert_json["error_message"] = json!(error_message.trim_end_matches('\0'));
                     }
                 }
+                if let Some(error) = StructuredError::decode_revert_data(&resp.inner.data) {
+                    revert_json["error"] = structured_error_to_json(&error);
+                }
                 
                 revert_json
             },
@@ -837,9 +1042,120 @@ fn format_error_data(output: &mut String, event: &JsonValue, indent_prefix: &str
                                 output.push_str(&format!("{}      💬 decoded_message: \"{}\"\n", indent_prefix, trimmed));
                             }
                         }
+                        format_structured_error(output, &bytes, indent_prefix);
                     }
                 }
             }
         }
     }
-}
\ No newline at end of file
+}
+
+/// Shows the structured error in revert data, if it carries one, as
+/// `Name { field: value }` with its code.
+fn format_structured_error(output: &mut String, data: &[u8], indent_prefix: &str) {
+    #[cfg(not(feature = "std"))]
+    use alloc::format;
+
+    if let Some(error) = StructuredError::decode_revert_data(data) {
+        output.push_str(&format!("{}      🧩 error: {} (code {})\n", indent_prefix, error, error.code));
+    }
+}
+
+fn error_value_to_json(value: &ErrorValue) -> JsonValue {
+    use serde_json::json;
+    match value {
+        // u128 doesn't fit a JSON number
+        ErrorValue::Uint(v) => json!(v.to_string()),
+        ErrorValue::Bool(v) => json!(v),
+        ErrorValue::Str(v) => json!(v),
+        ErrorValue::AlkaneId(id) => json!(format!("{}:{}", id.block, id.tx)),
+        ErrorValue::List(values) => JsonValue::Array(values.iter().map(error_value_to_json).collect()),
+    }
+}
+
+/// `{ "code", "name", "params": { field: value }, "display" }` for a
+/// structured error.
+pub fn structured_error_to_json(error: &StructuredError) -> JsonValue {
+    use serde_json::json;
+    let params: serde_json::Map<String, JsonValue> = error
+        .params
+        .iter()
+        .map(|(field, value)| (field.clone(), error_value_to_json(value)))
+        .collect();
+    json!({
+        "code": error.code,
+        "name": error.name,
+        "params": params,
+        "display": error.to_string(),
+    })
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alkanes_support::id::AlkaneId;
+    use alkanes_support::parcel::AlkaneTransfer;
+    use alkanes_support::trace::{
+        BalanceChange, InventoryDiff, OutputDiff, StateDiff, StorageDiff, StorageWrite,
+    };
+
+    #[test]
+    fn state_diff_renders_every_section() {
+        let token = AlkaneId { block: 2, tx: 1 };
+        let diff = StateDiff {
+            storage: vec![StorageDiff {
+                alkane: token,
+                writes: vec![StorageWrite {
+                    key: b"/totalsupply".to_vec(),
+                    old_value: vec![],
+                    new_value: vec![0xe8, 0x03],
+                }],
+            }],
+            inventories: vec![InventoryDiff {
+                holder: token,
+                changes: vec![BalanceChange {
+                    alkane: AlkaneId { block: 2, tx: 0 },
+                    old_balance: 10,
+                    new_balance: 4,
+                }],
+            }],
+            outputs: vec![OutputDiff {
+                vout: 0,
+                credited: vec![AlkaneTransfer { id: token, value: 1000 }],
+                debited: vec![],
+            }],
+            created: vec![token],
+        };
+        let rendered = format_state_diff(&diff);
+        assert!(rendered.contains("      - 2:1\n"));
+        assert!(rendered.contains("        \"/totalsupply\": (empty) → 0xe803\n"));
+        assert!(rendered.contains("        2:0: 10 → 4 (-6)\n"));
+        assert!(rendered.contains("      vout 0:\n        2:1: +1000\n"));
+        assert_eq!(format_state_diff(&StateDiff::default()), "📝 state_diff: {}\n");
+
+        let trace = alkanes_support::trace::Trace::default();
+        assert!(format_trace_pretty_with_diff(&trace, Some(&diff)).contains("📝 state_diff:\n"));
+        assert!(!format_trace_pretty(&trace).contains("state_diff"));
+    }
+
+    #[test]
+    fn structured_revert_is_decoded() {
+        let mut data = alkanes_support::error::REVERT_SELECTOR.to_vec();
+        data.extend_from_slice(
+            b"ALKANES: revert: Error: [E4097] InsufficientLiquidity { needed: 100, have: 3 }",
+        );
+        let mut output = String::new();
+        format_structured_error(&mut output, &data, "");
+        assert_eq!(
+            output,
+            "      🧩 error: InsufficientLiquidity { needed: 100, have: 3 } (code 4097)\n"
+        );
+        let error = StructuredError::decode_revert_data(&data).unwrap();
+        let json = structured_error_to_json(&error);
+        assert_eq!(json["name"], "InsufficientLiquidity");
+        assert_eq!(json["params"]["needed"], "100");
+
+        let mut plain = String::new();
+        format_structured_error(&mut plain, b"\x08\xc3\x79\xa0ALKANES: revert: Error: nope", "");
+        assert!(plain.is_empty());
+    }
+}
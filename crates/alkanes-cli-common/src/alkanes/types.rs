@@ -421,6 +421,18 @@ pub struct EnhancedExecuteParams {
     /// path. `espo` uses `essentials.get_address_spendable_outpoints`.
     #[serde(default, alias = "utxoSource")]
     pub utxo_source: UtxoDataSource,
+    /// Skip the `estimatefuel` check on single-transaction builds. By default
+    /// the built tx is simulated before it is returned for signing; when its
+    /// vfsize share of the block's fuel would not cover what its protostones
+    /// use, it is padded to a size that does, or split into wrap → execute
+    /// when no size does. Failures to reach the view never block a build.
+    #[serde(default)]
+    pub skip_fuel_estimate: bool,
+    /// Extra vbytes of `Nop` fields appended to the runestone so the tx's
+    /// fuel allocation covers its protostones. Set by the fuel check; callers
+    /// normally leave it 0.
+    #[serde(default)]
+    pub fuel_padding_vbytes: u64,
 }
 
 /// UTXO data source for EnhancedExecuteParams.
@@ -0,0 +1,312 @@
+//! Comparing a locally built contract against the bytecode deployed at an
+//! `AlkaneId`, and the manifest recording a verification.
+//!
+//! Both sides are normalized before comparing: custom sections (`name`,
+//! `producers`, `target_features`, DWARF, source maps) carry toolchain and
+//! host details but never affect execution, so they are dropped. Everything
+//! else has to match byte for byte. When it does not, [`diff_sections`]
+//! reports which sections differ so a mismatch can be traced to code, data
+//! or the import/export surface.
+
+use crate::{AlkanesError, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+#[cfg(not(feature = "std"))]
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+#[cfg(feature = "std")]
+use std::vec::Vec;
+
+const WASM_MAGIC: &[u8; 4] = b"\0asm";
+const GZIP_MAGIC: &[u8; 2] = &[0x1f, 0x8b];
+
+/// One top-level section of a WASM module.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WasmSection<'a> {
+    pub id: u8,
+    /// Custom section name; empty for standard sections
+    pub custom_name: String,
+    /// The whole section, header included
+    pub bytes: &'a [u8],
+}
+
+impl WasmSection<'_> {
+    /// `code`, `data`, ... or `custom:<name>`
+    pub fn label(&self) -> String {
+        let name = match self.id {
+            0 => return format!("custom:{}", self.custom_name),
+            1 => "type",
+            2 => "import",
+            3 => "function",
+            4 => "table",
+            5 => "memory",
+            6 => "global",
+            7 => "export",
+            8 => "start",
+            9 => "element",
+            10 => "code",
+            11 => "data",
+            12 => "datacount",
+            13 => "tag",
+            _ => return format!("unknown({})", self.id),
+        };
+        name.to_string()
+    }
+}
+
+fn read_leb128_u32(bytes: &[u8], pos: &mut usize) -> Result<u32> {
+    let mut result: u32 = 0;
+    let mut shift = 0;
+    loop {
+        let byte = *bytes
+            .get(*pos)
+            .ok_or_else(|| AlkanesError::Parse("truncated LEB128 in wasm".to_string()))?;
+        *pos += 1;
+        if shift >= 32 {
+            return Err(AlkanesError::Parse(
+                "LEB128 overflows u32 in wasm".to_string(),
+            ));
+        }
+        result |= ((byte & 0x7f) as u32) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(result);
+        }
+        shift += 7;
+    }
+}
+
+/// Split a module into its sections, in file order.
+pub fn wasm_sections(wasm: &[u8]) -> Result<Vec<WasmSection<'_>>> {
+    if wasm.len() < 8 || &wasm[..4] != WASM_MAGIC {
+        return Err(AlkanesError::Parse("not a wasm module".to_string()));
+    }
+    let mut sections = Vec::new();
+    let mut pos = 8;
+    while pos < wasm.len() {
+        let start = pos;
+        let id = wasm[pos];
+        pos += 1;
+        let size = read_leb128_u32(wasm, &mut pos)? as usize;
+        let end = pos
+            .checked_add(size)
+            .filter(|end| *end <= wasm.len())
+            .ok_or_else(|| AlkanesError::Parse(format!("section {} runs past end of wasm", id)))?;
+        let custom_name = if id == 0 {
+            let mut name_pos = pos;
+            let len = read_leb128_u32(wasm, &mut name_pos)? as usize;
+            let name = wasm
+                .get(name_pos..name_pos + len)
+                .filter(|_| name_pos + len <= end)
+                .ok_or_else(|| {
+                    AlkanesError::Parse("custom section name runs past section".to_string())
+                })?;
+            String::from_utf8_lossy(name).to_string()
+        } else {
+            String::new()
+        };
+        sections.push(WasmSection {
+            id,
+            custom_name,
+            bytes: &wasm[start..end],
+        });
+        pos = end;
+    }
+    Ok(sections)
+}
+
+/// The module with every custom section removed.
+pub fn normalize_wasm(wasm: &[u8]) -> Result<Vec<u8>> {
+    let sections = wasm_sections(wasm)?;
+    let mut out = wasm[..8].to_vec();
+    for section in sections.iter().filter(|s| s.id != 0) {
+        out.extend_from_slice(section.bytes);
+    }
+    Ok(out)
+}
+
+/// Bytecode as returned by `getbytecode`, gunzipped if it is still
+/// compressed.
+pub fn deployed_wasm(bytecode: Vec<u8>) -> Result<Vec<u8>> {
+    if bytecode.starts_with(GZIP_MAGIC) {
+        return alkanes_support::gz::decompress(bytecode)
+            .map_err(|e| AlkanesError::Parse(format!("failed to gunzip bytecode: {}", e)));
+    }
+    Ok(bytecode)
+}
+
+pub fn sha256_hex(bytes: &[u8]) -> String {
+    hex::encode(Sha256::digest(bytes))
+}
+
+/// How one section compares between the local build and the deployment.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SectionStatus {
+    Same,
+    Differs,
+    OnlyLocal,
+    OnlyDeployed,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SectionDiff {
+    pub section: String,
+    pub status: SectionStatus,
+    pub local_size: Option<usize>,
+    pub deployed_size: Option<usize>,
+}
+
+/// Compare two (normalized) modules section by section. Sections are
+/// paired by label, so a reordered module still lines up.
+pub fn diff_sections(local: &[u8], deployed: &[u8]) -> Result<Vec<SectionDiff>> {
+    let local = wasm_sections(local)?;
+    let deployed = wasm_sections(deployed)?;
+    let mut diffs = Vec::new();
+    for l in &local {
+        let label = l.label();
+        let d = deployed.iter().find(|d| d.label() == label);
+        diffs.push(SectionDiff {
+            status: match d {
+                Some(d) if d.bytes == l.bytes => SectionStatus::Same,
+                Some(_) => SectionStatus::Differs,
+                None => SectionStatus::OnlyLocal,
+            },
+            local_size: Some(l.bytes.len()),
+            deployed_size: d.map(|d| d.bytes.len()),
+            section: label,
+        });
+    }
+    for d in &deployed {
+        let label = d.label();
+        if !local.iter().any(|l| l.label() == label) {
+            diffs.push(SectionDiff {
+                section: label,
+                status: SectionStatus::OnlyDeployed,
+                local_size: None,
+                deployed_size: Some(d.bytes.len()),
+            });
+        }
+    }
+    Ok(diffs)
+}
+
+/// Record of a verification run: how the local wasm was built and how it
+/// compared. `alkanes-data-api` serves these next to alkane details, reading
+/// them from `<block>_<tx>.json` (see [`VerificationManifest::file_name`]).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VerificationManifest {
+    /// `block:tx`
+    pub alkane_id: String,
+    pub crate_name: String,
+    /// Git commit of the source tree, if it is a git checkout
+    pub source_commit: Option<String>,
+    pub toolchain: String,
+    pub target: String,
+    pub features: Vec<String>,
+    pub rustflags: String,
+    pub cargo_lock_sha256: Option<String>,
+    /// sha256 of the normalized local build
+    pub wasm_sha256: String,
+    /// sha256 of the normalized deployed bytecode
+    pub deployed_sha256: String,
+    pub matched: bool,
+    /// Sections that did not match; empty when `matched`
+    pub differences: Vec<SectionDiff>,
+    /// Unix seconds
+    pub verified_at: u64,
+}
+
+impl VerificationManifest {
+    /// `<block>_<tx>.json`
+    pub fn file_name(block: u128, tx: u128) -> String {
+        format!("{}_{}.json", block, tx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn section(id: u8, payload: &[u8]) -> Vec<u8> {
+        let mut out = vec![id, payload.len() as u8];
+        out.extend_from_slice(payload);
+        out
+    }
+
+    fn custom(name: &str, payload: &[u8]) -> Vec<u8> {
+        let mut body = vec![name.len() as u8];
+        body.extend_from_slice(name.as_bytes());
+        body.extend_from_slice(payload);
+        section(0, &body)
+    }
+
+    fn module(sections: &[Vec<u8>]) -> Vec<u8> {
+        let mut out = b"\0asm\x01\0\0\0".to_vec();
+        for s in sections {
+            out.extend_from_slice(s);
+        }
+        out
+    }
+
+    #[test]
+    fn normalize_drops_custom_sections_only() {
+        let code = section(10, &[1, 2, 3]);
+        let local = module(&[
+            section(1, &[9]),
+            custom("name", b"local-paths"),
+            code.clone(),
+        ]);
+        let deployed = module(&[section(1, &[9]), code, custom("producers", b"rustc 1.86.0")]);
+        assert_ne!(local, deployed);
+        assert_eq!(
+            normalize_wasm(&local).unwrap(),
+            normalize_wasm(&deployed).unwrap()
+        );
+        let labels: Vec<String> = wasm_sections(&local)
+            .unwrap()
+            .iter()
+            .map(|s| s.label())
+            .collect();
+        assert_eq!(labels, vec!["type", "custom:name", "code"]);
+    }
+
+    #[test]
+    fn diff_reports_per_section_status() {
+        let local = module(&[section(1, &[9]), section(10, &[1, 2, 3]), section(11, &[7])]);
+        let deployed = module(&[section(1, &[9]), section(10, &[1, 2, 4]), section(7, &[0])]);
+        let diffs = diff_sections(&local, &deployed).unwrap();
+        let summary: Vec<(&str, SectionStatus)> = diffs
+            .iter()
+            .map(|d| (d.section.as_str(), d.status.clone()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("type", SectionStatus::Same),
+                ("code", SectionStatus::Differs),
+                ("data", SectionStatus::OnlyLocal),
+                ("export", SectionStatus::OnlyDeployed),
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_truncated_modules() {
+        assert!(wasm_sections(b"not wasm").is_err());
+        let mut truncated = module(&[section(10, &[1, 2, 3])]);
+        truncated.pop();
+        assert!(wasm_sections(&truncated).is_err());
+    }
+
+    #[test]
+    fn deployed_wasm_gunzips_compressed_bytecode() {
+        let wasm = module(&[section(10, &[1, 2, 3])]);
+        let gz = alkanes_support::gz::compress(wasm.clone()).unwrap();
+        assert_eq!(deployed_wasm(gz).unwrap(), wasm);
+        assert_eq!(deployed_wasm(wasm.clone()).unwrap(), wasm);
+    }
+}
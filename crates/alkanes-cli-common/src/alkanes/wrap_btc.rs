@@ -110,6 +110,8 @@ impl<'a> WrapBtcExecutor<'a> {
         skip_diesel_mint: false,
             max_indexed_height,
             utxo_source: Default::default(),
+            skip_fuel_estimate: false,
+            fuel_padding_vbytes: 0,
         };
 
         // Execute using the enhanced alkanes executor
@@ -280,6 +280,13 @@ pub fn convert_trace_event(event: alkanes_pb::AlkanesTraceEvent) -> TraceEvent {
                 redirect_to: vt.redirect_to,
             }
         }
+        Some(alkanes_pb::alkanes_trace_event::Event::EmitEvent(e)) => {
+            TraceEvent::EmitEvent {
+                alkane: e.alkane.map_or(Default::default(), convert_alkane_id),
+                topic: e.topic,
+                data: e.data,
+            }
+        }
         None => panic!("unknown trace event"),
     }
 }
@@ -2910,6 +2910,8 @@ impl alkanes_cli_common::SystemAlkanes for SystemAlkanes {
                     skip_diesel_mint: false,
                     max_indexed_height,
                     utxo_source: alkanes_cli_common::alkanes::types::UtxoDataSource::default(),
+                    skip_fuel_estimate: false,
+                    fuel_padding_vbytes: 0,
                 };
 
                 let mut current_state = provider.execute(execute_params.clone()).await?;
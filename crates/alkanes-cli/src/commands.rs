@@ -1128,6 +1128,10 @@ pub enum Alkanes {
         /// Show the contract code hash
         #[arg(long)]
         codehash: bool,
+        /// Follow upgradeable and beacon proxies, show their upgrade history,
+        /// and inspect the implementation that actually runs
+        #[arg(long)]
+        resolve: bool,
         /// Show raw JSON output
         #[arg(long)]
         raw: bool,
@@ -1139,6 +1143,9 @@ pub enum Alkanes {
         /// Show raw JSON output
         #[arg(long)]
         raw: bool,
+        /// Also show the storage, balance and output changes the message made
+        #[arg(long)]
+        diff: bool,
     },
     /// Simulate an alkanes transaction
     Simulate {
@@ -1175,8 +1182,35 @@ pub enum Alkanes {
         #[arg(long, conflicts_with = "format")]
         raw: bool,
         /// Format the output data as a specific type (number, u128be, u64be, u32be, u16be, u8be, string)
-        #[arg(long, conflicts_with = "raw")]
+        #[arg(long, conflicts_with_all = ["raw", "profile"])]
         format: Option<String>,
+        /// Profile fuel usage and print a per-frame fuel tree (uses the "simulateprofile" view)
+        #[arg(long)]
+        profile: bool,
+    },
+    /// Call a contract method by name, encoding arguments and decoding the
+    /// return value from the ABI the contract exports through __meta
+    Call {
+        /// The alkane ID to call (format: block:tx)
+        alkane_id: String,
+        /// Method name from the ABI (e.g. get_name), or its opcode
+        method: String,
+        /// Method argument as name=value; repeat for each parameter.
+        /// AlkaneId values are block:tx, Vec values a JSON array or comma-separated list
+        #[arg(long = "arg", value_name = "NAME=VALUE")]
+        args: Vec<String>,
+        /// Input alkanes as comma-separated triplets (e.g., 2:1:1,2:2:100)
+        #[arg(long)]
+        inputs: Option<String>,
+        /// Block height for simulation (defaults to current metashrew_height)
+        #[arg(long)]
+        height: Option<u64>,
+        /// Block tag to query (e.g., "latest" or a block height)
+        #[arg(long)]
+        block_tag: Option<String>,
+        /// Show raw JSON output
+        #[arg(long)]
+        raw: bool,
     },
     /// Execute a tx-script with WASM bytecode
     TxScript {
@@ -1219,6 +1253,18 @@ pub enum Alkanes {
         #[arg(long)]
         raw: bool,
     },
+    /// Show the indexing workload of a range of blocks: protostones,
+    /// messages, reverts by class, fuel, storage bytes and extcall depth
+    BlockStats {
+        /// A block height or an inclusive range, e.g. 880000..880100
+        range: String,
+        /// Print one CSV row per block instead of a table
+        #[arg(long)]
+        csv: bool,
+        /// Show raw JSON output
+        #[arg(long)]
+        raw: bool,
+    },
     /// Get the bytecode for an alkane
     #[command(name = "getbytecode")]
     GetBytecode {
@@ -1231,6 +1277,54 @@ pub enum Alkanes {
         #[arg(long)]
         raw: bool,
     },
+    /// Rebuild a contract crate deterministically and compare it with the
+    /// bytecode deployed at an alkane ID
+    Verify {
+        /// The alkane ID to verify (format: block:tx)
+        alkane_id: String,
+        /// Path to the contract crate (e.g. crates/alkanes-std-owned-token)
+        #[arg(long)]
+        source: String,
+        /// Rust toolchain to build with (defaults to the workspace's rust-toolchain.toml)
+        #[arg(long)]
+        toolchain: Option<String>,
+        /// Cargo features to enable, comma-separated
+        #[arg(long, value_delimiter = ',')]
+        features: Vec<String>,
+        /// Directory to write the verification manifest (<block>_<tx>.json) to
+        #[arg(long, default_value = ".")]
+        manifest_dir: String,
+        /// Block tag to query (e.g., "latest" or a block height)
+        #[arg(long)]
+        block_tag: Option<String>,
+        /// Show raw JSON output
+        #[arg(long)]
+        raw: bool,
+    },
+    /// List the storage keys an alkane has written, with values and last-modified heights
+    #[command(name = "getstoragekeys")]
+    GetStorageKeys {
+        /// The alkane ID to list storage for (block:tx)
+        alkane_id: String,
+        /// Only list keys starting with this prefix (hex when 0x-prefixed, otherwise UTF-8)
+        #[arg(long)]
+        prefix: Option<String>,
+        /// Cursor returned by a previous page
+        #[arg(long, default_value_t = 0)]
+        cursor: u64,
+        /// Maximum number of keys to return (0 = indexer default)
+        #[arg(long, default_value_t = 0)]
+        limit: u32,
+        /// Follow cursors until every key has been listed
+        #[arg(long)]
+        all: bool,
+        /// Block tag to query (e.g., "latest" or a block height)
+        #[arg(long)]
+        block_tag: Option<String>,
+        /// Show raw JSON output
+        #[arg(long)]
+        raw: bool,
+    },
     /// Get the metadata (ABI) for an alkane
     Meta {
         /// The alkane ID to get the metadata for
@@ -1247,6 +1341,9 @@ pub enum Alkanes {
     GetBalance {
         /// The address to get the balance for
         address: Option<String>,
+        /// Also show how much of each alkane is locked in contracts, and which
+        #[arg(long)]
+        contract_locked: bool,
         /// Show raw JSON output
         #[arg(long)]
         raw: bool,
@@ -1490,6 +1587,14 @@ pub enum Alkanes {
         /// Print raw JSON instead of the pretty summary
         #[arg(long)]
         raw: bool,
+        /// Replace an outpoint's alkane balances before simulating, as
+        /// `txid:vout=block:tx:amount[,block:tx:amount...]`; an empty list
+        /// after `=` empties the outpoint (can be specified multiple times)
+        #[arg(long = "balance-override", value_name = "OVERRIDE")]
+        balance_overrides: Vec<String>,
+        /// Include a per-protostone fuel profile
+        #[arg(long)]
+        profile: bool,
     },
     /// Call metashrew_view "simulateprotostones" (RC8)
     #[command(name = "simulateprotostones")]
@@ -1514,6 +1619,9 @@ pub enum Alkanes {
         /// Print raw JSON instead of the pretty summary
         #[arg(long)]
         raw: bool,
+        /// Include a per-protostone fuel profile
+        #[arg(long)]
+        profile: bool,
     },
     /// Call metashrew_view "simulateblock" with a full consensus-encoded block (RC8)
     #[command(name = "simulateblock")]
@@ -1530,6 +1638,25 @@ pub enum Alkanes {
         #[arg(long)]
         raw: bool,
     },
+    /// Call metashrew_view "simulatechain" with pending txs that spend each other's outputs
+    #[command(name = "simulatechain")]
+    SimulateChain {
+        /// Raw transaction OR PSBT hex per tx, in mining order (0x prefix optional)
+        #[arg(required = true)]
+        transactions: Vec<String>,
+        /// Height of the first faux block (defaults to current metashrew_height)
+        #[arg(long)]
+        height: Option<u64>,
+        /// Block tag for the metashrew_view call (default: latest)
+        #[arg(long)]
+        block_tag: Option<String>,
+        /// Print raw JSON instead of the pretty summary
+        #[arg(long)]
+        raw: bool,
+        /// Include a per-protostone fuel profile
+        #[arg(long)]
+        profile: bool,
+    },
 }
 
 /// DataAPI subcommands
@@ -2686,6 +2813,10 @@ pub struct AlkanesExecute {
     /// Automatically confirm the transaction preview
     #[arg(long, short = 'y')]
     pub auto_confirm: bool,
+    /// Don't simulate the transaction to pad or split it when its fuel
+    /// allocation would not cover its protostones
+    #[arg(long)]
+    pub skip_fuel_estimate: bool,
 }
 
 impl From<WalletCommands> for alkanes_cli_common::commands::WalletCommands {
@@ -15,6 +15,7 @@ mod commands;
 mod pretty_print;
 mod format_parser;
 mod wc_signer;
+mod verify;
 use commands::{Alkanes, AlkanesExecute, Commands, DeezelCommands, MetashrewCommands, Protorunes, Runestone, WalletCommands, DataApiCommand, SubfrostCommands, OpiCommands, WcCommands};
 use alkanes_cli_common::alkanes;
 use pretty_print::*;
@@ -1467,7 +1468,7 @@ async fn execute_alkanes_command<T: System>(system: &mut T, command: Alkanes, fr
             }
             Ok(())
         },
-        Alkanes::Inspect { outpoint, disasm, fuzz, fuzz_ranges, meta, codehash, raw } => {
+        Alkanes::Inspect { outpoint, disasm, fuzz, fuzz_ranges, meta, codehash, resolve, raw } => {
             let config = alkanes::types::AlkanesInspectConfig {
                 disasm,
                 fuzz,
@@ -1476,15 +1477,39 @@ async fn execute_alkanes_command<T: System>(system: &mut T, command: Alkanes, fr
                 codehash,
                 raw,
             };
-            let result = system.provider().inspect(&outpoint, config).await?;
+            let resolution = if resolve {
+                let parts: Vec<&str> = outpoint.split(':').collect();
+                if parts.len() != 2 {
+                    return Err(anyhow::anyhow!("invalid alkane id '{}': expected block:tx", outpoint));
+                }
+                let alkane = alkanes_cli_common::alkanes::simulate_view::AlkaneId {
+                    block: parts[0].parse()?,
+                    tx: parts[1].parse()?,
+                };
+                Some(alkanes_cli_common::alkanes::implementation_view::resolve_implementation(system.provider(), &alkane, None).await?)
+            } else {
+                None
+            };
+            let target = match &resolution {
+                Some(r) => format!("{}:{}", r.implementation.block, r.implementation.tx),
+                None => outpoint.clone(),
+            };
+            let result = system.provider().inspect(&target, config).await?;
             if raw {
-                println!("{}", serde_json::to_string_pretty(&result)?);
+                let mut json = serde_json::to_value(&result)?;
+                if let Some(resolution) = &resolution {
+                    json["resolution"] = serde_json::to_value(resolution)?;
+                }
+                println!("{}", serde_json::to_string_pretty(&json)?);
             } else {
+                if let Some(resolution) = &resolution {
+                    pretty_print::print_resolution(&outpoint, resolution);
+                }
                 pretty_print::print_inspection_result(&result);
             }
             Ok(())
         },
-        Alkanes::Trace { outpoint, raw } => {
+        Alkanes::Trace { outpoint, raw, diff } => {
             let result = system.provider().trace(&outpoint).await;
             match result {
                 Ok(trace_pb) => {
@@ -1495,11 +1520,19 @@ async fn execute_alkanes_command<T: System>(system: &mut T, command: Alkanes, fr
                         let trace = alkanes_support::trace::Trace::try_from(
                             prost::Message::encode_to_vec(&alkanes_trace)
                         )?;
+                        let state_diff = if diff {
+                            Some(alkanes_cli_common::alkanes::trace::trace_diff(system.provider(), &outpoint).await?)
+                        } else {
+                            None
+                        };
                         if raw {
-                            let json = alkanes_cli_common::alkanes::trace::trace_to_json(&trace);
+                            let mut json = alkanes_cli_common::alkanes::trace::trace_to_json(&trace);
+                            if let Some(state_diff) = &state_diff {
+                                json["diff"] = alkanes_cli_common::alkanes::trace::state_diff_to_json(state_diff);
+                            }
                             println!("{}", serde_json::to_string_pretty(&json)?);
                         } else {
-                            let pretty = alkanes_cli_common::alkanes::trace::format_trace_pretty(&trace);
+                            let pretty = alkanes_cli_common::alkanes::trace::format_trace_pretty_with_diff(&trace, state_diff.as_ref());
                             println!("{}", pretty);
                         }
                     } else {
@@ -1524,7 +1557,8 @@ async fn execute_alkanes_command<T: System>(system: &mut T, command: Alkanes, fr
             refund,
             block_tag,
             raw,
-            format
+            format,
+            profile
         } => {
             use alkanes_cli_common::proto::alkanes::{MessageContextParcel, AlkaneTransfer, AlkaneId, Uint128};
             use alkanes_cli_common::traits::MetashrewRpcProvider;
@@ -1669,6 +1703,27 @@ async fn execute_alkanes_command<T: System>(system: &mut T, command: Alkanes, fr
             log::debug!("Context: height={}, txindex={}, {} input alkanes",
                 simulation_height, txindex, context.alkanes.len());
             
+            if profile {
+                use alkanes_cli_common::alkanes::fuel_profile;
+                let result = fuel_profile::simulate_profile(system.provider(), &context, block_tag.as_deref()).await?;
+                if raw {
+                    println!("{}", serde_json::to_string_pretty(&result)?);
+                    return Ok(());
+                }
+                println!("Gas used: {}", result.gas_used);
+                if !result.error.is_empty() {
+                    println!("Error: {}", result.error);
+                }
+                match &result.profile {
+                    Some(frame) => {
+                        println!();
+                        print!("{}", fuel_profile::format_fuel_profile(frame));
+                    }
+                    None => println!("No fuel profile returned (is the indexer older than the simulateprofile view?)"),
+                }
+                return Ok(());
+            }
+
             // Run simulation
             let contract_id_str = format!("{}:{}", target_block, target_tx);
             let result = system.provider().simulate(&contract_id_str, &context, block_tag).await?;
@@ -1855,6 +1910,23 @@ async fn execute_alkanes_command<T: System>(system: &mut T, command: Alkanes, fr
             }
             Ok(())
         },
+        Alkanes::BlockStats { range, csv, raw } => {
+            use alkanes_cli_common::alkanes::block_stats_view as bs;
+            let (start, end) = bs::parse_height_range(&range)?;
+            let blocks = bs::get_block_stats(system.provider(), start, end, None).await?;
+            let totals = bs::BlockStatsTotals::aggregate(&blocks);
+            if raw {
+                println!("{}", serde_json::to_string_pretty(&serde_json::json!({
+                    "blocks": blocks,
+                    "totals": totals,
+                }))?);
+            } else if csv {
+                print!("{}", bs::to_csv(&blocks));
+            } else {
+                pretty_print::print_block_stats(&blocks, &totals);
+            }
+            Ok(())
+        },
         Alkanes::TraceBlock { height, raw } => {
             let result = system.provider().trace_block(height).await?;
             // The result is AlkanesBlockTraceEvent which contains repeated AlkanesBlockEvent
@@ -1907,7 +1979,86 @@ async fn execute_alkanes_command<T: System>(system: &mut T, command: Alkanes, fr
             }
             Ok(())
         },
-        Alkanes::SimulateTransaction { transaction, height, block_tag, raw } => {
+        Alkanes::Call { alkane_id, method, args, inputs, height, block_tag, raw } => {
+            use alkanes_cli_common::alkanes::abi::{parse_named_arg, ContractAbi};
+            use alkanes_cli_common::proto::alkanes::{MessageContextParcel, AlkaneTransfer, AlkaneId, SimulateResponse, Uint128};
+            use alkanes_support::cellpack::Cellpack;
+            use prost::Message;
+
+            let (block, tx) = alkane_id.split_once(':')
+                .ok_or_else(|| anyhow::anyhow!("Invalid alkane_id format. Expected block:tx"))?;
+            let target = alkanes_support::id::AlkaneId { block: block.parse()?, tx: tx.parse()? };
+
+            let meta = AlkanesProvider::meta(system.provider(), &alkane_id, block_tag.clone()).await?;
+            let abi = ContractAbi::from_meta(&meta)?;
+            let abi_method = abi.method(&method)?;
+            let named_args = args.iter()
+                .map(|a| parse_named_arg(a.as_str()))
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+            let cellpack = Cellpack { target, inputs: abi_method.encode_call(&named_args)? };
+
+            let uint128 = |v: u128| Uint128 { lo: v as u64, hi: (v >> 64) as u64 };
+            let mut alkane_transfers = Vec::new();
+            for input in inputs.iter().flat_map(|s| s.split(',')) {
+                let parts: Vec<&str> = input.trim().split(':').collect();
+                if parts.len() != 3 {
+                    return Err(anyhow::anyhow!("Invalid input format '{}'. Expected block:tx:amount", input));
+                }
+                alkane_transfers.push(AlkaneTransfer {
+                    id: Some(AlkaneId { block: Some(uint128(parts[0].parse()?)), tx: Some(uint128(parts[1].parse()?)) }),
+                    value: Some(uint128(parts[2].parse()?)),
+                });
+            }
+
+            let simulation_height = match height {
+                Some(h) => h,
+                None => system.provider().get_metashrew_height().await?,
+            };
+            let context = MessageContextParcel {
+                alkanes: alkane_transfers,
+                height: simulation_height,
+                txindex: 1,
+                calldata: cellpack.encipher(),
+                ..Default::default()
+            };
+            log::debug!("Calling {} on {} with inputs {:?}", abi_method.signature(), alkane_id, cellpack.inputs);
+
+            let result = system.provider().simulate(&alkane_id, &context, block_tag).await?;
+            let hex_str = result.as_str()
+                .ok_or_else(|| anyhow::anyhow!("Unexpected simulate result: {}", result))?;
+            let bytes = hex::decode(hex_str.strip_prefix("0x").unwrap_or(hex_str))?;
+            let response = SimulateResponse::decode(bytes.as_slice())?;
+            let data = response.execution.as_ref().map(|e| e.data.clone()).unwrap_or_default();
+            let decoded = abi_method.decode_return(&data);
+
+            if raw {
+                println!("{}", serde_json::to_string_pretty(&json!({
+                    "contract": abi.contract,
+                    "method": abi_method.name,
+                    "opcode": abi_method.opcode.to_string(),
+                    "inputs": cellpack.inputs.iter().map(|v| v.to_string()).collect::<Vec<_>>(),
+                    "gas_used": response.gas_used,
+                    "error": response.error,
+                    "returns": abi_method.returns.to_string(),
+                    "data": format!("0x{}", hex::encode(&data)),
+                    "result": decoded,
+                }))?);
+            } else {
+                println!("📞 {}::{}", abi.contract, abi_method.signature());
+                println!("⛽ Gas used: {}", response.gas_used);
+                if !response.error.is_empty() {
+                    println!("❌ Error: {}", response.error);
+                } else {
+                    match &decoded {
+                        serde_json::Value::String(s) => println!("✅ Result: {}", s),
+                        serde_json::Value::Null => println!("✅ Result: (void)"),
+                        other => println!("✅ Result: {}", serde_json::to_string_pretty(other)?),
+                    }
+                }
+            }
+            Ok(())
+        },
+        Alkanes::SimulateTransaction { transaction, height, block_tag, raw, balance_overrides, profile } => {
             use alkanes_cli_common::alkanes::simulate_view as sv;
             use alkanes_cli_common::traits::MetashrewRpcProvider;
             let tx_hex = transaction.strip_prefix("0x").unwrap_or(&transaction);
@@ -1921,12 +2072,17 @@ async fn execute_alkanes_command<T: System>(system: &mut T, command: Alkanes, fr
                 height: height_resolved,
                 transaction: tx_bytes,
                 storage_overrides: vec![],
+                balance_overrides: balance_overrides
+                    .iter()
+                    .map(|o| parse_balance_override(o))
+                    .collect::<Result<Vec<_>>>()?,
+                profile,
             };
             let resp = sv::simulate_transaction(system.provider(), &input, block_tag.as_deref()).await?;
             print_simulate_transaction_response(&resp, raw)?;
             Ok(())
         },
-        Alkanes::SimulateProtostones { protostones, inputs, height, transaction, block, block_tag, raw } => {
+        Alkanes::SimulateProtostones { protostones, inputs, height, transaction, block, block_tag, raw, profile } => {
             use alkanes_cli_common::alkanes::simulate_view as sv;
             use alkanes_cli_common::traits::MetashrewRpcProvider;
             let ps_hex = protostones.strip_prefix("0x").unwrap_or(&protostones);
@@ -1967,6 +2123,7 @@ async fn execute_alkanes_command<T: System>(system: &mut T, command: Alkanes, fr
                 transaction: tx_bytes,
                 block: block_bytes,
                 storage_overrides: vec![],
+                profile,
             };
             let resp = sv::simulate_protostones(system.provider(), &input, block_tag.as_deref()).await?;
             print_simulate_transaction_response(&resp, raw)?;
@@ -1986,6 +2143,8 @@ async fn execute_alkanes_command<T: System>(system: &mut T, command: Alkanes, fr
                 height: height_resolved,
                 block: block_bytes,
                 storage_overrides: vec![],
+                balance_overrides: vec![],
+                profile: false,
             };
             let resp = sv::simulate_block(system.provider(), &input, block_tag.as_deref()).await?;
             if raw {
@@ -2008,6 +2167,47 @@ async fn execute_alkanes_command<T: System>(system: &mut T, command: Alkanes, fr
             }
             Ok(())
         },
+        Alkanes::SimulateChain { transactions, height, block_tag, raw, profile } => {
+            use alkanes_cli_common::alkanes::simulate_view as sv;
+            use alkanes_cli_common::traits::MetashrewRpcProvider;
+            let mut tx_bytes = Vec::with_capacity(transactions.len());
+            for (i, tx) in transactions.iter().enumerate() {
+                let tx_hex = tx.strip_prefix("0x").unwrap_or(tx);
+                tx_bytes.push(
+                    hex::decode(tx_hex)
+                        .map_err(|e| anyhow::anyhow!("invalid hex for tx[{}]: {}", i, e))?,
+                );
+            }
+            let height_resolved = match height {
+                Some(h) => h,
+                None => system.provider().get_metashrew_height().await?,
+            };
+            let input = sv::SimulateChainInput {
+                height: height_resolved,
+                transactions: tx_bytes,
+                storage_overrides: vec![],
+                balance_overrides: vec![],
+                profile,
+            };
+            let resp = sv::simulate_chain(system.provider(), &input, block_tag.as_deref()).await?;
+            if raw {
+                println!("{}", serde_json::to_string_pretty(&resp)?);
+            } else {
+                println!("⛓️  simulatechain");
+                println!("    height:           {}", resp.height);
+                println!("    txs:              {}", resp.txs.len());
+                println!("    total_fuel_used:  {}", resp.total_fuel_used);
+                if !resp.error.is_empty() {
+                    println!("    error:            {}", resp.error);
+                }
+                for (i, tx) in resp.txs.iter().enumerate() {
+                    println!();
+                    println!("  ── tx[{}] ──", i);
+                    print_simulate_transaction_response(tx, false)?;
+                }
+            }
+            Ok(())
+        },
         Alkanes::GetBytecode { alkane_id, block_tag, raw } => {
             let result = AlkanesProvider::get_bytecode(system.provider(), &alkane_id, block_tag).await?;
             if raw {
@@ -2017,6 +2217,121 @@ async fn execute_alkanes_command<T: System>(system: &mut T, command: Alkanes, fr
             }
             Ok(())
         },
+        Alkanes::Verify { alkane_id, source, toolchain, features, manifest_dir, block_tag, raw } => {
+            use alkanes_cli_common::alkanes::verify::{self as v, SectionStatus, VerificationManifest};
+
+            let (block, tx) = alkane_id.split_once(':')
+                .ok_or_else(|| anyhow::anyhow!("Invalid alkane_id format. Expected block:tx"))?;
+            let (block, tx): (u128, u128) = (block.parse()?, tx.parse()?);
+
+            let bytecode_hex = AlkanesProvider::get_bytecode(system.provider(), &alkane_id, block_tag).await?;
+            let bytecode = hex::decode(bytecode_hex.trim_start_matches("0x"))?;
+            let deployed = v::normalize_wasm(&v::deployed_wasm(bytecode)?)?;
+
+            let build = verify::build_contract(std::path::Path::new(&source), toolchain.as_deref(), &features)?;
+            let local = v::normalize_wasm(&build.wasm)?;
+
+            let matched = local == deployed;
+            let differences = if matched {
+                vec![]
+            } else {
+                v::diff_sections(&local, &deployed)?
+                    .into_iter()
+                    .filter(|d| d.status != SectionStatus::Same)
+                    .collect()
+            };
+            let manifest = VerificationManifest {
+                alkane_id: alkane_id.clone(),
+                crate_name: build.crate_name,
+                source_commit: build.source_commit,
+                toolchain: build.toolchain,
+                target: verify::WASM_TARGET.to_string(),
+                features: build.features,
+                rustflags: build.rustflags,
+                cargo_lock_sha256: build.cargo_lock_sha256,
+                wasm_sha256: v::sha256_hex(&local),
+                deployed_sha256: v::sha256_hex(&deployed),
+                matched,
+                differences,
+                verified_at: std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)?
+                    .as_secs(),
+            };
+            let manifest_path = std::path::Path::new(&manifest_dir).join(VerificationManifest::file_name(block, tx));
+            std::fs::create_dir_all(&manifest_dir)?;
+            std::fs::write(&manifest_path, serde_json::to_string_pretty(&manifest)?)
+                .with_context(|| format!("failed to write {}", manifest_path.display()))?;
+
+            if raw {
+                println!("{}", serde_json::to_string_pretty(&manifest)?);
+            } else {
+                println!("🔎 Verifying {} against {}", alkane_id, manifest.crate_name);
+                println!("   Toolchain: {} ({})", manifest.toolchain, manifest.target);
+                println!("   Local:     sha256 {}", manifest.wasm_sha256);
+                println!("   Deployed:  sha256 {}", manifest.deployed_sha256);
+                if matched {
+                    println!("✅ Bytecode matches (custom sections ignored)");
+                } else {
+                    println!("❌ Bytecode differs:");
+                    for d in &manifest.differences {
+                        let size = |s: Option<usize>| s.map(|n| format!("{} B", n)).unwrap_or_else(|| "-".to_string());
+                        println!("   {:<20} {:<14} local {:>10}  deployed {:>10}",
+                            d.section, format!("{:?}", d.status), size(d.local_size), size(d.deployed_size));
+                    }
+                }
+                println!("📝 Manifest written to {}", manifest_path.display());
+            }
+            if matched { Ok(()) } else { Err(anyhow::anyhow!("{} does not match {}", alkane_id, source)) }
+        },
+        Alkanes::GetStorageKeys { alkane_id, prefix, cursor, limit, all, block_tag, raw } => {
+            use alkanes_cli_common::alkanes::storage_keys_view as skv;
+            let parts: Vec<&str> = alkane_id.split(':').collect();
+            if parts.len() != 2 {
+                return Err(anyhow::anyhow!("invalid alkane id '{}': expected block:tx", alkane_id));
+            }
+            let prefix_bytes = match prefix {
+                Some(p) => match p.strip_prefix("0x") {
+                    Some(h) => hex::decode(h).map_err(|e| anyhow::anyhow!("invalid --prefix hex: {}", e))?,
+                    None => p.into_bytes(),
+                },
+                None => vec![],
+            };
+            let input = skv::StorageKeysInput {
+                alkane: alkanes_cli_common::alkanes::simulate_view::AlkaneId {
+                    block: parts[0].parse()?,
+                    tx: parts[1].parse()?,
+                },
+                prefix: prefix_bytes,
+                cursor,
+                limit,
+            };
+            let page = if all {
+                skv::StorageKeysPage {
+                    entries: skv::get_all_storage_keys(system.provider(), &input, block_tag.as_deref()).await?,
+                    next_cursor: 0,
+                }
+            } else {
+                skv::get_storage_keys(system.provider(), &input, block_tag.as_deref()).await?
+            };
+            if raw {
+                println!("{}", serde_json::to_string_pretty(&page)?);
+            } else {
+                println!("🗄️  Storage of {alkane_id} ({} keys)", page.entries.len());
+                for entry in &page.entries {
+                    let key = match String::from_utf8(entry.key.clone()) {
+                        Ok(s) if s.chars().all(|c| !c.is_control()) => s,
+                        _ => format!("0x{}", hex::encode(&entry.key)),
+                    };
+                    println!("  {key}");
+                    println!("    value:         0x{}", hex::encode(&entry.value));
+                    println!("    last modified: {}", entry.last_modified_height);
+                }
+                if page.next_cursor != 0 {
+                    println!("\nMore keys available: --cursor {}", page.next_cursor);
+                }
+            }
+            Ok(())
+        },
         Alkanes::Meta { alkane_id, block_tag, raw } => {
             let meta_bytes = AlkanesProvider::meta(system.provider(), &alkane_id, block_tag).await?;
 
@@ -2055,17 +2370,47 @@ async fn execute_alkanes_command<T: System>(system: &mut T, command: Alkanes, fr
             }
             Ok(())
         },
-        Alkanes::GetBalance { address, raw } => {
+        Alkanes::GetBalance { address, contract_locked, raw } => {
+            use alkanes_cli_common::alkanes::holders_view as hv;
             let resolved_address = if let Some(addr) = &address {
                 Some(system.provider().resolve_all_identifiers(addr).await?)
             } else {
                 None
             };
             let result = AlkanesProvider::get_balance(system.provider(), resolved_address.as_deref()).await?;
-            if raw {
-                println!("{}", serde_json::to_string_pretty(&result)?);
+            if !contract_locked {
+                if raw {
+                    println!("{}", serde_json::to_string_pretty(&result)?);
+                } else {
+                    print_alkane_balances(&result);
+                }
+                return Ok(());
+            }
+            let mut locked = Vec::with_capacity(result.len());
+            for balance in &result {
+                let input = hv::HoldersInput {
+                    alkane: alkanes_cli_common::alkanes::simulate_view::AlkaneId {
+                        block: balance.alkane_id.block.into(),
+                        tx: balance.alkane_id.tx.into(),
+                    },
+                    ..Default::default()
+                };
+                locked.push(hv::get_all_holders(system.provider(), &input, None).await?);
+            }
+            if raw {
+                let entries: Vec<serde_json::Value> = result.iter().zip(&locked)
+                    .map(|(balance, holders)| serde_json::json!({
+                        "balance": balance,
+                        "contract_locked": holders.iter().map(|h| h.balance).sum::<u128>().to_string(),
+                        "holders": holders.iter().map(|h| serde_json::json!({
+                            "holder": format!("{}:{}", h.holder.block, h.holder.tx),
+                            "balance": h.balance.to_string(),
+                        })).collect::<Vec<_>>(),
+                    }))
+                    .collect();
+                println!("{}", serde_json::to_string_pretty(&entries)?);
             } else {
-                print_alkane_balances(&result);
+                print_alkane_balances_with_locked(&result, &locked);
             }
             Ok(())
         }
@@ -3638,6 +3983,8 @@ async fn execute_alkanes_command<T: System>(system: &mut T, command: Alkanes, fr
                 skip_diesel_mint: false,
                 max_indexed_height,
                 utxo_source: alkanes_cli_common::alkanes::types::UtxoDataSource::default(),
+                skip_fuel_estimate: false,
+                fuel_padding_vbytes: 0,
             };
 
             println!("\n📤 Executing swap...");
@@ -4372,6 +4719,8 @@ fn to_enhanced_execute_params(
         skip_diesel_mint: false,
         max_indexed_height,
         utxo_source: alkanes::types::UtxoDataSource::default(),
+        skip_fuel_estimate: args.skip_fuel_estimate,
+        fuel_padding_vbytes: 0,
     })
 }
 
@@ -6660,6 +7009,40 @@ async fn execute_espo_command(
     Ok(())
 }
 
+/// Parse a `--balance-override` value, `txid:vout=block:tx:amount[,...]`.
+/// The txid is in the usual display order and goes on the wire in
+/// internal byte order.
+fn parse_balance_override(
+    spec: &str,
+) -> Result<alkanes_cli_common::alkanes::simulate_view::BalanceOverride> {
+    use alkanes_cli_common::alkanes::simulate_view as sv;
+    use bitcoin::hashes::Hash;
+    use std::str::FromStr;
+    let (outpoint, balances) = spec.split_once('=').ok_or_else(|| {
+        anyhow::anyhow!("invalid balance override '{}': expected txid:vout=block:tx:amount", spec)
+    })?;
+    let outpoint = bitcoin::OutPoint::from_str(outpoint.trim())
+        .map_err(|e| anyhow::anyhow!("invalid outpoint '{}': {}", outpoint, e))?;
+    let mut transfers = Vec::new();
+    for triplet in balances.split(',').map(str::trim).filter(|t| !t.is_empty()) {
+        let parts: Vec<&str> = triplet.split(':').collect();
+        if parts.len() != 3 {
+            return Err(anyhow::anyhow!("invalid balance '{}': expected block:tx:amount", triplet));
+        }
+        transfers.push(sv::AlkaneTransfer {
+            id: sv::AlkaneId { block: parts[0].parse()?, tx: parts[1].parse()? },
+            value: parts[2].parse()?,
+        });
+    }
+    Ok(sv::BalanceOverride {
+        outpoint: sv::Outpoint {
+            txid: outpoint.txid.to_byte_array().to_vec(),
+            vout: outpoint.vout,
+        },
+        balances: transfers,
+    })
+}
+
 /// Pretty-print an RC8 SimulateTransactionResponse (or print the raw JSON).
 fn print_simulate_transaction_response(
     resp: &alkanes_cli_common::alkanes::simulate_view::SimulateTransactionResponse,
@@ -6697,6 +7080,12 @@ fn print_simulate_transaction_response(
                 }
             }
         }
+        if let Some(frame) = &ps.profile {
+            println!("    fuel profile:");
+            for line in alkanes_cli_common::alkanes::fuel_profile::format_fuel_profile(frame).lines() {
+                println!("      {}", line);
+            }
+        }
     }
     if !resp.final_balances_by_vout.is_empty() {
         println!();
@@ -9,6 +9,7 @@ use alkanes_cli_common::{
     traits::UtxoInfo,
 };
 use alkanes_cli_common::alkanes::AlkaneBalance;
+use alkanes_cli_common::alkanes::holders_view::Holder;
 use alkanes_cli_common::traits::TransactionInfo;
 use termtree::Tree;
 use colored::*;
@@ -44,6 +45,27 @@ pub fn print_alkane_balances(balances: &[AlkaneBalance]) {
     println!("{}", root);
 }
 
+/// `print_alkane_balances`, with each alkane's contract-locked total and the
+/// contracts holding it. `locked[i]` are the holders of `balances[i]`.
+pub fn print_alkane_balances_with_locked(balances: &[AlkaneBalance], locked: &[Vec<Holder>]) {
+    let mut trees = Vec::new();
+    for (balance, holders) in balances.iter().zip(locked) {
+        let mut balance_tree = Tree::new(format!("{} {}:{}", "ID:".bold(), balance.alkane_id.block, balance.alkane_id.tx));
+        balance_tree.push(Tree::new(format!("{} {}", "Name:".bold(), balance.name)));
+        balance_tree.push(Tree::new(format!("{} {}", "Symbol:".bold(), balance.symbol)));
+        balance_tree.push(Tree::new(format!("{} {}", "Balance:".bold(), balance.balance)));
+        let total: u128 = holders.iter().map(|h| h.balance).sum();
+        let mut locked_tree = Tree::new(format!("{} {}", "Contract-locked:".bold(), total));
+        for holder in holders {
+            locked_tree.push(Tree::new(format!("{}:{} {}", holder.holder.block, holder.holder.tx, holder.balance)));
+        }
+        balance_tree.push(locked_tree);
+        trees.push(balance_tree);
+    }
+    let root = Tree::new("🪙 Alkane Balances".to_string()).with_leaves(trees);
+    println!("{}", root);
+}
+
 pub fn print_utxos(utxos: &[(OutPoint, UtxoInfo)]) {
     let mut trees = Vec::new();
     for (outpoint, utxo_info) in utxos {
@@ -189,6 +211,89 @@ pub fn print_inspection_result(result: &alkanes_cli_common::alkanes::types::Alka
     println!("{}", root);
 }
 
+pub fn print_resolution(target: &str, resolution: &alkanes_cli_common::alkanes::implementation_view::Resolution) {
+    let implementation = format!("{}:{}", resolution.implementation.block, resolution.implementation.tx);
+    let mut root = Tree::new(format!("🧭 Implementation of {}: {}", target, implementation.bold()));
+    if resolution.chain.is_empty() {
+        root.push(Tree::new("Not a proxy".to_string()));
+    } else {
+        let mut chain = Tree::new("🔗 Proxy chain:".to_string());
+        for hop in &resolution.chain {
+            chain.push(Tree::new(format!("{}:{} ({})", hop.alkane.block, hop.alkane.tx, hop.kind)));
+        }
+        chain.push(Tree::new(format!("{} (implementation)", implementation)));
+        root.push(chain);
+    }
+    root.push(Tree::new(format!("🔑 Bytecode Hash: {}", resolution.bytecode_hash)));
+    if !resolution.upgrades.is_empty() {
+        let mut upgrades = Tree::new("⬆️ Upgrades:".to_string());
+        for upgrade in &resolution.upgrades {
+            upgrades.push(Tree::new(format!(
+                "block {}: {}:{} {}:{} → {}:{}",
+                upgrade.height,
+                upgrade.alkane.block,
+                upgrade.alkane.tx,
+                upgrade.old_implementation.block,
+                upgrade.old_implementation.tx,
+                upgrade.new_implementation.block,
+                upgrade.new_implementation.tx,
+            )));
+        }
+        root.push(upgrades);
+    }
+    println!("{}", root);
+}
+
+pub fn print_block_stats(
+    blocks: &[alkanes_cli_common::alkanes::block_stats_view::BlockStats],
+    totals: &alkanes_cli_common::alkanes::block_stats_view::BlockStatsTotals,
+) {
+    if blocks.is_empty() {
+        println!("📭 No block stats found");
+        return;
+    }
+    println!(
+        "{}",
+        format!(
+            "{:>10} {:>11} {:>9} {:>8} {:>14} {:>7} {:>13} {:>8} {:>6}",
+            "height", "protostones", "messages", "reverts", "fuel", "fuel %", "storage bytes", "created", "depth"
+        )
+        .bold()
+    );
+    for b in blocks {
+        let utilization = if b.block_fuel == 0 { 0.0 } else { 100.0 * b.fuel_consumed as f64 / b.block_fuel as f64 };
+        println!(
+            "{:>10} {:>11} {:>9} {:>8} {:>14} {:>6.2}% {:>13} {:>8} {:>6}",
+            b.height, b.protostones, b.messages, b.total_reverts(), b.fuel_consumed, utilization,
+            b.storage_bytes, b.alkanes_created, b.max_call_depth()
+        );
+    }
+
+    let mut root = Tree::new(format!("📊 Totals over {} blocks", totals.blocks));
+    root.push(Tree::new(format!("{} {}", "Protostones:".bold(), totals.protostones)));
+    root.push(Tree::new(format!("{} {}", "Messages:".bold(), totals.messages)));
+    let mut reverts = Tree::new(format!("{} {}", "Reverts:".bold(), totals.reverts.values().sum::<u64>()));
+    for (class, count) in &totals.reverts {
+        reverts.push(Tree::new(format!("{}: {}", class, count)));
+    }
+    root.push(reverts);
+    root.push(Tree::new(format!(
+        "{} {} of {} ({:.2}%)",
+        "Fuel:".bold(),
+        totals.fuel_consumed,
+        totals.block_fuel,
+        100.0 * totals.fuel_utilization
+    )));
+    root.push(Tree::new(format!("{} {}", "Storage bytes:".bold(), totals.storage_bytes)));
+    root.push(Tree::new(format!("{} {}", "Alkanes created:".bold(), totals.alkanes_created)));
+    let mut depths = Tree::new("Call depth:".bold().to_string());
+    for (depth, count) in &totals.call_depths {
+        depths.push(Tree::new(format!("{}: {} messages", depth, count)));
+    }
+    root.push(depths);
+    println!("{}", root);
+}
+
 pub fn print_esplora_transactions(txs: &[alkanes_cli_common::esplora::EsploraTransaction]) {
     if txs.is_empty() {
         println!("📭 No transactions found");
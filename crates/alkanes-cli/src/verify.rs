@@ -0,0 +1,187 @@
+//! Deterministic local builds for `alkanes verify`.
+//!
+//! Mirrors `scripts/Dockerfile.wasm`: the pinned toolchain from
+//! `rust-toolchain.toml`, `--locked`, the workspace's release profile,
+//! `SOURCE_DATE_EPOCH=0`, and `--remap-path-prefix` for the workspace and
+//! cargo registry so host paths never reach the wasm.
+
+use anyhow::{anyhow, Context, Result};
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use alkanes_cli_common::alkanes::verify::sha256_hex;
+
+pub const WASM_TARGET: &str = "wasm32-unknown-unknown";
+
+/// The RUSTFLAGS we build with, as recorded in the manifest: the remapped
+/// prefixes are host paths, so they are written symbolically.
+const RECORDED_RUSTFLAGS: &str =
+    "--remap-path-prefix=<workspace>=. --remap-path-prefix=<cargo-home>/registry=registry";
+
+/// A contract built for verification, with everything the manifest records.
+pub struct LocalBuild {
+    pub crate_name: String,
+    pub wasm: Vec<u8>,
+    pub toolchain: String,
+    pub rustflags: String,
+    pub features: Vec<String>,
+    pub cargo_lock_sha256: Option<String>,
+    pub source_commit: Option<String>,
+}
+
+struct CrateInfo {
+    name: String,
+    workspace_root: PathBuf,
+    target_directory: PathBuf,
+}
+
+fn crate_info(source: &Path) -> Result<CrateInfo> {
+    let manifest = source
+        .join("Cargo.toml")
+        .canonicalize()
+        .with_context(|| format!("no Cargo.toml in {}", source.display()))?;
+    let output = Command::new("cargo")
+        .args([
+            "metadata",
+            "--format-version",
+            "1",
+            "--no-deps",
+            "--manifest-path",
+        ])
+        .arg(&manifest)
+        .output()
+        .context("failed to run cargo metadata")?;
+    if !output.status.success() {
+        return Err(anyhow!(
+            "cargo metadata failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+    let metadata: Value = serde_json::from_slice(&output.stdout)?;
+    let name = metadata["packages"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .find(|p| p["manifest_path"].as_str().map(Path::new) == Some(manifest.as_path()))
+        .and_then(|p| p["name"].as_str())
+        .ok_or_else(|| anyhow!("{} is not a package", manifest.display()))?
+        .to_string();
+    let path = |key: &str| {
+        metadata[key]
+            .as_str()
+            .map(PathBuf::from)
+            .ok_or_else(|| anyhow!("cargo metadata has no {}", key))
+    };
+    Ok(CrateInfo {
+        name,
+        workspace_root: path("workspace_root")?,
+        target_directory: path("target_directory")?,
+    })
+}
+
+/// The `channel` pinned by `rust-toolchain.toml` (or a bare `rust-toolchain`).
+fn pinned_toolchain(workspace_root: &Path) -> Option<String> {
+    if let Ok(toml) = std::fs::read_to_string(workspace_root.join("rust-toolchain.toml")) {
+        return toml.lines().find_map(|line| {
+            let (key, value) = line.split_once('=')?;
+            (key.trim() == "channel").then(|| value.trim().trim_matches('"').to_string())
+        });
+    }
+    std::fs::read_to_string(workspace_root.join("rust-toolchain"))
+        .ok()
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+}
+
+fn cargo_home() -> PathBuf {
+    std::env::var_os("CARGO_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".cargo")))
+        .unwrap_or_else(|| PathBuf::from(".cargo"))
+}
+
+fn git_commit(dir: &Path) -> Option<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(["rev-parse", "HEAD"])
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Build the contract crate at `source` for `wasm32-unknown-unknown`.
+/// `toolchain` overrides the workspace's pinned channel.
+pub fn build_contract(
+    source: &Path,
+    toolchain: Option<&str>,
+    features: &[String],
+) -> Result<LocalBuild> {
+    let info = crate_info(source)?;
+    let toolchain = match toolchain {
+        Some(t) => t.to_string(),
+        None => pinned_toolchain(&info.workspace_root).ok_or_else(|| {
+            anyhow!(
+                "{} pins no toolchain; pass --toolchain",
+                info.workspace_root.display()
+            )
+        })?,
+    };
+    let rustflags = format!(
+        "--remap-path-prefix={}=. --remap-path-prefix={}=registry",
+        info.workspace_root.display(),
+        cargo_home().join("registry").display()
+    );
+    let lock = info.workspace_root.join("Cargo.lock");
+    let cargo_lock_sha256 = std::fs::read(&lock).ok().map(|bytes| sha256_hex(&bytes));
+    // A separate target dir keeps these RUSTFLAGS from invalidating (or
+    // reusing) the developer's regular build cache.
+    let target_dir = info.target_directory.join("alkanes-verify");
+
+    let mut cmd = Command::new("cargo");
+    cmd.arg(format!("+{}", toolchain))
+        .args([
+            "build",
+            "--release",
+            "--target",
+            WASM_TARGET,
+            "-p",
+            &info.name,
+        ])
+        .current_dir(&info.workspace_root)
+        .env("SOURCE_DATE_EPOCH", "0")
+        .env("RUSTFLAGS", &rustflags)
+        .env("CARGO_TARGET_DIR", &target_dir)
+        .env_remove("CARGO_ENCODED_RUSTFLAGS");
+    if cargo_lock_sha256.is_some() {
+        cmd.arg("--locked");
+    }
+    if !features.is_empty() {
+        cmd.args(["--features", &features.join(",")]);
+    }
+    log::info!("Building {} with {:?}", info.name, cmd);
+    let status = cmd.status().context("failed to run cargo build")?;
+    if !status.success() {
+        return Err(anyhow!("cargo build of {} failed ({})", info.name, status));
+    }
+
+    let wasm_path = target_dir
+        .join(WASM_TARGET)
+        .join("release")
+        .join(format!("{}.wasm", info.name.replace('-', "_")));
+    let wasm = std::fs::read(&wasm_path)
+        .with_context(|| format!("build produced no {}", wasm_path.display()))?;
+    Ok(LocalBuild {
+        crate_name: info.name,
+        wasm,
+        toolchain,
+        rustflags: RECORDED_RUSTFLAGS.to_string(),
+        features: features.to_vec(),
+        cargo_lock_sha256,
+        source_commit: git_commit(&info.workspace_root),
+    })
+}
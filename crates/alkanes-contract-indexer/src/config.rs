@@ -12,6 +12,7 @@ pub struct AppConfig {
     pub start_height: Option<u64>,
     pub factory_block_id: String,
     pub factory_tx_id: String,
+    pub reorg_max_depth: u64,
 }
 
 impl AppConfig {
@@ -31,6 +32,10 @@ impl AppConfig {
         let start_height = env::var("START_HEIGHT").ok().and_then(|s| s.parse::<u64>().ok());
         let factory_block_id = env::var("FACTORY_BLOCK_ID").unwrap_or_else(|_| "0".to_string());
         let factory_tx_id = env::var("FACTORY_TX_ID").unwrap_or_else(|_| "0".to_string());
+        let reorg_max_depth = env::var("REORG_MAX_DEPTH")
+            .ok()
+            .and_then(|s| s.parse::<u64>().ok())
+            .unwrap_or(crate::reorg::DEFAULT_MAX_REORG_DEPTH);
 
         Ok(Self {
             database_url,
@@ -42,6 +47,7 @@ impl AppConfig {
             start_height,
             factory_block_id,
             factory_tx_id,
+            reorg_max_depth,
         })
     }
 }
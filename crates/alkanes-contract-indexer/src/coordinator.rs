@@ -1,4 +1,6 @@
 use anyhow::Result;
+use std::sync::Arc;
+use tokio::sync::Mutex;
 use alkanes_cli_common::traits::{MetashrewRpcProvider, BitcoinRpcProvider, EsploraProvider, JsonRpcProvider, DeezelProvider};
 use tracing::{info, error};
 
@@ -9,19 +11,29 @@ pub struct CatchUpCoordinator<P: MetashrewRpcProvider + BitcoinRpcProvider + Esp
     pipeline: Pipeline,
     progress: ProgressStore,
     start_height: Option<u64>,
+    index_lock: Arc<Mutex<()>>,
 }
 
 impl<P: MetashrewRpcProvider + BitcoinRpcProvider + EsploraProvider + JsonRpcProvider + DeezelProvider + Send + Sync> CatchUpCoordinator<P> {
-    pub fn new(provider: P, pipeline: Pipeline, progress: ProgressStore, start_height: Option<u64>) -> Self {
-        Self { provider, pipeline, progress, start_height }
+    pub fn new(provider: P, pipeline: Pipeline, progress: ProgressStore, start_height: Option<u64>, index_lock: Arc<Mutex<()>>) -> Self {
+        Self { provider, pipeline, progress, start_height, index_lock }
     }
 
     /// Run a single pass: check our current position and process the next block
     /// This is deterministic - we always check our position first before fetching the next block
+    ///
+    /// Reorgs are owned by the `BlockPoller`, which checks for one before each tick and
+    /// rolls back anything the coordinator indexed on a fork. The shared `index_lock`
+    /// keeps a rollback from interleaving with a block being processed here
     pub async fn run_once(&self) -> Result<()> {
+        let _index_guard = self.index_lock.lock().await;
+
         // First, check our current position
         let position = self.progress.get_position().await?;
 
+        // Check the metashrew tip to see if there's a block to process
+        let tip = canonical_tip_height(&self.provider).await?;
+
         // Determine the next block to process
         // If we have a position, continue from there
         // If no position, use start_height or default to 0
@@ -29,9 +41,6 @@ impl<P: MetashrewRpcProvider + BitcoinRpcProvider + EsploraProvider + JsonRpcPro
             Some(pos) => pos.height.saturating_add(1),
             None => self.start_height.unwrap_or(0),
         };
-
-        // Check the metashrew tip to see if there's a block to process
-        let tip = canonical_tip_height(&self.provider).await?;
         if next > tip {
             return Ok(()); // Nothing to process yet
         }
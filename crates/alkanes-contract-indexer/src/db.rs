@@ -5,6 +5,7 @@ pub mod pools;
 pub mod pool_state;
 pub mod transactions;
 pub mod blocks;
+pub mod reorg;
 
 pub async fn connect(database_url: &str, max_connections: u32) -> Result<PgPool> {
     let pool = PgPoolOptions::new()
@@ -57,3 +57,14 @@ pub async fn upsert_processed_block(
 }
 
 
+
+/// Hash recorded in "ProcessedBlocks" for a height, if that height was processed.
+pub async fn get_processed_block_hash(pool: &sqlx::PgPool, block_height: i32) -> Result<Option<String>> {
+    let row: Option<(String,)> = sqlx::query_as(
+        r#"select "blockHash" from "ProcessedBlocks" where "blockHeight" = $1"#,
+    )
+    .bind(block_height)
+    .fetch_optional(pool)
+    .await?;
+    Ok(row.map(|(h,)| h))
+}
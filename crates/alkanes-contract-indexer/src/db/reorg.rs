@@ -0,0 +1,114 @@
+use anyhow::Result;
+use sqlx::PgPool;
+use tracing::info;
+
+use crate::helpers::balance_tracker::refresh_holders;
+
+/// Height-scoped tables cleared above the fork point, in dependency-safe order
+/// (rows referencing "AlkaneTransaction" go before it).
+const HEIGHT_SCOPED_TABLES: &[&str] = &[
+    "PoolSwap",
+    "PoolMint",
+    "PoolBurn",
+    "PoolCreation",
+    "SubfrostWrap",
+    "SubfrostUnwrap",
+    "AmmTrade",
+    "AmmReserveSnapshot",
+    "TraceEvent",
+    "DecodedProtostone",
+    "ClockIn",
+    "AlkaneTransaction",
+    "PoolState",
+    "ClockInBlockSummary",
+    "AlkaneStorage",
+    "AlkaneBalanceUtxo",
+    "ProcessedBlocks",
+];
+
+/// Undo every block above `fork_height` and move indexer_position back to it.
+///
+/// Runs in a single transaction so a failed rollback leaves the database on the
+/// old branch. Aggregate "AlkaneBalance" rows are decremented by the UTXO
+/// balances being removed, and holders are refreshed for the affected alkanes.
+/// "AlkaneStorage" only keeps the latest value per key, so keys last written
+/// above the fork are removed rather than restored; they come back when the
+/// new branch writes them. "AmmCandle" is re-aggregated from "AmmTrade" and is
+/// left alone.
+pub async fn rollback_above(pool: &PgPool, fork_height: i32) -> Result<()> {
+    let mut tx = pool.begin().await?;
+
+    let affected: Vec<(i32, i64)> = sqlx::query_as(
+        r#"select distinct "alkaneIdBlock", "alkaneIdTx" from "AlkaneBalanceUtxo" where "blockHeight" > $1"#,
+    )
+    .bind(fork_height)
+    .fetch_all(&mut *tx)
+    .await?;
+
+    sqlx::query(
+        r#"
+        update "AlkaneBalance" b set
+            "amount" = (b."amount"::numeric - r."total")::text,
+            "updatedAt" = now()
+        from (
+            select "address", "alkaneIdBlock", "alkaneIdTx", sum("amount"::numeric) as "total"
+            from "AlkaneBalanceUtxo"
+            where "blockHeight" > $1
+            group by "address", "alkaneIdBlock", "alkaneIdTx"
+        ) r
+        where b."address" = r."address"
+          and b."alkaneIdBlock" = r."alkaneIdBlock"
+          and b."alkaneIdTx" = r."alkaneIdTx"
+        "#,
+    )
+    .bind(fork_height)
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query(
+        r#"
+        update "AlkaneBalanceUtxo" set
+            "spent" = false,
+            "spentTxid" = null,
+            "spentBlockHeight" = null,
+            "updatedAt" = now()
+        where "spentBlockHeight" > $1
+        "#,
+    )
+    .bind(fork_height)
+    .execute(&mut *tx)
+    .await?;
+
+    for table in HEIGHT_SCOPED_TABLES {
+        sqlx::query(&format!(
+            r#"delete from "{}" where "blockHeight" > $1"#,
+            table
+        ))
+        .bind(fork_height)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    let fork_hash: Option<(String,)> =
+        sqlx::query_as(r#"select "blockHash" from "ProcessedBlocks" where "blockHeight" = $1"#)
+            .bind(fork_height)
+            .fetch_optional(&mut *tx)
+            .await?;
+    sqlx::query(
+        "INSERT INTO indexer_position (id, height, block_hash)
+         VALUES (1, $1, $2)
+         ON CONFLICT (id) DO UPDATE SET height = $1, block_hash = $2",
+    )
+    .bind(fork_height as i64)
+    .bind(fork_hash.map(|(h,)| h).unwrap_or_default())
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    for (block, tx_num) in affected {
+        refresh_holders(pool, block, tx_num).await?;
+    }
+    info!(fork_height, "rolled back database to fork point");
+    Ok(())
+}
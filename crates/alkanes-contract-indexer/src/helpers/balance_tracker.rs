@@ -400,7 +400,7 @@ pub async fn refresh_holders_for_block(
 }
 
 /// Refresh holder materialized data for an alkane
-async fn refresh_holders(
+pub async fn refresh_holders(
     pool: &PgPool,
     alkane_id_block: i32,
     alkane_id_tx: i64,
@@ -15,6 +15,22 @@ where
 	Ok(hash)
 }
 
+// Resolve the block hash metashrew indexed at height via JSON-RPC method `metashrew_getblockhash`
+pub async fn get_metashrew_block_hash<P>(provider: &P, height: u64) -> Result<String>
+where
+	P: JsonRpcProvider + DeezelProvider + Send + Sync,
+{
+	let url = env::var("SANDSHREW_RPC_URL")
+		.ok()
+		.or_else(|| provider.get_metashrew_rpc_url())
+		.or_else(|| provider.get_bitcoin_rpc_url())
+		.unwrap_or_else(|| "http://localhost:18888".to_string());
+	let hash = resilient_call(provider, &url, "metashrew_getblockhash", json!([height]), 1).await?;
+	hash.as_str()
+		.map(|s| s.to_string())
+		.ok_or_else(|| anyhow::anyhow!("metashrew_getblockhash returned no hash for height {}", height))
+}
+
 // Get txids for a block via JSON-RPC method `esplora_block::txids`
 pub async fn get_block_txids<P>(provider: &P, block_hash: &str) -> Result<Vec<String>>
 where
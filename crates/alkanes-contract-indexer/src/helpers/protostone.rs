@@ -228,6 +228,23 @@ fn convert_trace_to_events(trace: &alkanes_pb::AlkanesTrace, vout: i32) -> Vec<J
                         },
                     }));
                 }
+                alkanes_pb::alkanes_trace_event::Event::EmitEvent(emit) => {
+                    let (block, tx) = emit.alkane.as_ref()
+                        .map(|id| alkane_id_to_strings(id))
+                        .unwrap_or_default();
+                    events.push(json!({
+                        "event": "emit_event",
+                        "vout": vout,
+                        "data": {
+                            "alkane": {
+                                "block": block,
+                                "tx": tx,
+                            },
+                            "topic": hex::encode(&emit.topic),
+                            "data": hex::encode(&emit.data),
+                        },
+                    }));
+                }
             }
         }
     }
@@ -8,6 +8,7 @@ pub mod coordinator;
 pub mod pipeline;
 pub mod poller;
 pub mod provider;
+pub mod reorg;
 pub mod transform_integration;
 
 
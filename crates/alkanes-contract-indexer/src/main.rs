@@ -2,7 +2,8 @@ use anyhow::Result;
 use dotenvy::dotenv;
 use tracing::info;
 use tracing_subscriber::{fmt, EnvFilter};
-use tokio::sync::oneshot;
+use std::sync::Arc;
+use tokio::sync::{oneshot, Mutex};
 
 mod config;
 mod db;
@@ -11,6 +12,7 @@ mod coordinator;
 mod pipeline;
 mod poller;
 mod provider;
+mod reorg;
 mod helpers;
 mod inferred_transfers;
 mod transform_integration;
@@ -72,6 +74,10 @@ async fn main() -> Result<()> {
         info!("no position found, starting fresh");
     }
 
+    // Held by the poller and the coordinator while they index, so a rollback
+    // never interleaves with a block being written
+    let index_lock = Arc::new(Mutex::new(()));
+
     // Spawn tip poller (always triggers pools fetch; also processes blocks when following tip)
     let tip_provider = provider;
     let poller_pipeline = pipeline.clone();
@@ -86,6 +92,8 @@ async fn main() -> Result<()> {
             cfg.poll_interval_ms,
             Some(poller_init_tx),
             cfg.start_height,
+            cfg.reorg_max_depth,
+            index_lock.clone(),
         );
         poller.run().await;
     };
@@ -99,7 +107,7 @@ async fn main() -> Result<()> {
         cfg.network_provider.clone(),
     )
     .await?;
-    let coordinator = coordinator::CatchUpCoordinator::new(coord_provider, pipeline, progress_store, cfg.start_height);
+    let coordinator = coordinator::CatchUpCoordinator::new(coord_provider, pipeline, progress_store, cfg.start_height, index_lock);
     let coordinator_fut = async move {
         // Wait for initial pools refresh + height init before starting catch-up
         let _ = poller_init_rx.await;
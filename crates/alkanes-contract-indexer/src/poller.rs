@@ -1,9 +1,11 @@
 use alkanes_cli_sys::SystemAlkanes as ConcreteProvider;
-use tokio::sync::oneshot;
+use std::sync::Arc;
+use tokio::sync::{oneshot, Mutex};
 use tokio::time::{sleep, Duration, Instant};
-use tracing::{error, info, warn};
+use tracing::{error, info};
 
 use crate::{pipeline::{BlockContext, Pipeline}, progress::ProgressStore, helpers::block::canonical_tip_height};
+use crate::reorg::{rollback_if_reorged, MetashrewChain};
 
 pub struct BlockPoller {
     provider: ConcreteProvider,
@@ -12,6 +14,8 @@ pub struct BlockPoller {
     poll_interval_ms: u64,
     init_signal: Option<oneshot::Sender<()>>, // fired once after initial pools refresh + height init
     start_height: Option<u64>,
+    reorg_max_depth: u64,
+    index_lock: Arc<Mutex<()>>, // shared with the catch-up coordinator
 }
 
 impl BlockPoller {
@@ -22,8 +26,10 @@ impl BlockPoller {
         poll_interval_ms: u64,
         init_signal: Option<oneshot::Sender<()>>,
         start_height: Option<u64>,
+        reorg_max_depth: u64,
+        index_lock: Arc<Mutex<()>>,
     ) -> Self {
-        Self { provider, pipeline, progress, poll_interval_ms, init_signal, start_height }
+        Self { provider, pipeline, progress, poll_interval_ms, init_signal, start_height, reorg_max_depth, index_lock }
     }
 
     pub async fn run(mut self) {
@@ -32,6 +38,8 @@ impl BlockPoller {
 
         loop {
             let tick_start = Instant::now();
+            // Hold off the coordinator from reading our position until we are done with it
+            let index_guard = self.index_lock.clone().lock_owned().await;
 
             // First, check our current position from the database
             let position = match self.progress.get_position().await {
@@ -61,30 +69,32 @@ impl BlockPoller {
                         }
                     }
 
+                    // Check for reorg: if the blocks behind our position are no longer on
+                    // metashrew's chain, roll back to the fork point before processing.
+                    // The poller is the only component that rolls back
+                    let fork = match &position {
+                        Some(pos) => match rollback_if_reorged(&MetashrewChain(&self.provider), &self.progress, pos, tip_height, self.reorg_max_depth).await {
+                            Ok(fork) => fork,
+                            Err(e) => {
+                                error!(tip = tip_height, our_position = pos.height, error = %e, "reorg check failed");
+                                sleep(Duration::from_millis(self.poll_interval_ms)).await;
+                                continue;
+                            }
+                        },
+                        None => None,
+                    };
+
                     // Determine the next block to process based on our position
-                    let next_height = match &position {
-                        Some(pos) => pos.height.saturating_add(1),
-                        None => {
+                    let next_height = match (&position, fork) {
+                        (_, Some(fork)) => fork.saturating_add(1),
+                        (Some(pos), None) => pos.height.saturating_add(1),
+                        (None, None) => {
                             // No position yet - start from configured start_height or 0
                             // The coordinator will handle the sequential catch-up
                             self.start_height.unwrap_or(0)
                         }
                     };
 
-                    // Check for reorg: if tip is less than our position, something's wrong
-                    if let Some(pos) = &position {
-                        if tip_height < pos.height {
-                            warn!(
-                                tip = tip_height,
-                                our_position = pos.height,
-                                "tip is behind our position, possible reorg"
-                            );
-                            // Don't process anything - wait for tip to catch up or handle reorg
-                            sleep(Duration::from_millis(self.poll_interval_ms)).await;
-                            continue;
-                        }
-                    }
-
                     // Process blocks one at a time if we're behind
                     if next_height <= tip_height {
                         // Refresh pools at new tip before processing
@@ -116,6 +126,7 @@ impl BlockPoller {
                 }
             }
 
+            drop(index_guard);
             let elapsed = tick_start.elapsed();
             let base = if backoff_ms == self.poll_interval_ms { self.poll_interval_ms } else { backoff_ms };
             let sleep_ms = base.saturating_sub(elapsed.as_millis() as u64);
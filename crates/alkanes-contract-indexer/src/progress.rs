@@ -1,6 +1,10 @@
 use anyhow::Result;
+use async_trait::async_trait;
 use sqlx::PgPool;
 
+use crate::db::{blocks::get_processed_block_hash, reorg::rollback_above};
+use crate::reorg::BlockHashStore;
+
 /// Represents the last fully indexed block position
 #[derive(Debug, Clone)]
 pub struct Position {
@@ -42,6 +46,19 @@ impl ProgressStore {
     }
 }
 
+/// Per-height hashes come from "ProcessedBlocks", which the pipeline writes in
+/// the same transaction as the position
+#[async_trait(?Send)]
+impl BlockHashStore for ProgressStore {
+    async fn block_hash_at(&self, height: u64) -> Result<Option<String>> {
+        get_processed_block_hash(&self.pool, height as i32).await
+    }
+
+    async fn rollback_to(&self, height: u64) -> Result<()> {
+        rollback_above(&self.pool, height as i32).await
+    }
+}
+
 /// Create the indexer_position table if it doesn't exist
 /// This table has exactly one row that tracks our sync position
 pub async fn ensure_position_table(pool: &PgPool) -> Result<()> {
@@ -0,0 +1,115 @@
+use alkanes_cli_common::traits::{DeezelProvider, JsonRpcProvider};
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use tracing::warn;
+
+use crate::helpers::block::get_metashrew_block_hash;
+use crate::progress::Position;
+
+/// Default number of blocks we are willing to walk back looking for a fork point
+pub const DEFAULT_MAX_REORG_DEPTH: u64 = 100;
+
+/// The chain as metashrew has indexed it
+#[async_trait(?Send)]
+pub trait ChainView {
+    async fn block_hash(&self, height: u64) -> Result<String>;
+}
+
+/// What we have recorded per height, and how to undo it
+#[async_trait(?Send)]
+pub trait BlockHashStore {
+    /// Hash we processed at `height`, if any
+    async fn block_hash_at(&self, height: u64) -> Result<Option<String>>;
+
+    /// Drop everything recorded above `height` and move the position back to it
+    async fn rollback_to(&self, height: u64) -> Result<()>;
+}
+
+/// Reads block hashes from metashrew's `metashrew_getblockhash`
+pub struct MetashrewChain<'a, P>(pub &'a P);
+
+#[async_trait(?Send)]
+impl<P: JsonRpcProvider + DeezelProvider + Send + Sync> ChainView for MetashrewChain<'_, P> {
+    async fn block_hash(&self, height: u64) -> Result<String> {
+        get_metashrew_block_hash(self.0, height).await
+    }
+}
+
+/// Compare two block hashes, ignoring a 0x prefix, case, and byte order.
+/// Metashrew reports hashes in internal byte order while bitcoind (which the
+/// pipeline records) reports them reversed.
+pub fn same_block_hash(a: &str, b: &str) -> bool {
+    let a = a.trim_start_matches("0x").to_ascii_lowercase();
+    let b = b.trim_start_matches("0x").to_ascii_lowercase();
+    if a.is_empty() || b.is_empty() {
+        return false;
+    }
+    if a == b {
+        return true;
+    }
+    match (hex::decode(&a), hex::decode(&b)) {
+        (Ok(mut x), Ok(y)) => {
+            x.reverse();
+            x == y
+        }
+        _ => false,
+    }
+}
+
+/// Find the highest height at or below our position whose recorded hash still
+/// matches the chain. Returns None when our position is on the chain.
+///
+/// If metashrew's tip is below our position the blocks above it have been
+/// dropped, so the search starts at the tip. Heights we have no hash for
+/// (before START_HEIGHT, or rows written before hashes were recorded) cannot be
+/// compared, so the search stops there.
+pub async fn find_fork_point<C: ChainView, S: BlockHashStore>(
+    chain: &C,
+    store: &S,
+    position: &Position,
+    tip: u64,
+    max_depth: u64,
+) -> Result<Option<u64>> {
+    let start = position.height.min(tip);
+    let mut height = start;
+    loop {
+        let Some(ours) = store.block_hash_at(height).await? else {
+            return Ok((height != position.height).then_some(height));
+        };
+        let theirs = chain.block_hash(height).await?;
+        if same_block_hash(&ours, &theirs) {
+            return Ok((height != position.height).then_some(height));
+        }
+        if height == 0 || start - height >= max_depth {
+            return Err(anyhow!(
+                "reorg below height {} is deeper than {} blocks; refusing to roll back",
+                start,
+                max_depth
+            ));
+        }
+        height -= 1;
+    }
+}
+
+/// Check `position` against the chain and, if we are on a stale branch, roll
+/// back to the fork point. Returns the fork height when a rollback happened;
+/// processing should resume from the block after it.
+pub async fn rollback_if_reorged<C: ChainView, S: BlockHashStore>(
+    chain: &C,
+    store: &S,
+    position: &Position,
+    tip: u64,
+    max_depth: u64,
+) -> Result<Option<u64>> {
+    let Some(fork) = find_fork_point(chain, store, position, tip, max_depth).await? else {
+        return Ok(None);
+    };
+    warn!(
+        fork_height = fork,
+        our_position = position.height,
+        tip,
+        "reorg detected, rolling back to fork point"
+    );
+    store.rollback_to(fork).await?;
+    Ok(Some(fork))
+}
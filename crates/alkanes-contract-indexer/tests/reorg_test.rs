@@ -0,0 +1,218 @@
+/// Reorg detection and rollback against a scripted chain that switches between
+/// two competing branches, with an in-memory stand-in for the Postgres store.
+use alkanes_contract_indexer::progress::Position;
+use alkanes_contract_indexer::reorg::{
+    BlockHashStore, ChainView, find_fork_point, rollback_if_reorged, same_block_hash,
+};
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use std::cell::{Cell, RefCell};
+use std::collections::BTreeMap;
+
+fn hash(branch: char, height: u64) -> String {
+    format!("{:0>64}", format!("{}{:x}", branch, height))
+}
+
+/// Serves block hashes from one of two branches that share blocks up to `fork`
+struct ScriptedChain {
+    fork: u64,
+    tips: [u64; 2],
+    active: Cell<usize>,
+}
+
+impl ScriptedChain {
+    fn new(fork: u64, tip_a: u64, tip_b: u64) -> Self {
+        Self {
+            fork,
+            tips: [tip_a, tip_b],
+            active: Cell::new(0),
+        }
+    }
+
+    fn switch_to_b(&self) {
+        self.active.set(1);
+    }
+
+    fn tip(&self) -> u64 {
+        self.tips[self.active.get()]
+    }
+}
+
+#[async_trait(?Send)]
+impl ChainView for ScriptedChain {
+    async fn block_hash(&self, height: u64) -> Result<String> {
+        if height > self.tip() {
+            return Err(anyhow!("height {} above tip {}", height, self.tip()));
+        }
+        let branch = if height <= self.fork {
+            'c'
+        } else if self.active.get() == 0 {
+            'a'
+        } else {
+            'b'
+        };
+        Ok(hash(branch, height))
+    }
+}
+
+/// What the pipeline and "ProcessedBlocks" would hold, plus a log of rollbacks
+#[derive(Default)]
+struct MemoryStore {
+    hashes: RefCell<BTreeMap<u64, String>>,
+    rollbacks: RefCell<Vec<u64>>,
+}
+
+impl MemoryStore {
+    async fn process(&self, chain: &ScriptedChain, from: u64, to: u64) -> Result<Position> {
+        for h in from..=to {
+            let block_hash = chain.block_hash(h).await?;
+            self.hashes.borrow_mut().insert(h, block_hash);
+        }
+        Ok(self.position())
+    }
+
+    fn position(&self) -> Position {
+        let hashes = self.hashes.borrow();
+        let (height, block_hash) = hashes
+            .iter()
+            .next_back()
+            .expect("processed at least one block");
+        Position {
+            height: *height,
+            block_hash: block_hash.clone(),
+        }
+    }
+}
+
+#[async_trait(?Send)]
+impl BlockHashStore for MemoryStore {
+    async fn block_hash_at(&self, height: u64) -> Result<Option<String>> {
+        Ok(self.hashes.borrow().get(&height).cloned())
+    }
+
+    async fn rollback_to(&self, height: u64) -> Result<()> {
+        self.hashes.borrow_mut().retain(|h, _| *h <= height);
+        self.rollbacks.borrow_mut().push(height);
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn test_no_rollback_while_on_the_same_branch() -> Result<()> {
+    let chain = ScriptedChain::new(5, 10, 12);
+    let store = MemoryStore::default();
+    let position = store.process(&chain, 0, 10).await?;
+
+    assert_eq!(
+        rollback_if_reorged(&chain, &store, &position, chain.tip(), 100).await?,
+        None
+    );
+    assert!(store.rollbacks.borrow().is_empty());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_rolls_back_to_fork_and_reprocesses_competing_branch() -> Result<()> {
+    let chain = ScriptedChain::new(5, 10, 12);
+    let store = MemoryStore::default();
+    let position = store.process(&chain, 0, 10).await?;
+
+    chain.switch_to_b();
+    let fork = rollback_if_reorged(&chain, &store, &position, chain.tip(), 100).await?;
+    assert_eq!(fork, Some(5));
+    assert_eq!(*store.rollbacks.borrow(), vec![5]);
+    assert_eq!(store.position().height, 5);
+
+    // Re-processing from the fork lands us on branch b
+    let position = store.process(&chain, 6, chain.tip()).await?;
+    assert_eq!(position.block_hash, hash('b', 12));
+    assert_eq!(
+        rollback_if_reorged(&chain, &store, &position, chain.tip(), 100).await?,
+        None
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_tip_below_position_starts_search_at_tip() -> Result<()> {
+    // Branch b is shorter than the branch we followed
+    let chain = ScriptedChain::new(5, 10, 7);
+    let store = MemoryStore::default();
+    let position = store.process(&chain, 0, 10).await?;
+
+    chain.switch_to_b();
+    assert_eq!(
+        find_fork_point(&chain, &store, &position, chain.tip(), 100).await?,
+        Some(5)
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_metashrew_dropping_blocks_rolls_back_to_its_tip() -> Result<()> {
+    // Same branch, but metashrew has rolled back below our position
+    let chain = ScriptedChain::new(5, 10, 10);
+    let store = MemoryStore::default();
+    let position = store.process(&chain, 0, 10).await?;
+
+    assert_eq!(
+        find_fork_point(&chain, &store, &position, 8, 100).await?,
+        Some(8)
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_refuses_reorg_deeper_than_max_depth() -> Result<()> {
+    let chain = ScriptedChain::new(5, 10, 12);
+    let store = MemoryStore::default();
+    let position = store.process(&chain, 0, 10).await?;
+
+    chain.switch_to_b();
+    assert!(
+        rollback_if_reorged(&chain, &store, &position, chain.tip(), 3)
+            .await
+            .is_err()
+    );
+    assert!(store.rollbacks.borrow().is_empty());
+    assert_eq!(store.position().height, 10);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_unrecorded_heights_are_not_treated_as_reorgs() -> Result<()> {
+    // Position migrated from kv_store: nothing recorded at or below it
+    let chain = ScriptedChain::new(5, 10, 12);
+    let store = MemoryStore::default();
+    let position = Position {
+        height: 10,
+        block_hash: String::new(),
+    };
+
+    chain.switch_to_b();
+    assert_eq!(
+        find_fork_point(&chain, &store, &position, chain.tip(), 100).await?,
+        None
+    );
+
+    // Hashes recorded only from START_HEIGHT=8: the search stops at the first gap
+    store.process(&ScriptedChain::new(5, 10, 12), 8, 10).await?;
+    assert_eq!(
+        find_fork_point(&chain, &store, &store.position(), chain.tip(), 100).await?,
+        Some(7)
+    );
+    Ok(())
+}
+
+#[test]
+fn test_same_block_hash_ignores_prefix_case_and_byte_order() {
+    let display = "00000000000000000001a2b3c4d5e6f708192a3b4c5d6e7f8091a2b3c4d5e6f7";
+    let mut internal = hex::decode(display).unwrap();
+    internal.reverse();
+    let internal = format!("0x{}", hex::encode(internal));
+
+    assert!(same_block_hash(display, &display.to_uppercase()));
+    assert!(same_block_hash(display, &internal));
+    assert!(!same_block_hash(display, &hash('a', 1)));
+    assert!(!same_block_hash("", ""));
+}
@@ -11,6 +11,8 @@ pub struct Config {
     pub network_env: String,
     pub infura_endpoint: String,
     pub alkane_factory_id: Option<String>,
+    /// Directory of `alkanes verify` manifests (`<block>_<tx>.json`)
+    pub verification_dir: Option<String>,
 }
 
 impl Config {
@@ -28,6 +30,7 @@ impl Config {
             infura_endpoint: env::var("INFURA_ENDPOINT")
                 .unwrap_or_else(|_| "https://mainnet.infura.io/v3/099fc58e0de9451d80b18d7c74caa7c1".to_string()),
             alkane_factory_id: env::var("ALKANE_FACTORY_ID").ok(),
+            verification_dir: env::var("VERIFICATION_MANIFEST_DIR").ok(),
         })
     }
 }
@@ -3,7 +3,7 @@ use serde_json::json;
 
 use crate::{
     models::{AddressRequest, AlkaneDetailsRequest, ApiResponse, ErrorResponse, SearchRequest, PaginationRequest},
-    services::{alkanes::AlkanesService, AppState},
+    services::{alkanes::AlkanesService, verification::load_manifest, AppState},
 };
 
 pub async fn get_alkanes(
@@ -98,6 +98,13 @@ pub async fn get_alkane_details(
         .await
     {
         Ok(alkane) => {
+            let mut alkane = json!(alkane);
+            if let Some(dir) = &state.config.verification_dir {
+                match load_manifest(dir, &req.id).await {
+                    Ok(manifest) => alkane["verification"] = json!(manifest),
+                    Err(e) => log::warn!("verification manifest for {}:{}: {}", req.id.block, req.id.tx, e),
+                }
+            }
             let response = ApiResponse::ok(alkane);
             HttpResponse::Ok().json(response)
         }
@@ -112,6 +119,34 @@ pub async fn get_alkane_details(
     }
 }
 
+pub async fn get_alkane_verification(
+    state: web::Data<AppState>,
+    req: web::Json<AlkaneDetailsRequest>,
+) -> impl Responder {
+    let Some(dir) = &state.config.verification_dir else {
+        return HttpResponse::NotFound().json(ErrorResponse::new(
+            404,
+            "verification manifests are not configured".to_string(),
+        ));
+    };
+
+    match load_manifest(dir, &req.id).await {
+        Ok(Some(manifest)) => HttpResponse::Ok().json(ApiResponse::ok(manifest)),
+        Ok(None) => HttpResponse::NotFound().json(ErrorResponse::new(
+            404,
+            format!("{}:{} has not been verified", req.id.block, req.id.tx),
+        )),
+        Err(e) => {
+            let error = ErrorResponse::with_stack(
+                500,
+                "Failed to load verification manifest".to_string(),
+                e.to_string(),
+            );
+            HttpResponse::InternalServerError().json(error)
+        }
+    }
+}
+
 pub async fn get_alkanes_utxo(
     state: web::Data<AppState>,
     req: web::Json<AddressRequest>,
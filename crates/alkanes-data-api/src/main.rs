@@ -86,6 +86,10 @@ async fn main() -> Result<()> {
                         "/get-alkane-details",
                         web::post().to(handlers::alkanes::get_alkane_details),
                     )
+                    .route(
+                        "/get-alkane-verification",
+                        web::post().to(handlers::alkanes::get_alkane_verification),
+                    )
                     .route(
                         "/get-holders",
                         web::post().to(handlers::alkanes::get_holders),
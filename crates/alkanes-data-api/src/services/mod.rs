@@ -7,6 +7,7 @@ pub mod pools;
 pub mod price;
 pub mod redis;
 pub mod query_service;
+pub mod verification;
 
 use crate::config::Config;
 use sqlx::PgPool;
@@ -0,0 +1,24 @@
+use alkanes_cli_common::alkanes::verify::VerificationManifest;
+use anyhow::{Context, Result};
+use std::path::Path;
+
+use crate::models::AlkaneId;
+
+/// Load the manifest `alkanes-cli alkanes verify` wrote for `id`, if any.
+///
+/// Manifests live in `dir` as `<block>_<tx>.json`. Ids that are not plain
+/// numbers are treated as unverified so they can never name another file.
+pub async fn load_manifest(dir: &str, id: &AlkaneId) -> Result<Option<VerificationManifest>> {
+    let (Ok(block), Ok(tx)) = (id.block.parse::<u128>(), id.tx.parse::<u128>()) else {
+        return Ok(None);
+    };
+    let path = Path::new(dir).join(VerificationManifest::file_name(block, tx));
+    let bytes = match tokio::fs::read(&path).await {
+        Ok(bytes) => bytes,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e).with_context(|| format!("failed to read {}", path.display())),
+    };
+    let manifest = serde_json::from_slice(&bytes)
+        .with_context(|| format!("invalid verification manifest {}", path.display()))?;
+    Ok(Some(manifest))
+}
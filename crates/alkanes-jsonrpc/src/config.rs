@@ -18,6 +18,10 @@ pub struct Config {
     pub subfrost_url: String,
 
     pub lua_script_path: Option<String>,
+
+    pub max_batch_size: usize,
+    pub batch_concurrency: usize,
+    pub ws_poll_interval_ms: u64,
 }
 
 impl Config {
@@ -50,6 +54,20 @@ impl Config {
                 .unwrap_or_else(|_| "http://localhost:8545".to_string()),
 
             lua_script_path: env::var("LUA_SCRIPT_PATH").ok(),
+
+            max_batch_size: env::var("MAX_BATCH_SIZE")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(100),
+            batch_concurrency: env::var("BATCH_CONCURRENCY")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .filter(|&n: &usize| n >= 1)
+                .unwrap_or(16),
+            ws_poll_interval_ms: env::var("WS_POLL_INTERVAL_MS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(1000),
         }
     }
 
@@ -1,9 +1,15 @@
-use alkanes_rpc_core::types::{JsonRpcRequest, JsonRpcResponse};
+use alkanes_rpc_core::types::{
+    JsonRpcRequest, JsonRpcResponse, INTERNAL_ERROR, INVALID_REQUEST, PARSE_ERROR,
+};
 use alkanes_rpc_core::RpcDispatcher;
 use anyhow::Result;
+use futures::stream::{self, StreamExt};
+use reqwest::Client;
+use serde_json::{json, Value};
 use std::sync::Arc;
 
 use crate::backends::*;
+use crate::config::Config;
 use crate::proxy::ProxyClient;
 use crate::sandshrew;
 
@@ -15,6 +21,126 @@ pub type ProdDispatcher = RpcDispatcher<
     ReqwestOrdBackend,
 >;
 
+/// Build the production dispatcher over reqwest backends for `config`.
+pub fn build_dispatcher(client: &Client, config: &Config) -> ProdDispatcher {
+    RpcDispatcher::new(
+        ReqwestBitcoinBackend::new(client.clone(), config),
+        ReqwestMetashrewBackend::new(client.clone(), config),
+        ReqwestEsploraBackend::new(client.clone(), config),
+        ReqwestOrdBackend::new(client.clone(), config),
+    )
+}
+
+/// Limits applied to JSON-RPC batch arrays.
+#[derive(Debug, Clone, Copy)]
+pub struct BatchLimits {
+    /// Largest batch accepted; bigger batches get a single Invalid Request error.
+    pub max_size: usize,
+    /// Batch entries dispatched at once.
+    pub concurrency: usize,
+}
+
+/// Handle a raw HTTP body. A body that is not JSON gets a Parse error; see
+/// [`handle_payload`] for everything else.
+pub async fn handle_body(
+    body: &[u8],
+    dispatcher: &Arc<ProdDispatcher>,
+    proxy: &ProxyClient,
+    script_storage: Option<&crate::lua_executor::ScriptStorage>,
+    limits: BatchLimits,
+) -> Option<Value> {
+    match serde_json::from_slice(body) {
+        Ok(payload) => handle_payload(payload, dispatcher, proxy, script_storage, limits).await,
+        Err(e) => Some(json!(JsonRpcResponse::error(
+            PARSE_ERROR,
+            format!("Parse error: {}", e),
+            Value::Null,
+        ))),
+    }
+}
+
+/// Handle a JSON-RPC 2.0 payload: either a single request object or a batch
+/// array. Batch entries are dispatched concurrently and answered in request
+/// order; an entry that is not a valid request gets its own error response.
+/// Notifications (requests without an `id`) are dispatched but not answered,
+/// so `None` means there is nothing to send back.
+pub async fn handle_payload(
+    payload: Value,
+    dispatcher: &Arc<ProdDispatcher>,
+    proxy: &ProxyClient,
+    script_storage: Option<&crate::lua_executor::ScriptStorage>,
+    limits: BatchLimits,
+) -> Option<Value> {
+    let entries = match payload {
+        Value::Array(entries) => entries,
+        single => {
+            return handle_entry(single, dispatcher, proxy, script_storage)
+                .await
+                .map(|response| json!(response))
+        }
+    };
+    if entries.is_empty() {
+        return Some(json!(JsonRpcResponse::error(
+            INVALID_REQUEST,
+            "Empty batch".to_string(),
+            Value::Null,
+        )));
+    }
+    if entries.len() > limits.max_size {
+        return Some(json!(JsonRpcResponse::error(
+            INVALID_REQUEST,
+            format!("Batch of {} requests exceeds the limit of {}", entries.len(), limits.max_size),
+            Value::Null,
+        )));
+    }
+    let responses: Vec<JsonRpcResponse> = stream::iter(entries)
+        .map(|entry| handle_entry(entry, dispatcher, proxy, script_storage))
+        .buffered(limits.concurrency.max(1))
+        .filter_map(|response| async move { response })
+        .collect()
+        .await;
+    (!responses.is_empty()).then(|| json!(responses))
+}
+
+/// A request object without an `id` member.
+fn is_notification(entry: &Value) -> bool {
+    entry
+        .as_object()
+        .is_some_and(|request| request.contains_key("method") && !request.contains_key("id"))
+}
+
+async fn handle_entry(
+    mut entry: Value,
+    dispatcher: &Arc<ProdDispatcher>,
+    proxy: &ProxyClient,
+    script_storage: Option<&crate::lua_executor::ScriptStorage>,
+) -> Option<JsonRpcResponse> {
+    let notification = is_notification(&entry);
+    if notification {
+        // `JsonRpcRequest` requires an id; the response is dropped anyway
+        entry["id"] = Value::Null;
+    }
+    let id = entry.get("id").cloned().unwrap_or(Value::Null);
+    let request: JsonRpcRequest = match serde_json::from_value(entry) {
+        Ok(request) => request,
+        Err(e) => {
+            return Some(JsonRpcResponse::error(
+                INVALID_REQUEST,
+                format!("Invalid request: {}", e),
+                id,
+            ))
+        }
+    };
+    let response = match handle_request_with_storage(&request, dispatcher, proxy, script_storage).await {
+        Ok(response) => response,
+        Err(e) => {
+            log::error!("Error handling request: {:?}", e);
+            JsonRpcResponse::error(INTERNAL_ERROR, e.to_string(), request.id.clone())
+        }
+    };
+    (!notification).then_some(response)
+}
+
 /// Handle a JSON-RPC request using the core dispatcher with pre-dispatch
 /// interception for memshrew, subfrost, and lua/sandshrew eval methods.
 pub async fn handle_request(
@@ -66,3 +192,115 @@ pub async fn handle_request_with_storage(
 
     dispatcher.dispatch(request).await
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stand_in::StandIn;
+
+    const LIMITS: BatchLimits = BatchLimits { max_size: 4, concurrency: 4 };
+
+    async fn setup() -> (StandIn, Arc<ProdDispatcher>, ProxyClient) {
+        let stand_in = StandIn::start().await;
+        let dispatcher = Arc::new(stand_in.dispatcher());
+        let proxy = ProxyClient::new(Config::from_env());
+        (stand_in, dispatcher, proxy)
+    }
+
+    fn sleep(id: u64, ms: u64, value: &str) -> Value {
+        json!({ "jsonrpc": "2.0", "id": id, "method": "metashrew_sleep", "params": [ms, value] })
+    }
+
+    #[actix_web::test]
+    async fn test_batch_is_dispatched_concurrently_and_answered_in_order() {
+        let (stand_in, dispatcher, proxy) = setup().await;
+        let batch = json!([sleep(1, 200, "slow"), sleep(2, 0, "fast"), sleep(3, 50, "middle")]);
+
+        let response = handle_payload(batch, &dispatcher, &proxy, None, LIMITS).await.unwrap();
+
+        let results: Vec<(&Value, &Value)> = response
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|r| (&r["id"], &r["result"]))
+            .collect();
+        assert_eq!(
+            results,
+            vec![(&json!(1), &json!("slow")), (&json!(2), &json!("fast")), (&json!(3), &json!("middle"))]
+        );
+        assert!(stand_in.max_in_flight() > 1);
+    }
+
+    #[actix_web::test]
+    async fn test_invalid_batch_entry_gets_its_own_error() {
+        let (_stand_in, dispatcher, proxy) = setup().await;
+        let batch = json!([sleep(1, 0, "a"), { "id": 2, "params": [] }, 7, sleep(4, 0, "d")]);
+
+        let response = handle_payload(batch, &dispatcher, &proxy, None, LIMITS).await.unwrap();
+
+        let responses = response.as_array().unwrap();
+        assert_eq!(responses.len(), 4);
+        assert_eq!(responses[0]["result"], "a");
+        assert_eq!(responses[1]["error"]["code"], INVALID_REQUEST);
+        assert_eq!(responses[1]["id"], 2);
+        assert_eq!(responses[2]["error"]["code"], INVALID_REQUEST);
+        assert_eq!(responses[2]["id"], Value::Null);
+        assert_eq!(responses[3]["result"], "d");
+    }
+
+    #[actix_web::test]
+    async fn test_empty_and_oversized_batches_are_rejected() {
+        let (stand_in, dispatcher, proxy) = setup().await;
+
+        let empty = handle_payload(json!([]), &dispatcher, &proxy, None, LIMITS).await.unwrap();
+        assert_eq!(empty["error"]["code"], INVALID_REQUEST);
+
+        let batch: Vec<Value> = (0..5).map(|i| sleep(i, 0, "x")).collect();
+        let oversized = handle_payload(json!(batch), &dispatcher, &proxy, None, LIMITS).await.unwrap();
+        assert_eq!(oversized["error"]["code"], INVALID_REQUEST);
+        assert_eq!(stand_in.max_in_flight(), 0);
+    }
+
+    #[actix_web::test]
+    async fn test_single_request_is_not_wrapped() {
+        let (stand_in, dispatcher, proxy) = setup().await;
+        stand_in.set_height(840_000);
+        let request = json!({ "jsonrpc": "2.0", "id": "h", "method": "metashrew_height", "params": [] });
+
+        let response = handle_payload(request, &dispatcher, &proxy, None, LIMITS).await.unwrap();
+        assert_eq!(response["id"], "h");
+        assert_eq!(response["result"], 840_000);
+    }
+
+    #[actix_web::test]
+    async fn test_body_that_is_not_json_is_a_parse_error() {
+        let (_stand_in, dispatcher, proxy) = setup().await;
+
+        let response = handle_body(b"{\"jsonrpc\": \"2.0\", \"method\"", &dispatcher, &proxy, None, LIMITS)
+            .await
+            .unwrap();
+        assert_eq!(response["error"]["code"], PARSE_ERROR);
+        assert_eq!(response["id"], Value::Null);
+    }
+
+    #[actix_web::test]
+    async fn test_notifications_are_not_answered() {
+        let (stand_in, dispatcher, proxy) = setup().await;
+        let notification = |ms: u64| {
+            json!({ "jsonrpc": "2.0", "method": "metashrew_sleep", "params": [ms, "n"] })
+        };
+
+        assert_eq!(handle_payload(notification(0), &dispatcher, &proxy, None, LIMITS).await, None);
+
+        let batch = json!([notification(100), sleep(2, 100, "b"), notification(100)]);
+        let response = handle_payload(batch, &dispatcher, &proxy, None, LIMITS).await.unwrap();
+        let responses = response.as_array().unwrap();
+        assert_eq!(responses.len(), 1);
+        assert_eq!(responses[0]["id"], 2);
+        // the notifications still ran alongside the request
+        assert!(stand_in.max_in_flight() > 1);
+
+        let batch = json!([notification(0), notification(0)]);
+        assert_eq!(handle_payload(batch, &dispatcher, &proxy, None, LIMITS).await, None);
+    }
+}
@@ -5,25 +5,30 @@ mod jsonrpc;
 mod lua_executor;
 mod proxy;
 mod sandshrew;
+#[cfg(test)]
+mod stand_in;
+mod ws;
 
 use actix_cors::Cors;
 use actix_web::{middleware::Logger, web, App, HttpRequest, HttpResponse, HttpServer};
 use config::Config;
-use jsonrpc::{JsonRpcRequest, JsonRpcResponse, INTERNAL_ERROR};
 use proxy::ProxyClient;
 use reqwest::Client;
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
 
 struct AppState {
     dispatcher: Arc<handler::ProdDispatcher>,
     proxy: Arc<ProxyClient>,
     script_storage: lua_executor::ScriptStorage,
+    limits: handler::BatchLimits,
+    heads: ws::Heads,
 }
 
 async fn handle_jsonrpc(
     req: HttpRequest,
-    body: web::Json<JsonRpcRequest>,
+    body: web::Bytes,
     state: web::Data<AppState>,
 ) -> HttpResponse {
     let ip = req
@@ -32,26 +37,31 @@ async fn handle_jsonrpc(
         .and_then(|v| v.to_str().ok())
         .unwrap_or("unknown");
 
-    log::info!("{}|{}", ip, serde_json::to_string(&body.0).unwrap_or_default());
+    log::info!("{}|{}", ip, String::from_utf8_lossy(&body));
 
-    match handler::handle_request_with_storage(
-        &body.0,
+    match handler::handle_body(
+        &body,
         &state.dispatcher,
         &state.proxy,
         Some(&state.script_storage),
-    ).await {
-        Ok(response) => HttpResponse::Ok().json(response),
-        Err(e) => {
-            log::error!("Error handling request: {:?}", e);
-            HttpResponse::Ok().json(JsonRpcResponse::error(
-                INTERNAL_ERROR,
-                e.to_string(),
-                body.0.id.clone(),
-            ))
-        }
+        state.limits,
+    )
+    .await
+    {
+        Some(response) => HttpResponse::Ok().json(response),
+        // Only notifications: JSON-RPC sends nothing back
+        None => HttpResponse::NoContent().finish(),
     }
 }
 
+async fn handle_ws(
+    req: HttpRequest,
+    body: web::Payload,
+    state: web::Data<AppState>,
+) -> actix_web::Result<HttpResponse> {
+    ws::serve(&req, body, state.dispatcher.clone(), &state.heads)
+}
+
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
     env_logger::init_from_env(env_logger::Env::new().default_filter_or("info"));
@@ -80,12 +90,15 @@ async fn main() -> std::io::Result<()> {
     let proxy = Arc::new(ProxyClient::new(config.clone()));
 
     // Create core dispatcher with reqwest backends
-    let dispatcher = Arc::new(alkanes_rpc_core::RpcDispatcher::new(
-        backends::ReqwestBitcoinBackend::new(client.clone(), &config),
-        backends::ReqwestMetashrewBackend::new(client.clone(), &config),
-        backends::ReqwestEsploraBackend::new(client.clone(), &config),
-        backends::ReqwestOrdBackend::new(client.clone(), &config),
-    ));
+    let dispatcher = Arc::new(handler::build_dispatcher(&client, &config));
+    let limits = handler::BatchLimits {
+        max_size: config.max_batch_size,
+        concurrency: config.batch_concurrency,
+    };
+    let heads = ws::spawn_head_watcher(
+        dispatcher.clone(),
+        Duration::from_millis(config.ws_poll_interval_ms),
+    );
 
     let server_host = config.server_host.clone();
     let server_port = config.server_port;
@@ -102,11 +115,14 @@ async fn main() -> std::io::Result<()> {
                 dispatcher: dispatcher.clone(),
                 proxy: proxy.clone(),
                 script_storage: script_storage.clone(),
+                limits,
+                heads: heads.clone(),
             }))
-            .app_data(web::JsonConfig::default().limit(100 * 1024 * 1024))
+            .app_data(web::PayloadConfig::new(100 * 1024 * 1024))
             .wrap(cors)
             .wrap(Logger::default())
             .route("/", web::post().to(handle_jsonrpc))
+            .route("/ws", web::get().to(handle_ws))
             .route("/{tail:.*}", web::post().to(handle_jsonrpc))
     })
     .bind((server_host.as_str(), server_port))?
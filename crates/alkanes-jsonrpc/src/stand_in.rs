@@ -0,0 +1,214 @@
+//! A local stand-in for metashrew and esplora, serving just enough of their
+//! APIs to exercise batches and `/ws` subscriptions end to end.
+
+use actix_web::{rt, web, App, HttpResponse, HttpServer};
+use alkanes_cli_common::proto::{alkanes, protorune};
+use prost::Message;
+use reqwest::Client;
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::config::Config;
+use crate::handler::ProdDispatcher;
+
+#[derive(Default)]
+struct State {
+    height: Mutex<u64>,
+    /// Alkanes invoked per block height, one trace each.
+    traces: Mutex<BTreeMap<u64, Vec<(u128, u128)>>>,
+    /// Amount of alkane 2:1 held by every address.
+    balance: Mutex<u128>,
+    in_flight: AtomicUsize,
+    max_in_flight: AtomicUsize,
+}
+
+pub struct StandIn {
+    url: String,
+    state: Arc<State>,
+}
+
+impl StandIn {
+    pub async fn start() -> Self {
+        let state = Arc::new(State::default());
+        let data = web::Data::from(state.clone());
+        let server = HttpServer::new(move || {
+            App::new()
+                .app_data(data.clone())
+                .route("/", web::post().to(rpc))
+                .route("/address/{address}/utxo", web::get().to(utxo))
+        })
+        .workers(1)
+        .bind(("127.0.0.1", 0))
+        .unwrap();
+        let url = format!("http://{}", server.addrs()[0]);
+        rt::spawn(server.run());
+        Self { url, state }
+    }
+
+    /// A dispatcher whose metashrew and esplora backends point here.
+    pub fn dispatcher(&self) -> ProdDispatcher {
+        let mut config = Config::from_env();
+        config.metashrew_url = self.url.clone();
+        config.esplora_url = self.url.clone();
+        let client = Client::new();
+        crate::handler::build_dispatcher(&client, &config)
+    }
+
+    pub fn set_height(&self, height: u64) {
+        *self.state.height.lock().unwrap() = height;
+    }
+
+    pub fn set_traces(&self, height: u64, alkanes: &[(u128, u128)]) {
+        self.state
+            .traces
+            .lock()
+            .unwrap()
+            .insert(height, alkanes.to_vec());
+    }
+
+    pub fn set_balance(&self, balance: u128) {
+        *self.state.balance.lock().unwrap() = balance;
+    }
+
+    /// Most `metashrew_sleep` calls seen running at once.
+    pub fn max_in_flight(&self) -> usize {
+        self.state.max_in_flight.load(Ordering::SeqCst)
+    }
+}
+
+async fn rpc(body: web::Json<Value>, state: web::Data<State>) -> HttpResponse {
+    let id = body["id"].clone();
+    let params = body["params"].as_array().cloned().unwrap_or_default();
+    let result = match body["method"].as_str().unwrap_or_default() {
+        "metashrew_height" => json!(*state.height.lock().unwrap()),
+        "metashrew_view" => match params.first().and_then(|v| v.as_str()) {
+            Some("traceblock") => {
+                let height = params
+                    .get(2)
+                    .and_then(|v| v.as_str())
+                    .and_then(|s| s.parse().ok());
+                json!(hex_of(&block_traces(&state, height.unwrap_or_default())))
+            }
+            Some("protorunesbyoutpoint") => json!(hex_of(&outpoint_response(&state))),
+            _ => json!("0x"),
+        },
+        // Holds the request open for params[0] ms, then answers params[1].
+        "metashrew_sleep" => {
+            let now = state.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            state.max_in_flight.fetch_max(now, Ordering::SeqCst);
+            let ms = params.first().and_then(|v| v.as_u64()).unwrap_or(0);
+            rt::time::sleep(Duration::from_millis(ms)).await;
+            state.in_flight.fetch_sub(1, Ordering::SeqCst);
+            params.get(1).cloned().unwrap_or(Value::Null)
+        }
+        other => {
+            return HttpResponse::Ok().json(json!({
+                "jsonrpc": "2.0",
+                "error": { "code": -32601, "message": format!("stand-in has no {}", other) },
+                "id": id,
+            }))
+        }
+    };
+    HttpResponse::Ok().json(json!({ "jsonrpc": "2.0", "result": result, "id": id }))
+}
+
+async fn utxo(state: web::Data<State>) -> HttpResponse {
+    if *state.balance.lock().unwrap() == 0 {
+        return HttpResponse::Ok().json(json!([]));
+    }
+    HttpResponse::Ok().json(json!([{ "txid": "ab".repeat(32), "vout": 0, "value": 546 }]))
+}
+
+fn hex_of<M: Message>(message: &M) -> String {
+    format!("0x{}", hex::encode(message.encode_to_vec()))
+}
+
+fn alkane_id(block: u128, tx: u128) -> alkanes::AlkaneId {
+    alkanes::AlkaneId {
+        block: Some(alkanes::Uint128 {
+            lo: block as u64,
+            hi: (block >> 64) as u64,
+        }),
+        tx: Some(alkanes::Uint128 {
+            lo: tx as u64,
+            hi: (tx >> 64) as u64,
+        }),
+    }
+}
+
+fn block_traces(state: &State, height: u64) -> alkanes::AlkanesBlockTraceEvent {
+    let invoked = state
+        .traces
+        .lock()
+        .unwrap()
+        .get(&height)
+        .cloned()
+        .unwrap_or_default();
+    let events = invoked
+        .into_iter()
+        .enumerate()
+        .map(|(txindex, (block, tx))| {
+            let enter = alkanes::AlkanesEnterContext {
+                call_type: alkanes::AlkanesTraceCallType::Call as i32,
+                context: Some(alkanes::TraceContext {
+                    inner: Some(alkanes::Context {
+                        myself: Some(alkane_id(block, tx)),
+                        ..Default::default()
+                    }),
+                    fuel: 1000,
+                }),
+            };
+            alkanes::AlkanesBlockEvent {
+                traces: Some(alkanes::AlkanesTrace {
+                    events: vec![alkanes::AlkanesTraceEvent {
+                        event: Some(alkanes::alkanes_trace_event::Event::EnterContext(enter)),
+                    }],
+                }),
+                outpoint: Some(alkanes::Outpoint {
+                    txid: vec![txindex as u8; 32],
+                    vout: 3,
+                }),
+                txindex: txindex as u64,
+            }
+        })
+        .collect();
+    alkanes::AlkanesBlockTraceEvent { events }
+}
+
+fn outpoint_response(state: &State) -> protorune::OutpointResponse {
+    let balance = *state.balance.lock().unwrap();
+    let uint128 = |v: u128| protorune::Uint128 {
+        lo: v as u64,
+        hi: (v >> 64) as u64,
+    };
+    let entries = if balance > 0 {
+        vec![protorune::BalanceSheetItem {
+            rune: Some(protorune::Rune {
+                rune_id: Some(protorune::ProtoruneRuneId {
+                    height: Some(uint128(2)),
+                    txindex: Some(uint128(1)),
+                }),
+                ..Default::default()
+            }),
+            balance: Some(uint128(balance)),
+        }]
+    } else {
+        vec![]
+    };
+    protorune::OutpointResponse {
+        balances: Some(protorune::BalanceSheet { entries }),
+        outpoint: Some(protorune::Outpoint {
+            txid: vec![0; 32],
+            vout: 0,
+        }),
+        output: Some(protorune::Output {
+            script: vec![],
+            value: 546,
+        }),
+        height: 0,
+        txindex: 0,
+    }
+}
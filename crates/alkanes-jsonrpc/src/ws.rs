@@ -0,0 +1,499 @@
+use actix_web::{rt, web, HttpRequest, HttpResponse};
+use actix_ws::{Message, MessageStream, Session};
+use alkanes_rpc_core::types::*;
+use anyhow::{anyhow, Result};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::broadcast;
+
+use crate::handler::ProdDispatcher;
+
+/// Sender side of the new-heights feed shared by every `/ws` connection.
+pub type Heads = broadcast::Sender<u64>;
+
+/// Most heights announced after a single poll. A watcher that fell further
+/// behind (or a fresh chain catching up) announces only the latest ones.
+const MAX_HEADS_PER_POLL: u64 = 100;
+
+/// Poll metashrew's height every `interval` and announce each new height on
+/// the returned channel. The first observed height is the baseline and is not
+/// announced.
+pub fn spawn_head_watcher(dispatcher: Arc<ProdDispatcher>, interval: Duration) -> Heads {
+    let (heads, _) = broadcast::channel(256);
+    let sender = heads.clone();
+    rt::spawn(async move {
+        let mut last: Option<u64> = None;
+        loop {
+            match metashrew_height(&dispatcher).await {
+                Ok(height) => {
+                    if let Some(prev) = last {
+                        let from = (prev + 1).max(height.saturating_sub(MAX_HEADS_PER_POLL - 1));
+                        for h in from..=height {
+                            let _ = sender.send(h);
+                        }
+                    }
+                    last = Some(height);
+                }
+                Err(e) => log::warn!("ws: failed to read metashrew height: {}", e),
+            }
+            rt::time::sleep(interval).await;
+        }
+    });
+    heads
+}
+
+/// Upgrade `req` to a WebSocket serving the `subscribe_*` methods.
+pub fn serve(
+    req: &HttpRequest,
+    body: web::Payload,
+    dispatcher: Arc<ProdDispatcher>,
+    heads: &Heads,
+) -> actix_web::Result<HttpResponse> {
+    let (response, session, stream) = actix_ws::handle(req, body)?;
+    rt::spawn(run_session(
+        session,
+        stream,
+        Subscriptions::new(dispatcher),
+        heads.subscribe(),
+    ));
+    Ok(response)
+}
+
+async fn run_session(
+    mut session: Session,
+    mut stream: MessageStream,
+    mut subscriptions: Subscriptions,
+    mut heads: broadcast::Receiver<u64>,
+) {
+    loop {
+        tokio::select! {
+            msg = stream.recv() => match msg {
+                Some(Ok(Message::Text(text))) => {
+                    let reply = subscriptions.handle_text(&text).await;
+                    if session.text(reply.to_string()).await.is_err() {
+                        return;
+                    }
+                }
+                Some(Ok(Message::Ping(bytes))) => {
+                    if session.pong(&bytes).await.is_err() {
+                        return;
+                    }
+                }
+                Some(Ok(Message::Close(reason))) => {
+                    let _ = session.close(reason).await;
+                    return;
+                }
+                Some(Ok(_)) => {}
+                Some(Err(_)) | None => break,
+            },
+            head = heads.recv() => match head {
+                Ok(height) => {
+                    for notification in subscriptions.on_head(height).await {
+                        if session.text(notification.to_string()).await.is_err() {
+                            return;
+                        }
+                    }
+                }
+                Err(broadcast::error::RecvError::Lagged(missed)) => {
+                    log::warn!("ws: connection fell {} heads behind", missed);
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+        }
+    }
+    let _ = session.close(None).await;
+}
+
+enum Subscription {
+    NewHeads,
+    AlkaneTraces { block: String, tx: String },
+    AddressBalances { address: String, last: Value },
+}
+
+/// One connection's subscriptions, keyed by the id handed back to the client.
+struct Subscriptions {
+    dispatcher: Arc<ProdDispatcher>,
+    active: BTreeMap<String, Subscription>,
+    next_id: u64,
+}
+
+impl Subscriptions {
+    fn new(dispatcher: Arc<ProdDispatcher>) -> Self {
+        Self {
+            dispatcher,
+            active: BTreeMap::new(),
+            next_id: 1,
+        }
+    }
+
+    async fn handle_text(&mut self, text: &str) -> Value {
+        let request: JsonRpcRequest = match serde_json::from_str(text) {
+            Ok(request) => request,
+            Err(e) => {
+                return json!(JsonRpcResponse::error(
+                    PARSE_ERROR,
+                    format!("Invalid request: {}", e),
+                    Value::Null,
+                ))
+            }
+        };
+        const METHODS: &[&str] = &[
+            "subscribe_newHeads",
+            "subscribe_alkaneTraces",
+            "subscribe_addressBalances",
+            "unsubscribe",
+        ];
+        if !METHODS.contains(&request.method.as_str()) {
+            return json!(JsonRpcResponse::error(
+                METHOD_NOT_FOUND,
+                format!("Method {} is not available over /ws", request.method),
+                request.id,
+            ));
+        }
+        let response = match self.handle(&request).await {
+            Ok(result) => JsonRpcResponse::success(result, request.id.clone()),
+            Err(e) => JsonRpcResponse::error(INVALID_PARAMS, e.to_string(), request.id.clone()),
+        };
+        json!(response)
+    }
+
+    async fn handle(&mut self, request: &JsonRpcRequest) -> Result<Value> {
+        let subscription = match request.method.as_str() {
+            "subscribe_newHeads" => Subscription::NewHeads,
+            "subscribe_alkaneTraces" => {
+                let (block, tx) = parse_alkane_id(request.params.first())?;
+                Subscription::AlkaneTraces { block, tx }
+            }
+            "subscribe_addressBalances" => {
+                let address = request
+                    .params
+                    .first()
+                    .and_then(|v| v.as_str())
+                    .ok_or_else(|| anyhow!("subscribe_addressBalances requires an address"))?
+                    .to_string();
+                let last = self.balances(&address).await?;
+                Subscription::AddressBalances { address, last }
+            }
+            "unsubscribe" => {
+                let id = request
+                    .params
+                    .first()
+                    .and_then(|v| v.as_str())
+                    .ok_or_else(|| anyhow!("unsubscribe requires a subscription id"))?;
+                return Ok(json!(self.active.remove(id).is_some()));
+            }
+            _ => unreachable!("checked in handle_text"),
+        };
+        let id = format!("0x{:x}", self.next_id);
+        self.next_id += 1;
+        self.active.insert(id.clone(), subscription);
+        Ok(json!(id))
+    }
+
+    /// Notifications owed to this connection for the block at `height`.
+    async fn on_head(&mut self, height: u64) -> Vec<Value> {
+        let wants_traces = self
+            .active
+            .values()
+            .any(|s| matches!(s, Subscription::AlkaneTraces { .. }));
+        let traces = if wants_traces {
+            match self.call("alkanes_traceblock", vec![json!(height)]).await {
+                Ok(Value::Array(traces)) => traces,
+                Ok(_) => vec![],
+                Err(e) => {
+                    log::warn!("ws: traceblock {} failed: {}", height, e);
+                    vec![]
+                }
+            }
+        } else {
+            vec![]
+        };
+
+        let mut notifications = Vec::new();
+        let mut balance_updates = Vec::new();
+        for (id, subscription) in self.active.iter() {
+            let result = match subscription {
+                Subscription::NewHeads => json!({ "height": height }),
+                Subscription::AlkaneTraces { block, tx } => {
+                    let matching: Vec<&Value> =
+                        traces.iter().filter(|t| involves(t, block, tx)).collect();
+                    if matching.is_empty() {
+                        continue;
+                    }
+                    json!({ "height": height, "traces": matching })
+                }
+                Subscription::AddressBalances { address, last } => {
+                    match self.balances(address).await {
+                        Ok(current) if &current != last => {
+                            balance_updates.push((id.clone(), current.clone()));
+                            json!({ "height": height, "address": address, "balances": current })
+                        }
+                        Ok(_) => continue,
+                        Err(e) => {
+                            log::warn!("ws: balances for {} failed: {}", address, e);
+                            continue;
+                        }
+                    }
+                }
+            };
+            notifications.push(json!({
+                "jsonrpc": "2.0",
+                "method": "subscription",
+                "params": { "subscription": id, "result": result }
+            }));
+        }
+        for (id, current) in balance_updates {
+            if let Some(Subscription::AddressBalances { last, .. }) = self.active.get_mut(&id) {
+                *last = current;
+            }
+        }
+        notifications
+    }
+
+    async fn balances(&self, address: &str) -> Result<Value> {
+        self.call(
+            "alkanes_protorunesbyaddress",
+            vec![json!({ "address": address, "protocolTag": "1" })],
+        )
+        .await
+    }
+
+    async fn call(&self, method: &str, params: Vec<Value>) -> Result<Value> {
+        let request = JsonRpcRequest {
+            jsonrpc: "2.0".to_string(),
+            method: method.to_string(),
+            params,
+            id: json!(0),
+        };
+        match self.dispatcher.dispatch(&request).await? {
+            JsonRpcResponse::Success { result, .. } => Ok(result),
+            JsonRpcResponse::Error { error, .. } => Err(anyhow!("{}", error.message)),
+        }
+    }
+}
+
+async fn metashrew_height(dispatcher: &ProdDispatcher) -> Result<u64> {
+    let request = JsonRpcRequest {
+        jsonrpc: "2.0".to_string(),
+        method: "metashrew_height".to_string(),
+        params: vec![],
+        id: json!(0),
+    };
+    match dispatcher.dispatch(&request).await? {
+        JsonRpcResponse::Success { result, .. } => result
+            .as_u64()
+            .or_else(|| result.as_str().and_then(|s| s.parse().ok()))
+            .ok_or_else(|| anyhow!("unexpected metashrew_height result {}", result)),
+        JsonRpcResponse::Error { error, .. } => Err(anyhow!("{}", error.message)),
+    }
+}
+
+/// Accepts `"2:1"` or `{"block": 2, "tx": 1}` (numbers or strings).
+fn parse_alkane_id(param: Option<&Value>) -> Result<(String, String)> {
+    let (block, tx) = match param {
+        Some(Value::String(s)) => {
+            let (block, tx) = s
+                .split_once(':')
+                .ok_or_else(|| anyhow!("alkane id must look like <block>:<tx>"))?;
+            (json!(block), json!(tx))
+        }
+        Some(Value::Object(obj)) => (
+            obj.get("block").cloned().unwrap_or_default(),
+            obj.get("tx").cloned().unwrap_or_default(),
+        ),
+        _ => return Err(anyhow!("subscribe_alkaneTraces requires an alkane id")),
+    };
+    match (
+        alkanes_rpc_core::codec::parse_u128(&block),
+        alkanes_rpc_core::codec::parse_u128(&tx),
+    ) {
+        (Some(block), Some(tx)) => Ok((block.to_string(), tx.to_string())),
+        _ => Err(anyhow!("invalid alkane id")),
+    }
+}
+
+/// Whether a decoded trace mentions the alkane anywhere (as the callee, the
+/// caller, a created alkane, or a transferred token).
+fn involves(value: &Value, block: &str, tx: &str) -> bool {
+    match value {
+        Value::Object(obj) => {
+            let is_id = obj.get("block").and_then(|v| v.as_str()) == Some(block)
+                && obj.get("tx").and_then(|v| v.as_str()) == Some(tx);
+            is_id || obj.values().any(|v| involves(v, block, tx))
+        }
+        Value::Array(items) => items.iter().any(|v| involves(v, block, tx)),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stand_in::StandIn;
+    use actix_web::{App, HttpServer};
+    use futures::{SinkExt, StreamExt};
+    use tokio_tungstenite::tungstenite::Message as WsMessage;
+
+    type Client = tokio_tungstenite::WebSocketStream<
+        tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>,
+    >;
+
+    async fn start_ws(stand_in: &StandIn) -> String {
+        let dispatcher = Arc::new(stand_in.dispatcher());
+        let heads = spawn_head_watcher(dispatcher.clone(), Duration::from_millis(20));
+        let server = HttpServer::new(move || {
+            let dispatcher = dispatcher.clone();
+            let heads = heads.clone();
+            App::new().route(
+                "/ws",
+                web::get().to(move |req: HttpRequest, body: web::Payload| {
+                    let dispatcher = dispatcher.clone();
+                    let heads = heads.clone();
+                    async move { serve(&req, body, dispatcher, &heads) }
+                }),
+            )
+        })
+        .workers(1)
+        .bind(("127.0.0.1", 0))
+        .unwrap();
+        let addr = server.addrs()[0];
+        rt::spawn(server.run());
+        format!("ws://{}/ws", addr)
+    }
+
+    async fn request(client: &mut Client, method: &str, params: Value) -> Value {
+        let body = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params });
+        client
+            .send(WsMessage::Text(body.to_string()))
+            .await
+            .unwrap();
+        next_json(client).await
+    }
+
+    async fn next_json(client: &mut Client) -> Value {
+        loop {
+            let msg = tokio::time::timeout(Duration::from_secs(5), client.next())
+                .await
+                .expect("timed out waiting for a ws message")
+                .unwrap()
+                .unwrap();
+            if let WsMessage::Text(text) = msg {
+                return serde_json::from_str(&text).unwrap();
+            }
+        }
+    }
+
+    #[actix_web::test]
+    async fn test_subscriptions_push_on_new_heights() {
+        let stand_in = StandIn::start().await;
+        stand_in.set_height(100);
+        let url = start_ws(&stand_in).await;
+        let (mut client, _) = tokio_tungstenite::connect_async(url).await.unwrap();
+
+        let heads = request(&mut client, "subscribe_newHeads", json!([])).await;
+        let heads_id = heads["result"].as_str().unwrap().to_string();
+        let traces = request(&mut client, "subscribe_alkaneTraces", json!(["2:1"])).await;
+        let traces_id = traces["result"].as_str().unwrap().to_string();
+        let balances = request(
+            &mut client,
+            "subscribe_addressBalances",
+            json!(["bcrt1qstandin"]),
+        )
+        .await;
+        let balances_id = balances["result"].as_str().unwrap().to_string();
+        assert_ne!(heads_id, traces_id);
+
+        // Block 101 calls 2:1 and credits the address; block 102 only calls 2:7.
+        stand_in.set_traces(101, &[(2, 1), (2, 7)]);
+        stand_in.set_balance(500);
+        stand_in.set_traces(102, &[(2, 7)]);
+        stand_in.set_height(102);
+
+        let mut seen: Vec<(String, Value)> = Vec::new();
+        while seen.len() < 4 {
+            let note = next_json(&mut client).await;
+            assert_eq!(note["method"], "subscription");
+            let id = note["params"]["subscription"].as_str().unwrap().to_string();
+            seen.push((id, note["params"]["result"].clone()));
+        }
+
+        let heights: Vec<&Value> = seen
+            .iter()
+            .filter(|(id, _)| *id == heads_id)
+            .map(|(_, r)| &r["height"])
+            .collect();
+        assert_eq!(heights, vec![&json!(101), &json!(102)]);
+
+        let traces: Vec<&Value> = seen
+            .iter()
+            .filter(|(id, _)| *id == traces_id)
+            .map(|(_, r)| r)
+            .collect();
+        assert_eq!(traces.len(), 1);
+        assert_eq!(traces[0]["height"], 101);
+        assert_eq!(traces[0]["traces"].as_array().unwrap().len(), 1);
+        assert_eq!(
+            traces[0]["traces"][0]["events"][0]["data"]["context"]["myself"]["tx"],
+            "1"
+        );
+
+        let balances: Vec<&Value> = seen
+            .iter()
+            .filter(|(id, _)| *id == balances_id)
+            .map(|(_, r)| r)
+            .collect();
+        assert_eq!(balances.len(), 1);
+        assert_eq!(balances[0]["height"], 101);
+        assert_eq!(
+            balances[0]["balances"]["outpoints"][0]["balance_sheet"]["cached"]["balances"][0]
+                ["amount"],
+            500
+        );
+    }
+
+    #[actix_web::test]
+    async fn test_unsubscribe_and_unknown_methods() {
+        let stand_in = StandIn::start().await;
+        stand_in.set_height(10);
+        let url = start_ws(&stand_in).await;
+        let (mut client, _) = tokio_tungstenite::connect_async(url).await.unwrap();
+
+        let sub = request(&mut client, "subscribe_newHeads", json!([])).await;
+        let id = sub["result"].clone();
+        assert_eq!(
+            request(&mut client, "unsubscribe", json!([id])).await["result"],
+            true
+        );
+        assert_eq!(
+            request(&mut client, "unsubscribe", json!([id])).await["result"],
+            false
+        );
+
+        let bad = request(&mut client, "subscribe_alkaneTraces", json!(["not-an-id"])).await;
+        assert_eq!(bad["error"]["code"], INVALID_PARAMS);
+        let other = request(&mut client, "alkanes_simulate", json!([])).await;
+        assert_eq!(other["error"]["code"], METHOD_NOT_FOUND);
+
+        // Nothing is pushed once the only subscription is gone.
+        stand_in.set_height(11);
+        let quiet = tokio::time::timeout(Duration::from_millis(300), client.next()).await;
+        assert!(quiet.is_err());
+    }
+
+    #[test]
+    fn test_involves_matches_any_nested_id() {
+        let trace = json!({
+            "events": [
+                { "event": "invoke", "data": { "context": {
+                    "myself": { "block": "2", "tx": "7" },
+                    "incomingAlkanes": [{ "id": { "block": "2", "tx": "1" }, "value": "5" }]
+                } } }
+            ]
+        });
+        assert!(involves(&trace, "2", "7"));
+        assert!(involves(&trace, "2", "1"));
+        assert!(!involves(&trace, "2", "3"));
+    }
+}
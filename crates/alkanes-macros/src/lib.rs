@@ -41,6 +41,11 @@ fn extract_returns_attr(attrs: &[Attribute]) -> Option<String> {
     None
 }
 
+/// Whether a variant is marked `#[nonreentrant]`
+fn has_nonreentrant_attr(attrs: &[Attribute]) -> bool {
+    attrs.iter().any(|attr| attr.path.is_ident("nonreentrant"))
+}
+
 /// Convert a variant name to a method name (snake_case)
 fn variant_to_method_name(variant_name: &Ident) -> String {
     let name = variant_name.to_string();
@@ -279,7 +284,10 @@ fn get_type_string(ty: &Type) -> String {
 }
 
 /// Derive macro for MessageDispatch trait
-#[proc_macro_derive(MessageDispatch, attributes(opcode, returns))]
+///
+/// Variants marked `#[nonreentrant]` dispatch under
+/// `alkanes_runtime::reentrancy::nonreentrant`.
+#[proc_macro_derive(MessageDispatch, attributes(opcode, returns, nonreentrant))]
 pub fn derive_message_dispatch(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
     let name = &input.ident;
@@ -363,6 +371,13 @@ pub fn derive_message_dispatch(input: TokenStream) -> TokenStream {
         let variant_name = &variant.ident;
         let method_name_str = variant_to_method_name(variant_name);
         let method_name = format_ident!("{}", method_name_str);
+        let guarded = |call: proc_macro2::TokenStream| {
+            if has_nonreentrant_attr(&variant.attrs) {
+                quote! { alkanes_runtime::reentrancy::nonreentrant(|| #call) }
+            } else {
+                call
+            }
+        };
 
         match &variant.fields {
             Fields::Named(fields_named) => {
@@ -382,11 +397,12 @@ pub fn derive_message_dispatch(input: TokenStream) -> TokenStream {
                 } else {
                     quote! {}
                 };
+                let call = guarded(quote! { responder.#method_name(#param_pass) });
 
                 quote! {
                     Self::#variant_name #pattern => {
                         // Call the method directly on the responder
-                        responder.#method_name(#param_pass)
+                        #call
                     }
                 }
             },
@@ -396,10 +412,11 @@ pub fn derive_message_dispatch(input: TokenStream) -> TokenStream {
             },
             Fields::Unit => {
                 // Handle unit variants (no fields)
+                let call = guarded(quote! { responder.#method_name() });
                 quote! {
                     Self::#variant_name => {
                         // Call the method directly on the responder
-                        responder.#method_name()
+                        #call
                     }
                 }
             },
@@ -509,3 +526,273 @@ pub fn derive_message_dispatch(input: TokenStream) -> TokenStream {
 
     TokenStream::from(expanded)
 }
+
+/// Extracts the error code attribute from a variant's attributes
+fn extract_error_code_attr(attrs: &[Attribute]) -> u32 {
+    for attr in attrs {
+        if attr.path.is_ident("error") {
+            if let Ok(Meta::List(meta_list)) = attr.parse_meta() {
+                if let Some(NestedMeta::Lit(Lit::Int(lit_int))) = meta_list.nested.first() {
+                    if let Ok(value) = lit_int.base10_parse::<u32>() {
+                        return value;
+                    }
+                }
+            }
+        }
+    }
+    panic!("Missing or invalid #[error(code)] attribute");
+}
+
+/// Derive macro for AlkaneError trait
+///
+/// Each variant carries a numeric code in `#[error(code)]`. The enum's
+/// `Display` renders the structured error message decoded by
+/// `alkanes_support::error::StructuredError`, so returning one of its variants
+/// as an error reverts with a code, a name and typed params. The enum must
+/// also implement `Debug`.
+#[proc_macro_derive(AlkaneError, attributes(error))]
+pub fn derive_alkane_error(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let name = &input.ident;
+
+    let variants = match &input.data {
+        Data::Enum(data) => &data.variants,
+        _ => panic!("AlkaneError can only be derived for enums"),
+    };
+
+    let mut codes = std::collections::BTreeSet::new();
+    let mut display_arms = Vec::new();
+    let mut code_arms = Vec::new();
+    let mut name_arms = Vec::new();
+    let mut error_json_entries = Vec::new();
+
+    for variant in variants.iter() {
+        let variant_name = &variant.ident;
+        let variant_name_str = variant_name.to_string();
+        let code = extract_error_code_attr(&variant.attrs);
+        if !codes.insert(code) {
+            panic!("Duplicate error code {} on variant {}", code, variant_name);
+        }
+
+        let (pattern, params, params_json) = match &variant.fields {
+            Fields::Named(fields_named) => {
+                let field_names: Vec<_> = fields_named.named.iter()
+                    .map(|field| field.ident.as_ref().unwrap())
+                    .collect();
+                let params = field_names.iter().map(|field_name| {
+                    let field_name_str = field_name.to_string();
+                    quote! {
+                        (#field_name_str, {
+                            let mut rendered = String::new();
+                            alkanes_support::error::ErrorParam::write_param(#field_name, &mut rendered);
+                            rendered
+                        })
+                    }
+                });
+                let params_json = fields_named.named.iter()
+                    .map(|field| format!(
+                        "{{ \"type\": \"{}\", \"name\": \"{}\" }}",
+                        get_type_string(&field.ty),
+                        field.ident.as_ref().unwrap()
+                    ))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                (
+                    quote! { { #(#field_names),* } },
+                    quote! { #(#params),* },
+                    params_json,
+                )
+            },
+            Fields::Unnamed(_) => {
+                panic!("Tuple variants are not supported for AlkaneError. Use named fields (struct variants) instead for variant {}", variant_name);
+            },
+            Fields::Unit => (quote! {}, quote! {}, String::new()),
+        };
+
+        display_arms.push(quote! {
+            Self::#variant_name #pattern => alkanes_support::error::encode_error(
+                #code,
+                #variant_name_str,
+                &[#params],
+            )
+        });
+        code_arms.push(quote! { Self::#variant_name { .. } => #code });
+        name_arms.push(quote! { Self::#variant_name { .. } => #variant_name_str });
+        error_json_entries.push(format!(
+            "{{ \"name\": \"{}\", \"code\": {}, \"params\": [{}] }}",
+            variant_name_str, code, params_json
+        ));
+    }
+
+    let errors_json_str = format!("[{}]", error_json_entries.join(", "));
+
+    let expanded = quote! {
+        impl core::fmt::Display for #name {
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                let message = match self {
+                    #(#display_arms),*
+                };
+                f.write_str(&message)
+            }
+        }
+
+        impl std::error::Error for #name {}
+
+        impl alkanes_runtime::error::AlkaneError for #name {
+            fn code(&self) -> u32 {
+                match self {
+                    #(#code_arms),*
+                }
+            }
+
+            fn name(&self) -> &'static str {
+                match self {
+                    #(#name_arms),*
+                }
+            }
+
+            fn export_errors() -> Vec<u8> {
+                #errors_json_str.as_bytes().to_vec()
+            }
+        }
+    };
+
+    TokenStream::from(expanded)
+}
+
+/// Derive macro for the Storable trait
+///
+/// Fields are encoded in declaration order with their own `Storable`
+/// encodings, so a struct can be kept in any typed storage collection.
+#[proc_macro_derive(Storable)]
+pub fn derive_storable(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let fields = match &input.data {
+        Data::Struct(data) => &data.fields,
+        _ => panic!("Storable can only be derived for structs"),
+    };
+
+    let (encode, decode) = match fields {
+        Fields::Named(named) => {
+            let names: Vec<_> = named.named.iter().map(|f| &f.ident).collect();
+            (
+                quote! { #(alkanes_runtime::storage::Storable::encode(&self.#names, out);)* },
+                quote! { Self { #(#names: alkanes_runtime::storage::Storable::decode(input)?,)* } },
+            )
+        }
+        Fields::Unnamed(unnamed) => {
+            let indices: Vec<_> = (0..unnamed.unnamed.len()).map(syn::Index::from).collect();
+            let decoders = indices
+                .iter()
+                .map(|_| quote! { alkanes_runtime::storage::Storable::decode(input)? });
+            (
+                quote! { #(alkanes_runtime::storage::Storable::encode(&self.#indices, out);)* },
+                quote! { Self(#(#decoders,)*) },
+            )
+        }
+        Fields::Unit => (quote! {}, quote! { Self }),
+    };
+
+    let expanded = quote! {
+        impl #impl_generics alkanes_runtime::storage::Storable for #name #ty_generics #where_clause {
+            #[allow(unused_variables)]
+            fn encode(&self, out: &mut Vec<u8>) {
+                #encode
+            }
+
+            #[allow(unused_variables)]
+            fn decode(input: &mut &[u8]) -> anyhow::Result<Self> {
+                Ok(#decode)
+            }
+        }
+    };
+
+    TokenStream::from(expanded)
+}
+
+/// Extracts the storage key from `#[key("...")]`, defaulting to
+/// `/<method name>`
+fn extract_storage_key(method: &syn::TraitItemMethod) -> String {
+    for attr in &method.attrs {
+        if attr.path.is_ident("key") {
+            if let Ok(Meta::List(meta_list)) = attr.parse_meta() {
+                if let Some(NestedMeta::Lit(Lit::Str(lit_str))) = meta_list.nested.first() {
+                    return lit_str.value();
+                }
+            }
+            panic!("Invalid #[key(\"...\")] attribute on {}", method.sig.ident);
+        }
+    }
+    format!("/{}", method.sig.ident)
+}
+
+/// Attribute macro generating typed storage accessors
+///
+/// Applied to a trait whose methods return `StorageValue`, `StorageVec` or
+/// `StorageMap`, it fills in each method without a body so the collection
+/// is anchored at its `#[key("...")]`, or `/<method name>` without one.
+/// Parameters after `&self` are `Storable` keys appended to the storage key,
+/// giving one collection per key. Implement the trait on a contract to get
+/// the accessors:
+///
+/// ```ignore
+/// #[storage]
+/// trait TokenStorage {
+///     #[key("/totalsupply")]
+///     fn total_supply(&self) -> StorageValue<u128>;
+///     fn allowance(&self, owner: AlkaneId) -> StorageMap<AlkaneId, u128>;
+/// }
+///
+/// impl TokenStorage for Token {}
+/// ```
+#[proc_macro_attribute]
+pub fn storage(_attr: TokenStream, item: TokenStream) -> TokenStream {
+    let mut item_trait = parse_macro_input!(item as syn::ItemTrait);
+
+    for trait_item in item_trait.items.iter_mut() {
+        let method = match trait_item {
+            syn::TraitItem::Method(method) if method.default.is_none() => method,
+            _ => continue,
+        };
+        let key = extract_storage_key(method);
+        method.attrs.retain(|attr| !attr.path.is_ident("key"));
+
+        let ret = match &method.sig.output {
+            syn::ReturnType::Type(_, ty) => ty.clone(),
+            syn::ReturnType::Default => {
+                panic!("Storage accessor {} must return a collection", method.sig.ident)
+            }
+        };
+
+        let mut keys = Vec::new();
+        for input in method.sig.inputs.iter() {
+            let pat_type = match input {
+                syn::FnArg::Receiver(_) => continue,
+                syn::FnArg::Typed(pat_type) => pat_type,
+            };
+            let arg = match &*pat_type.pat {
+                syn::Pat::Ident(pat_ident) => &pat_ident.ident,
+                _ => panic!("Storage key parameters must be plain identifiers"),
+            };
+            keys.push(match &*pat_type.ty {
+                Type::Reference(reference) => {
+                    let elem = &reference.elem;
+                    quote! { <#elem as alkanes_runtime::storage::Storable>::to_bytes(#arg) }
+                }
+                ty => quote! { <#ty as alkanes_runtime::storage::Storable>::to_bytes(&#arg) },
+            });
+        }
+
+        method.default = Some(syn::parse_quote! {
+            {
+                <#ret as alkanes_runtime::storage::StorageCollection>::at(#key, &[#(#keys),*])
+            }
+        });
+        method.semi_token = None;
+    }
+
+    TokenStream::from(quote! { #item_trait })
+}
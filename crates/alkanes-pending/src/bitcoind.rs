@@ -0,0 +1,107 @@
+//! The handful of bitcoind RPCs the projection needs: the best block, its
+//! header, and the mempool with its raw transactions.
+
+use anyhow::{anyhow, Context, Result};
+use bitcoin::consensus::deserialize;
+use bitcoin::{BlockHash, CompactTarget, Transaction, Txid};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+pub struct BitcoindClient {
+    client: reqwest::Client,
+    url: String,
+    user: String,
+    password: String,
+}
+
+/// The confirmed block the projection is built on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChainTip {
+    pub height: u32,
+    pub hash: BlockHash,
+    pub time: u32,
+    pub bits: CompactTarget,
+}
+
+/// A `getrawmempool true` entry, reduced to what ordering needs.
+#[derive(Debug, Clone, Deserialize)]
+pub struct MempoolEntry {
+    #[serde(default)]
+    pub time: u64,
+    /// Unconfirmed parents of the transaction.
+    #[serde(default)]
+    pub depends: Vec<Txid>,
+}
+
+#[derive(Deserialize)]
+struct BlockHeaderInfo {
+    height: u32,
+    time: u32,
+    bits: String,
+}
+
+impl BitcoindClient {
+    pub fn new(url: String, user: String, password: String) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            url,
+            user,
+            password,
+        }
+    }
+
+    async fn call(&self, method: &str, params: Value) -> Result<Value> {
+        let body = json!({
+            "jsonrpc": "1.0",
+            "id": "alkanes-pending",
+            "method": method,
+            "params": params,
+        });
+        let resp: Value = self
+            .client
+            .post(&self.url)
+            .basic_auth(&self.user, Some(&self.password))
+            .json(&body)
+            .send()
+            .await
+            .with_context(|| format!("bitcoind {}", method))?
+            .json()
+            .await
+            .with_context(|| format!("bitcoind {}: invalid response", method))?;
+        match resp.get("error") {
+            Some(error) if !error.is_null() => Err(anyhow!("bitcoind {}: {}", method, error)),
+            _ => Ok(resp.get("result").cloned().unwrap_or(Value::Null)),
+        }
+    }
+
+    pub async fn tip(&self) -> Result<ChainTip> {
+        let hash: BlockHash = serde_json::from_value(self.call("getbestblockhash", json!([])).await?)?;
+        let header: BlockHeaderInfo =
+            serde_json::from_value(self.call("getblockheader", json!([hash, true])).await?)?;
+        let bits = u32::from_str_radix(&header.bits, 16)
+            .with_context(|| format!("invalid bits '{}'", header.bits))?;
+        Ok(ChainTip {
+            height: header.height,
+            hash,
+            time: header.time,
+            bits: CompactTarget::from_consensus(bits),
+        })
+    }
+
+    pub async fn mempool(&self) -> Result<HashMap<Txid, MempoolEntry>> {
+        Ok(serde_json::from_value(
+            self.call("getrawmempool", json!([true])).await?,
+        )?)
+    }
+
+    /// `None` when bitcoind can't return the transaction, usually because
+    /// it left the mempool since it was listed.
+    pub async fn transaction(&self, txid: &Txid) -> Result<Option<Transaction>> {
+        let hex = match self.call("getrawtransaction", json!([txid, false])).await {
+            Ok(Value::String(hex)) => hex,
+            Ok(_) | Err(_) => return Ok(None),
+        };
+        Ok(Some(deserialize(&hex::decode(hex)?)?))
+    }
+}
@@ -0,0 +1,47 @@
+use anyhow::{Context, Result};
+use std::env;
+
+#[derive(Debug, Clone)]
+pub struct Config {
+    pub server_host: String,
+    pub server_port: u16,
+
+    pub bitcoin_rpc_url: String,
+    pub bitcoin_rpc_user: String,
+    pub bitcoin_rpc_password: String,
+
+    /// Confirmed-state indexer the projection reads through to.
+    pub metashrew_url: String,
+    /// The `alkanes.wasm` the confirmed indexer runs.
+    pub alkanes_wasm: String,
+
+    pub poll_interval_ms: u64,
+}
+
+impl Config {
+    pub fn from_env() -> Result<Self> {
+        Ok(Self {
+            server_host: env::var("SERVER_HOST").unwrap_or_else(|_| "0.0.0.0".to_string()),
+            server_port: env::var("SERVER_PORT")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(18889),
+
+            bitcoin_rpc_url: env::var("BITCOIN_RPC_URL")
+                .unwrap_or_else(|_| "http://localhost:8332".to_string()),
+            bitcoin_rpc_user: env::var("BITCOIN_RPC_USER")
+                .unwrap_or_else(|_| "bitcoinrpc".to_string()),
+            bitcoin_rpc_password: env::var("BITCOIN_RPC_PASSWORD")
+                .unwrap_or_else(|_| "bitcoinrpc".to_string()),
+
+            metashrew_url: env::var("METASHREW_URL")
+                .unwrap_or_else(|_| "http://localhost:8080".to_string()),
+            alkanes_wasm: env::var("ALKANES_WASM").context("ALKANES_WASM is not set")?,
+
+            poll_interval_ms: env::var("POLL_INTERVAL_MS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(1_000),
+        })
+    }
+}
@@ -0,0 +1,15 @@
+//! Mempool-projected alkanes state.
+//!
+//! `alkanes-pending` loads `alkanes.wasm` into the read-through
+//! `qubitcoin-indexer-fork` runtime, indexes the current mempool as one
+//! pending block on top of the confirmed tip and serves
+//! `protorunesbyaddress`, `protorunesbyoutpoint` and `trace` against the
+//! result. The [`watcher::Watcher`] rebuilds the projection from scratch
+//! whenever bitcoind reports a new tip or a different set of mempool
+//! transactions, so reorgs and evictions need no special handling.
+
+pub mod bitcoind;
+pub mod config;
+pub mod projection;
+pub mod server;
+pub mod watcher;
@@ -0,0 +1,54 @@
+use actix_web::{middleware::Logger, web, App, HttpResponse, HttpServer};
+use alkanes_pending::bitcoind::BitcoindClient;
+use alkanes_pending::config::Config;
+use alkanes_pending::projection::Projector;
+use alkanes_pending::server::handle_request;
+use alkanes_pending::watcher::{SharedProjection, Watcher};
+use alkanes_rpc_core::types::JsonRpcRequest;
+use qubitcoin_indexer_fork::HttpForkUpstream;
+use std::sync::Arc;
+use std::time::Duration;
+
+async fn handle_jsonrpc(
+    body: web::Json<JsonRpcRequest>,
+    projection: web::Data<SharedProjection>,
+) -> HttpResponse {
+    HttpResponse::Ok().json(handle_request(&projection, body.into_inner()).await)
+}
+
+#[actix_web::main]
+async fn main() -> anyhow::Result<()> {
+    env_logger::init_from_env(env_logger::Env::new().default_filter_or("info"));
+
+    let config = Config::from_env()?;
+
+    log::info!("Starting alkanes-pending server");
+    log::info!("Server: http://{}:{}", config.server_host, config.server_port);
+    log::info!("Bitcoin RPC: {}", config.bitcoin_rpc_url);
+    log::info!("Metashrew: {}", config.metashrew_url);
+    log::info!("Alkanes wasm: {}", config.alkanes_wasm);
+
+    let wasm = std::fs::read(&config.alkanes_wasm)?;
+    let upstream =
+        HttpForkUpstream::with_url(config.metashrew_url.clone()).map_err(anyhow::Error::msg)?;
+    let projector = Projector::new(&wasm, Arc::new(upstream))?;
+    let bitcoind = BitcoindClient::new(
+        config.bitcoin_rpc_url.clone(),
+        config.bitcoin_rpc_user.clone(),
+        config.bitcoin_rpc_password.clone(),
+    );
+    let watcher = Watcher::new(bitcoind, projector);
+    let projection = watcher.projection();
+    tokio::spawn(watcher.run(Duration::from_millis(config.poll_interval_ms)));
+
+    HttpServer::new(move || {
+        App::new()
+            .app_data(web::Data::new(projection.clone()))
+            .wrap(Logger::default())
+            .route("/", web::post().to(handle_jsonrpc))
+    })
+    .bind((config.server_host.as_str(), config.server_port))?
+    .run()
+    .await?;
+    Ok(())
+}
@@ -0,0 +1,217 @@
+//! A projection is the alkanes state after indexing the current mempool as
+//! one pending block on top of the confirmed tip.
+//!
+//! The block is handed to `alkanes.wasm` in a [`ForkRuntime`] whose storage
+//! reads through to the confirmed indexer, so only the keys the mempool
+//! touches are fetched and nothing is written back upstream. Views then run
+//! against the same storage with the pending height.
+
+use crate::bitcoind::{ChainTip, MempoolEntry};
+use anyhow::{anyhow, Result};
+use bitcoin::absolute::LockTime;
+use bitcoin::block::{Header, Version as BlockVersion};
+use bitcoin::consensus::serialize;
+use bitcoin::hashes::Hash;
+use bitcoin::script::Builder;
+use bitcoin::transaction::Version;
+use bitcoin::{
+    Amount, Block, OutPoint, ScriptBuf, Sequence, Transaction, TxIn, TxMerkleNode, TxOut, Txid,
+    Witness,
+};
+use qubitcoin_indexer_core::traits::IndexerStorageWriter;
+use qubitcoin_indexer_fork::{ForkRuntime, ForkUpstream, MemStorage};
+use std::collections::{BTreeSet, HashMap};
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Indexer label the runtime logs under.
+pub const LABEL: &str = "alkanes";
+
+/// Views served against the projection. Anything else is answered by the
+/// confirmed indexer.
+pub const PROJECTED_VIEWS: [&str; 3] = ["protorunesbyaddress", "protorunesbyoutpoint", "trace"];
+
+pub struct Projector {
+    runtime: Arc<ForkRuntime>,
+    upstream: Arc<dyn ForkUpstream>,
+}
+
+pub struct Projection {
+    pub tip: ChainTip,
+    /// Height the pending block is indexed at, `tip.height + 1`.
+    pub height: u32,
+    /// Mempool transactions in the pending block, in the order they were
+    /// indexed.
+    pub txids: Vec<Txid>,
+    /// Unix time the projection was built.
+    pub built_at: u64,
+    runtime: Arc<ForkRuntime>,
+    storage: MemStorage,
+}
+
+impl Projector {
+    pub fn new(wasm: &[u8], upstream: Arc<dyn ForkUpstream>) -> Result<Self> {
+        Ok(Self {
+            runtime: Arc::new(ForkRuntime::new(wasm).map_err(|e| anyhow!(e))?),
+            upstream,
+        })
+    }
+
+    pub fn upstream(&self) -> &Arc<dyn ForkUpstream> {
+        &self.upstream
+    }
+
+    /// Index `txs`, already in dependency order, as the block after `tip`.
+    pub async fn project(&self, tip: ChainTip, txs: Vec<Transaction>) -> Result<Projection> {
+        let height = tip.height + 1;
+        let block = pending_block(&tip, txs);
+        let txids = block.txdata[1..].iter().map(|tx| tx.compute_txid()).collect();
+
+        let mut input = height.to_le_bytes().to_vec();
+        input.extend(serialize(&block));
+        let storage = MemStorage::new(self.upstream.clone(), height);
+
+        // run_block drives its own tokio runtime, so it can't run on ours
+        let runtime = self.runtime.clone();
+        let block_storage = storage.clone();
+        let pairs =
+            tokio::task::spawn_blocking(move || runtime.run_block(input, block_storage, LABEL))
+                .await?
+                .map_err(|e| anyhow!("indexing pending block {}: {}", height, e))?;
+        for (key, value) in &pairs {
+            storage.put(key, value).map_err(|e| anyhow!(e))?;
+        }
+
+        Ok(Projection {
+            tip,
+            height,
+            txids,
+            built_at: unix_time(),
+            runtime: self.runtime.clone(),
+            storage,
+        })
+    }
+}
+
+impl Projection {
+    /// Run a view against the pending state. `input` is the view's input
+    /// without the height prefix, as passed to `metashrew_view`.
+    pub async fn view(&self, name: &str, input: Vec<u8>) -> Result<Vec<u8>> {
+        if !PROJECTED_VIEWS.contains(&name) {
+            return Err(anyhow!("view '{}' is not served with pending state", name));
+        }
+        self.runtime
+            .call_view_async(name, input, self.storage.clone(), LABEL)
+            .await
+            .map_err(|e| anyhow!(e))
+    }
+}
+
+/// The block `alkanes.wasm` indexes for a projection: a coinbase followed by
+/// `txs`, extending `tip`.
+pub fn pending_block(tip: &ChainTip, txs: Vec<Transaction>) -> Block {
+    let height = tip.height + 1;
+    let coinbase = Transaction {
+        version: Version::TWO,
+        lock_time: LockTime::ZERO,
+        input: vec![TxIn {
+            previous_output: OutPoint::null(),
+            script_sig: Builder::new().push_int(height as i64).into_script(),
+            sequence: Sequence::MAX,
+            witness: Witness::new(),
+        }],
+        output: vec![TxOut {
+            value: Amount::ZERO,
+            script_pubkey: ScriptBuf::new(),
+        }],
+    };
+    let mut block = Block {
+        header: Header {
+            version: BlockVersion::from_consensus(0x2000_0000),
+            prev_blockhash: tip.hash,
+            merkle_root: TxMerkleNode::all_zeros(),
+            time: (unix_time() as u32).max(tip.time + 1),
+            bits: tip.bits,
+            nonce: 0,
+        },
+        txdata: std::iter::once(coinbase).chain(txs).collect(),
+    };
+    if let Some(root) = block.compute_merkle_root() {
+        block.header.merkle_root = root;
+    }
+    block
+}
+
+/// Mempool txids with every transaction after its unconfirmed parents, and
+/// otherwise oldest first.
+pub fn order_mempool(entries: &HashMap<Txid, MempoolEntry>) -> Vec<Txid> {
+    let mut waiting: HashMap<Txid, usize> = HashMap::new();
+    let mut children: HashMap<Txid, Vec<Txid>> = HashMap::new();
+    let mut ready = BTreeSet::new();
+    for (txid, entry) in entries {
+        let parents = entry
+            .depends
+            .iter()
+            .filter(|parent| entries.contains_key(*parent))
+            .inspect(|parent| children.entry(**parent).or_default().push(*txid))
+            .count();
+        if parents == 0 {
+            ready.insert((entry.time, *txid));
+        } else {
+            waiting.insert(*txid, parents);
+        }
+    }
+
+    let mut ordered = Vec::with_capacity(entries.len());
+    while let Some((time, txid)) = ready.pop_first() {
+        ordered.push(txid);
+        for child in children.remove(&txid).unwrap_or_default() {
+            let parents = waiting.get_mut(&child).expect("child is waiting");
+            *parents -= 1;
+            if *parents == 0 {
+                waiting.remove(&child);
+                ready.insert((entries[&child].time.max(time), child));
+            }
+        }
+    }
+    ordered
+}
+
+fn unix_time() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn txid(n: u8) -> Txid {
+        Txid::from_byte_array([n; 32])
+    }
+
+    fn entry(time: u64, depends: &[u8]) -> MempoolEntry {
+        MempoolEntry {
+            time,
+            depends: depends.iter().map(|n| txid(*n)).collect(),
+        }
+    }
+
+    #[test]
+    fn parents_come_before_children() {
+        let entries = HashMap::from([
+            (txid(1), entry(30, &[])),
+            (txid(2), entry(10, &[1])),
+            (txid(3), entry(20, &[])),
+            (txid(4), entry(5, &[2, 3])),
+            // parent already confirmed
+            (txid(5), entry(1, &[9])),
+        ]);
+        assert_eq!(
+            order_mempool(&entries),
+            vec![txid(5), txid(3), txid(1), txid(2), txid(4)]
+        );
+    }
+}
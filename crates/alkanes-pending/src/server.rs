@@ -0,0 +1,67 @@
+//! JSON-RPC over the projection, in metashrew's shape so existing clients
+//! can point their metashrew URL here:
+//!
+//! * `metashrew_view [view, "0x<input>", tag]` for the views in
+//!   [`PROJECTED_VIEWS`](crate::projection::PROJECTED_VIEWS); the block tag
+//!   is ignored, every call reads the pending state.
+//! * `metashrew_height`, the height the pending block is indexed at.
+//! * `pending_status`, the tip and mempool transactions the projection was
+//!   built from.
+
+use crate::watcher::SharedProjection;
+use alkanes_rpc_core::types::{
+    JsonRpcRequest, JsonRpcResponse, INTERNAL_ERROR, INVALID_PARAMS, METHOD_NOT_FOUND,
+};
+use serde_json::{json, Value};
+
+/// Error code for calls that arrive before the first projection is built.
+pub const NOT_READY: i32 = -32000;
+
+pub async fn handle_request(projection: &SharedProjection, req: JsonRpcRequest) -> JsonRpcResponse {
+    let id = req.id.clone();
+    let Some(projection) = projection.get() else {
+        return JsonRpcResponse::error(NOT_READY, "projection not built yet".to_string(), id);
+    };
+    match req.method.as_str() {
+        "metashrew_view" => {
+            let (Some(view), Some(input)) = (
+                req.params.first().and_then(Value::as_str),
+                req.params.get(1).and_then(Value::as_str),
+            ) else {
+                return JsonRpcResponse::error(
+                    INVALID_PARAMS,
+                    "expected [view, hex input, block tag]".to_string(),
+                    id,
+                );
+            };
+            let input = match hex::decode(input.trim_start_matches("0x")) {
+                Ok(input) => input,
+                Err(e) => {
+                    return JsonRpcResponse::error(INVALID_PARAMS, format!("input: {}", e), id)
+                }
+            };
+            match projection.view(view, input).await {
+                Ok(bytes) => {
+                    JsonRpcResponse::success(json!(format!("0x{}", hex::encode(bytes))), id)
+                }
+                Err(e) => JsonRpcResponse::error(INTERNAL_ERROR, format!("{:#}", e), id),
+            }
+        }
+        "metashrew_height" => JsonRpcResponse::success(json!(projection.height.to_string()), id),
+        "pending_status" => JsonRpcResponse::success(
+            json!({
+                "tip_height": projection.tip.height,
+                "tip_hash": projection.tip.hash.to_string(),
+                "height": projection.height,
+                "txids": projection.txids.iter().map(|t| t.to_string()).collect::<Vec<_>>(),
+                "built_at": projection.built_at,
+            }),
+            id,
+        ),
+        method => JsonRpcResponse::error(
+            METHOD_NOT_FOUND,
+            format!("method '{}' is not served with pending state", method),
+            id,
+        ),
+    }
+}
@@ -0,0 +1,115 @@
+//! Polls bitcoind and rebuilds the projection whenever the tip or the set
+//! of mempool transactions changes.
+
+use crate::bitcoind::{BitcoindClient, ChainTip};
+use crate::projection::{order_mempool, Projection, Projector};
+use anyhow::Result;
+use bitcoin::{Transaction, Txid};
+use std::collections::{BTreeSet, HashMap};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+/// The current projection, shared between the watcher and the server.
+#[derive(Clone, Default)]
+pub struct SharedProjection(Arc<RwLock<Option<Arc<Projection>>>>);
+
+impl SharedProjection {
+    pub fn get(&self) -> Option<Arc<Projection>> {
+        self.0.read().unwrap().clone()
+    }
+
+    fn set(&self, projection: Projection) {
+        *self.0.write().unwrap() = Some(Arc::new(projection));
+    }
+}
+
+pub struct Watcher {
+    bitcoind: BitcoindClient,
+    projector: Projector,
+    projection: SharedProjection,
+    /// Tip and mempool the current projection was built from.
+    built_from: Option<(ChainTip, BTreeSet<Txid>)>,
+    /// Raw mempool transactions, kept while they stay in the mempool so a
+    /// rebuild only fetches new ones.
+    transactions: HashMap<Txid, Transaction>,
+}
+
+impl Watcher {
+    pub fn new(bitcoind: BitcoindClient, projector: Projector) -> Self {
+        Self {
+            bitcoind,
+            projector,
+            projection: SharedProjection::default(),
+            built_from: None,
+            transactions: HashMap::new(),
+        }
+    }
+
+    pub fn projection(&self) -> SharedProjection {
+        self.projection.clone()
+    }
+
+    pub async fn run(mut self, poll_interval: Duration) {
+        loop {
+            if let Err(e) = self.tick().await {
+                log::warn!("projection not rebuilt: {:#}", e);
+            }
+            tokio::time::sleep(poll_interval).await;
+        }
+    }
+
+    /// Rebuild the projection if the tip or mempool moved. Returns whether
+    /// it was rebuilt.
+    pub async fn tick(&mut self) -> Result<bool> {
+        let tip = self.bitcoind.tip().await?;
+        let mempool = self.bitcoind.mempool().await?;
+        let txids: BTreeSet<Txid> = mempool.keys().copied().collect();
+        if self.built_from.as_ref() == Some(&(tip, txids.clone())) {
+            return Ok(false);
+        }
+
+        // reads fall through to the confirmed indexer, which has to have
+        // caught up with the tip for them to be the tip's state
+        let indexed = self
+            .projector
+            .upstream()
+            .tip_height()
+            .await
+            .map_err(anyhow::Error::msg)?;
+        if indexed < tip.height {
+            log::debug!(
+                "waiting for the indexer to reach {} (at {})",
+                tip.height,
+                indexed
+            );
+            return Ok(false);
+        }
+
+        self.transactions.retain(|txid, _| txids.contains(txid));
+        let mut txs = Vec::with_capacity(txids.len());
+        for txid in order_mempool(&mempool) {
+            if !self.transactions.contains_key(&txid) {
+                match self.bitcoind.transaction(&txid).await? {
+                    Some(tx) => {
+                        self.transactions.insert(txid, tx);
+                    }
+                    // gone since it was listed, or bitcoind didn't answer;
+                    // it is left out of `built_from`, so a tick that still
+                    // lists it tries again
+                    None => continue,
+                }
+            }
+            txs.push(self.transactions[&txid].clone());
+        }
+
+        let projection = self.projector.project(tip, txs).await?;
+        log::info!(
+            "projected {} mempool transactions at height {}",
+            projection.txids.len(),
+            projection.height
+        );
+        self.built_from = Some((tip, projection.txids.iter().copied().collect()));
+        self.projection.set(projection);
+        Ok(true)
+    }
+}
@@ -0,0 +1,319 @@
+//! Drives the watcher against a local fake bitcoind serving a scripted tip
+//! and mempool. The indexer is a small module that records the size of the
+//! block it was given, and serves it back from `protorunesbyoutpoint`, so
+//! every rebuild is visible through the view.
+
+use alkanes_pending::bitcoind::BitcoindClient;
+use alkanes_pending::projection::Projector;
+use alkanes_pending::server::{handle_request, NOT_READY};
+use alkanes_pending::watcher::{SharedProjection, Watcher};
+use alkanes_rpc_core::types::{JsonRpcRequest, JsonRpcResponse, METHOD_NOT_FOUND};
+use async_trait::async_trait;
+use bitcoin::absolute::LockTime;
+use bitcoin::consensus::serialize;
+use bitcoin::hashes::Hash;
+use bitcoin::transaction::Version;
+use bitcoin::{
+    Amount, BlockHash, OutPoint, ScriptBuf, Sequence, Transaction, TxIn, TxOut, Txid, Witness,
+};
+use qubitcoin_indexer_fork::ForkUpstream;
+use serde_json::{json, Value};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+/// `_start` flushes `len => __host_len()`; `protorunesbyoutpoint` returns
+/// the stored value.
+const INDEXER: &str = r#"
+(module
+    (import "env" "__host_len" (func $host_len (result i32)))
+    (import "env" "__load_input" (func $load_input (param i32)))
+    (import "env" "__flush" (func $flush (param i32)))
+    (import "env" "__log" (func $log (param i32)))
+    (import "env" "__get" (func $get (param i32 i32)))
+    (import "env" "__get_len" (func $get_len (param i32) (result i32)))
+    (import "env" "abort" (func $abort (param i32 i32 i32 i32)))
+    (memory (export "memory") 1)
+    ;; KeyValueFlush { list: ["len", <4 bytes>] } at 100, length at 96
+    (data (i32.const 96) "\0b\00\00\00\0a\03len\0a\04\00\00\00\00")
+    ;; key "len" at 204, length at 200
+    (data (i32.const 200) "\03\00\00\00len")
+
+    (func (export "_start")
+        (i32.store (i32.const 107) (call $host_len))
+        (call $flush (i32.const 100))
+    )
+
+    (func (export "protorunesbyoutpoint") (result i32)
+        (i32.store (i32.const 296) (call $get_len (i32.const 204)))
+        (call $get (i32.const 204) (i32.const 300))
+        (i32.const 300)
+    )
+)
+"#;
+
+struct Script {
+    height: u32,
+    hash: BlockHash,
+    /// Transactions, their mempool time and unconfirmed parents.
+    mempool: Vec<(Transaction, u64, Vec<Txid>)>,
+}
+
+fn respond(script: &Script, request: &Value) -> Value {
+    let params = &request["params"];
+    let result = match request["method"].as_str().unwrap_or_default() {
+        "getbestblockhash" => json!(script.hash.to_string()),
+        "getblockheader" => json!({
+            "height": script.height,
+            "time": 1_700_000_000,
+            "bits": "207fffff",
+        }),
+        "getrawmempool" => Value::Object(
+            script
+                .mempool
+                .iter()
+                .map(|(tx, time, depends)| {
+                    (
+                        tx.compute_txid().to_string(),
+                        json!({ "time": time, "depends": depends }),
+                    )
+                })
+                .collect(),
+        ),
+        "getrawtransaction" => {
+            let txid = params[0].as_str().unwrap_or_default();
+            match script
+                .mempool
+                .iter()
+                .find(|(tx, _, _)| tx.compute_txid().to_string() == txid)
+            {
+                Some((tx, _, _)) => json!(hex::encode(serialize(tx))),
+                None => {
+                    return json!({
+                        "result": null,
+                        "error": { "code": -5, "message": "No such mempool transaction" },
+                        "id": request["id"],
+                    })
+                }
+            }
+        }
+        method => panic!("unscripted bitcoind call {}", method),
+    };
+    json!({ "result": result, "error": null, "id": request["id"] })
+}
+
+async fn serve_connection(mut stream: TcpStream, script: Arc<Mutex<Script>>) {
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 4096];
+    loop {
+        let n = stream.read(&mut chunk).await.unwrap();
+        if n == 0 {
+            return;
+        }
+        buf.extend_from_slice(&chunk[..n]);
+        let Some(end) = buf.windows(4).position(|w| w == b"\r\n\r\n") else {
+            continue;
+        };
+        let head = String::from_utf8_lossy(&buf[..end]).to_string();
+        let len = head
+            .lines()
+            .filter_map(|line| line.split_once(':'))
+            .find(|(name, _)| name.eq_ignore_ascii_case("content-length"))
+            .and_then(|(_, value)| value.trim().parse::<usize>().ok())
+            .unwrap_or(0);
+        if buf.len() < end + 4 + len {
+            continue;
+        }
+        let request: Value = serde_json::from_slice(&buf[end + 4..end + 4 + len]).unwrap();
+        let body = respond(&script.lock().unwrap(), &request).to_string();
+        let response = format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            body.len(),
+            body
+        );
+        stream.write_all(response.as_bytes()).await.unwrap();
+        return;
+    }
+}
+
+async fn fake_bitcoind(script: Arc<Mutex<Script>>) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        loop {
+            let (stream, _) = listener.accept().await.unwrap();
+            tokio::spawn(serve_connection(stream, script.clone()));
+        }
+    });
+    url
+}
+
+/// Confirmed indexer with no state, at a height the test moves.
+struct Upstream(Arc<AtomicU32>);
+
+#[async_trait]
+impl ForkUpstream for Upstream {
+    async fn fetch(&self, _key: &[u8]) -> Result<Option<Vec<u8>>, String> {
+        Ok(None)
+    }
+
+    async fn tip_height(&self) -> Result<u32, String> {
+        Ok(self.0.load(Ordering::SeqCst))
+    }
+}
+
+fn spend(previous_output: OutPoint) -> Transaction {
+    Transaction {
+        version: Version::TWO,
+        lock_time: LockTime::ZERO,
+        input: vec![TxIn {
+            previous_output,
+            script_sig: ScriptBuf::new(),
+            sequence: Sequence::MAX,
+            witness: Witness::new(),
+        }],
+        output: vec![TxOut {
+            value: Amount::from_sat(1_000),
+            script_pubkey: ScriptBuf::new(),
+        }],
+    }
+}
+
+/// The block size the indexer recorded for the current projection.
+async fn block_size(shared: &SharedProjection) -> u32 {
+    let bytes = shared
+        .get()
+        .unwrap()
+        .view("protorunesbyoutpoint", vec![])
+        .await
+        .unwrap();
+    u32::from_le_bytes(bytes.try_into().unwrap())
+}
+
+fn request(method: &str, params: Value) -> JsonRpcRequest {
+    serde_json::from_value(json!({
+        "jsonrpc": "2.0",
+        "method": method,
+        "params": params,
+        "id": 1,
+    }))
+    .unwrap()
+}
+
+fn result(response: JsonRpcResponse) -> Value {
+    match response {
+        JsonRpcResponse::Success { result, .. } => result,
+        JsonRpcResponse::Error { error, .. } => panic!("rpc error: {}", error.message),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn rebuilds_on_mempool_change_and_new_block() {
+    let script = Arc::new(Mutex::new(Script {
+        height: 100,
+        hash: BlockHash::from_byte_array([1; 32]),
+        mempool: vec![],
+    }));
+    let indexed = Arc::new(AtomicU32::new(100));
+    let bitcoind = BitcoindClient::new(
+        fake_bitcoind(script.clone()).await,
+        "user".to_string(),
+        "pass".to_string(),
+    );
+    let projector = Projector::new(
+        &wat::parse_str(INDEXER).unwrap(),
+        Arc::new(Upstream(indexed.clone())),
+    )
+    .unwrap();
+    let mut watcher = Watcher::new(bitcoind, projector);
+    let shared = watcher.projection();
+
+    assert!(watcher.tick().await.unwrap());
+    assert_eq!(shared.get().unwrap().height, 101);
+    assert!(shared.get().unwrap().txids.is_empty());
+    let empty = block_size(&shared).await;
+    assert!(!watcher.tick().await.unwrap(), "nothing changed");
+
+    // a child listed before its parent is still indexed after it
+    let parent = spend(OutPoint::new(Txid::from_byte_array([7; 32]), 0));
+    let child = spend(OutPoint::new(parent.compute_txid(), 0));
+    script.lock().unwrap().mempool = vec![
+        (child.clone(), 1, vec![parent.compute_txid()]),
+        (parent.clone(), 2, vec![]),
+    ];
+    assert!(watcher.tick().await.unwrap());
+    assert_eq!(
+        shared.get().unwrap().txids,
+        vec![parent.compute_txid(), child.compute_txid()]
+    );
+    assert_eq!(
+        block_size(&shared).await,
+        empty + (serialize(&parent).len() + serialize(&child).len()) as u32
+    );
+
+    // the parent is mined; wait for the confirmed indexer before rebuilding
+    {
+        let mut script = script.lock().unwrap();
+        script.height = 101;
+        script.hash = BlockHash::from_byte_array([2; 32]);
+        script.mempool.retain(|(tx, _, _)| *tx == child);
+        script.mempool[0].2.clear();
+    }
+    assert!(!watcher.tick().await.unwrap());
+    assert_eq!(shared.get().unwrap().height, 101);
+    indexed.store(101, Ordering::SeqCst);
+    assert!(watcher.tick().await.unwrap());
+    let projection = shared.get().unwrap();
+    assert_eq!(projection.height, 102);
+    assert_eq!(projection.txids, vec![child.compute_txid()]);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn serves_metashrew_views() {
+    let script = Arc::new(Mutex::new(Script {
+        height: 5,
+        hash: BlockHash::from_byte_array([3; 32]),
+        mempool: vec![],
+    }));
+    let bitcoind = BitcoindClient::new(
+        fake_bitcoind(script).await,
+        "user".to_string(),
+        "pass".to_string(),
+    );
+    let projector = Projector::new(
+        &wat::parse_str(INDEXER).unwrap(),
+        Arc::new(Upstream(Arc::new(AtomicU32::new(5)))),
+    )
+    .unwrap();
+    let mut watcher = Watcher::new(bitcoind, projector);
+    let shared = watcher.projection();
+
+    match handle_request(&shared, request("metashrew_height", json!([]))).await {
+        JsonRpcResponse::Error { error, .. } => assert_eq!(error.code, NOT_READY),
+        _ => panic!("served before the first projection"),
+    }
+
+    watcher.tick().await.unwrap();
+    assert_eq!(
+        result(handle_request(&shared, request("metashrew_height", json!([]))).await),
+        json!("6")
+    );
+    let view = result(
+        handle_request(
+            &shared,
+            request("metashrew_view", json!(["protorunesbyoutpoint", "0x", "latest"])),
+        )
+        .await,
+    );
+    assert_eq!(view.as_str().unwrap().len(), 2 + 8);
+    assert!(matches!(
+        handle_request(&shared, request("metashrew_view", json!(["simulate", "0x", "latest"])))
+            .await,
+        JsonRpcResponse::Error { .. }
+    ));
+    match handle_request(&shared, request("getblockcount", json!([]))).await {
+        JsonRpcResponse::Error { error, .. } => assert_eq!(error.code, METHOD_NOT_FOUND),
+        _ => panic!("unknown method served"),
+    }
+}
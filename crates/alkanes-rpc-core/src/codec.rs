@@ -5,7 +5,10 @@ use serde_json::{Value, json};
 use alkanes_cli_common::proto::alkanes::{
     MessageContextParcel, AlkaneTransfer, AlkaneId, Uint128, SimulateResponse, KeyValuePair,
     AlkaneIdToOutpointRequest, AlkaneIdToOutpointResponse,
-    AlkanesTrace, AlkanesTraceEvent,
+    AlkaneStorageKeysRequest, AlkaneStorageKeysResponse,
+    HoldersOfAlkaneRequest, HoldersOfAlkaneResponse,
+    EventsByHeightRequest, EventsByAlkaneRequest, AlkaneEventsResponse,
+    AlkanesTrace, AlkanesTraceEvent, AlkanesBlockTraceEvent,
     alkanes_trace_event::Event as TraceEventEnum,
     Outpoint,
 };
@@ -371,6 +374,120 @@ pub fn decode_trace_response(hex_response: &str) -> Result<Value> {
     Ok(json!(events))
 }
 
+/// Decode AlkanesBlockTraceEvent protobuf (the `traceblock` view) to a JSON
+/// array of `{outpoint, txindex, events}`.
+pub fn decode_traceblock_response(hex_response: &str) -> Result<Value> {
+    let hex_data = hex_response.strip_prefix("0x").unwrap_or(hex_response);
+
+    if hex_data.is_empty() {
+        return Ok(json!([]));
+    }
+
+    let bytes = hex::decode(hex_data)?;
+    let block = AlkanesBlockTraceEvent::decode(bytes.as_slice())?;
+
+    let traces: Vec<Value> = block.events.iter()
+        .map(|item| {
+            let outpoint = item.outpoint.as_ref().map(|o| {
+                let mut txid = o.txid.clone();
+                txid.reverse();
+                json!({ "txid": hex::encode(txid), "vout": o.vout })
+            });
+            let events: Vec<Value> = item.traces.as_ref()
+                .map(|t| t.events.iter().map(trace_event_to_json).collect())
+                .unwrap_or_default();
+            json!({
+                "outpoint": outpoint,
+                "txindex": item.txindex,
+                "events": events
+            })
+        })
+        .collect();
+
+    Ok(json!(traces))
+}
+
+/// Decode AlkaneStorageKeysResponse protobuf to JSON.
+pub fn decode_storage_keys_response(hex_response: &str) -> Result<Value> {
+    let hex_data = hex_response.strip_prefix("0x").unwrap_or(hex_response);
+    let bytes = hex::decode(hex_data)?;
+    let response = AlkaneStorageKeysResponse::decode(bytes.as_slice())?;
+
+    let entries: Vec<Value> = response.entries.iter()
+        .map(|entry| json!({
+            "key": format_key(&entry.key),
+            "keyHex": format!("0x{}", hex::encode(&entry.key)),
+            "value": format!("0x{}", hex::encode(&entry.value)),
+            "lastModifiedHeight": entry.last_modified_height
+        }))
+        .collect();
+
+    Ok(json!({
+        "entries": entries,
+        "nextCursor": response.next_cursor
+    }))
+}
+
+/// Decode AlkaneEventsResponse protobuf into JSON.
+pub fn decode_events_response(hex_response: &str) -> Result<Value> {
+    let hex_data = hex_response.strip_prefix("0x").unwrap_or(hex_response);
+    let bytes = hex::decode(hex_data)?;
+    let response = AlkaneEventsResponse::decode(bytes.as_slice())?;
+
+    let events: Vec<Value> = response.events.iter()
+        .map(|event| {
+            let outpoint = event.outpoint.as_ref().map(|o| {
+                let mut txid = o.txid.clone();
+                txid.reverse();
+                json!({ "txid": hex::encode(txid), "vout": o.vout })
+            });
+            let alkane = event.alkane.as_ref().map(|id| json!({
+                "block": from_uint128(&id.block).to_string(),
+                "tx": from_uint128(&id.tx).to_string()
+            }));
+            json!({
+                "height": event.height,
+                "outpoint": outpoint,
+                "alkane": alkane,
+                "topic": String::from_utf8(event.topic.clone())
+                    .unwrap_or_else(|_| hex::encode(&event.topic)),
+                "topicHex": format!("0x{}", hex::encode(&event.topic)),
+                "data": format!("0x{}", hex::encode(&event.data))
+            })
+        })
+        .collect();
+
+    Ok(json!({
+        "events": events,
+        "nextCursor": response.next_cursor
+    }))
+}
+
+/// Decode HoldersOfAlkaneResponse protobuf to JSON.
+pub fn decode_holders_response(hex_response: &str) -> Result<Value> {
+    let hex_data = hex_response.strip_prefix("0x").unwrap_or(hex_response);
+    let bytes = hex::decode(hex_data)?;
+    let response = HoldersOfAlkaneResponse::decode(bytes.as_slice())?;
+
+    let holders: Vec<Value> = response.holders.iter()
+        .map(|entry| {
+            let holder = entry.holder.as_ref().map(|id| json!({
+                "block": from_uint128(&id.block).to_string(),
+                "tx": from_uint128(&id.tx).to_string()
+            }));
+            json!({
+                "holder": holder,
+                "balance": from_uint128(&entry.balance).to_string()
+            })
+        })
+        .collect();
+
+    Ok(json!({
+        "holders": holders,
+        "nextCursor": response.next_cursor
+    }))
+}
+
 /// Decode WalletResponse protobuf into JSON.
 pub fn decode_wallet_response(hex_response: &str) -> Result<Value> {
     let hex_data = hex_response.strip_prefix("0x").unwrap_or(hex_response);
@@ -501,6 +618,149 @@ pub fn encode_alkanes_id_to_outpoint_request(params: &Value) -> Result<String> {
     Ok(format!("0x{}", hex::encode(buf)))
 }
 
+/// Encode getstoragekeys request: {block, tx, prefix?, cursor?, limit?} →
+/// protobuf hex. `prefix` is hex when `0x`-prefixed, raw UTF-8 otherwise.
+pub fn encode_getstoragekeys_request(params: &Value) -> Result<String> {
+    let obj = params.as_object()
+        .ok_or_else(|| anyhow::anyhow!("getstoragekeys params must be an object"))?;
+
+    let block = parse_u128(obj.get("block")
+        .ok_or_else(|| anyhow::anyhow!("Missing 'block' parameter"))?)
+        .ok_or_else(|| anyhow::anyhow!("Invalid 'block' parameter"))?;
+
+    let tx = parse_u128(obj.get("tx")
+        .ok_or_else(|| anyhow::anyhow!("Missing 'tx' parameter"))?)
+        .ok_or_else(|| anyhow::anyhow!("Invalid 'tx' parameter"))?;
+
+    let prefix = parse_bytes_param(obj.get("prefix"), "prefix")?;
+    let cursor = parse_u64_param(obj.get("cursor"));
+
+    let limit = obj.get("limit")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(0) as u32;
+
+    let request = AlkaneStorageKeysRequest {
+        id: Some(AlkaneId {
+            block: Some(to_uint128(block)),
+            tx: Some(to_uint128(tx)),
+        }),
+        prefix,
+        cursor,
+        limit,
+    };
+
+    let mut buf = Vec::new();
+    request.encode(&mut buf)?;
+    Ok(format!("0x{}", hex::encode(buf)))
+}
+
+/// Parse an optional byte-string parameter: hex when `0x`-prefixed, raw
+/// UTF-8 otherwise.
+fn parse_bytes_param(value: Option<&Value>, name: &str) -> Result<Vec<u8>> {
+    match value.and_then(|v| v.as_str()) {
+        Some(p) => match p.strip_prefix("0x") {
+            Some(hex_str) => hex::decode(hex_str)
+                .map_err(|e| anyhow::anyhow!("Invalid {} hex: {}", name, e)),
+            None => Ok(p.as_bytes().to_vec()),
+        },
+        None => Ok(Vec::new()),
+    }
+}
+
+/// Parse an optional u64 parameter given as a number or a decimal string.
+fn parse_u64_param(value: Option<&Value>) -> u64 {
+    value
+        .and_then(|v| match v {
+            Value::Number(n) => n.as_u64(),
+            Value::String(s) => s.parse::<u64>().ok(),
+            _ => None,
+        })
+        .unwrap_or(0)
+}
+
+/// Encode eventsbyheight request: {height, topic?} → protobuf hex.
+pub fn encode_eventsbyheight_request(params: &Value) -> Result<String> {
+    let obj = params.as_object()
+        .ok_or_else(|| anyhow::anyhow!("eventsbyheight params must be an object"))?;
+
+    let height = obj.get("height")
+        .ok_or_else(|| anyhow::anyhow!("Missing 'height' parameter"))?;
+    let height = parse_u128(height)
+        .ok_or_else(|| anyhow::anyhow!("Invalid 'height' parameter"))? as u64;
+
+    let request = EventsByHeightRequest {
+        height,
+        topic: parse_bytes_param(obj.get("topic"), "topic")?,
+    };
+
+    let mut buf = Vec::new();
+    request.encode(&mut buf)?;
+    Ok(format!("0x{}", hex::encode(buf)))
+}
+
+/// Encode eventsbyalkane request:
+/// {block, tx, topic?, fromHeight?, toHeight?, cursor?, limit?} → protobuf hex.
+pub fn encode_eventsbyalkane_request(params: &Value) -> Result<String> {
+    let obj = params.as_object()
+        .ok_or_else(|| anyhow::anyhow!("eventsbyalkane params must be an object"))?;
+
+    let block = parse_u128(obj.get("block")
+        .ok_or_else(|| anyhow::anyhow!("Missing 'block' parameter"))?)
+        .ok_or_else(|| anyhow::anyhow!("Invalid 'block' parameter"))?;
+
+    let tx = parse_u128(obj.get("tx")
+        .ok_or_else(|| anyhow::anyhow!("Missing 'tx' parameter"))?)
+        .ok_or_else(|| anyhow::anyhow!("Invalid 'tx' parameter"))?;
+
+    let request = EventsByAlkaneRequest {
+        id: Some(AlkaneId {
+            block: Some(to_uint128(block)),
+            tx: Some(to_uint128(tx)),
+        }),
+        topic: parse_bytes_param(obj.get("topic"), "topic")?,
+        from_height: parse_u64_param(obj.get("fromHeight")),
+        to_height: parse_u64_param(obj.get("toHeight")),
+        cursor: parse_u64_param(obj.get("cursor")),
+        limit: obj.get("limit").and_then(|v| v.as_u64()).unwrap_or(0) as u32,
+    };
+
+    let mut buf = Vec::new();
+    request.encode(&mut buf)?;
+    Ok(format!("0x{}", hex::encode(buf)))
+}
+
+/// Encode holdersofalkane request: {block, tx, cursor?, limit?} → protobuf hex.
+pub fn encode_holdersofalkane_request(params: &Value) -> Result<String> {
+    let obj = params.as_object()
+        .ok_or_else(|| anyhow::anyhow!("holdersofalkane params must be an object"))?;
+
+    let block = parse_u128(obj.get("block")
+        .ok_or_else(|| anyhow::anyhow!("Missing 'block' parameter"))?)
+        .ok_or_else(|| anyhow::anyhow!("Invalid 'block' parameter"))?;
+
+    let tx = parse_u128(obj.get("tx")
+        .ok_or_else(|| anyhow::anyhow!("Missing 'tx' parameter"))?)
+        .ok_or_else(|| anyhow::anyhow!("Invalid 'tx' parameter"))?;
+
+    let request = HoldersOfAlkaneRequest {
+        id: Some(AlkaneId {
+            block: Some(to_uint128(block)),
+            tx: Some(to_uint128(tx)),
+        }),
+        cursor: parse_u64_param(obj.get("cursor")),
+        limit: obj.get("limit").and_then(|v| v.as_u64()).unwrap_or(0) as u32,
+    };
+
+    let mut buf = Vec::new();
+    request.encode(&mut buf)?;
+    Ok(format!("0x{}", hex::encode(buf)))
+}
+
+/// Encode traceblock request: the block height as a little-endian u32.
+pub fn encode_traceblock_request(height: u32) -> String {
+    format!("0x{}", hex::encode(height.to_le_bytes()))
+}
+
 /// Encode trace request: {txid, vout} → protobuf hex.
 pub fn encode_trace_request(params: &Value) -> Result<String> {
     let obj = params.as_object()
@@ -354,6 +354,45 @@ where
         Ok(outpoints)
     }
 
+    /// `alkanes_traceblock [height]`: every trace indexed in the block at
+    /// `height`, decoded.
+    async fn handle_traceblock(
+        &self,
+        input: &Value,
+        request_id: &Value,
+    ) -> Result<JsonRpcResponse> {
+        let Some(height) = codec::parse_u128(input).and_then(|h| u32::try_from(h).ok()) else {
+            return Ok(JsonRpcResponse::error(
+                INVALID_PARAMS,
+                "traceblock requires a block height".to_string(),
+                request_id.clone(),
+            ));
+        };
+        let view_request = JsonRpcRequest {
+            jsonrpc: "2.0".to_string(),
+            method: "metashrew_view".to_string(),
+            params: vec![
+                Value::String("traceblock".to_string()),
+                Value::String(codec::encode_traceblock_request(height)),
+                Value::String(height.to_string()),
+            ],
+            id: request_id.clone(),
+        };
+        match self.metashrew.forward(&view_request).await? {
+            JsonRpcResponse::Success { result, .. } => {
+                match codec::decode_traceblock_response(result.as_str().unwrap_or_default()) {
+                    Ok(traces) => Ok(JsonRpcResponse::success(traces, request_id.clone())),
+                    Err(e) => Ok(JsonRpcResponse::error(
+                        INTERNAL_ERROR,
+                        format!("Failed to decode traceblock response: {}", e),
+                        request_id.clone(),
+                    )),
+                }
+            }
+            error => Ok(error),
+        }
+    }
+
     /// `alkanes_protorunesbyaddress` (and the metashrew_view equivalent):
     /// assemble the WalletResponse from the shared fan-out.
     async fn handle_protorunesbyaddress(
@@ -429,6 +468,12 @@ where
             return self.handle_protorunesbyaddress(params, request_id).await;
         }
 
+        // traceblock reads the height metashrew prefixes to the view input, so
+        // the block tag must be the requested height rather than "latest".
+        if method == "traceblock" {
+            return self.handle_traceblock(&input, request_id).await;
+        }
+
         // For protorunesbyoutpoint with positional params, block_tag is at index 2
         let block_tag = if method == "protorunesbyoutpoint" {
             let first = params.get(0);
@@ -496,6 +541,54 @@ where
                     }
                 }
             }
+            "getstoragekeys" => {
+                match codec::encode_getstoragekeys_request(&input) {
+                    Ok(hex) => ("getstoragekeys", Value::String(hex), "getstoragekeys"),
+                    Err(e) => {
+                        return Ok(JsonRpcResponse::error(
+                            INTERNAL_ERROR,
+                            format!("Failed to encode getstoragekeys request: {}", e),
+                            request_id.clone(),
+                        ));
+                    }
+                }
+            }
+            "holdersofalkane" => {
+                match codec::encode_holdersofalkane_request(&input) {
+                    Ok(hex) => ("holdersofalkane", Value::String(hex), "holdersofalkane"),
+                    Err(e) => {
+                        return Ok(JsonRpcResponse::error(
+                            INTERNAL_ERROR,
+                            format!("Failed to encode holdersofalkane request: {}", e),
+                            request_id.clone(),
+                        ));
+                    }
+                }
+            }
+            "eventsbyheight" => {
+                match codec::encode_eventsbyheight_request(&input) {
+                    Ok(hex) => ("eventsbyheight", Value::String(hex), "events"),
+                    Err(e) => {
+                        return Ok(JsonRpcResponse::error(
+                            INTERNAL_ERROR,
+                            format!("Failed to encode eventsbyheight request: {}", e),
+                            request_id.clone(),
+                        ));
+                    }
+                }
+            }
+            "eventsbyalkane" => {
+                match codec::encode_eventsbyalkane_request(&input) {
+                    Ok(hex) => ("eventsbyalkane", Value::String(hex), "events"),
+                    Err(e) => {
+                        return Ok(JsonRpcResponse::error(
+                            INTERNAL_ERROR,
+                            format!("Failed to encode eventsbyalkane request: {}", e),
+                            request_id.clone(),
+                        ));
+                    }
+                }
+            }
             "trace" => {
                 match codec::encode_trace_request(&input) {
                     Ok(hex) => ("trace", Value::String(hex), "trace"),
@@ -557,6 +650,9 @@ where
                         "meta" => codec::decode_meta_response(hex_str),
                         "alkanesidtooutpoint" => codec::decode_alkanes_id_to_outpoint_response(hex_str),
                         "trace" => codec::decode_trace_response(hex_str),
+                        "getstoragekeys" => codec::decode_storage_keys_response(hex_str),
+                        "holdersofalkane" => codec::decode_holders_response(hex_str),
+                        "events" => codec::decode_events_response(hex_str),
                         "protorunesbyoutpoint" => codec::decode_outpoint_response(hex_str),
                         "protorunesbyaddress" => codec::decode_wallet_response(hex_str),
                         _ => unreachable!()
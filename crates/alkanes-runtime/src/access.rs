@@ -0,0 +1,161 @@
+//! Role-based access control.
+//!
+//! Roles are `u128` ids, each with an admin role whose holders may grant and
+//! revoke it; every role is administered by [`DEFAULT_ADMIN_ROLE`] until
+//! [`AccessControl::set_role_admin`] says otherwise. Roles are granted to
+//! alkane ids. A call acts for an account when the account is the calling
+//! alkane, or when the call carries some of the account as an incoming
+//! alkane, so externally owned callers hold roles through tokens the way
+//! [`crate::auth::AuthenticatedResponder`] uses auth tokens.
+//!
+//! Because of that, granting a role to a fungible alkane grants it to every
+//! holder of a single unit. Grant roles only to contracts, which act through
+//! the caller id, or to auth tokens minted with a supply of 1 and kept by
+//! whoever should hold the role.
+//!
+//! `RoleGranted` and `RoleRevoked` events are only emitted with the `events`
+//! feature. A contract importing `__emit_event` fails to instantiate on
+//! networks before their events fork, so leave it off for contracts meant
+//! to deploy there.
+//!
+//! The handlers for the standard opcodes are trait methods named after the
+//! message variants, so a contract exposes them, and exports them in
+//! `__meta`, by adding the variants to its message enum:
+//!
+//! ```ignore
+//! #[opcode(0x7f00)]
+//! #[returns(bool)]
+//! HasRole { role: u128, account: AlkaneId },
+//! #[opcode(0x7f01)]
+//! #[returns(u128)]
+//! GetRoleAdmin { role: u128 },
+//! #[opcode(0x7f02)]
+//! GrantRole { role: u128, account: AlkaneId },
+//! #[opcode(0x7f03)]
+//! RevokeRole { role: u128, account: AlkaneId },
+//! #[opcode(0x7f04)]
+//! RenounceRole { role: u128, account: AlkaneId },
+//! ```
+
+use crate::runtime::AlkaneResponder;
+use crate::storage::{StorageCollection, StorageMap, StoragePointer, StorageValue};
+use alkanes_support::{id::AlkaneId, response::CallResponse};
+use anyhow::{anyhow, Result};
+use metashrew_support::index_pointer::KeyValuePointer;
+
+pub const DEFAULT_ADMIN_ROLE: u128 = 0;
+
+pub const HAS_ROLE: u128 = 0x7f00;
+pub const GET_ROLE_ADMIN: u128 = 0x7f01;
+pub const GRANT_ROLE: u128 = 0x7f02;
+pub const REVOKE_ROLE: u128 = 0x7f03;
+pub const RENOUNCE_ROLE: u128 = 0x7f04;
+
+#[cfg(feature = "events")]
+fn role_event_data(role: u128, account: &AlkaneId) -> Vec<u8> {
+    let mut data = role.to_le_bytes().to_vec();
+    data.extend(Vec::<u8>::from(account));
+    data
+}
+
+pub trait AccessControl: AlkaneResponder {
+    fn role_pointer(&self, role: u128) -> StoragePointer {
+        StoragePointer::from_keyword("/roles/").select(&role.to_le_bytes().to_vec())
+    }
+    fn role_members(&self, role: u128) -> StorageMap<AlkaneId, bool> {
+        StorageMap::from_pointer(self.role_pointer(role).keyword("/members"))
+    }
+    fn role_admin(&self, role: u128) -> u128 {
+        StorageValue::<u128>::from_pointer(self.role_pointer(role).keyword("/admin")).get()
+    }
+    fn set_role_admin(&self, role: u128, admin_role: u128) {
+        StorageValue::<u128>::from_pointer(self.role_pointer(role).keyword("/admin"))
+            .set(admin_role);
+    }
+    fn role_granted(&self, role: u128, account: &AlkaneId) -> bool {
+        self.role_members(role).contains_key(account)
+    }
+    /// Grants without checking the caller, for initializers. Returns whether
+    /// the account didn't hold the role already. The same caveat about
+    /// fungible accounts as [`AccessControl::grant_role`] applies.
+    fn grant_role_to(&self, role: u128, account: AlkaneId) -> bool {
+        if self.role_granted(role, &account) {
+            return false;
+        }
+        self.role_members(role).insert(account, true);
+        #[cfg(feature = "events")]
+        self.emit_event(b"RoleGranted", &role_event_data(role, &account));
+        true
+    }
+    /// Revokes without checking the caller. Returns whether the account held
+    /// the role.
+    fn revoke_role_from(&self, role: u128, account: AlkaneId) -> bool {
+        if self.role_members(role).remove(&account).is_none() {
+            return false;
+        }
+        #[cfg(feature = "events")]
+        self.emit_event(b"RoleRevoked", &role_event_data(role, &account));
+        true
+    }
+    fn acts_as(&self, account: &AlkaneId) -> Result<bool> {
+        let context = self.context()?;
+        Ok(context.caller == *account
+            || context
+                .incoming_alkanes
+                .0
+                .iter()
+                .any(|transfer| transfer.id == *account && transfer.value > 0))
+    }
+    fn only_role(&self, role: u128) -> Result<()> {
+        let context = self.context()?;
+        let members = self.role_members(role);
+        if members.contains_key(&context.caller)
+            || context
+                .incoming_alkanes
+                .0
+                .iter()
+                .any(|transfer| transfer.value > 0 && members.contains_key(&transfer.id))
+        {
+            Ok(())
+        } else {
+            Err(anyhow!("AccessControl: missing role {}", role))
+        }
+    }
+
+    fn has_role(&self, role: u128, account: AlkaneId) -> Result<CallResponse> {
+        let context = self.context()?;
+        let mut response = CallResponse::forward(&context.incoming_alkanes);
+        response.data = vec![self.role_granted(role, &account) as u8];
+        Ok(response)
+    }
+    fn get_role_admin(&self, role: u128) -> Result<CallResponse> {
+        let context = self.context()?;
+        let mut response = CallResponse::forward(&context.incoming_alkanes);
+        response.data = self.role_admin(role).to_le_bytes().to_vec();
+        Ok(response)
+    }
+    /// Callers holding any unit of `account` pass [`AccessControl::only_role`]
+    /// for `role` afterwards, so `account` should be a contract or an auth
+    /// token with a supply of 1; granting to a fungible alkane hands the role
+    /// to all of its holders.
+    fn grant_role(&self, role: u128, account: AlkaneId) -> Result<CallResponse> {
+        let context = self.context()?;
+        self.only_role(self.role_admin(role))?;
+        self.grant_role_to(role, account);
+        Ok(CallResponse::forward(&context.incoming_alkanes))
+    }
+    fn revoke_role(&self, role: u128, account: AlkaneId) -> Result<CallResponse> {
+        let context = self.context()?;
+        self.only_role(self.role_admin(role))?;
+        self.revoke_role_from(role, account);
+        Ok(CallResponse::forward(&context.incoming_alkanes))
+    }
+    fn renounce_role(&self, role: u128, account: AlkaneId) -> Result<CallResponse> {
+        let context = self.context()?;
+        if !self.acts_as(&account)? {
+            return Err(anyhow!("AccessControl: can only renounce roles for self"));
+        }
+        self.revoke_role_from(role, account);
+        Ok(CallResponse::forward(&context.incoming_alkanes))
+    }
+}
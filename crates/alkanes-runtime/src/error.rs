@@ -0,0 +1,32 @@
+// Re-export the AlkaneError derive macro
+pub use alkanes_macros::AlkaneError;
+pub use alkanes_support::error::{ErrorParam, StructuredError};
+
+/// Trait for contract error enums with structured revert encodings
+pub trait AlkaneError: std::error::Error {
+    /// Numeric code of the error, from `#[error(code)]`
+    fn code(&self) -> u32;
+
+    /// Name of the error variant
+    fn name(&self) -> &'static str;
+
+    /// Export ABI metadata for the error enum, as a JSON array
+    fn export_errors() -> Vec<u8>
+    where
+        Self: Sized;
+}
+
+/// Adds the `errors` array from `AlkaneError::export_errors` to the JSON
+/// object `MessageDispatch::export_abi` returns.
+pub fn abi_with_errors(abi: Vec<u8>, errors: Vec<u8>) -> Vec<u8> {
+    let mut abi = abi;
+    match abi.iter().rposition(|b| *b == b'}') {
+        Some(end) => {
+            let mut spliced = b", \"errors\": ".to_vec();
+            spliced.extend(errors);
+            abi.splice(end..end, spliced);
+            abi
+        }
+        None => abi,
+    }
+}
@@ -1,8 +1,14 @@
+pub mod access;
 pub mod auth;
 #[cfg(feature = "panic-hook")]
 pub mod compat;
+pub mod error;
 pub mod imports;
 pub mod message;
+#[cfg(feature = "test-utils")]
+pub mod mock;
+pub mod pausable;
+pub mod reentrancy;
 pub mod runtime;
 pub mod stdio;
 pub mod storage;
@@ -14,6 +20,30 @@ macro_rules! declare_alkane {
     (impl AlkaneResponder for $struct_name:ident {
         type Message = $message_type:ident;
     }) => {
+        $crate::declare_alkane!(@execute $struct_name, $message_type);
+
+        #[no_mangle]
+        pub extern "C" fn __meta() -> i32 {
+            let abi = $message_type::export_abi();
+            export_bytes(&abi)
+        }
+    };
+    (impl AlkaneResponder for $struct_name:ident {
+        type Message = $message_type:ident;
+        type Error = $error_type:ident;
+    }) => {
+        $crate::declare_alkane!(@execute $struct_name, $message_type);
+
+        #[no_mangle]
+        pub extern "C" fn __meta() -> i32 {
+            let abi = alkanes_runtime::error::abi_with_errors(
+                $message_type::export_abi(),
+                <$error_type as alkanes_runtime::error::AlkaneError>::export_errors(),
+            );
+            export_bytes(&abi)
+        }
+    };
+    (@execute $struct_name:ident, $message_type:ident) => {
         #[no_mangle]
         pub extern "C" fn __execute() -> i32 {
             use alkanes_runtime::runtime::AlkaneResponder;
@@ -53,12 +83,6 @@ macro_rules! declare_alkane {
             alkanes_runtime::runtime::response_to_i32(extended)
         }
 
-        #[no_mangle]
-        pub extern "C" fn __meta() -> i32 {
-            let abi = $message_type::export_abi();
-            export_bytes(&abi)
-        }
-
         fn export_bytes(data: &[u8]) -> i32 {
             let response_bytes = to_arraybuffer_layout(data);
             Box::leak(Box::new(response_bytes)).as_mut_ptr() as usize as i32 + 4
@@ -0,0 +1,611 @@
+//! Native mock host for contract unit tests, enabled by the `test-utils`
+//! feature.
+//!
+//! With `test-utils` the [`AlkaneResponder`] host calls are answered by this
+//! module instead of wasm imports, so a contract's methods run under plain
+//! `cargo test`. The host keeps an in-memory storage map per alkane, a
+//! balance ledger, the height, sequence, fuel, transaction and block the
+//! contract sees, and a registry of native contracts extcalls dispatch to.
+//!
+//! State lives in a thread local, so each test thread gets its own host;
+//! [`MockHost::new`] resets it. Calls follow the indexer's extcall rules:
+//!
+//! * the caller's pending storage writes are flushed before the callee runs,
+//!   and incoming alkanes move from caller to callee (except for
+//!   delegatecalls, which run in the caller's context)
+//! * on success the callee's storage writes are saved and the alkanes it
+//!   returns move back to the caller; a staticcall then rolls everything
+//!   back, other calls commit
+//! * on error everything since the call started is rolled back and the
+//!   caller gets `Extcall failed: ...`
+//!
+//! Deployments through `5:n` and `6:n` clone the contract registered at
+//! `2:n` or `4:n` to `2:<sequence>`. Deploying new code (`1:0`, `3:n`) isn't
+//! possible natively; register the contract instead.
+//!
+//! ```ignore
+//! let host = MockHost::new().with_height(880_000);
+//! host.register::<OwnedToken, OwnedTokenMessage>(AlkaneId::new(2, 1));
+//! let response = host.call(AlkaneId::new(2, 1), vec![0, 1, 1000], Default::default())?;
+//! assert_eq!(host.balance(&AlkaneId::new(2, 1), &AlkaneId::new(2, 1)), 0);
+//! ```
+
+use crate::message::MessageDispatch;
+use crate::runtime::{swap_cache, AlkaneResponder};
+use alkanes_support::{
+    cellpack::Cellpack,
+    context::Context,
+    id::AlkaneId,
+    parcel::{AlkaneTransfer, AlkaneTransferParcel},
+    response::CallResponse,
+    storage::StorageMap,
+};
+use anyhow::{anyhow, Result};
+use bitcoin::consensus::serialize;
+use bitcoin::{Block, Transaction};
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::marker::PhantomData;
+use std::rc::Rc;
+
+/// Same limits the indexer enforces on `emit_event`.
+pub const MAX_EVENT_TOPIC_SIZE: usize = 64;
+pub const MAX_EVENT_DATA_SIZE: usize = 4096;
+
+/// Extcall depth at which calls are rejected as runaway recursion.
+pub const MAX_CALL_DEPTH: usize = 75;
+
+/// Alkane id the indexer serves block data from.
+const PRECOMPILE_BLOCK: u128 = 800000000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CallKind {
+    Call,
+    Delegatecall,
+    Staticcall,
+}
+
+/// A contract extcalls can dispatch to. It runs with the callee's frame
+/// active, so it reads its context, storage and balances through
+/// [`AlkaneResponder`] like it would on chain.
+pub trait MockContract {
+    fn execute(&self) -> Result<CallResponse>;
+}
+
+/// Runs `T` the way `declare_alkane!`'s `__execute` does.
+struct Responder<T, M>(PhantomData<(T, M)>);
+
+impl<T: AlkaneResponder + Default, M: MessageDispatch<T>> MockContract for Responder<T, M> {
+    fn execute(&self) -> Result<CallResponse> {
+        let responder = T::default();
+        let mut inputs = responder.context()?.inputs;
+        if inputs.is_empty() {
+            return Err(anyhow!("No opcode provided"));
+        }
+        let opcode = inputs.remove(0);
+        match M::from_opcode(opcode, inputs) {
+            Ok(message) => message.dispatch(&responder),
+            Err(_) => responder.fallback(),
+        }
+    }
+}
+
+struct FnContract<F>(F);
+
+impl<F: Fn(&Context) -> Result<CallResponse>> MockContract for FnContract<F> {
+    fn execute(&self) -> Result<CallResponse> {
+        (self.0)(&context())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MockEvent {
+    pub alkane: AlkaneId,
+    pub topic: Vec<u8>,
+    pub data: Vec<u8>,
+}
+
+/// The state calls commit or roll back.
+#[derive(Default, Clone)]
+struct Ledger {
+    storage: BTreeMap<AlkaneId, BTreeMap<Vec<u8>, Vec<u8>>>,
+    balances: BTreeMap<(AlkaneId, AlkaneId), u128>,
+    events: Vec<MockEvent>,
+}
+
+struct HostState {
+    /// Context of code run outside a call, set by [`set_mock_context`].
+    base: Context,
+    frames: Vec<Context>,
+    ledger: Ledger,
+    checkpoints: Vec<Ledger>,
+    contracts: BTreeMap<AlkaneId, Rc<dyn MockContract>>,
+    height: u64,
+    sequence: u128,
+    fuel: u64,
+    transaction: Vec<u8>,
+    block: Vec<u8>,
+    diesel_mints: u128,
+    miner_fee: u128,
+    logs: Vec<String>,
+}
+
+impl Default for HostState {
+    fn default() -> Self {
+        Self {
+            base: Context::default(),
+            frames: vec![],
+            ledger: Ledger::default(),
+            checkpoints: vec![],
+            contracts: BTreeMap::new(),
+            height: 0,
+            sequence: 1,
+            fuel: 100_000_000,
+            transaction: vec![],
+            block: vec![],
+            diesel_mints: 0,
+            miner_fee: 0,
+            logs: vec![],
+        }
+    }
+}
+
+impl HostState {
+    fn current(&self) -> &Context {
+        self.frames.last().unwrap_or(&self.base)
+    }
+
+    fn checkpoint(&mut self) {
+        self.checkpoints.push(self.ledger.clone());
+    }
+
+    fn commit(&mut self) {
+        self.checkpoints.pop();
+    }
+
+    fn rollback(&mut self) {
+        if let Some(ledger) = self.checkpoints.pop() {
+            self.ledger = ledger;
+        }
+    }
+
+    fn save_storage(&mut self, alkane: AlkaneId, writes: StorageMap) {
+        self.ledger
+            .storage
+            .entry(alkane)
+            .or_default()
+            .extend(writes.0);
+    }
+
+    /// Moves `parcel` from `from` to `to`. An alkane can always send its
+    /// own token, minting whatever it doesn't hold.
+    fn transfer(&mut self, parcel: &AlkaneTransferParcel, from: AlkaneId, to: AlkaneId) -> Result<()> {
+        for transfer in &parcel.0 {
+            self.debit(transfer, from)?;
+            self.credit(transfer, to)?;
+        }
+        Ok(())
+    }
+
+    fn debit(&mut self, transfer: &AlkaneTransfer, from: AlkaneId) -> Result<()> {
+        let balance = self.ledger.balances.entry((from, transfer.id)).or_default();
+        if *balance < transfer.value {
+            if transfer.id != from {
+                return Err(anyhow!(
+                    "balance underflow, transferring({:?}), from({:?}), balance({})",
+                    transfer,
+                    from,
+                    balance
+                ));
+            }
+            *balance = transfer.value;
+        }
+        *balance -= transfer.value;
+        Ok(())
+    }
+
+    fn credit(&mut self, transfer: &AlkaneTransfer, to: AlkaneId) -> Result<()> {
+        // alkanes sent to a non-contract caller leave the ledger
+        if to == AlkaneId::default() {
+            return Ok(());
+        }
+        let balance = self.ledger.balances.entry((to, transfer.id)).or_default();
+        *balance = balance
+            .checked_add(transfer.value)
+            .ok_or_else(|| anyhow!("balance overflow during transfer_from"))?;
+        Ok(())
+    }
+
+    /// Resolves a call target to the alkane that runs and its code,
+    /// deploying a clone for `5:n` and `6:n`.
+    fn resolve(&mut self, target: AlkaneId) -> Result<(AlkaneId, Rc<dyn MockContract>)> {
+        let template = match target.block {
+            1 | 3 => {
+                return Err(anyhow!(
+                    "the mock host can't deploy code, register a contract at the target instead"
+                ))
+            }
+            5 => Some(AlkaneId::new(2, target.tx)),
+            6 => Some(AlkaneId::new(4, target.tx)),
+            _ => None,
+        };
+        let code = |id: AlkaneId, contracts: &BTreeMap<AlkaneId, Rc<dyn MockContract>>| {
+            contracts
+                .get(&id)
+                .cloned()
+                .ok_or_else(|| anyhow!("no mock contract registered at {}:{}", id.block, id.tx))
+        };
+        match template {
+            Some(template) => {
+                let contract = code(template, &self.contracts)?;
+                let id = AlkaneId::new(2, self.sequence);
+                self.sequence += 1;
+                self.contracts.insert(id, contract.clone());
+                Ok((id, contract))
+            }
+            None => Ok((target, code(target, &self.contracts)?)),
+        }
+    }
+
+    fn precompile(&self, target: AlkaneId) -> Result<Vec<u8>> {
+        let block = || -> Result<Block> {
+            bitcoin::consensus::deserialize(&self.block)
+                .map_err(|_| anyhow!("no mock block set"))
+        };
+        match target.tx {
+            0 => Ok(serialize(&block()?.header)),
+            1 => block()?
+                .txdata
+                .first()
+                .map(serialize)
+                .ok_or_else(|| anyhow!("mock block has no coinbase")),
+            2 => Ok(self.diesel_mints.to_le_bytes().to_vec()),
+            3 => Ok(self.miner_fee.to_le_bytes().to_vec()),
+            tx => Err(anyhow!("unknown precompile {}:{}", target.block, tx)),
+        }
+    }
+}
+
+thread_local! {
+    static HOST: RefCell<HostState> = RefCell::new(HostState::default());
+}
+
+fn with_host<R>(f: impl FnOnce(&mut HostState) -> R) -> R {
+    HOST.with(|host| f(&mut host.borrow_mut()))
+}
+
+/// Runs `contract` in a new frame with `context`, returning its response
+/// and the storage writes it left pending.
+fn execute_frame(
+    context: Context,
+    contract: &dyn MockContract,
+) -> Result<(CallResponse, StorageMap)> {
+    with_host(|host| host.frames.push(context));
+    let caller_cache = swap_cache(None);
+    let result = contract.execute();
+    let writes = swap_cache(caller_cache).unwrap_or_default();
+    with_host(|host| host.frames.pop());
+    result.map(|response| (response, writes))
+}
+
+/// Handle to this thread's mock host.
+#[derive(Clone, Copy)]
+pub struct MockHost(());
+
+impl MockHost {
+    /// Resets this thread's host to an empty one.
+    pub fn new() -> Self {
+        with_host(|host| *host = HostState::default());
+        swap_cache(None);
+        MockHost(())
+    }
+
+    pub fn with_height(self, height: u64) -> Self {
+        with_host(|host| host.height = height);
+        self
+    }
+
+    pub fn with_context(self, context: Context) -> Self {
+        set_mock_context(context);
+        self
+    }
+
+    pub fn set_height(&self, height: u64) {
+        with_host(|host| host.height = height);
+    }
+
+    /// The tx of the next alkane deployed to `2:n`.
+    pub fn set_sequence(&self, sequence: u128) {
+        with_host(|host| host.sequence = sequence);
+    }
+
+    pub fn set_fuel(&self, fuel: u64) {
+        with_host(|host| host.fuel = fuel);
+    }
+
+    pub fn set_transaction(&self, transaction: &Transaction) {
+        with_host(|host| host.transaction = serialize(transaction));
+    }
+
+    /// Also serves the header and coinbase precompiles.
+    pub fn set_block(&self, block: &Block) {
+        with_host(|host| host.block = serialize(block));
+    }
+
+    pub fn set_diesel_mints(&self, mints: u128) {
+        with_host(|host| host.diesel_mints = mints);
+    }
+
+    pub fn set_miner_fee(&self, fee: u128) {
+        with_host(|host| host.miner_fee = fee);
+    }
+
+    /// Registers `T`, dispatched through `M`, as the code of `id`.
+    pub fn register<T, M>(&self, id: AlkaneId)
+    where
+        T: AlkaneResponder + Default,
+        M: MessageDispatch<T> + 'static,
+    {
+        self.register_contract(id, Responder::<T, M>(PhantomData));
+    }
+
+    /// Registers a closure over the call's context as the code of `id`.
+    pub fn register_fn(
+        &self,
+        id: AlkaneId,
+        f: impl Fn(&Context) -> Result<CallResponse> + 'static,
+    ) {
+        self.register_contract(id, FnContract(f));
+    }
+
+    pub fn register_contract(&self, id: AlkaneId, contract: impl MockContract + 'static) {
+        with_host(|host| host.contracts.insert(id, Rc::new(contract)));
+    }
+
+    /// Credits `owner` with `value` of `token` out of thin air.
+    pub fn mint(&self, owner: &AlkaneId, token: &AlkaneId, value: u128) {
+        with_host(|host| {
+            *host.ledger.balances.entry((*owner, *token)).or_default() += value;
+        });
+    }
+
+    pub fn balance(&self, owner: &AlkaneId, token: &AlkaneId) -> u128 {
+        balance(owner, token)
+    }
+
+    /// Committed storage of `alkane`.
+    pub fn storage(&self, alkane: &AlkaneId, key: &[u8]) -> Vec<u8> {
+        with_host(|host| {
+            host.ledger
+                .storage
+                .get(alkane)
+                .and_then(|storage| storage.get(key))
+                .cloned()
+                .unwrap_or_default()
+        })
+    }
+
+    pub fn set_storage(&self, alkane: &AlkaneId, key: &[u8], value: &[u8]) {
+        with_host(|host| {
+            host.ledger
+                .storage
+                .entry(*alkane)
+                .or_default()
+                .insert(key.to_vec(), value.to_vec());
+        });
+    }
+
+    /// Saves what code run outside a call has written, as the indexer does
+    /// when a call returns.
+    pub fn commit(&self) {
+        let writes = swap_cache(None).unwrap_or_default();
+        with_host(|host| {
+            let myself = host.base.myself;
+            host.save_storage(myself, writes);
+        });
+    }
+
+    pub fn events(&self) -> Vec<MockEvent> {
+        with_host(|host| host.ledger.events.clone())
+    }
+
+    pub fn logs(&self) -> Vec<String> {
+        with_host(|host| host.logs.clone())
+    }
+
+    /// Calls `target` the way a protostone does: `incoming` is credited to
+    /// the target, and the alkanes it returns leave the ledger. Nothing is
+    /// saved if the call fails.
+    pub fn call(
+        &self,
+        target: AlkaneId,
+        inputs: Vec<u128>,
+        incoming: AlkaneTransferParcel,
+    ) -> Result<CallResponse> {
+        with_host(|host| host.checkpoint());
+        let result = (|| {
+            let (myself, contract) = with_host(|host| host.resolve(target))?;
+            with_host(|host| {
+                incoming
+                    .0
+                    .iter()
+                    .try_for_each(|transfer| host.credit(transfer, myself))
+            })?;
+            let context = Context {
+                myself,
+                caller: AlkaneId::default(),
+                vout: 0,
+                incoming_alkanes: incoming.clone(),
+                inputs,
+            };
+            let (response, writes) = execute_frame(context, &*contract)?;
+            with_host(|host| {
+                host.save_storage(myself, writes);
+                response
+                    .alkanes
+                    .0
+                    .iter()
+                    .try_for_each(|transfer| host.debit(transfer, myself))
+            })?;
+            Ok(response)
+        })();
+        with_host(|host| match result {
+            Ok(_) => host.commit(),
+            Err(_) => host.rollback(),
+        });
+        result
+    }
+}
+
+impl Default for MockHost {
+    fn default() -> Self {
+        MockHost::new()
+    }
+}
+
+/// Sets the context of code run outside a call.
+pub fn set_mock_context(context: Context) {
+    with_host(|host| host.base = context);
+}
+
+pub(crate) fn context() -> Context {
+    with_host(|host| host.current().clone())
+}
+
+/// Committed storage of the running alkane.
+pub(crate) fn load(key: &[u8]) -> Vec<u8> {
+    with_host(|host| {
+        let myself = host.current().myself;
+        host.ledger
+            .storage
+            .get(&myself)
+            .and_then(|storage| storage.get(key))
+            .cloned()
+            .unwrap_or_default()
+    })
+}
+
+pub(crate) fn balance(owner: &AlkaneId, token: &AlkaneId) -> u128 {
+    with_host(|host| {
+        host.ledger
+            .balances
+            .get(&(*owner, *token))
+            .copied()
+            .unwrap_or_default()
+    })
+}
+
+pub(crate) fn height() -> u64 {
+    with_host(|host| host.height)
+}
+
+pub(crate) fn sequence() -> u128 {
+    with_host(|host| host.sequence)
+}
+
+pub(crate) fn fuel() -> u64 {
+    with_host(|host| host.fuel)
+}
+
+pub(crate) fn transaction() -> Vec<u8> {
+    with_host(|host| host.transaction.clone())
+}
+
+pub(crate) fn block() -> Vec<u8> {
+    with_host(|host| host.block.clone())
+}
+
+pub(crate) fn log(message: &str) {
+    with_host(|host| host.logs.push(message.to_string()));
+    #[cfg(target_arch = "wasm32")]
+    crate::imports::externs::write(message);
+    #[cfg(not(target_arch = "wasm32"))]
+    print!("{}", message);
+}
+
+pub(crate) fn emit_event(topic: &[u8], data: &[u8]) {
+    if topic.len() > MAX_EVENT_TOPIC_SIZE || data.len() > MAX_EVENT_DATA_SIZE {
+        panic!(
+            "event of {} topic bytes and {} data bytes exceeds the limits",
+            topic.len(),
+            data.len()
+        );
+    }
+    with_host(|host| {
+        let alkane = host.current().myself;
+        host.ledger.events.push(MockEvent {
+            alkane,
+            topic: topic.to_vec(),
+            data: data.to_vec(),
+        });
+    });
+}
+
+/// An extcall from the running alkane.
+pub(crate) fn extcall(
+    kind: CallKind,
+    cellpack: &Cellpack,
+    incoming: &AlkaneTransferParcel,
+) -> Result<CallResponse> {
+    if cellpack.target.block == PRECOMPILE_BLOCK {
+        return with_host(|host| host.precompile(cellpack.target)).map(|data| CallResponse {
+            alkanes: AlkaneTransferParcel::default(),
+            data,
+        });
+    }
+    let caller = with_host(|host| -> Result<Context> {
+        if host.frames.len() >= MAX_CALL_DEPTH {
+            return Err(anyhow!(
+                "Possible infinite recursion encountered: checkpoint depth too large({})",
+                host.frames.len()
+            ));
+        }
+        Ok(host.current().clone())
+    })?;
+
+    with_host(|host| {
+        host.checkpoint();
+        // the indexer saves the caller's pending writes before the call
+        if let Some(pending) = swap_cache(None) {
+            host.save_storage(caller.myself, pending.clone());
+            swap_cache(Some(pending));
+        }
+    });
+    let result = (|| {
+        let (target, contract) = with_host(|host| host.resolve(cellpack.target))?;
+        let context = match kind {
+            CallKind::Delegatecall => Context {
+                myself: caller.myself,
+                caller: caller.caller,
+                vout: caller.vout,
+                incoming_alkanes: incoming.clone(),
+                inputs: cellpack.inputs.clone(),
+            },
+            CallKind::Call | CallKind::Staticcall => {
+                with_host(|host| host.transfer(incoming, caller.myself, target))?;
+                Context {
+                    myself: target,
+                    caller: caller.myself,
+                    vout: caller.vout,
+                    incoming_alkanes: incoming.clone(),
+                    inputs: cellpack.inputs.clone(),
+                }
+            }
+        };
+        let (from, to) = (context.myself, context.caller);
+        let (response, writes) = execute_frame(context, &*contract)?;
+        with_host(|host| {
+            host.save_storage(from, writes);
+            if kind != CallKind::Delegatecall {
+                host.transfer(&response.alkanes, from, to)?;
+            }
+            Ok::<_, anyhow::Error>(())
+        })?;
+        Ok(response)
+    })();
+    with_host(|host| match (&result, kind) {
+        (Ok(_), CallKind::Staticcall) | (Err(_), _) => host.rollback(),
+        (Ok(_), _) => host.commit(),
+    });
+    result.map_err(|e| anyhow!("Extcall failed: ALKANES: revert: Error: {}", e))
+}
@@ -0,0 +1,79 @@
+//! Emergency stop.
+//!
+//! `Paused` and `Unpaused` events are only emitted with the `events`
+//! feature, for the same reason as in [`crate::access`].
+//!
+//! Contracts decide who may pause by implementing
+//! [`Pausable::authorize_pause`], usually with `only_owner` or `only_role`,
+//! and guard the methods a pause should stop with
+//! [`Pausable::when_not_paused`]. Like [`crate::access`], the standard
+//! opcodes are exposed by adding their variants to the message enum:
+//!
+//! ```ignore
+//! #[opcode(0x7f10)]
+//! Pause,
+//! #[opcode(0x7f11)]
+//! Unpause,
+//! #[opcode(0x7f12)]
+//! #[returns(bool)]
+//! Paused,
+//! ```
+
+use crate::runtime::AlkaneResponder;
+use crate::storage::StorageValue;
+use alkanes_support::response::CallResponse;
+use anyhow::{anyhow, Result};
+
+pub const PAUSE: u128 = 0x7f10;
+pub const UNPAUSE: u128 = 0x7f11;
+pub const PAUSED: u128 = 0x7f12;
+
+pub trait Pausable: AlkaneResponder {
+    fn authorize_pause(&self) -> Result<()>;
+
+    fn paused_value(&self) -> StorageValue<bool> {
+        StorageValue::new("/paused")
+    }
+    fn is_paused(&self) -> bool {
+        self.paused_value().get()
+    }
+    fn when_not_paused(&self) -> Result<()> {
+        if self.is_paused() {
+            Err(anyhow!("Pausable: paused"))
+        } else {
+            Ok(())
+        }
+    }
+    fn when_paused(&self) -> Result<()> {
+        if self.is_paused() {
+            Ok(())
+        } else {
+            Err(anyhow!("Pausable: not paused"))
+        }
+    }
+
+    fn pause(&self) -> Result<CallResponse> {
+        let context = self.context()?;
+        self.authorize_pause()?;
+        self.when_not_paused()?;
+        self.paused_value().set(true);
+        #[cfg(feature = "events")]
+        self.emit_event(b"Paused", &[]);
+        Ok(CallResponse::forward(&context.incoming_alkanes))
+    }
+    fn unpause(&self) -> Result<CallResponse> {
+        let context = self.context()?;
+        self.authorize_pause()?;
+        self.when_paused()?;
+        self.paused_value().clear();
+        #[cfg(feature = "events")]
+        self.emit_event(b"Unpaused", &[]);
+        Ok(CallResponse::forward(&context.incoming_alkanes))
+    }
+    fn paused(&self) -> Result<CallResponse> {
+        let context = self.context()?;
+        let mut response = CallResponse::forward(&context.incoming_alkanes);
+        response.data = vec![self.is_paused() as u8];
+        Ok(response)
+    }
+}
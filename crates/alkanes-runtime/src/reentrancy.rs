@@ -0,0 +1,51 @@
+//! Storage-backed reentrancy guard.
+//!
+//! The indexer saves a caller's pending storage writes before every extcall,
+//! so a lock set on entry is visible to any nested frame of the same alkane,
+//! at whatever depth it is re-entered. Mark a message variant
+//! `#[nonreentrant]` in a `MessageDispatch` enum to run its handler under the
+//! guard, or hold a [`ReentrancyGuard`] in code:
+//!
+//! ```ignore
+//! #[derive(MessageDispatch)]
+//! enum PoolMessage {
+//!     #[opcode(3)]
+//!     #[nonreentrant]
+//!     Swap { amount_out: u128 },
+//! }
+//! ```
+//!
+//! Guarded methods of one alkane share a single lock, so none of them can be
+//! entered while another is running further up the call stack.
+
+use crate::storage::StorageValue;
+use anyhow::{anyhow, Result};
+
+/// Storage key of the lock.
+pub const REENTRANCY_LOCK: &str = "/__reentrancy_lock";
+
+/// Holds the lock until dropped.
+pub struct ReentrancyGuard(StorageValue<bool>);
+
+impl ReentrancyGuard {
+    pub fn enter() -> Result<Self> {
+        let lock = StorageValue::<bool>::new(REENTRANCY_LOCK);
+        if lock.get() {
+            return Err(anyhow!("ReentrancyGuard: reentrant call"));
+        }
+        lock.set(true);
+        Ok(ReentrancyGuard(lock))
+    }
+}
+
+impl Drop for ReentrancyGuard {
+    fn drop(&mut self) {
+        self.0.clear();
+    }
+}
+
+/// Runs `f` holding the lock, failing if it is already held.
+pub fn nonreentrant<T>(f: impl FnOnce() -> Result<T>) -> Result<T> {
+    let _guard = ReentrancyGuard::enter()?;
+    f()
+}
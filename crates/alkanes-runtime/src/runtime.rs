@@ -1,7 +1,10 @@
+#[cfg(feature = "test-utils")]
+use crate::mock;
+#[cfg(not(feature = "test-utils"))]
 #[allow(unused_imports)]
 use crate::imports::{
-    __balance, __call, __delegatecall, __fuel, __height, __load_block, __load_context,
-    __load_storage, __load_transaction, __log, __request_block, __request_context,
+    __balance, __call, __delegatecall, __emit_event, __fuel, __height, __load_block,
+    __load_context, __load_storage, __load_transaction, __log, __request_block, __request_context,
     __request_storage, __request_transaction, __returndatacopy, __sequence, __staticcall,
     abort, /*, __load_output, __request_output */
 };
@@ -33,28 +36,53 @@ use alkanes_support::{
 #[cfg(feature = "panic-hook")]
 use std::panic;
 
+#[cfg(not(feature = "test-utils"))]
 fn _abort() {
     unsafe {
         abort(0, 0, 0, 0);
     }
 }
 
+// the mock host reports a failed call through its `Result`
+#[cfg(feature = "test-utils")]
+fn _abort() {}
+
+#[cfg(not(feature = "test-utils"))]
 static mut _CACHE: Option<StorageMap> = None;
 
-pub fn initialize_cache() {
-    unsafe {
-        if _CACHE.is_none() {
-            _CACHE = Some(StorageMap::default());
-        }
-    }
+// tests run on many threads, each against its own mock host
+#[cfg(feature = "test-utils")]
+thread_local! {
+    static _CACHE: std::cell::RefCell<Option<StorageMap>> = const { std::cell::RefCell::new(None) };
 }
 
 #[allow(static_mut_refs)]
-pub fn get_cache() -> StorageMap {
+fn with_cache<R>(f: impl FnOnce(&mut Option<StorageMap>) -> R) -> R {
+    #[cfg(not(feature = "test-utils"))]
     unsafe {
-        initialize_cache();
-        _CACHE.as_ref().unwrap().clone()
+        f(&mut _CACHE)
     }
+    #[cfg(feature = "test-utils")]
+    _CACHE.with(|cache| f(&mut cache.borrow_mut()))
+}
+
+pub fn initialize_cache() {
+    with_cache(|cache| {
+        if cache.is_none() {
+            *cache = Some(StorageMap::default());
+        }
+    });
+}
+
+pub fn get_cache() -> StorageMap {
+    with_cache(|cache| cache.get_or_insert_with(StorageMap::default).clone())
+}
+
+/// Replaces the pending storage writes, returning the old ones. The mock
+/// host gives every call frame its own.
+#[cfg(feature = "test-utils")]
+pub(crate) fn swap_cache(next: Option<StorageMap>) -> Option<StorageMap> {
+    with_cache(|cache| std::mem::replace(cache, next))
 }
 
 #[allow(static_mut_refs)]
@@ -85,8 +113,11 @@ pub fn response_to_i32(response: ExtendedCallResponse) -> i32 {
 }
 
 pub trait Extcall {
+    #[cfg(not(feature = "test-utils"))]
     fn __call(cellpack: i32, outgoing_alkanes: i32, checkpoint: i32, fuel: u64) -> i32;
-    #[allow(static_mut_refs)]
+    #[cfg(feature = "test-utils")]
+    const KIND: mock::CallKind;
+    #[cfg(not(feature = "test-utils"))]
     fn call(
         cellpack: &Cellpack,
         outgoing_alkanes: &AlkaneTransferParcel,
@@ -136,30 +167,47 @@ pub trait Extcall {
             Ok(response)
         }
     }
+    #[cfg(feature = "test-utils")]
+    fn call(
+        cellpack: &Cellpack,
+        outgoing_alkanes: &AlkaneTransferParcel,
+        _fuel: u64,
+    ) -> Result<CallResponse> {
+        mock::extcall(Self::KIND, cellpack, outgoing_alkanes)
+    }
 }
 
 pub struct Call(());
 
 impl Extcall for Call {
+    #[cfg(not(feature = "test-utils"))]
     fn __call(cellpack: i32, outgoing_alkanes: i32, checkpoint: i32, fuel: u64) -> i32 {
         unsafe { __call(cellpack, outgoing_alkanes, checkpoint, fuel) }
     }
+    #[cfg(feature = "test-utils")]
+    const KIND: mock::CallKind = mock::CallKind::Call;
 }
 
 pub struct Delegatecall(());
 
 impl Extcall for Delegatecall {
+    #[cfg(not(feature = "test-utils"))]
     fn __call(cellpack: i32, outgoing_alkanes: i32, checkpoint: i32, fuel: u64) -> i32 {
         unsafe { __delegatecall(cellpack, outgoing_alkanes, checkpoint, fuel) }
     }
+    #[cfg(feature = "test-utils")]
+    const KIND: mock::CallKind = mock::CallKind::Delegatecall;
 }
 
 pub struct Staticcall(());
 
 impl Extcall for Staticcall {
+    #[cfg(not(feature = "test-utils"))]
     fn __call(cellpack: i32, outgoing_alkanes: i32, checkpoint: i32, fuel: u64) -> i32 {
         unsafe { __staticcall(cellpack, outgoing_alkanes, checkpoint, fuel) }
     }
+    #[cfg(feature = "test-utils")]
+    const KIND: mock::CallKind = mock::CallKind::Staticcall;
 }
 
 pub trait AlkaneResponder: 'static {
@@ -181,6 +229,11 @@ pub trait AlkaneResponder: 'static {
             Err(anyhow!("proxy already initialized"))
         }
     }
+    #[cfg(feature = "test-utils")]
+    fn context(&self) -> Result<Context> {
+        Ok(mock::context())
+    }
+    #[cfg(not(feature = "test-utils"))]
     fn context(&self) -> Result<Context> {
         unsafe {
             let mut buffer: Vec<u8> = to_arraybuffer_layout(vec![0; __request_context() as usize]);
@@ -189,6 +242,11 @@ pub trait AlkaneResponder: 'static {
             res
         }
     }
+    #[cfg(feature = "test-utils")]
+    fn block(&self) -> Vec<u8> {
+        mock::block()
+    }
+    #[cfg(not(feature = "test-utils"))]
     fn block(&self) -> Vec<u8> {
         unsafe {
             let mut buffer: Vec<u8> = to_arraybuffer_layout(vec![0; __request_block() as usize]);
@@ -196,17 +254,19 @@ pub trait AlkaneResponder: 'static {
             (&buffer[4..]).to_vec()
         }
     }
-    #[allow(static_mut_refs)]
     fn initialize(&self) -> &Self {
-        unsafe {
-            if _CACHE.is_none() {
-                initialize_cache();
-                #[cfg(feature = "panic-hook")]
-                panic::set_hook(Box::new(panic_hook));
-            }
-            self
+        if with_cache(|cache| cache.is_none()) {
+            initialize_cache();
+            #[cfg(feature = "panic-hook")]
+            panic::set_hook(Box::new(panic_hook));
         }
+        self
+    }
+    #[cfg(feature = "test-utils")]
+    fn transaction(&self) -> Vec<u8> {
+        mock::transaction()
     }
+    #[cfg(not(feature = "test-utils"))]
     fn transaction(&self) -> Vec<u8> {
         unsafe {
             let mut buffer: Vec<u8> =
@@ -241,34 +301,37 @@ pub trait AlkaneResponder: 'static {
         }
     }
     */
-    #[allow(static_mut_refs)]
     fn load(&self, k: Vec<u8>) -> Vec<u8> {
+        let cached = with_cache(|cache| {
+            cache
+                .get_or_insert_with(StorageMap::default)
+                .0
+                .get(&k)
+                .cloned()
+        });
+        if let Some(v) = cached {
+            return v;
+        }
+        #[cfg(feature = "test-utils")]
+        return mock::load(&k);
+        #[cfg(not(feature = "test-utils"))]
         unsafe {
-            initialize_cache();
-            if _CACHE.as_ref().unwrap().0.contains_key(&k) {
-                _CACHE
-                    .as_ref()
-                    .unwrap()
-                    .get(&k)
-                    .map(|v| v.clone())
-                    .unwrap_or_else(|| Vec::<u8>::new())
-            } else {
-                let mut key_bytes = to_arraybuffer_layout(&k);
-                let key = to_passback_ptr(&mut key_bytes);
-                let buf_size = __request_storage(key) as usize;
-                let mut buffer: Vec<u8> = to_arraybuffer_layout(vec![0; buf_size]);
-                __load_storage(key, to_passback_ptr(&mut buffer));
-                (&buffer[4..]).to_vec()
-            }
+            let mut key_bytes = to_arraybuffer_layout(&k);
+            let key = to_passback_ptr(&mut key_bytes);
+            let buf_size = __request_storage(key) as usize;
+            let mut buffer: Vec<u8> = to_arraybuffer_layout(vec![0; buf_size]);
+            __load_storage(key, to_passback_ptr(&mut buffer));
+            (&buffer[4..]).to_vec()
         }
     }
-    #[allow(static_mut_refs)]
     fn store(&self, k: Vec<u8>, v: Vec<u8>) {
-        unsafe {
-            initialize_cache();
-            _CACHE.as_mut().unwrap().set(&k, &v);
-        }
+        with_cache(|cache| cache.get_or_insert_with(StorageMap::default).set(&k, &v));
     }
+    #[cfg(feature = "test-utils")]
+    fn balance(&self, who: &AlkaneId, what: &AlkaneId) -> u128 {
+        mock::balance(who, what)
+    }
+    #[cfg(not(feature = "test-utils"))]
     fn balance(&self, who: &AlkaneId, what: &AlkaneId) -> u128 {
         unsafe {
             let mut who_bytes: Vec<u8> = to_arraybuffer_layout::<Vec<u8>>(who.clone().into());
@@ -280,6 +343,11 @@ pub trait AlkaneResponder: 'static {
             u128::from_le_bytes((&output[4..]).try_into().unwrap())
         }
     }
+    #[cfg(feature = "test-utils")]
+    fn sequence(&self) -> u128 {
+        mock::sequence()
+    }
+    #[cfg(not(feature = "test-utils"))]
     fn sequence(&self) -> u128 {
         unsafe {
             let mut buffer: Vec<u8> = to_arraybuffer_layout(vec![0; 16]);
@@ -287,6 +355,11 @@ pub trait AlkaneResponder: 'static {
             u128::from_le_bytes((&buffer[4..]).try_into().unwrap())
         }
     }
+    #[cfg(feature = "test-utils")]
+    fn fuel(&self) -> u64 {
+        mock::fuel()
+    }
+    #[cfg(not(feature = "test-utils"))]
     fn fuel(&self) -> u64 {
         unsafe {
             let mut buffer: Vec<u8> = to_arraybuffer_layout(vec![0; 8]);
@@ -294,6 +367,11 @@ pub trait AlkaneResponder: 'static {
             u64::from_le_bytes((&buffer[4..]).try_into().unwrap())
         }
     }
+    #[cfg(feature = "test-utils")]
+    fn height(&self) -> u64 {
+        mock::height()
+    }
+    #[cfg(not(feature = "test-utils"))]
     fn height(&self) -> u64 {
         unsafe {
             let mut buffer: Vec<u8> = to_arraybuffer_layout(vec![0; 8]);
@@ -301,6 +379,25 @@ pub trait AlkaneResponder: 'static {
             u64::from_le_bytes((&buffer[4..]).try_into().unwrap())
         }
     }
+    /// Emits a structured event that the indexer records per block, keyed by
+    /// this alkane and `topic`. Events emitted by a frame that later reverts,
+    /// or inside a staticcall, are dropped. Topics are capped at 64 bytes and
+    /// data at 4096 bytes; exceeding either aborts the call.
+    #[cfg(not(feature = "test-utils"))]
+    fn emit_event(&self, topic: &[u8], data: &[u8]) {
+        unsafe {
+            let mut topic_bytes = to_arraybuffer_layout(topic);
+            let mut data_bytes = to_arraybuffer_layout(data);
+            __emit_event(
+                to_passback_ptr(&mut topic_bytes),
+                to_passback_ptr(&mut data_bytes),
+            );
+        }
+    }
+    #[cfg(feature = "test-utils")]
+    fn emit_event(&self, topic: &[u8], data: &[u8]) {
+        mock::emit_event(topic, data)
+    }
     fn extcall<T: Extcall>(
         &self,
         cellpack: &Cellpack,
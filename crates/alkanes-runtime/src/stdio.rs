@@ -1,10 +1,18 @@
+#[cfg(not(feature = "test-utils"))]
 use crate::imports::__log;
+#[cfg(not(feature = "test-utils"))]
 use metashrew_support::compat::{to_arraybuffer_layout, to_passback_ptr};
 pub use std::fmt::{Error, Write};
 
 pub struct Stdout(());
 
 impl Write for Stdout {
+    #[cfg(feature = "test-utils")]
+    fn write_str(&mut self, s: &str) -> Result<(), Error> {
+        crate::mock::log(s);
+        Ok(())
+    }
+    #[cfg(not(feature = "test-utils"))]
     fn write_str(&mut self, s: &str) -> Result<(), Error> {
         let mut data = to_arraybuffer_layout::<Vec<u8>>(s.to_string().as_bytes().to_vec());
         unsafe {
@@ -1,10 +1,15 @@
 use crate::runtime::AlkaneResponder;
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 
+use alkanes_support::id::AlkaneId;
 use alkanes_support::response::CallResponse;
 use metashrew_support::index_pointer::KeyValuePointer;
+use std::marker::PhantomData;
 use std::sync::Arc;
 
+// Re-export the Storable derive and the storage attribute macro
+pub use alkanes_macros::{storage, Storable};
+
 struct StorageHandle(());
 
 impl AlkaneResponder for StorageHandle {}
@@ -30,3 +35,398 @@ impl KeyValuePointer for StoragePointer {
         Arc::new(RUNTIME_STORAGE.load(self.unwrap().as_ref().clone()))
     }
 }
+
+/// Encoding of values kept in typed storage.
+///
+/// Inside tuples and derived structs every value is self-delimiting:
+/// integers are fixed-width little endian and `String` / `Vec<u8>` carry a
+/// u32 length prefix. A value that fills a whole slot is stored by
+/// [`Storable::to_bytes`], which keeps `String` and `Vec<u8>` raw, so typed
+/// slots read the same bytes as `StoragePointer::get_value` and `get`.
+pub trait Storable: Sized {
+    fn encode(&self, out: &mut Vec<u8>);
+    fn decode(input: &mut &[u8]) -> Result<Self>;
+
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        self.encode(&mut out);
+        out
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let mut input = bytes;
+        let value = Self::decode(&mut input)?;
+        if !input.is_empty() {
+            return Err(anyhow!("{} trailing bytes in stored value", input.len()));
+        }
+        Ok(value)
+    }
+}
+
+fn take<'a>(input: &mut &'a [u8], len: usize) -> Result<&'a [u8]> {
+    if input.len() < len {
+        return Err(anyhow!(
+            "stored value truncated: wanted {} bytes, {} left",
+            len,
+            input.len()
+        ));
+    }
+    let (head, rest) = input.split_at(len);
+    *input = rest;
+    Ok(head)
+}
+
+macro_rules! storable_int {
+    ($($t:ty),*) => {
+        $(
+            impl Storable for $t {
+                fn encode(&self, out: &mut Vec<u8>) {
+                    out.extend_from_slice(&self.to_le_bytes());
+                }
+                fn decode(input: &mut &[u8]) -> Result<Self> {
+                    let bytes = take(input, std::mem::size_of::<$t>())?;
+                    Ok(<$t>::from_le_bytes(bytes.try_into()?))
+                }
+            }
+        )*
+    };
+}
+
+storable_int!(u8, u16, u32, u64, u128, i64, i128);
+
+impl Storable for bool {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.push(*self as u8);
+    }
+    fn decode(input: &mut &[u8]) -> Result<Self> {
+        match take(input, 1)?[0] {
+            0 => Ok(false),
+            1 => Ok(true),
+            v => Err(anyhow!("invalid stored bool {}", v)),
+        }
+    }
+}
+
+impl Storable for AlkaneId {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.block.encode(out);
+        self.tx.encode(out);
+    }
+    fn decode(input: &mut &[u8]) -> Result<Self> {
+        Ok(AlkaneId {
+            block: u128::decode(input)?,
+            tx: u128::decode(input)?,
+        })
+    }
+}
+
+impl Storable for Vec<u8> {
+    fn encode(&self, out: &mut Vec<u8>) {
+        (self.len() as u32).encode(out);
+        out.extend_from_slice(self);
+    }
+    fn decode(input: &mut &[u8]) -> Result<Self> {
+        let len = u32::decode(input)? as usize;
+        Ok(take(input, len)?.to_vec())
+    }
+    fn to_bytes(&self) -> Vec<u8> {
+        self.clone()
+    }
+    fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        Ok(bytes.to_vec())
+    }
+}
+
+impl Storable for String {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.as_bytes().to_vec().encode(out);
+    }
+    fn decode(input: &mut &[u8]) -> Result<Self> {
+        Ok(String::from_utf8(Vec::<u8>::decode(input)?)?)
+    }
+    fn to_bytes(&self) -> Vec<u8> {
+        self.as_bytes().to_vec()
+    }
+    fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        Ok(String::from_utf8(bytes.to_vec())?)
+    }
+}
+
+macro_rules! storable_tuple {
+    ($($name:ident),+) => {
+        impl<$($name: Storable),+> Storable for ($($name,)+) {
+            #[allow(non_snake_case)]
+            fn encode(&self, out: &mut Vec<u8>) {
+                let ($($name,)+) = self;
+                $($name.encode(out);)+
+            }
+            fn decode(input: &mut &[u8]) -> Result<Self> {
+                Ok(($($name::decode(input)?,)+))
+            }
+        }
+    };
+}
+
+storable_tuple!(A, B);
+storable_tuple!(A, B, C);
+storable_tuple!(A, B, C, D);
+
+/// Typed storage anchored at a pointer. `#[storage]` builds accessors out of
+/// [`StorageCollection::at`].
+pub trait StorageCollection: Sized {
+    fn from_pointer(pointer: StoragePointer) -> Self;
+
+    /// Anchored at `keyword` followed by each of `keys`.
+    fn at(keyword: &str, keys: &[Vec<u8>]) -> Self {
+        let pointer = keys
+            .iter()
+            .fold(StoragePointer::from_keyword(keyword), |pointer, key| {
+                pointer.select(key)
+            });
+        Self::from_pointer(pointer)
+    }
+}
+
+fn read<T: Storable>(pointer: &StoragePointer) -> T {
+    // only typed code writes these slots, so a value that doesn't decode is
+    // a bug in the contract; abort rather than carry on with a default
+    T::from_bytes(&pointer.get()).unwrap_or_else(|e| {
+        panic!(
+            "corrupt value at {}: {}",
+            String::from_utf8_lossy(&pointer.unwrap()),
+            e
+        )
+    })
+}
+
+/// A single value. An unset slot, or one holding zero bytes such as an empty
+/// `String`, reads as `None`, or the default from [`StorageValue::get`].
+pub struct StorageValue<T> {
+    pointer: StoragePointer,
+    _value: PhantomData<T>,
+}
+
+impl<T: Storable> StorageCollection for StorageValue<T> {
+    fn from_pointer(pointer: StoragePointer) -> Self {
+        StorageValue {
+            pointer,
+            _value: PhantomData,
+        }
+    }
+}
+
+impl<T: Storable> StorageValue<T> {
+    pub fn new(keyword: &str) -> Self {
+        Self::from_pointer(StoragePointer::from_keyword(keyword))
+    }
+
+    pub fn get(&self) -> T
+    where
+        T: Default,
+    {
+        self.try_get().unwrap_or_default()
+    }
+
+    pub fn try_get(&self) -> Option<T> {
+        if self.is_set() {
+            Some(read(&self.pointer))
+        } else {
+            None
+        }
+    }
+
+    pub fn set(&self, value: T) {
+        self.pointer.clone().set(Arc::new(value.to_bytes()));
+    }
+
+    pub fn is_set(&self) -> bool {
+        !self.pointer.get().is_empty()
+    }
+
+    pub fn clear(&self) {
+        self.pointer.clone().set(Arc::new(vec![]));
+    }
+}
+
+/// A list kept in the `KeyValuePointer` list layout: the length at
+/// `u32::MAX` and items at their u32 index, so it reads lists written with
+/// `append`.
+pub struct StorageVec<T> {
+    pointer: StoragePointer,
+    _item: PhantomData<T>,
+}
+
+impl<T: Storable> StorageCollection for StorageVec<T> {
+    fn from_pointer(pointer: StoragePointer) -> Self {
+        StorageVec {
+            pointer,
+            _item: PhantomData,
+        }
+    }
+}
+
+impl<T: Storable> StorageVec<T> {
+    pub fn new(keyword: &str) -> Self {
+        Self::from_pointer(StoragePointer::from_keyword(keyword))
+    }
+
+    pub fn len(&self) -> u32 {
+        self.pointer.length()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn get(&self, index: u32) -> Option<T> {
+        if index >= self.len() {
+            return None;
+        }
+        Some(read(&self.pointer.select_index(index)))
+    }
+
+    pub fn set(&self, index: u32, value: T) -> Result<()> {
+        if index >= self.len() {
+            return Err(anyhow!(
+                "index {} out of bounds for length {}",
+                index,
+                self.len()
+            ));
+        }
+        self.pointer
+            .select_index(index)
+            .set(Arc::new(value.to_bytes()));
+        Ok(())
+    }
+
+    pub fn push(&self, value: T) {
+        self.pointer.append(Arc::new(value.to_bytes()));
+    }
+
+    pub fn pop(&self) -> Option<T> {
+        let len = self.len();
+        if len == 0 {
+            return None;
+        }
+        let value = self.get(len - 1);
+        self.pointer.select_index(len - 1).set(Arc::new(vec![]));
+        self.pointer.length_key().set_value::<u32>(len - 1);
+        value
+    }
+
+    /// Drops every item.
+    pub fn clear(&self) {
+        for index in 0..self.len() {
+            self.pointer.select_index(index).set(Arc::new(vec![]));
+        }
+        self.pointer.length_key().set_value::<u32>(0);
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = T> + '_ {
+        (0..self.len()).map(move |index| read(&self.pointer.select_index(index)))
+    }
+}
+
+/// A map that tracks its keys, so it has a length and can be iterated.
+///
+/// Values sit at `<pointer>/v/<key>`, the keys in a [`StorageVec`] at
+/// `<pointer>/k`, and each key's position in that list (plus one) at
+/// `<pointer>/i/<key>`. Removal swaps the last key into the freed position,
+/// so iteration order is insertion order only until something is removed.
+pub struct StorageMap<K, V> {
+    pointer: StoragePointer,
+    _entry: PhantomData<(K, V)>,
+}
+
+impl<K: Storable, V: Storable> StorageCollection for StorageMap<K, V> {
+    fn from_pointer(pointer: StoragePointer) -> Self {
+        StorageMap {
+            pointer,
+            _entry: PhantomData,
+        }
+    }
+}
+
+impl<K: Storable, V: Storable> StorageMap<K, V> {
+    pub fn new(keyword: &str) -> Self {
+        Self::from_pointer(StoragePointer::from_keyword(keyword))
+    }
+
+    fn value_pointer(&self, key: &K) -> StoragePointer {
+        self.pointer.keyword("/v/").select(&key.to_bytes())
+    }
+
+    fn position(&self, key: &K) -> StorageValue<u32> {
+        StorageValue::from_pointer(self.pointer.keyword("/i/").select(&key.to_bytes()))
+    }
+
+    fn keys_vec(&self) -> StorageVec<K> {
+        StorageVec::from_pointer(self.pointer.keyword("/k"))
+    }
+
+    pub fn len(&self) -> u32 {
+        self.keys_vec().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.position(key).is_set()
+    }
+
+    pub fn get(&self, key: &K) -> Option<V> {
+        if !self.contains_key(key) {
+            return None;
+        }
+        Some(read(&self.value_pointer(key)))
+    }
+
+    /// Returns the value `key` held before.
+    pub fn insert(&self, key: K, value: V) -> Option<V> {
+        let previous = self.get(&key);
+        self.value_pointer(&key).set(Arc::new(value.to_bytes()));
+        let position = self.position(&key);
+        if !position.is_set() {
+            let keys = self.keys_vec();
+            position.set(keys.len() + 1);
+            keys.push(key);
+        }
+        previous
+    }
+
+    pub fn remove(&self, key: &K) -> Option<V> {
+        let position = self.position(key);
+        let index = position.try_get()? - 1;
+        let previous = self.get(key);
+        let keys = self.keys_vec();
+        let last = keys.len() - 1;
+        if index != last {
+            if let Some(moved) = keys.get(last) {
+                self.position(&moved).set(index + 1);
+                let _ = keys.set(index, moved);
+            }
+        }
+        keys.pop();
+        position.clear();
+        self.value_pointer(key).set(Arc::new(vec![]));
+        previous
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = K> {
+        let keys = self.keys_vec();
+        (0..keys.len()).map(move |index| read(&keys.pointer.select_index(index)))
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = V> + '_ {
+        self.iter().map(|(_, value)| value)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (K, V)> + '_ {
+        self.keys().map(move |key| {
+            let value = read(&self.value_pointer(&key));
+            (key, value)
+        })
+    }
+}
@@ -0,0 +1,181 @@
+//! Access control, pausing and the reentrancy guard on the mock host.
+
+use alkanes_runtime::access::{AccessControl, DEFAULT_ADMIN_ROLE};
+use alkanes_runtime::message::MessageDispatch;
+use alkanes_runtime::mock::MockHost;
+use alkanes_runtime::pausable::Pausable;
+use alkanes_runtime::reentrancy::REENTRANCY_LOCK;
+use alkanes_runtime::runtime::AlkaneResponder;
+use alkanes_support::{
+    cellpack::Cellpack,
+    id::AlkaneId,
+    parcel::{AlkaneTransfer, AlkaneTransferParcel},
+    response::CallResponse,
+};
+use anyhow::Result;
+
+const VAULT: AlkaneId = AlkaneId { block: 2, tx: 1 };
+const ADMIN_TOKEN: AlkaneId = AlkaneId { block: 2, tx: 5 };
+const OPERATOR: AlkaneId = AlkaneId { block: 2, tx: 6 };
+const OPERATOR_ROLE: u128 = 1;
+
+#[derive(Default)]
+struct Vault(());
+
+#[derive(MessageDispatch)]
+enum VaultMessage {
+    #[opcode(0)]
+    Initialize { admin: AlkaneId },
+
+    #[opcode(1)]
+    Operate,
+
+    #[opcode(2)]
+    #[nonreentrant]
+    Reenter,
+
+    #[opcode(3)]
+    #[nonreentrant]
+    Guarded,
+
+    #[opcode(0x7f00)]
+    #[returns(bool)]
+    HasRole { role: u128, account: AlkaneId },
+
+    #[opcode(0x7f02)]
+    GrantRole { role: u128, account: AlkaneId },
+
+    #[opcode(0x7f04)]
+    RenounceRole { role: u128, account: AlkaneId },
+
+    #[opcode(0x7f10)]
+    Pause,
+
+    #[opcode(0x7f11)]
+    Unpause,
+}
+
+impl Vault {
+    fn initialize(&self, admin: AlkaneId) -> Result<CallResponse> {
+        self.observe_initialization()?;
+        self.grant_role_to(DEFAULT_ADMIN_ROLE, admin);
+        Ok(CallResponse::default())
+    }
+
+    fn operate(&self) -> Result<CallResponse> {
+        self.only_role(OPERATOR_ROLE)?;
+        self.when_not_paused()?;
+        Ok(CallResponse::default())
+    }
+
+    fn reenter(&self) -> Result<CallResponse> {
+        let context = self.context()?;
+        self.call(
+            &Cellpack {
+                target: context.myself,
+                inputs: vec![3],
+            },
+            &AlkaneTransferParcel::default(),
+            self.fuel(),
+        )
+    }
+
+    fn guarded(&self) -> Result<CallResponse> {
+        Ok(CallResponse::default())
+    }
+}
+
+impl AccessControl for Vault {}
+
+impl Pausable for Vault {
+    fn authorize_pause(&self) -> Result<()> {
+        self.only_role(DEFAULT_ADMIN_ROLE)
+    }
+}
+
+impl AlkaneResponder for Vault {}
+
+/// Calls the vault from the operator contract.
+struct Operator;
+
+impl AlkaneResponder for Operator {}
+
+fn host() -> Result<MockHost> {
+    let host = MockHost::new();
+    host.register::<Vault, VaultMessage>(VAULT);
+    host.register_fn(OPERATOR, |context| {
+        Operator.call(
+            &Cellpack {
+                target: VAULT,
+                inputs: context.inputs.clone(),
+            },
+            &AlkaneTransferParcel::default(),
+            0,
+        )
+    });
+    host.call(VAULT, vec![0, ADMIN_TOKEN.block, ADMIN_TOKEN.tx], Default::default())?;
+    Ok(host)
+}
+
+fn with_admin_token() -> AlkaneTransferParcel {
+    AlkaneTransferParcel(vec![AlkaneTransfer {
+        id: ADMIN_TOKEN,
+        value: 1,
+    }])
+}
+
+fn role_inputs(opcode: u128, role: u128, account: AlkaneId) -> Vec<u128> {
+    vec![opcode, role, account.block, account.tx]
+}
+
+#[test]
+fn roles_are_granted_by_their_admin() -> Result<()> {
+    let host = host()?;
+    let grant = role_inputs(0x7f02, OPERATOR_ROLE, OPERATOR);
+    let err = host.call(VAULT, grant.clone(), Default::default()).unwrap_err();
+    assert!(err.to_string().contains("missing role 0"), "{}", err);
+
+    host.call(VAULT, grant, with_admin_token())?;
+    let has_role = host.call(VAULT, role_inputs(0x7f00, OPERATOR_ROLE, OPERATOR), Default::default())?;
+    assert_eq!(has_role.data, vec![1]);
+
+    // the role is checked against the calling alkane
+    assert!(host.call(VAULT, vec![1], Default::default()).is_err());
+    host.call(OPERATOR, vec![1], Default::default())?;
+
+    // only the operator can renounce its role
+    let renounce = role_inputs(0x7f04, OPERATOR_ROLE, OPERATOR);
+    assert!(host.call(VAULT, renounce.clone(), with_admin_token()).is_err());
+    host.call(OPERATOR, renounce, Default::default())?;
+    assert!(host.call(OPERATOR, vec![1], Default::default()).is_err());
+    Ok(())
+}
+
+#[test]
+fn pausing_stops_guarded_methods() -> Result<()> {
+    let host = host()?;
+    host.call(VAULT, role_inputs(0x7f02, OPERATOR_ROLE, OPERATOR), with_admin_token())?;
+    assert!(host.call(VAULT, vec![0x7f10], Default::default()).is_err());
+    host.call(VAULT, vec![0x7f10], with_admin_token())?;
+
+    let err = host.call(OPERATOR, vec![1], Default::default()).unwrap_err();
+    assert!(err.to_string().contains("Pausable: paused"), "{}", err);
+    assert!(host.call(VAULT, vec![0x7f10], with_admin_token()).is_err());
+
+    host.call(VAULT, vec![0x7f11], with_admin_token())?;
+    host.call(OPERATOR, vec![1], Default::default())?;
+    Ok(())
+}
+
+#[test]
+fn nonreentrant_methods_cannot_be_reentered() -> Result<()> {
+    let host = host()?;
+    let err = host.call(VAULT, vec![2], Default::default()).unwrap_err();
+    assert!(err.to_string().contains("ReentrancyGuard: reentrant call"), "{}", err);
+
+    // the lock is released when a guarded method returns
+    host.call(VAULT, vec![3], Default::default())?;
+    host.call(VAULT, vec![3], Default::default())?;
+    assert!(host.storage(&VAULT, REENTRANCY_LOCK.as_bytes()).is_empty());
+    Ok(())
+}
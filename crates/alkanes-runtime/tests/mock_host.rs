@@ -0,0 +1,223 @@
+//! Runs a small contract natively against the mock host.
+
+use alkanes_runtime::message::MessageDispatch;
+use alkanes_runtime::mock::MockHost;
+use alkanes_runtime::runtime::AlkaneResponder;
+use alkanes_runtime::storage::StoragePointer;
+use alkanes_support::{
+    cellpack::Cellpack,
+    id::AlkaneId,
+    parcel::{AlkaneTransfer, AlkaneTransferParcel},
+    response::CallResponse,
+};
+use anyhow::{anyhow, Result};
+use metashrew_support::index_pointer::KeyValuePointer;
+
+const VAULT: AlkaneId = AlkaneId { block: 2, tx: 1 };
+const TOKEN: AlkaneId = AlkaneId { block: 2, tx: 2 };
+const SINK: AlkaneId = AlkaneId { block: 2, tx: 3 };
+
+/// Holds deposits of any alkane and hands them back out.
+#[derive(Default)]
+struct Vault(());
+
+#[derive(MessageDispatch)]
+enum VaultMessage {
+    #[opcode(0)]
+    Deposit,
+
+    #[opcode(1)]
+    Withdraw { amount: u128 },
+
+    #[opcode(2)]
+    #[returns(u128)]
+    Deposits,
+
+    #[opcode(3)]
+    #[returns(u64)]
+    Height,
+
+    #[opcode(4)]
+    Send { amount: u128 },
+
+    #[opcode(5)]
+    Peek { amount: u128 },
+
+    #[opcode(6)]
+    #[returns(u128)]
+    DieselMints,
+}
+
+impl Vault {
+    fn deposits_pointer(&self) -> StoragePointer {
+        StoragePointer::from_keyword("/deposits")
+    }
+
+    fn deposit(&self) -> Result<CallResponse> {
+        let context = self.context()?;
+        let amount: u128 = context.incoming_alkanes.0.iter().map(|t| t.value).sum();
+        let mut pointer = self.deposits_pointer();
+        pointer.set_value::<u128>(pointer.get_value::<u128>() + amount);
+        self.emit_event(b"deposit", &amount.to_le_bytes());
+        Ok(CallResponse::default())
+    }
+
+    fn withdraw(&self, amount: u128) -> Result<CallResponse> {
+        let mut pointer = self.deposits_pointer();
+        pointer.set_value::<u128>(pointer.get_value::<u128>().saturating_sub(amount));
+        let mut response = CallResponse::default();
+        response.alkanes.0.push(AlkaneTransfer {
+            id: TOKEN,
+            value: amount,
+        });
+        Ok(response)
+    }
+
+    fn deposits(&self) -> Result<CallResponse> {
+        let mut response = CallResponse::default();
+        response.data = self.deposits_pointer().get_value::<u128>().to_le_bytes().to_vec();
+        Ok(response)
+    }
+
+    fn height(&self) -> Result<CallResponse> {
+        let mut response = CallResponse::default();
+        response.data = AlkaneResponder::height(self).to_le_bytes().to_vec();
+        Ok(response)
+    }
+
+    fn sink_call(&self, amount: u128) -> (Cellpack, AlkaneTransferParcel) {
+        (
+            Cellpack {
+                target: SINK,
+                inputs: vec![],
+            },
+            AlkaneTransferParcel(vec![AlkaneTransfer {
+                id: TOKEN,
+                value: amount,
+            }]),
+        )
+    }
+
+    fn send(&self, amount: u128) -> Result<CallResponse> {
+        let (cellpack, parcel) = self.sink_call(amount);
+        self.call(&cellpack, &parcel, self.fuel())?;
+        Ok(CallResponse::default())
+    }
+
+    fn peek(&self, amount: u128) -> Result<CallResponse> {
+        let (cellpack, parcel) = self.sink_call(amount);
+        self.staticcall(&cellpack, &parcel, self.fuel())
+    }
+
+    fn diesel_mints(&self) -> Result<CallResponse> {
+        let mut response = CallResponse::default();
+        response.data = self.number_diesel_mints()?.to_le_bytes().to_vec();
+        Ok(response)
+    }
+}
+
+impl AlkaneResponder for Vault {}
+
+/// A vault with `sink` registered at `SINK`: it keeps what it's sent, records
+/// the caller and fails when sent more than 100.
+fn host() -> MockHost {
+    let host = MockHost::new().with_height(840_000);
+    host.register::<Vault, VaultMessage>(VAULT);
+    host.register_fn(SINK, |context| {
+        let received: u128 = context.incoming_alkanes.0.iter().map(|t| t.value).sum();
+        if received > 100 {
+            return Err(anyhow!("too much"));
+        }
+        StoragePointer::from_keyword("/caller").set(std::sync::Arc::new(context.caller.into()));
+        Ok(CallResponse {
+            alkanes: AlkaneTransferParcel::default(),
+            data: received.to_le_bytes().to_vec(),
+        })
+    });
+    host
+}
+
+fn deposit(host: &MockHost, value: u128) -> Result<CallResponse> {
+    host.call(
+        VAULT,
+        vec![0],
+        AlkaneTransferParcel(vec![AlkaneTransfer { id: TOKEN, value }]),
+    )
+}
+
+fn u128_data(response: &CallResponse) -> u128 {
+    u128::from_le_bytes(response.data[..16].try_into().unwrap())
+}
+
+#[test]
+fn storage_and_balances_persist_across_calls() -> Result<()> {
+    let host = host();
+    deposit(&host, 60)?;
+    deposit(&host, 40)?;
+    assert_eq!(host.balance(&VAULT, &TOKEN), 100);
+    assert_eq!(u128_data(&host.call(VAULT, vec![2], Default::default())?), 100);
+    assert_eq!(host.storage(&VAULT, b"/deposits"), 100u128.to_le_bytes());
+    assert_eq!(host.events().len(), 2);
+
+    let response = host.call(VAULT, vec![1, 30], Default::default())?;
+    assert_eq!(response.alkanes.0, vec![AlkaneTransfer { id: TOKEN, value: 30 }]);
+    assert_eq!(host.balance(&VAULT, &TOKEN), 70);
+    assert_eq!(u128_data(&host.call(VAULT, vec![2], Default::default())?), 70);
+    Ok(())
+}
+
+#[test]
+fn failed_call_rolls_back_storage_and_balances() -> Result<()> {
+    let host = host();
+    deposit(&host, 10)?;
+    let err = host.call(VAULT, vec![1, 11], Default::default()).unwrap_err();
+    assert!(err.to_string().contains("balance underflow"), "{}", err);
+    assert_eq!(host.balance(&VAULT, &TOKEN), 10);
+    assert_eq!(host.storage(&VAULT, b"/deposits"), 10u128.to_le_bytes());
+    assert!(host.call(VAULT, vec![42], Default::default()).is_err());
+    Ok(())
+}
+
+#[test]
+fn extcall_moves_alkanes_and_commits_callee_storage() -> Result<()> {
+    let host = host();
+    deposit(&host, 100)?;
+    host.call(VAULT, vec![4, 25], Default::default())?;
+    assert_eq!(host.balance(&VAULT, &TOKEN), 75);
+    assert_eq!(host.balance(&SINK, &TOKEN), 25);
+    assert_eq!(host.storage(&SINK, b"/caller"), Vec::<u8>::from(VAULT));
+
+    // the sink reverts, and so does everything the vault did around it
+    let err = host.call(VAULT, vec![4, 101], Default::default()).unwrap_err();
+    assert!(err.to_string().starts_with("Extcall failed: "), "{}", err);
+    assert!(err.to_string().contains("too much"), "{}", err);
+    assert_eq!(host.balance(&VAULT, &TOKEN), 75);
+    assert_eq!(host.balance(&SINK, &TOKEN), 25);
+    Ok(())
+}
+
+#[test]
+fn staticcall_rolls_back_after_returning() -> Result<()> {
+    let host = host();
+    deposit(&host, 100)?;
+    let response = host.call(VAULT, vec![5, 40], Default::default())?;
+    assert_eq!(u128_data(&response), 40);
+    assert_eq!(host.balance(&VAULT, &TOKEN), 100);
+    assert_eq!(host.balance(&SINK, &TOKEN), 0);
+    assert!(host.storage(&SINK, b"/caller").is_empty());
+    Ok(())
+}
+
+#[test]
+fn host_values_are_configurable() -> Result<()> {
+    let host = host();
+    let height = host.call(VAULT, vec![3], Default::default())?;
+    assert_eq!(height.data, 840_000u64.to_le_bytes());
+    host.set_height(840_001);
+    let height = host.call(VAULT, vec![3], Default::default())?;
+    assert_eq!(height.data, 840_001u64.to_le_bytes());
+
+    host.set_diesel_mints(7);
+    assert_eq!(u128_data(&host.call(VAULT, vec![6], Default::default())?), 7);
+    Ok(())
+}
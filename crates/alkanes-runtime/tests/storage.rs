@@ -0,0 +1,108 @@
+//! Typed storage against the mock host.
+
+use alkanes_runtime::mock::MockHost;
+use alkanes_runtime::storage::{
+    storage, Storable, StorageMap, StoragePointer, StorageValue, StorageVec,
+};
+use alkanes_support::id::AlkaneId;
+use metashrew_support::index_pointer::KeyValuePointer;
+
+#[derive(Storable, Default, Debug, Clone, PartialEq)]
+struct Position {
+    owner: AlkaneId,
+    amount: u128,
+    note: String,
+}
+
+#[storage]
+trait Ledger {
+    #[key("/totalsupply")]
+    fn total_supply(&self) -> StorageValue<u128>;
+    fn positions(&self) -> StorageVec<Position>;
+    fn allowances(&self, owner: &AlkaneId) -> StorageMap<AlkaneId, u128>;
+}
+
+struct Contract;
+
+impl Ledger for Contract {}
+
+const ALICE: AlkaneId = AlkaneId { block: 2, tx: 10 };
+const BOB: AlkaneId = AlkaneId { block: 2, tx: 11 };
+
+#[test]
+fn values_share_the_pointer_layout() {
+    let host = MockHost::new();
+    let supply = Contract.total_supply();
+    assert_eq!(supply.try_get(), None);
+    assert_eq!(supply.get(), 0);
+    supply.set(1_000);
+    assert_eq!(
+        StoragePointer::from_keyword("/totalsupply").get_value::<u128>(),
+        1_000
+    );
+
+    let name = StorageValue::<String>::new("/name");
+    name.set("ALKANE".to_string());
+    host.commit();
+    assert_eq!(host.storage(&AlkaneId::default(), b"/name"), b"ALKANE");
+    assert_eq!(name.get(), "ALKANE");
+    name.clear();
+    assert!(!name.is_set());
+}
+
+#[test]
+fn vec_pushes_pops_and_iterates() {
+    let _host = MockHost::new();
+    let positions = Contract.positions();
+    let first = Position {
+        owner: ALICE,
+        amount: 5,
+        note: String::new(),
+    };
+    let second = Position {
+        owner: BOB,
+        amount: 7,
+        note: "locked".to_string(),
+    };
+    positions.push(first.clone());
+    positions.push(second.clone());
+    assert_eq!(positions.len(), 2);
+    assert_eq!(positions.iter().collect::<Vec<_>>(), vec![first.clone(), second]);
+    assert!(positions.set(2, first.clone()).is_err());
+
+    assert_eq!(positions.pop().map(|p| p.amount), Some(7));
+    assert_eq!(positions.get(1), None);
+    assert_eq!(positions.get(0), Some(first));
+    positions.clear();
+    assert!(positions.is_empty());
+    assert_eq!(positions.pop(), None);
+}
+
+#[test]
+fn map_tracks_keys_through_removal() {
+    let _host = MockHost::new();
+    let allowances = Contract.allowances(&ALICE);
+    let carol = AlkaneId::new(2, 12);
+    assert_eq!(allowances.insert(BOB, 10), None);
+    assert_eq!(allowances.insert(carol, 20), None);
+    assert_eq!(allowances.insert(BOB, 15), Some(10));
+    assert_eq!(allowances.len(), 2);
+    assert_eq!(allowances.get(&BOB), Some(15));
+
+    assert_eq!(allowances.remove(&BOB), Some(15));
+    assert_eq!(allowances.remove(&BOB), None);
+    assert!(!allowances.contains_key(&BOB));
+    assert_eq!(allowances.iter().collect::<Vec<_>>(), vec![(carol, 20)]);
+
+    // each owner has its own map
+    assert!(Contract.allowances(&BOB).is_empty());
+}
+
+#[test]
+fn composite_values_round_trip() {
+    let value = (ALICE, 3u128, "x".to_string(), vec![1u8, 2]);
+    let bytes = value.to_bytes();
+    assert_eq!(<(AlkaneId, u128, String, Vec<u8>)>::from_bytes(&bytes).unwrap(), value);
+    assert!(u128::from_bytes(&bytes[..8]).is_err());
+    assert!(bool::from_bytes(&[2]).is_err());
+}
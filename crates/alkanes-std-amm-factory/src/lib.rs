@@ -0,0 +1,413 @@
+//! Factory and router for constant-product AMM pools.
+//!
+//! Each pool is a beacon proxy cloned from the template deployed at
+//! `4:pool_factory_id` and pointed at the beacon given on initialization,
+//! so every pool runs the current `alkanes-std-amm-pool` implementation.
+//! Pools hold their pair in sorted order and there is at most one per pair.
+//!
+//! The router opcodes sell or buy along a path of tokens, pricing each hop
+//! with the pool's simulate opcodes and settling it with a pool swap before
+//! handing the output to the next hop.
+
+use alkanes_runtime::declare_alkane;
+use alkanes_runtime::message::MessageDispatch;
+#[allow(unused_imports)]
+use alkanes_runtime::{
+    println,
+    stdio::{stdout, Write},
+};
+use alkanes_runtime::runtime::AlkaneResponder;
+use alkanes_runtime::storage::{storage, StorageValue, StorageVec};
+use alkanes_support::{
+    cellpack::Cellpack,
+    context::Context,
+    id::AlkaneId,
+    parcel::{AlkaneTransfer, AlkaneTransferParcel},
+    response::CallResponse,
+};
+use anyhow::{anyhow, Result};
+use metashrew_support::compat::{to_arraybuffer_layout, to_passback_ptr};
+
+const POOL_OPCODE_INIT_POOL: u128 = 0;
+const POOL_OPCODE_SWAP: u128 = 3;
+const POOL_OPCODE_SIMULATE_SWAP: u128 = 4;
+const POOL_OPCODE_SIMULATE_SWAP_EXACT_OUT: u128 = 5;
+const BEACON_PROXY_OPCODE_INITIALIZE: u128 = 0x7fff;
+
+#[derive(Default)]
+pub struct AmmFactory(());
+
+#[derive(MessageDispatch)]
+enum AmmFactoryMessage {
+    #[opcode(0)]
+    InitFactory {
+        pool_factory_id: u128,
+        beacon_id: AlkaneId,
+    },
+
+    #[opcode(1)]
+    CreateNewPool {
+        token_a: AlkaneId,
+        token_b: AlkaneId,
+        amount_a: u128,
+        amount_b: u128,
+    },
+
+    #[opcode(2)]
+    #[returns(AlkaneId)]
+    FindExistingPoolId {
+        alkane_a: AlkaneId,
+        alkane_b: AlkaneId,
+    },
+
+    #[opcode(3)]
+    #[returns(Vec<u8>)]
+    GetAllPools,
+
+    #[opcode(4)]
+    #[returns(u128)]
+    GetNumPools,
+
+    #[opcode(13)]
+    SwapExactTokensForTokens {
+        path: Vec<AlkaneId>,
+        amount_in: u128,
+        amount_out_min: u128,
+        deadline: u128,
+    },
+
+    #[opcode(14)]
+    SwapTokensForExactTokens {
+        path: Vec<AlkaneId>,
+        amount_out: u128,
+        amount_in_max: u128,
+        deadline: u128,
+    },
+
+    #[opcode(50)]
+    Forward,
+}
+
+#[storage]
+pub trait AmmFactoryStorage {
+    fn pool_factory_id(&self) -> StorageValue<u128>;
+    fn beacon(&self) -> StorageValue<AlkaneId>;
+    fn all_pools(&self) -> StorageVec<AlkaneId>;
+    #[key("/pools")]
+    fn pool_for(&self, alkane_a: &AlkaneId, alkane_b: &AlkaneId) -> StorageValue<AlkaneId>;
+}
+
+impl AmmFactoryStorage for AmmFactory {}
+
+fn sort_pair(a: AlkaneId, b: AlkaneId) -> (AlkaneId, AlkaneId) {
+    if a < b {
+        (a, b)
+    } else {
+        (b, a)
+    }
+}
+
+fn decode_u128(data: &[u8]) -> Result<u128> {
+    Ok(u128::from_le_bytes(
+        data.get(0..16)
+            .ok_or_else(|| anyhow!("AMMFactory: short pool response"))?
+            .try_into()?,
+    ))
+}
+
+impl AmmFactory {
+    fn find_pool(&self, alkane_a: AlkaneId, alkane_b: AlkaneId) -> Option<AlkaneId> {
+        let (alkane_a, alkane_b) = sort_pair(alkane_a, alkane_b);
+        self.pool_for(&alkane_a, &alkane_b).try_get()
+    }
+
+    fn pool_or_err(&self, alkane_a: AlkaneId, alkane_b: AlkaneId) -> Result<AlkaneId> {
+        self.find_pool(alkane_a, alkane_b)
+            .ok_or_else(|| anyhow!("AMMFactory: pool not found for {:?} / {:?}", alkane_a, alkane_b))
+    }
+
+    /// Takes `amounts` of each of `ids` out of the incoming alkanes and
+    /// returns a response refunding the rest.
+    fn take_exact(
+        &self,
+        context: &Context,
+        ids: &[AlkaneId],
+        amounts: &[u128],
+    ) -> Result<CallResponse> {
+        let mut needed = amounts.to_vec();
+        let mut response = CallResponse::default();
+        for transfer in context.incoming_alkanes.0.iter() {
+            let mut transfer = *transfer;
+            if let Some(i) = ids.iter().position(|id| *id == transfer.id) {
+                let taken = std::cmp::min(needed[i], transfer.value);
+                needed[i] -= taken;
+                transfer.value -= taken;
+            }
+            if transfer.value > 0 {
+                response.alkanes.0.push(transfer);
+            }
+        }
+        if let Some(i) = needed.iter().position(|value| *value > 0) {
+            return Err(anyhow!(
+                "AMMFactory: insufficient {:?} sent, missing {}",
+                ids[i],
+                needed[i]
+            ));
+        }
+        Ok(response)
+    }
+
+    fn check_deadline(&self, deadline: u128) -> Result<()> {
+        if deadline != 0 && self.height() as u128 > deadline {
+            return Err(anyhow!("AMMFactory: expired deadline"));
+        }
+        Ok(())
+    }
+
+    fn check_path(&self, path: &[AlkaneId]) -> Result<()> {
+        if path.len() < 2 {
+            return Err(anyhow!("AMMFactory: invalid path"));
+        }
+        Ok(())
+    }
+
+    fn quote(&self, pool: AlkaneId, opcode: u128, token: AlkaneId, amount: u128) -> Result<u128> {
+        let response = self.staticcall(
+            &Cellpack {
+                target: pool,
+                inputs: vec![opcode, token.block, token.tx, amount],
+            },
+            &AlkaneTransferParcel::default(),
+            self.fuel(),
+        )?;
+        decode_u128(&response.data)
+    }
+
+    /// Output of each hop for selling `amount_in` of `path[0]`.
+    fn amounts_out(&self, path: &[AlkaneId], amount_in: u128) -> Result<Vec<u128>> {
+        let mut amounts = vec![amount_in];
+        for hop in path.windows(2) {
+            let pool = self.pool_or_err(hop[0], hop[1])?;
+            let last = amounts[amounts.len() - 1];
+            amounts.push(self.quote(pool, POOL_OPCODE_SIMULATE_SWAP, hop[0], last)?);
+        }
+        Ok(amounts)
+    }
+
+    /// Input of each hop for buying `amount_out` of the last token in `path`.
+    fn amounts_in(&self, path: &[AlkaneId], amount_out: u128) -> Result<Vec<u128>> {
+        let mut amounts = vec![amount_out];
+        for hop in path.windows(2).rev() {
+            let pool = self.pool_or_err(hop[0], hop[1])?;
+            let first = amounts[0];
+            amounts.insert(
+                0,
+                self.quote(pool, POOL_OPCODE_SIMULATE_SWAP_EXACT_OUT, hop[1], first)?,
+            );
+        }
+        Ok(amounts)
+    }
+
+    /// Runs each hop through its pool, feeding the output of one hop into
+    /// the next, and returns the last hop's response.
+    fn swap_along(&self, path: &[AlkaneId], amounts: &[u128]) -> Result<CallResponse> {
+        let mut response = CallResponse::default();
+        for (i, hop) in path.windows(2).enumerate() {
+            let (token_in, token_out) = (hop[0], hop[1]);
+            let pool = self.pool_or_err(token_in, token_out)?;
+            let amount_out = amounts[i + 1];
+            let (amount_a_out, amount_b_out) = if token_out < token_in {
+                (amount_out, 0)
+            } else {
+                (0, amount_out)
+            };
+            response = self.call(
+                &Cellpack {
+                    target: pool,
+                    inputs: vec![POOL_OPCODE_SWAP, amount_a_out, amount_b_out],
+                },
+                &AlkaneTransferParcel(vec![AlkaneTransfer {
+                    id: token_in,
+                    value: amounts[i],
+                }]),
+                self.fuel(),
+            )?;
+        }
+        Ok(response)
+    }
+
+    fn init_factory(&self, pool_factory_id: u128, beacon_id: AlkaneId) -> Result<CallResponse> {
+        self.observe_initialization()?;
+        let context = self.context()?;
+        self.pool_factory_id().set(pool_factory_id);
+        self.beacon().set(beacon_id);
+        Ok(CallResponse::forward(&context.incoming_alkanes))
+    }
+
+    /// Deploys a pool for the pair and seeds it with `amount_a` and
+    /// `amount_b` from the incoming alkanes. The LP tokens are returned along
+    /// with anything left over, and the pool id is returned as data.
+    fn create_new_pool(
+        &self,
+        token_a: AlkaneId,
+        token_b: AlkaneId,
+        amount_a: u128,
+        amount_b: u128,
+    ) -> Result<CallResponse> {
+        let context = self.context()?;
+        if token_a == token_b {
+            return Err(anyhow!("AMMFactory: identical tokens"));
+        }
+        if self.find_pool(token_a, token_b).is_some() {
+            return Err(anyhow!("AMMFactory: pool already exists"));
+        }
+        let mut response = self.take_exact(&context, &[token_a, token_b], &[amount_a, amount_b])?;
+        let ((alkane_a, amount_a), (alkane_b, amount_b)) = if token_a < token_b {
+            ((token_a, amount_a), (token_b, amount_b))
+        } else {
+            ((token_b, amount_b), (token_a, amount_a))
+        };
+
+        let beacon = self.beacon().get();
+        let pool = AlkaneId {
+            block: 2,
+            tx: self.sequence(),
+        };
+        self.call(
+            &Cellpack {
+                target: AlkaneId {
+                    block: 6,
+                    tx: self.pool_factory_id().get(),
+                },
+                inputs: vec![BEACON_PROXY_OPCODE_INITIALIZE, beacon.block, beacon.tx],
+            },
+            &AlkaneTransferParcel::default(),
+            self.fuel(),
+        )?;
+        let minted = self.call(
+            &Cellpack {
+                target: pool,
+                inputs: vec![
+                    POOL_OPCODE_INIT_POOL,
+                    alkane_a.block,
+                    alkane_a.tx,
+                    alkane_b.block,
+                    alkane_b.tx,
+                    context.myself.block,
+                    context.myself.tx,
+                ],
+            },
+            &AlkaneTransferParcel(vec![
+                AlkaneTransfer {
+                    id: alkane_a,
+                    value: amount_a,
+                },
+                AlkaneTransfer {
+                    id: alkane_b,
+                    value: amount_b,
+                },
+            ]),
+            self.fuel(),
+        )?;
+
+        self.pool_for(&alkane_a, &alkane_b).set(pool);
+        self.all_pools().push(pool);
+
+        response.alkanes.0.extend(minted.alkanes.0);
+        response.data = pool.into();
+        Ok(response)
+    }
+
+    fn find_existing_pool_id(&self, alkane_a: AlkaneId, alkane_b: AlkaneId) -> Result<CallResponse> {
+        let context = self.context()?;
+        let mut response = CallResponse::forward(&context.incoming_alkanes);
+
+        response.data = self.pool_or_err(alkane_a, alkane_b)?.into();
+
+        Ok(response)
+    }
+
+    /// The pool count followed by every pool id, in creation order.
+    fn get_all_pools(&self) -> Result<CallResponse> {
+        let context = self.context()?;
+        let mut response = CallResponse::forward(&context.incoming_alkanes);
+        let pools = self.all_pools();
+
+        response.data = (pools.len() as u128).to_le_bytes().to_vec();
+        for pool in pools.iter() {
+            response.data.extend(Vec::<u8>::from(pool));
+        }
+
+        Ok(response)
+    }
+
+    fn get_num_pools(&self) -> Result<CallResponse> {
+        let context = self.context()?;
+        let mut response = CallResponse::forward(&context.incoming_alkanes);
+
+        response.data = (self.all_pools().len() as u128).to_le_bytes().to_vec();
+
+        Ok(response)
+    }
+
+    /// Sells exactly `amount_in` of the first token in `path` for at least
+    /// `amount_out_min` of the last.
+    fn swap_exact_tokens_for_tokens(
+        &self,
+        path: Vec<AlkaneId>,
+        amount_in: u128,
+        amount_out_min: u128,
+        deadline: u128,
+    ) -> Result<CallResponse> {
+        let context = self.context()?;
+        self.check_deadline(deadline)?;
+        self.check_path(&path)?;
+        let mut response = self.take_exact(&context, &path[..1], &[amount_in])?;
+        let amounts = self.amounts_out(&path, amount_in)?;
+        if amounts[amounts.len() - 1] < amount_out_min {
+            return Err(anyhow!("AMMFactory: insufficient output amount"));
+        }
+        response
+            .alkanes
+            .0
+            .extend(self.swap_along(&path, &amounts)?.alkanes.0);
+        Ok(response)
+    }
+
+    /// Buys exactly `amount_out` of the last token in `path`, spending at
+    /// most `amount_in_max` of the first and refunding the difference.
+    fn swap_tokens_for_exact_tokens(
+        &self,
+        path: Vec<AlkaneId>,
+        amount_out: u128,
+        amount_in_max: u128,
+        deadline: u128,
+    ) -> Result<CallResponse> {
+        let context = self.context()?;
+        self.check_deadline(deadline)?;
+        self.check_path(&path)?;
+        let amounts = self.amounts_in(&path, amount_out)?;
+        if amounts[0] > amount_in_max {
+            return Err(anyhow!("AMMFactory: excessive input amount"));
+        }
+        let mut response = self.take_exact(&context, &path[..1], &[amounts[0]])?;
+        response
+            .alkanes
+            .0
+            .extend(self.swap_along(&path, &amounts)?.alkanes.0);
+        Ok(response)
+    }
+
+    fn forward(&self) -> Result<CallResponse> {
+        let context = self.context()?;
+        Ok(CallResponse::forward(&context.incoming_alkanes))
+    }
+}
+
+impl AlkaneResponder for AmmFactory {}
+
+declare_alkane! {
+    impl AlkaneResponder for AmmFactory {
+        type Message = AmmFactoryMessage;
+    }
+}
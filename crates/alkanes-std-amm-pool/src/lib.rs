@@ -0,0 +1,488 @@
+//! Constant-product AMM pool.
+//!
+//! Pools are beacon proxies created by `alkanes-std-amm-factory`, so this
+//! crate is the implementation the beacon points at and runs in the proxy's
+//! context through delegatecall: `myself` is the pool and its own units are
+//! the LP token. The pool keeps its reserves in storage and trades them
+//! against incoming alkanes with a 0.3% fee on the input.
+//!
+//! [`AmmPool::swap`] is the low-level swap: the caller names the amounts it
+//! wants out, sends the input and the pool checks the fee-adjusted invariant.
+//! Exact-in and exact-out swaps are routed by the factory, which prices each
+//! hop with the simulate opcodes.
+
+use alkanes_runtime::declare_alkane;
+use alkanes_runtime::message::MessageDispatch;
+#[allow(unused_imports)]
+use alkanes_runtime::{
+    println,
+    stdio::{stdout, Write},
+};
+use alkanes_runtime::runtime::AlkaneResponder;
+use alkanes_runtime::storage::{storage, StorageValue};
+use alkanes_support::{
+    cellpack::Cellpack,
+    context::Context,
+    id::AlkaneId,
+    parcel::{AlkaneTransfer, AlkaneTransferParcel},
+    response::CallResponse,
+};
+use anyhow::{anyhow, Result};
+use metashrew_support::compat::{to_arraybuffer_layout, to_passback_ptr};
+use ruint::aliases::U512;
+
+/// LP units minted on initialization that are never handed out, so the
+/// supply can't be drained back to zero.
+pub const MINIMUM_LIQUIDITY: u128 = 1000;
+/// Swap fee in basis points, taken from the input.
+pub const FEE_BPS: u128 = 30;
+pub const BPS: u128 = 10_000;
+/// Spot prices are fixed point with 18 decimals.
+pub const PRICE_SCALE: u128 = 1_000_000_000_000_000_000;
+
+fn wide(value: u128) -> U512 {
+    U512::from(value)
+}
+
+fn narrow(value: U512) -> Result<u128> {
+    u128::try_from(value).map_err(|_| anyhow!("AMM: arithmetic overflow"))
+}
+
+/// `a * b / c` without overflowing the product.
+pub fn mul_div(a: u128, b: u128, c: u128) -> Result<u128> {
+    if c == 0 {
+        return Err(anyhow!("AMM: division by zero"));
+    }
+    narrow(wide(a) * wide(b) / wide(c))
+}
+
+fn sqrt(y: U512) -> U512 {
+    if y > wide(3) {
+        let mut z = y;
+        let mut x = y / wide(2) + wide(1);
+        while x < z {
+            z = x;
+            x = (y / x + x) / wide(2);
+        }
+        z
+    } else if y != U512::ZERO {
+        wide(1)
+    } else {
+        U512::ZERO
+    }
+}
+
+/// Output for selling `amount_in` into the pool, after the fee.
+pub fn get_amount_out(amount_in: u128, reserve_in: u128, reserve_out: u128) -> Result<u128> {
+    if amount_in == 0 {
+        return Err(anyhow!("AMM: insufficient input amount"));
+    }
+    if reserve_in == 0 || reserve_out == 0 {
+        return Err(anyhow!("AMM: insufficient liquidity"));
+    }
+    let amount_in_with_fee = wide(amount_in) * wide(BPS - FEE_BPS);
+    narrow(
+        amount_in_with_fee * wide(reserve_out)
+            / (wide(reserve_in) * wide(BPS) + amount_in_with_fee),
+    )
+}
+
+/// Input needed to buy `amount_out` from the pool, fee included.
+pub fn get_amount_in(amount_out: u128, reserve_in: u128, reserve_out: u128) -> Result<u128> {
+    if amount_out == 0 {
+        return Err(anyhow!("AMM: insufficient output amount"));
+    }
+    if reserve_in == 0 || amount_out >= reserve_out {
+        return Err(anyhow!("AMM: insufficient liquidity"));
+    }
+    narrow(
+        wide(reserve_in) * wide(amount_out) * wide(BPS)
+            / (wide(reserve_out - amount_out) * wide(BPS - FEE_BPS))
+            + wide(1),
+    )
+}
+
+#[derive(Default)]
+pub struct AmmPool(());
+
+#[derive(MessageDispatch)]
+enum AmmPoolMessage {
+    #[opcode(0)]
+    InitPool {
+        alkane_a: AlkaneId,
+        alkane_b: AlkaneId,
+        factory: AlkaneId,
+    },
+
+    #[opcode(1)]
+    AddLiquidity,
+
+    #[opcode(2)]
+    RemoveLiquidity,
+
+    #[opcode(3)]
+    Swap { amount_a_out: u128, amount_b_out: u128 },
+
+    #[opcode(4)]
+    #[returns(u128)]
+    SimulateSwap { token_in: AlkaneId, amount_in: u128 },
+
+    #[opcode(5)]
+    #[returns(u128)]
+    SimulateSwapExactOut { token_out: AlkaneId, amount_out: u128 },
+
+    #[opcode(50)]
+    Forward,
+
+    #[opcode(97)]
+    #[returns(Vec<u8>)]
+    GetReserves,
+
+    #[opcode(98)]
+    #[returns(Vec<u8>)]
+    GetPrice,
+
+    #[opcode(99)]
+    #[returns(String)]
+    GetName,
+
+    #[opcode(101)]
+    #[returns(u128)]
+    GetTotalSupply,
+
+    #[opcode(999)]
+    #[returns(Vec<u8>)]
+    PoolDetails,
+}
+
+#[storage]
+pub trait AmmPoolStorage {
+    fn factory(&self) -> StorageValue<AlkaneId>;
+    fn alkane_a(&self) -> StorageValue<AlkaneId>;
+    fn alkane_b(&self) -> StorageValue<AlkaneId>;
+    fn reserve_a(&self) -> StorageValue<u128>;
+    fn reserve_b(&self) -> StorageValue<u128>;
+    #[key("/totalsupply")]
+    fn total_supply(&self) -> StorageValue<u128>;
+    #[key("/name")]
+    fn pool_name(&self) -> StorageValue<String>;
+}
+
+impl AmmPoolStorage for AmmPool {}
+
+impl AmmPool {
+    /// Sums the incoming units of each of `ids` and forwards everything else.
+    fn take_incoming(&self, context: &Context, ids: &[AlkaneId]) -> Result<(Vec<u128>, CallResponse)> {
+        let mut amounts = vec![0u128; ids.len()];
+        let mut response = CallResponse::default();
+        for transfer in context.incoming_alkanes.0.iter() {
+            match ids.iter().position(|id| *id == transfer.id) {
+                Some(i) => {
+                    amounts[i] = amounts[i]
+                        .checked_add(transfer.value)
+                        .ok_or_else(|| anyhow!("AMM: arithmetic overflow"))?;
+                }
+                None => response.alkanes.0.push(*transfer),
+            }
+        }
+        Ok((amounts, response))
+    }
+
+    fn reserves(&self) -> (u128, u128) {
+        (self.reserve_a().get(), self.reserve_b().get())
+    }
+
+    fn set_reserves(&self, reserve_a: u128, reserve_b: u128) {
+        self.reserve_a().set(reserve_a);
+        self.reserve_b().set(reserve_b);
+    }
+
+    /// Reserves ordered as (in, out) for a trade selling `token_in`.
+    fn reserves_for(&self, token_in: &AlkaneId) -> Result<(u128, u128)> {
+        let (reserve_a, reserve_b) = self.reserves();
+        if *token_in == self.alkane_a().get() {
+            Ok((reserve_a, reserve_b))
+        } else if *token_in == self.alkane_b().get() {
+            Ok((reserve_b, reserve_a))
+        } else {
+            Err(anyhow!("AMM: {:?} is not in the pool", token_in))
+        }
+    }
+
+    fn push_nonzero(response: &mut CallResponse, id: AlkaneId, value: u128) {
+        if value > 0 {
+            response.alkanes.0.push(AlkaneTransfer { id, value });
+        }
+    }
+
+    fn token_name(&self, token: &AlkaneId) -> String {
+        self.staticcall(
+            &Cellpack {
+                target: *token,
+                inputs: vec![99],
+            },
+            &AlkaneTransferParcel::default(),
+            self.fuel(),
+        )
+        .ok()
+        .and_then(|response| String::from_utf8(response.data).ok())
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| format!("{}:{}", token.block, token.tx))
+    }
+
+    fn init_pool(
+        &self,
+        alkane_a: AlkaneId,
+        alkane_b: AlkaneId,
+        factory: AlkaneId,
+    ) -> Result<CallResponse> {
+        self.observe_initialization()?;
+        let context = self.context()?;
+        if alkane_a == alkane_b {
+            return Err(anyhow!("AMM: identical tokens"));
+        }
+        self.factory().set(factory);
+        self.alkane_a().set(alkane_a);
+        self.alkane_b().set(alkane_b);
+        self.pool_name().set(format!(
+            "{} / {} LP",
+            self.token_name(&alkane_a),
+            self.token_name(&alkane_b)
+        ));
+
+        let (amounts, mut response) = self.take_incoming(&context, &[alkane_a, alkane_b])?;
+        let (amount_a, amount_b) = (amounts[0], amounts[1]);
+        if amount_a == 0 || amount_b == 0 {
+            return Err(anyhow!("AMM: initial liquidity needs both tokens"));
+        }
+        let liquidity = narrow(sqrt(wide(amount_a) * wide(amount_b)))?;
+        if liquidity <= MINIMUM_LIQUIDITY {
+            return Err(anyhow!("AMM: insufficient liquidity minted"));
+        }
+        self.set_reserves(amount_a, amount_b);
+        self.total_supply().set(liquidity);
+
+        response.alkanes.0.push(AlkaneTransfer {
+            id: context.myself,
+            value: liquidity - MINIMUM_LIQUIDITY,
+        });
+        Ok(response)
+    }
+
+    /// Mints LP for both incoming tokens at the pool's ratio and refunds
+    /// whatever exceeds it.
+    fn add_liquidity(&self) -> Result<CallResponse> {
+        let context = self.context()?;
+        let (alkane_a, alkane_b) = (self.alkane_a().get(), self.alkane_b().get());
+        let (reserve_a, reserve_b) = self.reserves();
+        let total_supply = self.total_supply().get();
+        if total_supply == 0 {
+            return Err(anyhow!("AMM: pool not initialized"));
+        }
+        let (amounts, mut response) = self.take_incoming(&context, &[alkane_a, alkane_b])?;
+        let (amount_a, amount_b) = (amounts[0], amounts[1]);
+        if amount_a == 0 || amount_b == 0 {
+            return Err(anyhow!("AMM: liquidity needs both tokens"));
+        }
+        let amount_b_optimal = mul_div(amount_a, reserve_b, reserve_a)?;
+        let (used_a, used_b) = if amount_b_optimal <= amount_b {
+            (amount_a, amount_b_optimal)
+        } else {
+            (mul_div(amount_b, reserve_a, reserve_b)?, amount_b)
+        };
+        let liquidity = std::cmp::min(
+            mul_div(used_a, total_supply, reserve_a)?,
+            mul_div(used_b, total_supply, reserve_b)?,
+        );
+        if liquidity == 0 {
+            return Err(anyhow!("AMM: insufficient liquidity minted"));
+        }
+        self.set_reserves(
+            reserve_a
+                .checked_add(used_a)
+                .ok_or_else(|| anyhow!("AMM: arithmetic overflow"))?,
+            reserve_b
+                .checked_add(used_b)
+                .ok_or_else(|| anyhow!("AMM: arithmetic overflow"))?,
+        );
+        self.total_supply().set(
+            total_supply
+                .checked_add(liquidity)
+                .ok_or_else(|| anyhow!("AMM: arithmetic overflow"))?,
+        );
+
+        response.alkanes.0.push(AlkaneTransfer {
+            id: context.myself,
+            value: liquidity,
+        });
+        Self::push_nonzero(&mut response, alkane_a, amount_a - used_a);
+        Self::push_nonzero(&mut response, alkane_b, amount_b - used_b);
+        Ok(response)
+    }
+
+    /// Burns the incoming LP for its share of both reserves.
+    fn remove_liquidity(&self) -> Result<CallResponse> {
+        let context = self.context()?;
+        let (amounts, mut response) = self.take_incoming(&context, &[context.myself])?;
+        let liquidity = amounts[0];
+        if liquidity == 0 {
+            return Err(anyhow!("AMM: no liquidity tokens to burn"));
+        }
+        let (reserve_a, reserve_b) = self.reserves();
+        let total_supply = self.total_supply().get();
+        let amount_a = mul_div(liquidity, reserve_a, total_supply)?;
+        let amount_b = mul_div(liquidity, reserve_b, total_supply)?;
+        if amount_a == 0 && amount_b == 0 {
+            return Err(anyhow!("AMM: insufficient liquidity burned"));
+        }
+        self.set_reserves(reserve_a - amount_a, reserve_b - amount_b);
+        self.total_supply().set(total_supply - liquidity);
+
+        Self::push_nonzero(&mut response, self.alkane_a().get(), amount_a);
+        Self::push_nonzero(&mut response, self.alkane_b().get(), amount_b);
+        Ok(response)
+    }
+
+    /// Sends out the requested amounts if the incoming tokens keep the
+    /// fee-adjusted product of the reserves from shrinking. Input beyond
+    /// what the invariant needs stays in the pool.
+    fn swap(&self, amount_a_out: u128, amount_b_out: u128) -> Result<CallResponse> {
+        let context = self.context()?;
+        if amount_a_out == 0 && amount_b_out == 0 {
+            return Err(anyhow!("AMM: insufficient output amount"));
+        }
+        let (alkane_a, alkane_b) = (self.alkane_a().get(), self.alkane_b().get());
+        let (reserve_a, reserve_b) = self.reserves();
+        if amount_a_out >= reserve_a || amount_b_out >= reserve_b {
+            return Err(anyhow!("AMM: insufficient liquidity"));
+        }
+        let (amounts, mut response) = self.take_incoming(&context, &[alkane_a, alkane_b])?;
+        let (amount_a_in, amount_b_in) = (amounts[0], amounts[1]);
+        if amount_a_in == 0 && amount_b_in == 0 {
+            return Err(anyhow!("AMM: insufficient input amount"));
+        }
+        let balance_a = reserve_a
+            .checked_add(amount_a_in)
+            .ok_or_else(|| anyhow!("AMM: arithmetic overflow"))?
+            - amount_a_out;
+        let balance_b = reserve_b
+            .checked_add(amount_b_in)
+            .ok_or_else(|| anyhow!("AMM: arithmetic overflow"))?
+            - amount_b_out;
+        let adjusted_a = wide(balance_a) * wide(BPS) - wide(amount_a_in) * wide(FEE_BPS);
+        let adjusted_b = wide(balance_b) * wide(BPS) - wide(amount_b_in) * wide(FEE_BPS);
+        if adjusted_a * adjusted_b < wide(reserve_a) * wide(reserve_b) * wide(BPS * BPS) {
+            return Err(anyhow!("AMM: invariant violated"));
+        }
+        self.set_reserves(balance_a, balance_b);
+
+        Self::push_nonzero(&mut response, alkane_a, amount_a_out);
+        Self::push_nonzero(&mut response, alkane_b, amount_b_out);
+        Ok(response)
+    }
+
+    fn simulate_swap(&self, token_in: AlkaneId, amount_in: u128) -> Result<CallResponse> {
+        let context = self.context()?;
+        let mut response = CallResponse::forward(&context.incoming_alkanes);
+        let (reserve_in, reserve_out) = self.reserves_for(&token_in)?;
+
+        response.data = get_amount_out(amount_in, reserve_in, reserve_out)?
+            .to_le_bytes()
+            .to_vec();
+
+        Ok(response)
+    }
+
+    fn simulate_swap_exact_out(&self, token_out: AlkaneId, amount_out: u128) -> Result<CallResponse> {
+        let context = self.context()?;
+        let mut response = CallResponse::forward(&context.incoming_alkanes);
+        let (reserve_out, reserve_in) = self.reserves_for(&token_out)?;
+
+        response.data = get_amount_in(amount_out, reserve_in, reserve_out)?
+            .to_le_bytes()
+            .to_vec();
+
+        Ok(response)
+    }
+
+    fn forward(&self) -> Result<CallResponse> {
+        let context = self.context()?;
+        Ok(CallResponse::forward(&context.incoming_alkanes))
+    }
+
+    fn get_reserves(&self) -> Result<CallResponse> {
+        let context = self.context()?;
+        let mut response = CallResponse::forward(&context.incoming_alkanes);
+        let (reserve_a, reserve_b) = self.reserves();
+
+        response.data = reserve_a.to_le_bytes().to_vec();
+        response.data.extend(reserve_b.to_le_bytes());
+
+        Ok(response)
+    }
+
+    /// The price of token a in units of token b, then of b in units of a.
+    fn get_price(&self) -> Result<CallResponse> {
+        let context = self.context()?;
+        let mut response = CallResponse::forward(&context.incoming_alkanes);
+        let (reserve_a, reserve_b) = self.reserves();
+        if reserve_a == 0 || reserve_b == 0 {
+            return Err(anyhow!("AMM: insufficient liquidity"));
+        }
+
+        response.data = mul_div(reserve_b, PRICE_SCALE, reserve_a)?
+            .to_le_bytes()
+            .to_vec();
+        response
+            .data
+            .extend(mul_div(reserve_a, PRICE_SCALE, reserve_b)?.to_le_bytes());
+
+        Ok(response)
+    }
+
+    fn get_name(&self) -> Result<CallResponse> {
+        let context = self.context()?;
+        let mut response = CallResponse::forward(&context.incoming_alkanes);
+
+        response.data = self.pool_name().get().into_bytes();
+
+        Ok(response)
+    }
+
+    fn get_total_supply(&self) -> Result<CallResponse> {
+        let context = self.context()?;
+        let mut response = CallResponse::forward(&context.incoming_alkanes);
+
+        response.data = self.total_supply().get().to_le_bytes().to_vec();
+
+        Ok(response)
+    }
+
+    /// Both tokens, both reserves, the LP supply and the length-prefixed
+    /// name, all little endian.
+    fn pool_details(&self) -> Result<CallResponse> {
+        let context = self.context()?;
+        let mut response = CallResponse::forward(&context.incoming_alkanes);
+        let (reserve_a, reserve_b) = self.reserves();
+        let name = self.pool_name().get().into_bytes();
+
+        let mut data: Vec<u8> = self.alkane_a().get().into();
+        data.extend(Vec::<u8>::from(self.alkane_b().get()));
+        data.extend(reserve_a.to_le_bytes());
+        data.extend(reserve_b.to_le_bytes());
+        data.extend(self.total_supply().get().to_le_bytes());
+        data.extend((name.len() as u32).to_le_bytes());
+        data.extend(name);
+        response.data = data;
+
+        Ok(response)
+    }
+}
+
+impl AlkaneResponder for AmmPool {}
+
+declare_alkane! {
+    impl AlkaneResponder for AmmPool {
+        type Message = AmmPoolMessage;
+    }
+}
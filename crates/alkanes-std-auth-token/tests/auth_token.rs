@@ -0,0 +1,61 @@
+#![cfg(not(target_arch = "wasm32"))]
+
+use alkanes_support::cellpack::Cellpack;
+use alkanes_support::constants::AUTH_TOKEN_FACTORY_ID;
+use alkanes_support::id::AlkaneId;
+use alkanes_support::utils::string_to_u128_list;
+use alkanes_test::{contract_wasm, Deployment, TestChain};
+use anyhow::Result;
+
+/// Deploys the auth token at `4:AUTH_TOKEN_FACTORY_ID`, minting `amount`.
+fn deploy(chain: &mut TestChain, amount: u128) -> Result<Deployment> {
+    let mut inputs = vec![0];
+    inputs.extend(string_to_u128_list("AUTH".to_string()));
+    inputs.extend(string_to_u128_list("AUTH".to_string()));
+    inputs.push(amount);
+    chain.deploy(
+        contract_wasm!()?,
+        Cellpack {
+            target: AlkaneId::new(3, AUTH_TOKEN_FACTORY_ID),
+            inputs,
+        },
+    )
+}
+
+#[test]
+fn test_initialize_mints_to_deployer() -> Result<()> {
+    let mut chain = TestChain::new()?;
+    let auth = deploy(&mut chain, 5)?;
+    assert_eq!(auth.id, AlkaneId::new(4, AUTH_TOKEN_FACTORY_ID));
+    chain.assert_balances(&auth.sent.output(0), &[(auth.id, 5)]);
+    assert_eq!(chain.call_view(auth.id, vec![99])?, b"AUTH");
+    Ok(())
+}
+
+#[test]
+fn test_authenticate_returns_the_token() -> Result<()> {
+    let mut chain = TestChain::new()?;
+    let auth = deploy(&mut chain, 5)?;
+    let sent = chain
+        .tx()
+        .spend(auth.sent.output(0))
+        .call(auth.id, vec![1])
+        .mine()?;
+    let response = chain.assert_returned(&sent.protostone(0));
+    assert_eq!(response.inner.data, vec![0x01]);
+    chain.assert_balances(&sent.output(0), &[(auth.id, 5)]);
+    Ok(())
+}
+
+#[test]
+fn test_authenticate_without_the_token_reverts() -> Result<()> {
+    let mut chain = TestChain::new()?;
+    let auth = deploy(&mut chain, 5)?;
+    let sent = chain.tx().call(auth.id, vec![1]).mine()?;
+    chain.assert_reverted(
+        &sent.protostone(0),
+        "did not authenticate with only the authentication token",
+    );
+    chain.assert_balances(&auth.sent.output(0), &[(auth.id, 5)]);
+    Ok(())
+}
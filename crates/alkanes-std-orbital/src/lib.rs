@@ -5,12 +5,12 @@ use alkanes_runtime::{
     println,
     stdio::{stdout, Write},
 };
-use alkanes_runtime::{runtime::AlkaneResponder, storage::StoragePointer, token::Token};
+use alkanes_runtime::storage::{storage, StorageValue};
+use alkanes_runtime::{runtime::AlkaneResponder, token::Token};
 use alkanes_support::{parcel::AlkaneTransfer, response::CallResponse};
 use anyhow::{anyhow, Result};
 use hex_lit::hex;
 use metashrew_support::compat::{to_arraybuffer_layout, to_passback_ptr};
-use metashrew_support::index_pointer::KeyValuePointer;
 
 #[derive(Default)]
 pub struct Orbital(());
@@ -46,19 +46,15 @@ impl Token for Orbital {
     }
 }
 
-impl Orbital {
-    pub fn total_supply_pointer(&self) -> StoragePointer {
-        StoragePointer::from_keyword("/totalsupply")
-    }
-
-    pub fn total_supply(&self) -> u128 {
-        self.total_supply_pointer().get_value::<u128>()
-    }
+#[storage]
+pub trait OrbitalStorage {
+    #[key("/totalsupply")]
+    fn total_supply(&self) -> StorageValue<u128>;
+}
 
-    pub fn set_total_supply(&self, v: u128) {
-        self.total_supply_pointer().set_value::<u128>(v);
-    }
+impl OrbitalStorage for Orbital {}
 
+impl Orbital {
     pub fn data(&self) -> Vec<u8> {
         // in this reference implementation, we return a 1x1 PNG
         // NFT data can be anything, however
@@ -70,7 +66,7 @@ impl Orbital {
         let context = self.context()?;
         let mut response = CallResponse::forward(&context.incoming_alkanes);
 
-        self.set_total_supply(1);
+        self.total_supply().set(1);
         response.alkanes.0.push(AlkaneTransfer {
             id: context.myself.clone(),
             value: 1u128,
@@ -101,7 +97,7 @@ impl Orbital {
         let context = self.context()?;
         let mut response = CallResponse::forward(&context.incoming_alkanes);
 
-        response.data = (&self.total_supply().to_le_bytes()).to_vec();
+        response.data = self.total_supply().get().to_le_bytes().to_vec();
 
         Ok(response)
     }
@@ -0,0 +1,123 @@
+#![cfg(not(target_arch = "wasm32"))]
+
+use alkanes_support::cellpack::Cellpack;
+use alkanes_support::constants::AUTH_TOKEN_FACTORY_ID;
+use alkanes_support::id::AlkaneId;
+use alkanes_test::{contract_wasm, TestChain};
+use anyhow::Result;
+use bitcoin::OutPoint;
+
+struct Fixture {
+    chain: TestChain,
+    token: AlkaneId,
+    auth: AlkaneId,
+    /// Holds the initial 1000 tokens and the auth token.
+    holdings: OutPoint,
+}
+
+/// Deploys the auth token factory, then an owned token initialized with
+/// 1 auth token and 1000 tokens.
+fn setup() -> Result<Fixture> {
+    let mut chain = TestChain::new()?;
+    chain.deploy(
+        contract_wasm!("../alkanes-std-auth-token")?,
+        Cellpack {
+            target: AlkaneId::new(3, AUTH_TOKEN_FACTORY_ID),
+            inputs: vec![100],
+        },
+    )?;
+    let token = chain.deploy(
+        contract_wasm!()?,
+        Cellpack {
+            target: AlkaneId::new(1, 0),
+            inputs: vec![0, 1, 1000],
+        },
+    )?;
+    let auth: AlkaneId = chain.storage(&token.id, b"/auth")?.try_into()?;
+    Ok(Fixture {
+        chain,
+        token: token.id,
+        auth,
+        holdings: token.sent.output(0),
+    })
+}
+
+#[test]
+fn test_initialize() -> Result<()> {
+    let Fixture {
+        chain,
+        token,
+        auth,
+        holdings,
+    } = setup()?;
+    chain.assert_balances(&holdings, &[(token, 1000), (auth, 1)]);
+    assert_eq!(chain.call_view(token, vec![99])?, b"OWNED");
+    Ok(())
+}
+
+#[test]
+fn test_mint_with_auth_token() -> Result<()> {
+    let Fixture {
+        mut chain,
+        token,
+        auth,
+        holdings,
+    } = setup()?;
+    let sent = chain
+        .tx()
+        .spend(holdings)
+        .call(token, vec![77, 500])
+        .mine()?;
+    chain.assert_returned(&sent.protostone(0));
+    chain.assert_balances(&sent.output(0), &[(token, 1500), (auth, 1)]);
+    // Known baseline behaviour: `initialize` hands out its `token_units`
+    // without going through `MintableToken::mint`, so the 1000 initial tokens
+    // are never added to `/totalsupply` and only the minted 500 are counted.
+    chain.assert_storage(&token, b"/totalsupply", 500u128.to_le_bytes());
+    Ok(())
+}
+
+#[test]
+fn test_mint_without_auth_token_reverts() -> Result<()> {
+    let Fixture {
+        mut chain,
+        token,
+        auth,
+        holdings,
+    } = setup()?;
+    let sent = chain.tx().call(token, vec![77, 500]).mine()?;
+    chain.assert_reverted(&sent.protostone(0), "Auth token is not in incoming alkanes");
+    chain.assert_balances(&sent.output(0), &[]);
+    chain.assert_balances(&holdings, &[(token, 1000), (auth, 1)]);
+    Ok(())
+}
+
+#[test]
+fn test_burn_reduces_supply() -> Result<()> {
+    let Fixture {
+        mut chain,
+        token,
+        auth,
+        holdings,
+    } = setup()?;
+    let minted = chain
+        .tx()
+        .spend(holdings)
+        .call(token, vec![77, 500])
+        .mine()?;
+
+    // Keep the auth token and the original 1000, burn the 500 minted.
+    let tx = chain.tx().spend(minted.output(0));
+    let burn = tx.protostone_vout(1);
+    let sent = tx
+        .edict(auth, 1, 0)
+        .edict(token, 1000, 0)
+        .pointer(burn)
+        .call(token, vec![88])
+        .mine()?;
+    chain.assert_returned(&sent.protostone(1));
+    chain.assert_balances(&sent.output(0), &[(token, 1000), (auth, 1)]);
+    chain.assert_storage(&token, b"/totalsupply", 0u128.to_le_bytes());
+    assert_eq!(chain.height(), 4);
+    Ok(())
+}
@@ -31,7 +31,8 @@
 //! **never mint alkanes the bin was not given** (the core safety invariant).
 
 use alkanes_runtime::runtime::AlkaneResponder;
-use alkanes_runtime::{declare_alkane, message::MessageDispatch, storage::StoragePointer};
+use alkanes_runtime::storage::{storage, Storable, StorageValue};
+use alkanes_runtime::{declare_alkane, message::MessageDispatch};
 #[allow(unused_imports)]
 use alkanes_runtime::{
     println,
@@ -43,13 +44,41 @@ use alkanes_support::{
 use anyhow::{anyhow, Result};
 use bitcoin::{ScriptBuf, Transaction};
 use metashrew_support::compat::{to_arraybuffer_layout, to_passback_ptr};
-use metashrew_support::index_pointer::KeyValuePointer;
-use std::sync::Arc;
 
-/// Storage key prefix for the per-recipient ledger. `/recycle/<spk>` holds the
-/// serialized list of `(AlkaneId, value)` owed to that script_pubkey. The
-/// indexer capture is the **only** writer; this contract only reads + clears.
-const RECYCLE_LEDGER_PREFIX: &str = "/recycle/";
+#[storage]
+pub trait RecycleStorage {
+    /// The per-recipient ledger. `/recycle/<spk>` holds the serialized list of
+    /// `(AlkaneId, value)` owed to that script_pubkey. The indexer capture is
+    /// the **only** writer; this contract only reads + clears.
+    #[key("/recycle/")]
+    fn ledger(&self, spk: Vec<u8>) -> StorageValue<RecycleLedger>;
+}
+
+impl RecycleStorage for Recycle {}
+
+/// What the bin owes one recipient, stored in the capture's flat encoding.
+#[derive(Default, Clone, Debug, PartialEq)]
+pub struct RecycleLedger(pub Vec<(AlkaneId, u128)>);
+
+impl Storable for RecycleLedger {
+    fn encode(&self, out: &mut Vec<u8>) {
+        (self.0.len() as u32).encode(out);
+        out.extend(encode_ledger(&self.0));
+    }
+    fn decode(input: &mut &[u8]) -> Result<Self> {
+        let len = u32::decode(input)? as usize;
+        (0..len)
+            .map(|_| <(AlkaneId, u128)>::decode(input))
+            .collect::<Result<_>>()
+            .map(RecycleLedger)
+    }
+    fn to_bytes(&self) -> Vec<u8> {
+        encode_ledger(&self.0)
+    }
+    fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        Ok(RecycleLedger(decode_ledger(bytes)))
+    }
+}
 
 #[derive(Default)]
 pub struct Recycle(());
@@ -83,18 +112,6 @@ enum RecycleMessage {
 }
 
 impl Recycle {
-    // ── storage ────────────────────────────────────────────────────────────
-    fn ledger_pointer(&self, spk: &[u8]) -> StoragePointer {
-        StoragePointer::from_keyword(RECYCLE_LEDGER_PREFIX).select(&spk.to_vec())
-    }
-
-    /// Decode the ledger blob: a flat sequence of (block:u128, tx:u128,
-    /// value:u128) little-endian triples. Matches the capture's encoding.
-    fn read_ledger(&self, spk: &[u8]) -> Vec<(AlkaneId, u128)> {
-        let raw = self.ledger_pointer(spk).get();
-        decode_ledger(raw.as_ref())
-    }
-
     // ── helpers ──────────────────────────────────────────────────────────────
     /// The claim/view recipient = first non-OP_RETURN output of this tx, EOA only.
     fn recipient_script(&self) -> Result<ScriptBuf> {
@@ -135,9 +152,9 @@ impl Recycle {
     fn get_recycle_balance(&self) -> Result<CallResponse> {
         let context = self.context()?;
         let spk = self.recipient_script()?;
-        let owed = self.read_ledger(spk.as_bytes());
+        let owed = self.ledger(spk.to_bytes()).get();
         let mut response = CallResponse::forward(&context.incoming_alkanes);
-        response.data = encode_ledger(&owed);
+        response.data = owed.to_bytes();
         Ok(response)
     }
 
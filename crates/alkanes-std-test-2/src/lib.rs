@@ -1,5 +1,10 @@
 use alkanes_runtime::{
-    declare_alkane, message::MessageDispatch, runtime::AlkaneResponder, storage::StoragePointer,
+    access::{AccessControl, DEFAULT_ADMIN_ROLE},
+    declare_alkane,
+    message::MessageDispatch,
+    pausable::Pausable,
+    runtime::AlkaneResponder,
+    storage::StoragePointer,
 };
 use alkanes_support::{
     cellpack::Cellpack,
@@ -115,8 +120,43 @@ enum LoggerAlkaneMessage {
 
     #[opcode(105)]
     IncClaimableFees,
+
+    #[opcode(130)]
+    InitializeAdmin { admin: AlkaneId },
+
+    #[opcode(131)]
+    Operate,
+
+    #[opcode(0x7f00)]
+    #[returns(bool)]
+    HasRole { role: u128, account: AlkaneId },
+
+    #[opcode(0x7f01)]
+    #[returns(u128)]
+    GetRoleAdmin { role: u128 },
+
+    #[opcode(0x7f02)]
+    GrantRole { role: u128, account: AlkaneId },
+
+    #[opcode(0x7f03)]
+    RevokeRole { role: u128, account: AlkaneId },
+
+    #[opcode(0x7f04)]
+    RenounceRole { role: u128, account: AlkaneId },
+
+    #[opcode(0x7f10)]
+    Pause,
+
+    #[opcode(0x7f11)]
+    Unpause,
+
+    #[opcode(0x7f12)]
+    #[returns(bool)]
+    Paused,
 }
 
+const OPERATOR_ROLE: u128 = 1;
+
 impl LoggerAlkane {
     fn initialize(&self) -> Result<CallResponse> {
         self.observe_initialization()?;
@@ -145,6 +185,24 @@ impl LoggerAlkane {
             .set_value::<u128>(self.claimable_fees_pointer().get_value::<u128>() + 2);
         Ok(CallResponse::forward(&context.incoming_alkanes))
     }
+    fn initialize_admin(&self, admin: AlkaneId) -> Result<CallResponse> {
+        let context = self.context()?;
+        if self.role_admin_initialized().get_value::<u8>() == 1 {
+            return Err(anyhow!("admin already initialized"));
+        }
+        self.role_admin_initialized().set_value::<u8>(1);
+        self.grant_role_to(DEFAULT_ADMIN_ROLE, admin);
+        Ok(CallResponse::forward(&context.incoming_alkanes))
+    }
+    fn role_admin_initialized(&self) -> StoragePointer {
+        StoragePointer::from_keyword("/roleadmininitialized")
+    }
+    fn operate(&self) -> Result<CallResponse> {
+        let context = self.context()?;
+        self.only_role(OPERATOR_ROLE)?;
+        self.when_not_paused()?;
+        Ok(CallResponse::forward(&context.incoming_alkanes))
+    }
     fn self_call(&self) -> Result<CallResponse> {
         let context = self.context()?;
         let mut response = CallResponse::forward(&context.incoming_alkanes);
@@ -425,6 +483,14 @@ impl LoggerAlkane {
     }
 }
 
+impl AccessControl for LoggerAlkane {}
+
+impl Pausable for LoggerAlkane {
+    fn authorize_pause(&self) -> Result<()> {
+        self.only_role(DEFAULT_ADMIN_ROLE)
+    }
+}
+
 impl AlkaneResponder for LoggerAlkane {}
 
 // Use the new macro format
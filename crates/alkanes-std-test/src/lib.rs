@@ -1,7 +1,8 @@
 use std::collections::BTreeSet;
 
 use alkanes_runtime::{
-    declare_alkane, message::MessageDispatch, runtime::AlkaneResponder, storage::StoragePointer,
+    declare_alkane, error::AlkaneError, message::MessageDispatch, runtime::AlkaneResponder,
+    storage::StoragePointer,
 };
 use alkanes_support::{
     cellpack::Cellpack,
@@ -134,6 +135,25 @@ enum LoggerAlkaneMessage {
 
     #[opcode(110)]
     TestExtCallReturnLeftovers { target: AlkaneId, inputs: Vec<u128> },
+
+    #[opcode(120)]
+    EmitTestEvent { topic: String, value: u128 },
+
+    #[opcode(121)]
+    EmitTestEventThenRevert { topic: String, value: u128 },
+
+    #[opcode(122)]
+    RevertWithError { needed: u128, have: u128 },
+
+    #[opcode(123)]
+    #[nonreentrant]
+    GuardedExtCall { target: AlkaneId, inputs: Vec<u128> },
+}
+
+#[derive(Debug, AlkaneError)]
+enum LoggerAlkaneError {
+    #[error(4097)]
+    InsufficientLiquidity { needed: u128, have: u128 },
 }
 
 impl LoggerAlkane {
@@ -484,6 +504,34 @@ impl LoggerAlkane {
         Err(anyhow!("Revert"))
     }
 
+    fn emit_test_event(&self, topic: String, value: u128) -> Result<CallResponse> {
+        let context = self.context()?;
+        self.emit_event(topic.as_bytes(), &value.to_le_bytes());
+        Ok(CallResponse::forward(&context.incoming_alkanes))
+    }
+
+    fn emit_test_event_then_revert(&self, topic: String, value: u128) -> Result<CallResponse> {
+        self.emit_event(topic.as_bytes(), &value.to_le_bytes());
+        Err(anyhow!("Revert after emit"))
+    }
+
+    fn revert_with_error(&self, needed: u128, have: u128) -> Result<CallResponse> {
+        Err(LoggerAlkaneError::InsufficientLiquidity { needed, have }.into())
+    }
+
+    fn guarded_ext_call(&self, target: AlkaneId, inputs: Vec<u128>) -> Result<CallResponse> {
+        let context = self.context()?;
+        let mut response = CallResponse::forward(&context.incoming_alkanes);
+        response.data = self
+            .call(
+                &Cellpack { target, inputs },
+                &AlkaneTransferParcel::default(),
+                self.fuel(),
+            )?
+            .data;
+        Ok(response)
+    }
+
     fn my_get_block_header(&self) -> Result<CallResponse> {
         let context = self.context()?;
         let mut response = CallResponse::forward(&context.incoming_alkanes);
@@ -527,5 +575,6 @@ impl AlkaneResponder for LoggerAlkane {}
 declare_alkane! {
     impl AlkaneResponder for LoggerAlkane {
         type Message = LoggerAlkaneMessage;
+        type Error = LoggerAlkaneError;
     }
 }
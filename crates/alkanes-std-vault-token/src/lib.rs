@@ -0,0 +1,322 @@
+//! Fungible vault token.
+//!
+//! The vault custodies units of one underlying alkane, fixed at
+//! initialization, and keeps the deposited amounts as internal balances in
+//! its own storage. Balances move between accounts without touching the
+//! underlying, either directly or through allowances, and leave the vault
+//! again by withdrawing to the protostone's pointer output.
+//!
+//! Accounts are alkane ids. A call acts for an account by the rule of
+//! [`AccessControl::acts_as`]: the account is the calling alkane, or the call
+//! carries some of the account as an incoming alkane, so a wallet holds its
+//! balance through an auth token it keeps spending back to itself.
+
+use alkanes_runtime::access::AccessControl;
+use alkanes_runtime::declare_alkane;
+use alkanes_runtime::message::MessageDispatch;
+#[allow(unused_imports)]
+use alkanes_runtime::{
+    println,
+    stdio::{stdout, Write},
+};
+use alkanes_runtime::storage::{storage, StorageMap, StorageValue};
+use alkanes_runtime::{runtime::AlkaneResponder, token::Token};
+use alkanes_support::{id::AlkaneId, parcel::AlkaneTransfer, response::CallResponse};
+use anyhow::{anyhow, Result};
+use metashrew_support::compat::{to_arraybuffer_layout, to_passback_ptr};
+
+#[derive(Default)]
+pub struct VaultToken(());
+
+#[derive(MessageDispatch)]
+enum VaultTokenMessage {
+    #[opcode(0)]
+    Initialize { underlying: AlkaneId },
+
+    #[opcode(1)]
+    Deposit { owner: AlkaneId },
+
+    #[opcode(2)]
+    Withdraw { owner: AlkaneId, amount: u128 },
+
+    #[opcode(3)]
+    Approve {
+        owner: AlkaneId,
+        spender: AlkaneId,
+        amount: u128,
+    },
+
+    #[opcode(4)]
+    Transfer {
+        owner: AlkaneId,
+        to: AlkaneId,
+        amount: u128,
+    },
+
+    #[opcode(5)]
+    TransferFrom {
+        spender: AlkaneId,
+        owner: AlkaneId,
+        to: AlkaneId,
+        amount: u128,
+    },
+
+    #[opcode(99)]
+    #[returns(String)]
+    GetName,
+
+    #[opcode(100)]
+    #[returns(String)]
+    GetSymbol,
+
+    #[opcode(101)]
+    #[returns(u128)]
+    GetTotalSupply,
+
+    #[opcode(102)]
+    #[returns(AlkaneId)]
+    GetUnderlying,
+
+    #[opcode(103)]
+    #[returns(u128)]
+    BalanceOf { owner: AlkaneId },
+
+    #[opcode(104)]
+    #[returns(u128)]
+    Allowance { owner: AlkaneId, spender: AlkaneId },
+}
+
+impl Token for VaultToken {
+    fn name(&self) -> String {
+        String::from("VAULT")
+    }
+    fn symbol(&self) -> String {
+        String::from("VAULT")
+    }
+}
+
+#[storage]
+pub trait VaultTokenStorage {
+    fn underlying(&self) -> StorageValue<AlkaneId>;
+    #[key("/totalsupply")]
+    fn total_supply(&self) -> StorageValue<u128>;
+    fn balances(&self) -> StorageMap<AlkaneId, u128>;
+    fn allowances(&self, owner: &AlkaneId) -> StorageMap<AlkaneId, u128>;
+}
+
+impl VaultTokenStorage for VaultToken {}
+
+impl AccessControl for VaultToken {}
+
+impl VaultToken {
+    fn owner_balance(&self, owner: &AlkaneId) -> u128 {
+        self.balances().get(owner).unwrap_or_default()
+    }
+
+    /// Empty balances and allowances are removed so the key lists only hold
+    /// live entries.
+    fn set_balance(&self, owner: AlkaneId, amount: u128) {
+        if amount == 0 {
+            self.balances().remove(&owner);
+        } else {
+            self.balances().insert(owner, amount);
+        }
+    }
+
+    fn spender_allowance(&self, owner: &AlkaneId, spender: &AlkaneId) -> u128 {
+        self.allowances(owner).get(spender).unwrap_or_default()
+    }
+
+    fn set_allowance(&self, owner: &AlkaneId, spender: AlkaneId, amount: u128) {
+        if amount == 0 {
+            self.allowances(owner).remove(&spender);
+        } else {
+            self.allowances(owner).insert(spender, amount);
+        }
+    }
+
+    fn only_account(&self, account: &AlkaneId) -> Result<()> {
+        if self.acts_as(account)? {
+            Ok(())
+        } else {
+            Err(anyhow!("VaultToken: caller does not act for {:?}", account))
+        }
+    }
+
+    fn move_balance(&self, from: AlkaneId, to: AlkaneId, amount: u128) -> Result<()> {
+        let from_balance = self.owner_balance(&from);
+        if from_balance < amount {
+            return Err(anyhow!("VaultToken: insufficient balance"));
+        }
+        self.set_balance(from, from_balance - amount);
+        let to_balance = self.owner_balance(&to);
+        self.set_balance(
+            to,
+            to_balance
+                .checked_add(amount)
+                .ok_or_else(|| anyhow!("VaultToken: balance overflow"))?,
+        );
+        Ok(())
+    }
+
+    fn initialize(&self, underlying: AlkaneId) -> Result<CallResponse> {
+        self.observe_initialization()?;
+        let context = self.context()?;
+        if underlying == context.myself {
+            return Err(anyhow!("VaultToken: cannot wrap itself"));
+        }
+        self.underlying().set(underlying);
+        Ok(CallResponse::forward(&context.incoming_alkanes))
+    }
+
+    /// Keeps every incoming unit of the underlying and credits it to `owner`,
+    /// forwarding anything else.
+    fn deposit(&self, owner: AlkaneId) -> Result<CallResponse> {
+        let context = self.context()?;
+        let underlying = self.underlying().get();
+        let mut response = CallResponse::default();
+        let mut amount = 0u128;
+        for transfer in context.incoming_alkanes.0.iter() {
+            if transfer.id == underlying {
+                amount = amount
+                    .checked_add(transfer.value)
+                    .ok_or_else(|| anyhow!("VaultToken: deposit overflow"))?;
+            } else {
+                response.alkanes.0.push(transfer.clone());
+            }
+        }
+        if amount == 0 {
+            return Err(anyhow!("VaultToken: nothing to deposit"));
+        }
+        let total_supply = self.total_supply();
+        total_supply.set(
+            total_supply
+                .get()
+                .checked_add(amount)
+                .ok_or_else(|| anyhow!("VaultToken: supply overflow"))?,
+        );
+        self.set_balance(owner, self.owner_balance(&owner) + amount);
+        Ok(response)
+    }
+
+    /// Debits `owner` and sends the underlying out with the response, so it
+    /// lands on the pointer output.
+    fn withdraw(&self, owner: AlkaneId, amount: u128) -> Result<CallResponse> {
+        let context = self.context()?;
+        self.only_account(&owner)?;
+        let balance = self.owner_balance(&owner);
+        if balance < amount {
+            return Err(anyhow!("VaultToken: insufficient balance"));
+        }
+        self.set_balance(owner, balance - amount);
+        let total_supply = self.total_supply();
+        total_supply.set(total_supply.get() - amount);
+
+        let mut response = CallResponse::forward(&context.incoming_alkanes);
+        if amount > 0 {
+            response.alkanes.0.push(AlkaneTransfer {
+                id: self.underlying().get(),
+                value: amount,
+            });
+        }
+        Ok(response)
+    }
+
+    fn approve(&self, owner: AlkaneId, spender: AlkaneId, amount: u128) -> Result<CallResponse> {
+        let context = self.context()?;
+        self.only_account(&owner)?;
+        self.set_allowance(&owner, spender, amount);
+        Ok(CallResponse::forward(&context.incoming_alkanes))
+    }
+
+    fn transfer(&self, owner: AlkaneId, to: AlkaneId, amount: u128) -> Result<CallResponse> {
+        let context = self.context()?;
+        self.only_account(&owner)?;
+        self.move_balance(owner, to, amount)?;
+        Ok(CallResponse::forward(&context.incoming_alkanes))
+    }
+
+    /// Moves `owner`'s balance on behalf of `spender`, spending its
+    /// allowance. An allowance of `u128::MAX` is never spent down.
+    fn transfer_from(
+        &self,
+        spender: AlkaneId,
+        owner: AlkaneId,
+        to: AlkaneId,
+        amount: u128,
+    ) -> Result<CallResponse> {
+        let context = self.context()?;
+        self.only_account(&spender)?;
+        let allowance = self.spender_allowance(&owner, &spender);
+        if allowance < amount {
+            return Err(anyhow!("VaultToken: insufficient allowance"));
+        }
+        if allowance != u128::MAX {
+            self.set_allowance(&owner, spender, allowance - amount);
+        }
+        self.move_balance(owner, to, amount)?;
+        Ok(CallResponse::forward(&context.incoming_alkanes))
+    }
+
+    fn get_name(&self) -> Result<CallResponse> {
+        let context = self.context()?;
+        let mut response = CallResponse::forward(&context.incoming_alkanes);
+
+        response.data = self.name().into_bytes().to_vec();
+
+        Ok(response)
+    }
+
+    fn get_symbol(&self) -> Result<CallResponse> {
+        let context = self.context()?;
+        let mut response = CallResponse::forward(&context.incoming_alkanes);
+
+        response.data = self.symbol().into_bytes().to_vec();
+
+        Ok(response)
+    }
+
+    fn get_total_supply(&self) -> Result<CallResponse> {
+        let context = self.context()?;
+        let mut response = CallResponse::forward(&context.incoming_alkanes);
+
+        response.data = self.total_supply().get().to_le_bytes().to_vec();
+
+        Ok(response)
+    }
+
+    fn get_underlying(&self) -> Result<CallResponse> {
+        let context = self.context()?;
+        let mut response = CallResponse::forward(&context.incoming_alkanes);
+
+        response.data = self.underlying().get().into();
+
+        Ok(response)
+    }
+
+    fn balance_of(&self, owner: AlkaneId) -> Result<CallResponse> {
+        let context = self.context()?;
+        let mut response = CallResponse::forward(&context.incoming_alkanes);
+
+        response.data = self.owner_balance(&owner).to_le_bytes().to_vec();
+
+        Ok(response)
+    }
+
+    fn allowance(&self, owner: AlkaneId, spender: AlkaneId) -> Result<CallResponse> {
+        let context = self.context()?;
+        let mut response = CallResponse::forward(&context.incoming_alkanes);
+
+        response.data = self.spender_allowance(&owner, &spender).to_le_bytes().to_vec();
+
+        Ok(response)
+    }
+}
+
+impl AlkaneResponder for VaultToken {}
+
+declare_alkane! {
+    impl AlkaneResponder for VaultToken {
+        type Message = VaultTokenMessage;
+    }
+}
@@ -0,0 +1,345 @@
+//! Structured revert errors.
+//!
+//! A contract reverts by returning `0x08c379a0` followed by a UTF-8 message,
+//! which the indexer wraps (`ALKANES: revert: Error: ...`) and, for
+//! extcalls, nests inside the caller's revert. A structured error is a
+//! message of the form
+//!
+//! ```text
+//! [E4097] InsufficientLiquidity { needed: 100, have: 3 }
+//! ```
+//!
+//! with a numeric code, the error's name and its typed params, so it reads
+//! the same as a plain string revert to clients that don't decode it and
+//! survives the wrapping unchanged. Params are rendered by [`ErrorParam`]:
+//! integers in decimal, `true`/`false`, strings quoted with Rust escapes,
+//! alkane ids as `block:tx` and lists as `[a, b]`.
+//!
+//! Contracts declare their errors with `#[derive(AlkaneError)]` from
+//! `alkanes-runtime`; [`StructuredError::decode`] recovers them from a
+//! revert message.
+
+use crate::id::AlkaneId;
+use std::fmt;
+
+/// Revert selector every revert payload starts with.
+pub const REVERT_SELECTOR: [u8; 4] = [0x08, 0xc3, 0x79, 0xa0];
+
+/// A type that can appear as a param of a structured error.
+pub trait ErrorParam {
+    fn write_param(&self, out: &mut String);
+}
+
+macro_rules! impl_error_param_display {
+    ($($t:ty),*) => {
+        $(impl ErrorParam for $t {
+            fn write_param(&self, out: &mut String) {
+                out.push_str(&self.to_string());
+            }
+        })*
+    };
+}
+
+impl_error_param_display!(u8, u16, u32, u64, u128, usize, bool);
+
+impl ErrorParam for String {
+    fn write_param(&self, out: &mut String) {
+        out.push_str(&format!("{:?}", self));
+    }
+}
+
+impl ErrorParam for &str {
+    fn write_param(&self, out: &mut String) {
+        out.push_str(&format!("{:?}", self));
+    }
+}
+
+impl ErrorParam for AlkaneId {
+    fn write_param(&self, out: &mut String) {
+        out.push_str(&format!("{}:{}", self.block, self.tx));
+    }
+}
+
+impl<T: ErrorParam> ErrorParam for Vec<T> {
+    fn write_param(&self, out: &mut String) {
+        out.push('[');
+        for (i, v) in self.iter().enumerate() {
+            if i > 0 {
+                out.push_str(", ");
+            }
+            v.write_param(out);
+        }
+        out.push(']');
+    }
+}
+
+/// Renders a structured error message. `params` are already rendered with
+/// [`ErrorParam::write_param`].
+pub fn encode_error(code: u32, name: &str, params: &[(&str, String)]) -> String {
+    let mut out = format!("[E{}] {}", code, name);
+    if !params.is_empty() {
+        out.push_str(" { ");
+        for (i, (field, value)) in params.iter().enumerate() {
+            if i > 0 {
+                out.push_str(", ");
+            }
+            out.push_str(field);
+            out.push_str(": ");
+            out.push_str(value);
+        }
+        out.push_str(" }");
+    }
+    out
+}
+
+/// A decoded param value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ErrorValue {
+    Uint(u128),
+    Bool(bool),
+    Str(String),
+    AlkaneId(AlkaneId),
+    List(Vec<ErrorValue>),
+}
+
+impl fmt::Display for ErrorValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ErrorValue::Uint(v) => write!(f, "{}", v),
+            ErrorValue::Bool(v) => write!(f, "{}", v),
+            ErrorValue::Str(v) => write!(f, "{:?}", v),
+            ErrorValue::AlkaneId(id) => write!(f, "{}:{}", id.block, id.tx),
+            ErrorValue::List(values) => {
+                write!(f, "[")?;
+                for (i, v) in values.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", v)?;
+                }
+                write!(f, "]")
+            }
+        }
+    }
+}
+
+/// A structured error recovered from a revert message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StructuredError {
+    pub code: u32,
+    pub name: String,
+    pub params: Vec<(String, ErrorValue)>,
+}
+
+/// `Name { field: value, ... }`, without the code.
+impl fmt::Display for StructuredError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)?;
+        if !self.params.is_empty() {
+            write!(f, " {{ ")?;
+            for (i, (field, value)) in self.params.iter().enumerate() {
+                if i > 0 {
+                    write!(f, ", ")?;
+                }
+                write!(f, "{}: {}", field, value)?;
+            }
+            write!(f, " }}")?;
+        }
+        Ok(())
+    }
+}
+
+impl StructuredError {
+    /// The innermost structured error in `message`, which is usually the one
+    /// that caused an extcall chain to revert.
+    pub fn decode(message: &str) -> Option<StructuredError> {
+        message
+            .rmatch_indices("[E")
+            .find_map(|(i, _)| Parser::new(&message[i..]).error())
+    }
+
+    /// [`decode`](Self::decode) of a revert payload, with or without the
+    /// revert selector.
+    pub fn decode_revert_data(data: &[u8]) -> Option<StructuredError> {
+        let message = data.strip_prefix(&REVERT_SELECTOR[..]).unwrap_or(data);
+        StructuredError::decode(&String::from_utf8_lossy(message))
+    }
+
+    pub fn param(&self, name: &str) -> Option<&ErrorValue> {
+        self.params.iter().find(|(f, _)| f == name).map(|(_, v)| v)
+    }
+}
+
+struct Parser<'a> {
+    rest: &'a str,
+}
+
+impl<'a> Parser<'a> {
+    fn new(input: &'a str) -> Self {
+        Parser { rest: input }
+    }
+
+    fn eat(&mut self, prefix: &str) -> Option<()> {
+        self.rest = self.rest.strip_prefix(prefix)?;
+        Some(())
+    }
+
+    fn take_while(&mut self, f: impl Fn(char) -> bool) -> &'a str {
+        let end = self.rest.find(|c: char| !f(c)).unwrap_or(self.rest.len());
+        let (taken, rest) = self.rest.split_at(end);
+        self.rest = rest;
+        taken
+    }
+
+    fn ident(&mut self) -> Option<&'a str> {
+        let ident = self.take_while(|c| c.is_ascii_alphanumeric() || c == '_');
+        ident
+            .starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+            .then_some(ident)
+    }
+
+    fn number(&mut self) -> Option<u128> {
+        self.take_while(|c| c.is_ascii_digit()).parse().ok()
+    }
+
+    fn error(&mut self) -> Option<StructuredError> {
+        self.eat("[E")?;
+        let code = self.number()?.try_into().ok()?;
+        self.eat("] ")?;
+        let name = self.ident()?.to_string();
+        let mut params = vec![];
+        if self.eat(" { ").is_some() {
+            loop {
+                let field = self.ident()?.to_string();
+                self.eat(": ")?;
+                params.push((field, self.value()?));
+                if self.eat(" }").is_some() {
+                    break;
+                }
+                self.eat(", ")?;
+            }
+        }
+        Some(StructuredError { code, name, params })
+    }
+
+    fn value(&mut self) -> Option<ErrorValue> {
+        if self.eat("true").is_some() {
+            return Some(ErrorValue::Bool(true));
+        }
+        if self.eat("false").is_some() {
+            return Some(ErrorValue::Bool(false));
+        }
+        if self.rest.starts_with('"') {
+            return self.string().map(ErrorValue::Str);
+        }
+        if self.eat("[").is_some() {
+            let mut values = vec![];
+            if self.eat("]").is_some() {
+                return Some(ErrorValue::List(values));
+            }
+            loop {
+                values.push(self.value()?);
+                if self.eat("]").is_some() {
+                    return Some(ErrorValue::List(values));
+                }
+                self.eat(", ")?;
+            }
+        }
+        let first = self.number()?;
+        if self.eat(":").is_some() {
+            return Some(ErrorValue::AlkaneId(AlkaneId::new(first, self.number()?)));
+        }
+        Some(ErrorValue::Uint(first))
+    }
+
+    /// A string in `{:?}` form.
+    fn string(&mut self) -> Option<String> {
+        self.eat("\"")?;
+        let mut out = String::new();
+        let mut chars = self.rest.char_indices();
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '"' => {
+                    self.rest = &self.rest[i + 1..];
+                    return Some(out);
+                }
+                '\\' => match chars.next()?.1 {
+                    'n' => out.push('\n'),
+                    'r' => out.push('\r'),
+                    't' => out.push('\t'),
+                    '0' => out.push('\0'),
+                    'u' => {
+                        let (open, _) = chars.next()?;
+                        if &self.rest[open..open + 1] != "{" {
+                            return None;
+                        }
+                        let close = self.rest[open..].find('}')? + open;
+                        let code = u32::from_str_radix(&self.rest[open + 1..close], 16).ok()?;
+                        out.push(char::from_u32(code)?);
+                        while chars.next()?.0 < close {}
+                    }
+                    other => out.push(other),
+                },
+                c => out.push(c),
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(v: impl ErrorParam) -> String {
+        let mut out = String::new();
+        v.write_param(&mut out);
+        out
+    }
+
+    #[test]
+    fn round_trips_through_a_wrapped_revert() {
+        let message = encode_error(
+            4097,
+            "InsufficientLiquidity",
+            &[
+                ("needed", render(100u128)),
+                ("pool", render(AlkaneId::new(2, 7))),
+                ("note", render("a \"quoted\", { tricky }\n\u{1f4a7}".to_string())),
+                ("path", render(vec![AlkaneId::new(2, 0), AlkaneId::new(32, 0)])),
+                ("exact", render(false)),
+            ],
+        );
+        let wrapped = format!(
+            "ALKANES: revert: Error: Extcall failed: ALKANES: revert: Error: {}",
+            message
+        );
+        let mut data = REVERT_SELECTOR.to_vec();
+        data.extend_from_slice(wrapped.as_bytes());
+        let decoded = StructuredError::decode_revert_data(&data).unwrap();
+        assert_eq!(decoded.code, 4097);
+        assert_eq!(decoded.name, "InsufficientLiquidity");
+        assert_eq!(decoded.param("needed"), Some(&ErrorValue::Uint(100)));
+        assert_eq!(
+            decoded.param("pool"),
+            Some(&ErrorValue::AlkaneId(AlkaneId::new(2, 7)))
+        );
+        assert_eq!(
+            decoded.param("note"),
+            Some(&ErrorValue::Str("a \"quoted\", { tricky }\n\u{1f4a7}".to_string()))
+        );
+        assert_eq!(decoded.param("exact"), Some(&ErrorValue::Bool(false)));
+        assert_eq!(format!("[E{}] {}", decoded.code, decoded), message);
+    }
+
+    #[test]
+    fn unit_errors_and_plain_strings() {
+        let decoded = StructuredError::decode("ALKANES: revert: Error: [E7] Paused").unwrap();
+        assert_eq!((decoded.code, decoded.name.as_str()), (7, "Paused"));
+        assert!(decoded.params.is_empty());
+        assert_eq!(
+            StructuredError::decode("ALKANES: revert: Error: balance underflow [Eek]"),
+            None
+        );
+    }
+}
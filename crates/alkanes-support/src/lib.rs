@@ -2,6 +2,7 @@ pub mod cellpack;
 pub mod constants;
 pub mod context;
 pub mod envelope;
+pub mod error;
 pub mod gz;
 pub mod id;
 pub mod parcel;
@@ -0,0 +1,233 @@
+use crate::id::AlkaneId;
+use crate::parcel::AlkaneTransfer;
+use crate::proto;
+use prost::Message;
+
+/// A storage slot written by a message, with its value before and after.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StorageWrite {
+    pub key: Vec<u8>,
+    pub old_value: Vec<u8>,
+    pub new_value: Vec<u8>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StorageDiff {
+    pub alkane: AlkaneId,
+    pub writes: Vec<StorageWrite>,
+}
+
+/// A contract's balance of `alkane` before and after a message.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BalanceChange {
+    pub alkane: AlkaneId,
+    pub old_balance: u128,
+    pub new_balance: u128,
+}
+
+impl BalanceChange {
+    /// `+n` or `-n`.
+    pub fn delta_string(&self) -> String {
+        if self.new_balance >= self.old_balance {
+            format!("+{}", self.new_balance - self.old_balance)
+        } else {
+            format!("-{}", self.old_balance - self.new_balance)
+        }
+    }
+}
+
+/// Balance changes of the alkanes held by the contract `holder`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct InventoryDiff {
+    pub holder: AlkaneId,
+    pub changes: Vec<BalanceChange>,
+}
+
+/// Alkanes a message took from (`debited`) or sent to (`credited`) an
+/// output of its transaction.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OutputDiff {
+    pub vout: u32,
+    pub credited: Vec<AlkaneTransfer>,
+    pub debited: Vec<AlkaneTransfer>,
+}
+
+/// State committed by the message at one protostone outpoint, as served by
+/// the `tracediff` view.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StateDiff {
+    pub storage: Vec<StorageDiff>,
+    pub inventories: Vec<InventoryDiff>,
+    pub outputs: Vec<OutputDiff>,
+    pub created: Vec<AlkaneId>,
+}
+
+impl StateDiff {
+    pub fn is_empty(&self) -> bool {
+        self.storage.is_empty()
+            && self.inventories.is_empty()
+            && self.outputs.is_empty()
+            && self.created.is_empty()
+    }
+}
+
+impl From<StateDiff> for proto::alkanes::StateDiff {
+    fn from(v: StateDiff) -> Self {
+        proto::alkanes::StateDiff {
+            storage: v
+                .storage
+                .into_iter()
+                .map(|s| proto::alkanes::AlkaneStorageDiff {
+                    alkane: Some(s.alkane.into()),
+                    writes: s
+                        .writes
+                        .into_iter()
+                        .map(|w| proto::alkanes::StorageWrite {
+                            key: w.key,
+                            old_value: w.old_value,
+                            new_value: w.new_value,
+                        })
+                        .collect(),
+                })
+                .collect(),
+            inventories: v
+                .inventories
+                .into_iter()
+                .map(|i| proto::alkanes::AlkaneInventoryDiff {
+                    holder: Some(i.holder.into()),
+                    changes: i
+                        .changes
+                        .into_iter()
+                        .map(|c| proto::alkanes::BalanceChange {
+                            alkane: Some(c.alkane.into()),
+                            old_balance: Some(c.old_balance.into()),
+                            new_balance: Some(c.new_balance.into()),
+                        })
+                        .collect(),
+                })
+                .collect(),
+            outputs: v
+                .outputs
+                .into_iter()
+                .map(|o| proto::alkanes::OutputBalanceDiff {
+                    vout: o.vout,
+                    credited: o.credited.into_iter().map(Into::into).collect(),
+                    debited: o.debited.into_iter().map(Into::into).collect(),
+                })
+                .collect(),
+            created: v.created.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl From<proto::alkanes::StateDiff> for StateDiff {
+    fn from(v: proto::alkanes::StateDiff) -> Self {
+        StateDiff {
+            storage: v
+                .storage
+                .into_iter()
+                .map(|s| StorageDiff {
+                    alkane: s.alkane.map_or(AlkaneId::default(), Into::into),
+                    writes: s
+                        .writes
+                        .into_iter()
+                        .map(|w| StorageWrite {
+                            key: w.key,
+                            old_value: w.old_value,
+                            new_value: w.new_value,
+                        })
+                        .collect(),
+                })
+                .collect(),
+            inventories: v
+                .inventories
+                .into_iter()
+                .map(|i| InventoryDiff {
+                    holder: i.holder.map_or(AlkaneId::default(), Into::into),
+                    changes: i
+                        .changes
+                        .into_iter()
+                        .map(|c| BalanceChange {
+                            alkane: c.alkane.map_or(AlkaneId::default(), Into::into),
+                            old_balance: c.old_balance.map_or(0, Into::into),
+                            new_balance: c.new_balance.map_or(0, Into::into),
+                        })
+                        .collect(),
+                })
+                .collect(),
+            outputs: v
+                .outputs
+                .into_iter()
+                .map(|o| OutputDiff {
+                    vout: o.vout,
+                    credited: o.credited.into_iter().map(Into::into).collect(),
+                    debited: o.debited.into_iter().map(Into::into).collect(),
+                })
+                .collect(),
+            created: v.created.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl TryFrom<Vec<u8>> for StateDiff {
+    type Error = anyhow::Error;
+    fn try_from(v: Vec<u8>) -> Result<Self, Self::Error> {
+        Ok(proto::alkanes::StateDiff::decode(v.as_ref())?.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn state_diff_round_trips_through_proto() {
+        let token = AlkaneId { block: 2, tx: 1 };
+        let diff = StateDiff {
+            storage: vec![StorageDiff {
+                alkane: token,
+                writes: vec![StorageWrite {
+                    key: b"/totalsupply".to_vec(),
+                    old_value: vec![],
+                    new_value: 1000u128.to_le_bytes().to_vec(),
+                }],
+            }],
+            inventories: vec![InventoryDiff {
+                holder: token,
+                changes: vec![BalanceChange {
+                    alkane: AlkaneId { block: 2, tx: 0 },
+                    old_balance: u128::MAX,
+                    new_balance: 5,
+                }],
+            }],
+            outputs: vec![OutputDiff {
+                vout: 0,
+                credited: vec![AlkaneTransfer {
+                    id: token,
+                    value: 1000,
+                }],
+                debited: vec![],
+            }],
+            created: vec![token],
+        };
+        let bytes =
+            <StateDiff as Into<proto::alkanes::StateDiff>>::into(diff.clone()).encode_to_vec();
+        assert_eq!(StateDiff::try_from(bytes).unwrap(), diff);
+        assert!(StateDiff::try_from(vec![]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn balance_change_delta_is_signed() {
+        let change = |old_balance, new_balance| BalanceChange {
+            alkane: AlkaneId::default(),
+            old_balance,
+            new_balance,
+        };
+        assert_eq!(change(10, 15).delta_string(), "+5");
+        assert_eq!(change(15, 10).delta_string(), "-5");
+        assert_eq!(
+            change(0, u128::MAX).delta_string(),
+            format!("+{}", u128::MAX)
+        );
+    }
+}
@@ -38,6 +38,14 @@ pub enum TraceEvent {
         transfers: Vec<AlkaneTransfer>,
         redirect_to: u32,
     },
+    // Clocked by the `__emit_event` host function. Unlike the two variants
+    // above this one is produced by the indexer; `alkane` is the emitting
+    // frame's `myself`.
+    EmitEvent {
+        alkane: AlkaneId,
+        topic: Vec<u8>,
+        data: Vec<u8>,
+    },
 }
 
 impl Into<TraceResponse> for ExtendedCallResponse {
@@ -164,6 +172,17 @@ impl Into<proto::alkanes::AlkanesTraceEvent> for TraceEvent {
                 };
                 proto::alkanes::alkanes_trace_event::Event::ValueTransfer(value_transfer)
             }
+            TraceEvent::EmitEvent {
+                alkane,
+                topic,
+                data,
+            } => proto::alkanes::alkanes_trace_event::Event::EmitEvent(
+                proto::alkanes::AlkanesEmitEvent {
+                    alkane: Some(alkane.into()),
+                    topic,
+                    data,
+                },
+            ),
         };
         proto::alkanes::AlkanesTraceEvent { event: Some(event) }
     }
@@ -284,6 +303,11 @@ impl From<proto::alkanes::AlkanesTraceEvent> for TraceEvent {
                         redirect_to: v.redirect_to,
                     }
                 }
+                proto::alkanes::alkanes_trace_event::Event::EmitEvent(v) => TraceEvent::EmitEvent {
+                    alkane: v.alkane.map_or(Default::default(), |a| a.into()),
+                    topic: v.topic,
+                    data: v.data,
+                },
             }
         } else {
             TraceEvent::CreateAlkane(AlkaneId { block: 0, tx: 0 })
@@ -385,3 +409,35 @@ mod fuel_used_tests {
         assert_eq!(back.fuel_used, 0);
     }
 }
+
+#[cfg(test)]
+mod emit_event_tests {
+    use super::*;
+
+    // EmitEvent survives the wire round-trip with its emitter, topic and data.
+    #[test]
+    fn emit_event_round_trips_through_proto() {
+        let event = TraceEvent::EmitEvent {
+            alkane: AlkaneId { block: 2, tx: 7 },
+            topic: b"Swap".to_vec(),
+            data: vec![1, 2, 3],
+        };
+        let encoded =
+            <TraceEvent as Into<proto::alkanes::AlkanesTraceEvent>>::into(event).encode_to_vec();
+        let decoded: TraceEvent = proto::alkanes::AlkanesTraceEvent::decode(encoded.as_ref())
+            .unwrap()
+            .into();
+        match decoded {
+            TraceEvent::EmitEvent {
+                alkane,
+                topic,
+                data,
+            } => {
+                assert_eq!(alkane, AlkaneId { block: 2, tx: 7 });
+                assert_eq!(topic, b"Swap".to_vec());
+                assert_eq!(data, vec![1, 2, 3]);
+            }
+            other => panic!("expected EmitEvent, got {:?}", other),
+        }
+    }
+}
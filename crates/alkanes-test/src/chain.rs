@@ -0,0 +1,291 @@
+use crate::render::{self, Balances};
+use crate::tx::{coinbase, Sent, TxBuilder};
+use alkanes::message::AlkaneMessageContext;
+use alkanes::native::NativeIndexer;
+use alkanes::view;
+use alkanes_support::cellpack::Cellpack;
+use alkanes_support::id::AlkaneId;
+use alkanes_support::proto::alkanes::AlkaneStorageRequest;
+use alkanes_support::trace::{Trace, TraceEvent, TraceResponse};
+use anyhow::{anyhow, Result};
+use bitcoin::block::{Header, Version};
+use bitcoin::hashes::Hash;
+use bitcoin::{Block, BlockHash, CompactTarget, OutPoint, Transaction, TxMerkleNode, Txid};
+use metashrew_native::{KeyValueStore, MemoryStore};
+use metashrew_support::utils::consensus_encode;
+use protorune::balance_sheet::load_sheet;
+use protorune::message::MessageContext;
+use protorune::tables::RuneTable;
+use protorune_support::balance_sheet::BalanceSheetOperations;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// Fuel for [`TestChain::call_view`], as `meta_safe` allows.
+pub const VIEW_FUEL: u64 = 100_000_000;
+
+// The indexer keeps process-wide state (fuel tank, view mode), so chains in
+// parallel tests take turns: every indexer call holds this lock.
+static INDEXER: Mutex<()> = Mutex::new(());
+
+fn exclusive() -> MutexGuard<'static, ()> {
+    INDEXER
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// An alkane deployed by [`TestChain::deploy`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Deployment {
+    pub id: AlkaneId,
+    /// The deploying transaction; output 0 holds whatever the deployment
+    /// call returned.
+    pub sent: Sent,
+}
+
+/// A regtest chain indexed in memory by the real indexer
+/// ([`NativeIndexer`] over a [`MemoryStore`]).
+pub struct TestChain {
+    indexer: NativeIndexer,
+    height: u32,
+    tip: BlockHash,
+    pending: Vec<Transaction>,
+    funding: u32,
+}
+
+impl TestChain {
+    /// A chain with its genesis block (height 0) indexed.
+    pub fn new() -> Result<Self> {
+        Self::with_store(Arc::new(MemoryStore::new()))
+    }
+
+    /// A chain indexing into `store`, which must be empty.
+    pub fn with_store(store: Arc<dyn KeyValueStore>) -> Result<Self> {
+        let mut chain = TestChain {
+            indexer: NativeIndexer::new(store),
+            height: 0,
+            tip: BlockHash::all_zeros(),
+            pending: vec![],
+            funding: 0,
+        };
+        chain.index(0)?;
+        Ok(chain)
+    }
+
+    /// Height of the last mined block.
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn indexer(&self) -> &NativeIndexer {
+        &self.indexer
+    }
+
+    /// Starts a transaction; see [`TxBuilder`].
+    pub fn tx(&mut self) -> TxBuilder<'_> {
+        TxBuilder::new(self)
+    }
+
+    pub(crate) fn queue(&mut self, tx: Transaction) {
+        self.pending.push(tx);
+    }
+
+    pub(crate) fn funding_outpoint(&mut self) -> OutPoint {
+        self.funding += 1;
+        OutPoint {
+            txid: Txid::from_byte_array([0xfe; 32]),
+            vout: self.funding,
+        }
+    }
+
+    fn index(&mut self, height: u32) -> Result<Block> {
+        let mut txdata = vec![coinbase(height)];
+        txdata.append(&mut self.pending);
+        let mut block = Block {
+            header: Header {
+                version: Version::ONE,
+                prev_blockhash: self.tip,
+                merkle_root: TxMerkleNode::all_zeros(),
+                time: 1_231_006_505 + height * 600,
+                bits: CompactTarget::from_consensus(0x207fffff),
+                nonce: 0,
+            },
+            txdata,
+        };
+        block.header.merkle_root = block
+            .compute_merkle_root()
+            .unwrap_or(block.header.merkle_root);
+        {
+            let _turn = exclusive();
+            self.indexer.index_block(&block, height)?;
+        }
+        self.height = height;
+        self.tip = block.block_hash();
+        Ok(block)
+    }
+
+    /// Mines the queued transactions into the next block.
+    pub fn mine(&mut self) -> Result<Block> {
+        self.index(self.height + 1)
+    }
+
+    /// Mines `blocks` blocks; queued transactions go into the first.
+    pub fn advance(&mut self, blocks: u32) -> Result<()> {
+        for _ in 0..blocks {
+            self.mine()?;
+        }
+        Ok(())
+    }
+
+    /// Deploys `wasm` with `cellpack` (`1:0` for a new `2:n` alkane, `3:n`
+    /// for `4:n`) in its own block. Fails with the trace if the deployment
+    /// call reverts.
+    pub fn deploy(&mut self, wasm: Vec<u8>, cellpack: Cellpack) -> Result<Deployment> {
+        let sent = self.tx().envelope(wasm).cellpack(cellpack).mine()?;
+        let events = self.trace(&sent.protostone(0))?;
+        if let Some(TraceEvent::RevertContext(_)) = events.last() {
+            return Err(anyhow!("deployment reverted:\n{}", render::trace(&events)));
+        }
+        events
+            .iter()
+            .find_map(|event| match event {
+                TraceEvent::CreateAlkane(id) => Some(Deployment { id: *id, sent }),
+                _ => None,
+            })
+            .ok_or_else(|| anyhow!("deployment created no alkane:\n{}", render::trace(&events)))
+    }
+
+    /// Runs `f` against the indexed state, e.g. `chain.view(|| view::getinventory(&req))`.
+    pub fn view<T>(&self, f: impl FnOnce() -> Result<T>) -> Result<T> {
+        let _turn = exclusive();
+        self.indexer.view(f)
+    }
+
+    /// Alkanes held by `outpoint`.
+    pub fn balances(&self, outpoint: &OutPoint) -> Result<Balances> {
+        let key = consensus_encode(outpoint)?;
+        self.view(|| {
+            let sheet = load_sheet(
+                &RuneTable::for_protocol(AlkaneMessageContext::protocol_tag())
+                    .OUTPOINT_TO_RUNES
+                    .select(&key),
+            );
+            Ok(sheet
+                .balances()
+                .iter()
+                .filter(|(_, value)| **value > 0)
+                .map(|(id, value)| (AlkaneId::from(*id), *value))
+                .collect())
+        })
+    }
+
+    /// The value `id` stores under `key`; empty if unset.
+    pub fn storage(&self, id: &AlkaneId, key: &[u8]) -> Result<Vec<u8>> {
+        let request = AlkaneStorageRequest {
+            id: Some((*id).into()),
+            path: key.to_vec(),
+        };
+        self.view(|| Ok(view::getstorageat(&request)?.value))
+    }
+
+    /// The trace recorded at a protostone's virtual outpoint.
+    pub fn trace(&self, outpoint: &OutPoint) -> Result<Vec<TraceEvent>> {
+        let bytes = self.view(|| view::trace(outpoint))?;
+        if bytes.is_empty() {
+            return Ok(vec![]);
+        }
+        let trace: Trace = bytes.try_into()?;
+        let events = trace
+            .0
+            .lock()
+            .map_err(|_| anyhow!("trace mutex poisoned"))?
+            .clone();
+        Ok(events)
+    }
+
+    /// Simulates calling `target` with `inputs` against the current state,
+    /// without mining anything, and returns the response data.
+    pub fn call_view(&self, target: AlkaneId, inputs: Vec<u128>) -> Result<Vec<u8>> {
+        let mut parcel = view::plain_parcel_from_cellpack(Cellpack { target, inputs });
+        parcel.height = self.height as u64;
+        let (response, _fuel) = self.view(|| view::simulate_safe(&parcel, VIEW_FUEL))?;
+        Ok(response.data)
+    }
+
+    /// Asserts `outpoint` holds exactly `expected`.
+    #[track_caller]
+    pub fn assert_balances(&self, outpoint: &OutPoint, expected: &[(AlkaneId, u128)]) {
+        let actual = self.balances(outpoint).expect("failed to read balances");
+        let expected: Balances = expected.iter().copied().filter(|(_, v)| *v > 0).collect();
+        if let Some(diff) = render::balance_diff(&expected, &actual) {
+            panic!("balances at {} differ:\n{}", outpoint, diff);
+        }
+    }
+
+    /// Asserts `outpoint` holds `amount` of `id`, whatever else it holds.
+    #[track_caller]
+    pub fn assert_balance(&self, outpoint: &OutPoint, id: AlkaneId, amount: u128) {
+        let actual = self.balances(outpoint).expect("failed to read balances");
+        let held = actual.get(&id).copied().unwrap_or(0);
+        if held != amount {
+            panic!(
+                "{} holds {} of {}, expected {}; it holds:\n{}",
+                outpoint,
+                held,
+                render::id(&id),
+                amount,
+                render::balances(&actual)
+            );
+        }
+    }
+
+    /// Asserts the value `id` stores under `key`.
+    #[track_caller]
+    pub fn assert_storage(&self, id: &AlkaneId, key: &[u8], expected: impl AsRef<[u8]>) {
+        let actual = self.storage(id, key).expect("failed to read storage");
+        if actual != expected.as_ref() {
+            panic!(
+                "storage {} of {} differs:\n  expected {}\n  actual   {}",
+                render::bytes(key),
+                render::id(id),
+                render::bytes(expected.as_ref()),
+                render::bytes(&actual)
+            );
+        }
+    }
+
+    /// Asserts the call at a protostone's outpoint returned, and gives back
+    /// its response.
+    #[track_caller]
+    pub fn assert_returned(&self, outpoint: &OutPoint) -> TraceResponse {
+        let events = self.trace(outpoint).expect("failed to read trace");
+        match events.last() {
+            Some(TraceEvent::ReturnContext(response)) => response.clone(),
+            _ => panic!(
+                "expected {} to return, trace:\n{}",
+                outpoint,
+                render::trace(&events)
+            ),
+        }
+    }
+
+    /// Asserts the call at a protostone's outpoint reverted with an error
+    /// containing `message`, in its own frame or a nested one.
+    #[track_caller]
+    pub fn assert_reverted(&self, outpoint: &OutPoint, message: &str) {
+        let events = self.trace(outpoint).expect("failed to read trace");
+        let reverted = matches!(events.last(), Some(TraceEvent::RevertContext(_)));
+        let found = events.iter().any(|event| match event {
+            TraceEvent::RevertContext(r) => {
+                String::from_utf8_lossy(&r.inner.data).contains(message)
+            }
+            _ => false,
+        });
+        if !reverted || !found {
+            panic!(
+                "expected {} to revert with {:?}, trace:\n{}",
+                outpoint,
+                message,
+                render::trace(&events)
+            );
+        }
+    }
+}
@@ -0,0 +1,96 @@
+use anyhow::{anyhow, Context, Result};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::Mutex;
+
+const WASM_TARGET: &str = "wasm32-unknown-unknown";
+
+// Built wasm by crate directory, so each contract builds once per test binary.
+static BUILT: Mutex<BTreeMap<PathBuf, Vec<u8>>> = Mutex::new(BTreeMap::new());
+
+fn package_name(manifest: &Path) -> Result<String> {
+    let toml = std::fs::read_to_string(manifest)
+        .with_context(|| format!("failed to read {}", manifest.display()))?;
+    let mut in_package = false;
+    for line in toml.lines().map(str::trim) {
+        if line.starts_with('[') {
+            in_package = line == "[package]";
+        } else if let Some((key, value)) = line.split_once('=') {
+            if in_package && key.trim() == "name" {
+                return Ok(value.trim().trim_matches('"').to_string());
+            }
+        }
+    }
+    Err(anyhow!("{} has no package name", manifest.display()))
+}
+
+/// Builds the contract crate at `crate_dir` for `wasm32-unknown-unknown`
+/// (release) under `target_dir` and returns its wasm. Builds run on the
+/// host, so tests that use them cannot run under the wasm test runner.
+///
+/// Most tests want [`contract_wasm!`](crate::contract_wasm) instead.
+pub fn build_contract(
+    crate_dir: impl AsRef<Path>,
+    target_dir: impl AsRef<Path>,
+) -> Result<Vec<u8>> {
+    let crate_dir = crate_dir
+        .as_ref()
+        .canonicalize()
+        .with_context(|| format!("no contract crate at {}", crate_dir.as_ref().display()))?;
+    let mut built = BUILT
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    if let Some(wasm) = built.get(&crate_dir) {
+        return Ok(wasm.clone());
+    }
+    let manifest = crate_dir.join("Cargo.toml");
+    let name = package_name(&manifest)?;
+    let target_dir = target_dir.as_ref().join("alkanes-test");
+    let cargo = std::env::var_os("CARGO").unwrap_or_else(|| "cargo".into());
+    let status = Command::new(cargo)
+        .args([
+            "build",
+            "--release",
+            "--target",
+            WASM_TARGET,
+            "--manifest-path",
+        ])
+        .arg(&manifest)
+        .current_dir(&crate_dir)
+        .env("CARGO_TARGET_DIR", &target_dir)
+        .status()
+        .with_context(|| format!("failed to run cargo build for {}", name))?;
+    if !status.success() {
+        return Err(anyhow!("cargo build of {} failed ({})", name, status));
+    }
+    let path = target_dir
+        .join(WASM_TARGET)
+        .join("release")
+        .join(format!("{}.wasm", name.replace('-', "_")));
+    let wasm =
+        std::fs::read(&path).with_context(|| format!("build produced no {}", path.display()))?;
+    built.insert(crate_dir, wasm.clone());
+    Ok(wasm)
+}
+
+/// The wasm of the contract crate under test, or of the crate at a path
+/// relative to it, built by [`build_contract`] into the test's
+/// `CARGO_TARGET_TMPDIR`. For integration tests (`tests/*.rs`):
+///
+/// ```ignore
+/// let token = contract_wasm!()?;
+/// let auth = contract_wasm!("../alkanes-std-auth-token")?;
+/// ```
+#[macro_export]
+macro_rules! contract_wasm {
+    () => {
+        $crate::build_contract(env!("CARGO_MANIFEST_DIR"), env!("CARGO_TARGET_TMPDIR"))
+    };
+    ($path:expr) => {
+        $crate::build_contract(
+            ::std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join($path),
+            env!("CARGO_TARGET_TMPDIR"),
+        )
+    };
+}
@@ -0,0 +1,38 @@
+//! Test chains for alkanes contracts.
+//!
+//! A [`TestChain`] runs the real indexer (`alkanes::native` over an
+//! in-memory store) behind a small builder: deploy wasm, send protostones
+//! with inputs and edicts, mine and advance blocks, then assert on
+//! per-outpoint balances, storage slots, traces and reverts. Assertions
+//! panic with a rendered diff or call trace rather than a bare `assert_eq!`.
+//!
+//! ```ignore
+//! use alkanes_support::{cellpack::Cellpack, id::AlkaneId};
+//! use alkanes_test::{contract_wasm, TestChain};
+//!
+//! let mut chain = TestChain::new()?;
+//! let token = chain.deploy(
+//!     contract_wasm!()?,
+//!     Cellpack { target: AlkaneId::new(1, 0), inputs: vec![0, 1, 1000] },
+//! )?;
+//! let mint = chain
+//!     .tx()
+//!     .spend(token.sent.output(0))
+//!     .call(token.id, vec![77, 500])
+//!     .mine()?;
+//! chain.assert_returned(&mint.protostone(0));
+//! chain.assert_balance(&mint.output(0), token.id, 1500);
+//! ```
+//!
+//! Tests run on the host target (`cargo test -p <contract>` from the
+//! workspace root); chains in parallel tests take turns on the indexer.
+
+mod chain;
+mod contract;
+pub mod render;
+mod tx;
+
+pub use chain::{Deployment, TestChain, VIEW_FUEL};
+pub use contract::build_contract;
+pub use render::Balances;
+pub use tx::{Sent, TxBuilder};
@@ -0,0 +1,187 @@
+//! Plain-text renderings for assertion failures: balance diffs, storage
+//! values and call traces.
+
+use alkanes_support::id::AlkaneId;
+use alkanes_support::parcel::AlkaneTransferParcel;
+use alkanes_support::trace::TraceEvent;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+/// Alkane balances held by one outpoint.
+pub type Balances = BTreeMap<AlkaneId, u128>;
+
+/// `block:tx`
+pub fn id(id: &AlkaneId) -> String {
+    format!("{}:{}", id.block, id.tx)
+}
+
+fn amount(value: Option<&u128>) -> String {
+    value.map_or_else(|| "-".to_string(), |v| v.to_string())
+}
+
+/// One row per alkane whose balance differs, `-` marking an absent
+/// balance; `None` when the two agree.
+pub fn balance_diff(expected: &Balances, actual: &Balances) -> Option<String> {
+    let mut rows = vec![(
+        "alkane".to_string(),
+        "expected".to_string(),
+        "actual".to_string(),
+    )];
+    let ids = expected
+        .keys()
+        .chain(actual.keys())
+        .collect::<std::collections::BTreeSet<_>>();
+    for alkane in ids {
+        let (want, got) = (expected.get(alkane), actual.get(alkane));
+        if want != got {
+            rows.push((id(alkane), amount(want), amount(got)));
+        }
+    }
+    if rows.len() == 1 {
+        return None;
+    }
+    let w0 = rows.iter().map(|r| r.0.len()).max().unwrap_or(0);
+    let w1 = rows.iter().map(|r| r.1.len()).max().unwrap_or(0);
+    let mut out = String::new();
+    for (alkane, want, got) in &rows {
+        let _ = writeln!(out, "  {:<w0$}  {:>w1$}  {}", alkane, want, got);
+    }
+    Some(out)
+}
+
+/// All balances, one per line.
+pub fn balances(balances: &Balances) -> String {
+    if balances.is_empty() {
+        return "  (none)\n".to_string();
+    }
+    balances
+        .iter()
+        .map(|(alkane, value)| format!("  {} = {}\n", id(alkane), value))
+        .collect()
+}
+
+/// `"text"` when the bytes are printable UTF-8, otherwise `0x…` (with the
+/// little-endian u128 for 16-byte values, the usual encoding of a number).
+pub fn bytes(data: &[u8]) -> String {
+    if data.is_empty() {
+        return "(empty)".to_string();
+    }
+    if let Ok(text) = std::str::from_utf8(data) {
+        if text.chars().all(|c| !c.is_control() || c == '\n') {
+            return format!("{:?}", text);
+        }
+    }
+    match <[u8; 16]>::try_from(data) {
+        Ok(word) => format!(
+            "0x{} (u128 {})",
+            hex::encode(data),
+            u128::from_le_bytes(word)
+        ),
+        Err(_) => format!("0x{}", hex::encode(data)),
+    }
+}
+
+fn parcel(alkanes: &AlkaneTransferParcel) -> String {
+    alkanes
+        .0
+        .iter()
+        .map(|t| format!("{} {}", t.value, id(&t.id)))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// An indented call tree, one event per line.
+pub fn trace(events: &[TraceEvent]) -> String {
+    if events.is_empty() {
+        return "  (no trace)\n".to_string();
+    }
+    let mut out = String::new();
+    let mut depth = 1;
+    for event in events {
+        let line = match event {
+            TraceEvent::EnterCall(c)
+            | TraceEvent::EnterStaticcall(c)
+            | TraceEvent::EnterDelegatecall(c) => {
+                let kind = match event {
+                    TraceEvent::EnterStaticcall(_) => "staticcall",
+                    TraceEvent::EnterDelegatecall(_) => "delegatecall",
+                    _ => "call",
+                };
+                let line = format!(
+                    "{} {} {:?} with [{}]",
+                    kind,
+                    id(&c.target),
+                    c.inner.inputs,
+                    parcel(&c.inner.incoming_alkanes)
+                );
+                let _ = writeln!(out, "{:indent$}{}", "", line, indent = depth * 2);
+                depth += 1;
+                continue;
+            }
+            TraceEvent::ReturnContext(r) | TraceEvent::RevertContext(r) => {
+                depth = depth.saturating_sub(1).max(1);
+                let kind = match event {
+                    TraceEvent::RevertContext(_) => "revert",
+                    _ => "return",
+                };
+                format!(
+                    "{} {} with [{}]",
+                    kind,
+                    bytes(&r.inner.data),
+                    parcel(&r.inner.alkanes)
+                )
+            }
+            TraceEvent::CreateAlkane(created) => format!("create {}", id(created)),
+            TraceEvent::EmitEvent {
+                alkane,
+                topic,
+                data,
+            } => {
+                format!("event {} {} {}", id(alkane), bytes(topic), bytes(data))
+            }
+            TraceEvent::ReceiveIntent { incoming_alkanes } => {
+                format!("receive [{}]", parcel(incoming_alkanes))
+            }
+            TraceEvent::ValueTransfer {
+                transfers,
+                redirect_to,
+            } => format!(
+                "transfer [{}] to vout {}",
+                parcel(&AlkaneTransferParcel(transfers.clone())),
+                redirect_to
+            ),
+        };
+        let _ = writeln!(out, "{:indent$}{}", "", line, indent = depth * 2);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn balance_diff_lists_only_mismatches() {
+        let a = AlkaneId::new(2, 1);
+        let b = AlkaneId::new(2, 2);
+        let c = AlkaneId::new(2, 3);
+        let expected = Balances::from([(a, 1000), (b, 1)]);
+        let actual = Balances::from([(a, 1000), (c, 5)]);
+        assert_eq!(
+            balance_diff(&expected, &actual).unwrap(),
+            "  alkane  expected  actual\n  2:2            1  -\n  2:3            -  5\n"
+        );
+        assert!(balance_diff(&expected, &expected).is_none());
+    }
+
+    #[test]
+    fn bytes_prefers_text_then_numbers() {
+        assert_eq!(bytes(b"OWNED"), "\"OWNED\"");
+        assert_eq!(
+            bytes(&1500u128.to_le_bytes()),
+            format!("0x{} (u128 1500)", hex::encode(1500u128.to_le_bytes()))
+        );
+        assert_eq!(bytes(&[0xff, 0x00]), "0xff00");
+        assert_eq!(bytes(&[]), "(empty)");
+    }
+}
@@ -0,0 +1,265 @@
+//! Transactions for a [`TestChain`]: spends, an optional wasm envelope, and
+//! the protostones that call alkanes and move balances.
+
+use crate::chain::TestChain;
+use alkanes_support::cellpack::Cellpack;
+use alkanes_support::envelope::RawEnvelope;
+use alkanes_support::id::AlkaneId;
+use anyhow::{anyhow, Result};
+use bitcoin::blockdata::transaction::Version;
+use bitcoin::{Amount, OutPoint, ScriptBuf, Sequence, Transaction, TxIn, TxOut, Txid, Witness};
+use ordinals::Runestone;
+use protorune::protostone::Protostones;
+use protorune_support::protostone::{Protostone, ProtostoneEdict};
+
+/// The p2sh script every test output pays to (protorune's `ADDRESS1`).
+const RECIPIENT_SCRIPT: &str = "a914ad8028e0e0f9b863174e0efc67f65223c3b7ab5387";
+
+pub(crate) fn recipient() -> ScriptBuf {
+    ScriptBuf::from_bytes(hex::decode(RECIPIENT_SCRIPT).unwrap())
+}
+
+pub(crate) fn coinbase(height: u32) -> Transaction {
+    Transaction {
+        version: Version::TWO,
+        lock_time: bitcoin::absolute::LockTime::from_height(height).unwrap(),
+        input: vec![TxIn {
+            previous_output: OutPoint::null(),
+            script_sig: ScriptBuf::new(),
+            sequence: Sequence::MAX,
+            witness: Witness::new(),
+        }],
+        output: vec![TxOut {
+            value: Amount::from_sat(5_000_000_000),
+            script_pubkey: recipient(),
+        }],
+    }
+}
+
+/// A transaction under construction, from [`TestChain::tx`].
+///
+/// Outputs `0..outputs` pay the test recipient, the OP_RETURN follows, and
+/// protostone `i` gets the virtual vout [`TxBuilder::protostone_vout`]`(i)`.
+/// Balances from the spent outpoints go to the first protostone. `edict`,
+/// `pointer` and `refund` apply to the most recently added protostone.
+pub struct TxBuilder<'a> {
+    chain: &'a mut TestChain,
+    inputs: Vec<OutPoint>,
+    envelope: Option<Vec<u8>>,
+    outputs: u32,
+    protostones: Vec<Protostone>,
+}
+
+impl<'a> TxBuilder<'a> {
+    pub(crate) fn new(chain: &'a mut TestChain) -> Self {
+        TxBuilder {
+            chain,
+            inputs: vec![],
+            envelope: None,
+            outputs: 1,
+            protostones: vec![],
+        }
+    }
+
+    /// Spends `outpoint`, bringing its alkanes into the transaction.
+    pub fn spend(mut self, outpoint: OutPoint) -> Self {
+        self.inputs.push(outpoint);
+        self
+    }
+
+    /// Carries `wasm` in the first input's witness, for a deploying
+    /// cellpack (`1:0`, `3:n`) to pick up.
+    pub fn envelope(mut self, wasm: Vec<u8>) -> Self {
+        self.envelope = Some(wasm);
+        self
+    }
+
+    /// Number of spendable outputs before the OP_RETURN (default 1).
+    pub fn outputs(mut self, outputs: u32) -> Self {
+        self.outputs = outputs.max(1);
+        self
+    }
+
+    /// Adds a protostone calling `target` with `inputs` (opcode first),
+    /// returning what is left to output 0.
+    pub fn call(self, target: AlkaneId, inputs: Vec<u128>) -> Self {
+        self.cellpack(Cellpack { target, inputs })
+    }
+
+    pub fn cellpack(self, cellpack: Cellpack) -> Self {
+        self.protostone(Protostone {
+            message: cellpack.encipher(),
+            ..transfer_protostone()
+        })
+    }
+
+    /// Adds a protostone as is.
+    pub fn protostone(mut self, protostone: Protostone) -> Self {
+        self.protostones.push(protostone);
+        self
+    }
+
+    /// Sends `amount` of `id` to `vout`. Starts a transfer-only protostone
+    /// when none has been added yet. On a calling protostone the edict runs
+    /// after the call, against what the call returned.
+    pub fn edict(mut self, id: AlkaneId, amount: u128, vout: u32) -> Self {
+        if self.protostones.is_empty() {
+            self.protostones.push(transfer_protostone());
+        }
+        self.current().edicts.push(ProtostoneEdict {
+            id: id.into(),
+            amount,
+            output: vout.into(),
+        });
+        self
+    }
+
+    /// Where the current protostone's leftover balance goes.
+    pub fn pointer(mut self, vout: u32) -> Self {
+        self.current().pointer = Some(vout);
+        self
+    }
+
+    /// Where the current protostone's balance goes if its call reverts.
+    pub fn refund(mut self, vout: u32) -> Self {
+        self.current().refund = Some(vout);
+        self
+    }
+
+    /// Virtual vout of protostone `index`, for edicts and pointers that
+    /// feed a later protostone.
+    pub fn protostone_vout(&self, index: u32) -> u32 {
+        self.outputs + 1 + 1 + index
+    }
+
+    fn current(&mut self) -> &mut Protostone {
+        self.protostones
+            .last_mut()
+            .expect("add a protostone (call, cellpack, edict) first")
+    }
+
+    fn build(&mut self) -> Result<Transaction> {
+        let mut input = self
+            .inputs
+            .iter()
+            .map(|outpoint| TxIn {
+                previous_output: *outpoint,
+                script_sig: ScriptBuf::new(),
+                sequence: Sequence::MAX,
+                witness: Witness::new(),
+            })
+            .collect::<Vec<_>>();
+        if input.is_empty() {
+            // A fresh funding outpoint keeps otherwise identical
+            // transactions from sharing a txid.
+            input.push(TxIn {
+                previous_output: self.chain.funding_outpoint(),
+                script_sig: ScriptBuf::new(),
+                sequence: Sequence::MAX,
+                witness: Witness::new(),
+            });
+        }
+        if let Some(wasm) = self.envelope.take() {
+            input[0].witness = RawEnvelope::from(wasm).to_witness(true);
+        }
+        let runestone = Runestone {
+            etching: None,
+            pointer: Some(0),
+            edicts: vec![],
+            mint: None,
+            protocol: match self.protostones.is_empty() {
+                true => None,
+                false => Some(
+                    self.protostones
+                        .encipher()
+                        .map_err(|e| anyhow!("failed to encipher protostones: {}", e))?,
+                ),
+            },
+        };
+        let mut output = (0..self.outputs)
+            .map(|_| TxOut {
+                value: Amount::from_sat(546),
+                script_pubkey: recipient(),
+            })
+            .collect::<Vec<_>>();
+        output.push(TxOut {
+            value: Amount::from_sat(0),
+            script_pubkey: runestone.encipher(),
+        });
+        Ok(Transaction {
+            version: Version::ONE,
+            lock_time: bitcoin::absolute::LockTime::ZERO,
+            input,
+            output,
+        })
+    }
+
+    fn queue(&mut self) -> Result<Sent> {
+        let tx = self.build()?;
+        let sent = Sent {
+            txid: tx.compute_txid(),
+            outputs: self.outputs,
+            protostones: self.protostones.len() as u32,
+        };
+        self.chain.queue(tx);
+        Ok(sent)
+    }
+
+    /// Queues the transaction for the next [`TestChain::mine`].
+    pub fn send(mut self) -> Result<Sent> {
+        self.queue()
+    }
+
+    /// Sends the transaction and mines it into the next block.
+    pub fn mine(mut self) -> Result<Sent> {
+        let sent = self.queue()?;
+        self.chain.mine()?;
+        Ok(sent)
+    }
+}
+
+fn transfer_protostone() -> Protostone {
+    Protostone {
+        burn: None,
+        message: vec![],
+        edicts: vec![],
+        refund: Some(0),
+        pointer: Some(0),
+        from: None,
+        protocol_tag: 1,
+    }
+}
+
+/// A sent transaction, for locating its outputs and protostones.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Sent {
+    pub txid: Txid,
+    outputs: u32,
+    protostones: u32,
+}
+
+impl Sent {
+    /// Spendable output `vout`.
+    pub fn output(&self, vout: u32) -> OutPoint {
+        OutPoint {
+            txid: self.txid,
+            vout,
+        }
+    }
+
+    /// Protostone `index`'s virtual outpoint, where its trace is kept.
+    pub fn protostone(&self, index: u32) -> OutPoint {
+        assert!(
+            index < self.protostones,
+            "transaction has {} protostones, no protostone {}",
+            self.protostones,
+            index
+        );
+        self.output(self.outputs + 1 + 1 + index)
+    }
+
+    /// The last protostone's virtual outpoint.
+    pub fn last_protostone(&self) -> OutPoint {
+        self.protostone(self.protostones.saturating_sub(1))
+    }
+}
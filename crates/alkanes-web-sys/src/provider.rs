@@ -9792,6 +9792,8 @@ impl DeezelProvider for WebProvider {
                 skip_diesel_mint: false,
                 max_indexed_height: None,
                 utxo_source: Default::default(),
+                skip_fuel_estimate: false,
+                fuel_padding_vbytes: 0,
         };
 
         match executor.execute(params).await? {
@@ -9838,6 +9840,8 @@ impl DeezelProvider for WebProvider {
                 skip_diesel_mint: false,
                 max_indexed_height: None,
                 utxo_source: Default::default(),
+                skip_fuel_estimate: false,
+                fuel_padding_vbytes: 0,
         };
 
         match executor.execute(params).await? {
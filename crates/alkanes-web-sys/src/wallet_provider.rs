@@ -2015,6 +2015,8 @@ impl DeezelProvider for BrowserWalletProvider {
             skip_diesel_mint: false,
         max_indexed_height: None,
         utxo_source: Default::default(),
+        skip_fuel_estimate: false,
+        fuel_padding_vbytes: 0,
         };
 
         match executor.execute(params).await? {
@@ -2063,6 +2065,8 @@ impl DeezelProvider for BrowserWalletProvider {
             skip_diesel_mint: false,
         max_indexed_height: None,
         utxo_source: Default::default(),
+        skip_fuel_estimate: false,
+        fuel_padding_vbytes: 0,
         };
 
         match executor.execute(params).await? {
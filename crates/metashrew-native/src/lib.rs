@@ -0,0 +1,63 @@
+//! Native stand-in for the `metashrew-core` host layer.
+//!
+//! The indexer programs (`protorune`, `alkanes`) read and write state through
+//! `IndexPointer`/`AtomicPointer`, which in `metashrew-core` bottom out in the
+//! `__get`/`__flush`/`__load_input` host imports of the METASHREW wasm
+//! runtime. Those imports take 32-bit wasm pointers, so the indexers cannot
+//! run outside of `alkanes.wasm`.
+//!
+//! This crate provides the same pointer API over a pluggable
+//! [`store::KeyValueStore`]. The indexers import their pointers from
+//! [`index_pointer`], which re-exports the `metashrew-core` types by default
+//! and this crate's [`pointer`] types when the `native` feature is on:
+//!
+//! ```ignore
+//! use metashrew_native::index_pointer::{AtomicPointer, IndexPointer};
+//! ```
+//!
+//! Writes are buffered in a per-thread cache and written to the installed
+//! store as one batch on [`runtime::flush`], like METASHREW flushes a block.
+//! With no store installed the cache is the only storage, which is how the
+//! `metashrew-core` `test-utils` stubs behave.
+
+pub mod pointer;
+#[cfg(feature = "rocksdb")]
+pub mod rocks;
+pub mod runtime;
+pub mod store;
+
+pub use store::{KeyValueStore, MemoryStore};
+
+#[cfg(feature = "rocksdb")]
+pub use rocks::RocksDbStore;
+
+/// The pointer types the indexers are built against.
+pub mod index_pointer {
+    #[cfg(not(feature = "native"))]
+    pub use metashrew_core::index_pointer::{AtomicPointer, IndexPointer};
+
+    #[cfg(feature = "native")]
+    pub use crate::pointer::{AtomicPointer, IndexPointer};
+}
+
+#[cfg(not(feature = "native"))]
+pub use metashrew_core::{clear, flush, input};
+
+/// Drops every cached value and pending write.
+#[cfg(feature = "native")]
+pub fn clear() {
+    runtime::clear();
+}
+
+/// Writes the pending writes to the installed store. A failed write aborts,
+/// as a failed `__flush` traps the wasm indexer.
+#[cfg(feature = "native")]
+pub fn flush() {
+    runtime::flush().expect("failed to flush pending writes to the store");
+}
+
+/// The input set with [`runtime::set_input`].
+#[cfg(feature = "native")]
+pub fn input() -> Vec<u8> {
+    runtime::input()
+}
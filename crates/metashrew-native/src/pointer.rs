@@ -0,0 +1,164 @@
+use crate::runtime;
+use metashrew_support::index_pointer::KeyValuePointer;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// Pointer into the global key space, read and written through
+/// [`runtime`]. Mirrors `metashrew_core::index_pointer::IndexPointer`.
+#[derive(Debug, Clone, Default)]
+pub struct IndexPointer(Arc<Vec<u8>>);
+
+impl KeyValuePointer for IndexPointer {
+    fn wrap(word: &Vec<u8>) -> Self {
+        IndexPointer(Arc::new(word.clone()))
+    }
+    fn unwrap(&self) -> Arc<Vec<u8>> {
+        self.0.clone()
+    }
+    fn inherits(&mut self, _v: &Self) {}
+    fn set(&mut self, v: Arc<Vec<u8>>) {
+        runtime::set(self.unwrap(), v)
+    }
+    fn get(&self) -> Arc<Vec<u8>> {
+        runtime::get(self.unwrap())
+    }
+}
+
+type Checkpoint = HashMap<Arc<Vec<u8>>, Arc<Vec<u8>>>;
+
+/// Pointer whose writes are staged in a stack of checkpoints shared by every
+/// pointer derived from it. Mirrors
+/// `metashrew_core::index_pointer::AtomicPointer`: the stack starts one deep,
+/// `commit` merges the top checkpoint into the one below it, and committing
+/// the last checkpoint writes it through to the global key space.
+#[derive(Debug, Clone)]
+pub struct AtomicPointer {
+    pointer: IndexPointer,
+    store: Arc<Mutex<Vec<Checkpoint>>>,
+}
+
+impl Default for AtomicPointer {
+    fn default() -> Self {
+        AtomicPointer {
+            pointer: IndexPointer::default(),
+            store: Arc::new(Mutex::new(vec![Checkpoint::new()])),
+        }
+    }
+}
+
+impl AtomicPointer {
+    /// `pointer`, staged in this pointer's checkpoints.
+    pub fn derive(&self, pointer: &IndexPointer) -> Self {
+        AtomicPointer {
+            pointer: pointer.clone(),
+            store: self.store.clone(),
+        }
+    }
+
+    pub fn get_pointer(&self) -> IndexPointer {
+        self.pointer.clone()
+    }
+
+    pub fn checkpoint(&mut self) {
+        self.store.lock().unwrap().push(Checkpoint::new());
+    }
+
+    pub fn commit(&mut self) {
+        let mut stack = self.store.lock().unwrap();
+        let top = stack.pop().unwrap_or_default();
+        match stack.last_mut() {
+            Some(below) => below.extend(top),
+            None => {
+                for (k, v) in top {
+                    runtime::set(k, v);
+                }
+                stack.push(Checkpoint::new());
+            }
+        }
+    }
+
+    pub fn rollback(&mut self) {
+        let mut stack = self.store.lock().unwrap();
+        stack.pop();
+        if stack.is_empty() {
+            stack.push(Checkpoint::new());
+        }
+    }
+
+    pub fn checkpoint_depth(&self) -> usize {
+        self.store.lock().unwrap().len()
+    }
+}
+
+impl KeyValuePointer for AtomicPointer {
+    fn wrap(word: &Vec<u8>) -> Self {
+        AtomicPointer {
+            pointer: IndexPointer::wrap(word),
+            ..Default::default()
+        }
+    }
+    fn unwrap(&self) -> Arc<Vec<u8>> {
+        self.pointer.unwrap()
+    }
+    fn inherits(&mut self, from: &Self) {
+        self.store = from.store.clone();
+    }
+    fn set(&mut self, v: Arc<Vec<u8>>) {
+        let mut stack = self.store.lock().unwrap();
+        if let Some(top) = stack.last_mut() {
+            top.insert(self.pointer.unwrap(), v);
+        }
+    }
+    fn get(&self) -> Arc<Vec<u8>> {
+        let key = self.pointer.unwrap();
+        let staged = self
+            .store
+            .lock()
+            .unwrap()
+            .iter()
+            .rev()
+            .find_map(|checkpoint| checkpoint.get(&key).cloned());
+        staged.unwrap_or_else(|| self.pointer.get())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::{KeyValueStore, MemoryStore};
+
+    #[test]
+    fn atomic_checkpoints_commit_and_roll_back() {
+        runtime::clear();
+        let mut atomic = AtomicPointer::default();
+        let mut ptr = atomic.keyword("/a");
+        atomic.checkpoint();
+        ptr.set_value::<u32>(1);
+        atomic.checkpoint();
+        ptr.set_value::<u32>(2);
+        assert_eq!(atomic.checkpoint_depth(), 3);
+        atomic.rollback();
+        assert_eq!(ptr.get_value::<u32>(), 1);
+        atomic.commit();
+        assert_eq!(IndexPointer::from_keyword("/a").get_value::<u32>(), 0);
+        atomic.commit();
+        assert_eq!(atomic.checkpoint_depth(), 1);
+        assert_eq!(IndexPointer::from_keyword("/a").get_value::<u32>(), 1);
+    }
+
+    #[test]
+    fn flush_writes_through_to_the_installed_store() {
+        let store = Arc::new(MemoryStore::new());
+        {
+            let _guard = runtime::install(store.clone());
+            IndexPointer::from_keyword("/k").set(Arc::new(vec![1]));
+            assert!(store.is_empty());
+            runtime::flush().unwrap();
+        }
+        assert_eq!(store.get(b"/k").unwrap(), Some(vec![1]));
+
+        let _guard = runtime::install(store.clone());
+        assert_eq!(*IndexPointer::from_keyword("/k").get(), vec![1]);
+        assert!(IndexPointer::from_keyword("/missing").get().is_empty());
+    }
+}
@@ -0,0 +1,53 @@
+use crate::store::KeyValueStore;
+use anyhow::{anyhow, Result};
+use rocksdb::{IteratorMode, Options, WriteBatch, DB};
+use std::path::Path;
+
+/// RocksDB-backed [`KeyValueStore`] for long-running indexers.
+pub struct RocksDbStore {
+    db: DB,
+}
+
+impl RocksDbStore {
+    /// Opens the database at `path`, creating it if it does not exist.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let mut opts = Options::default();
+        opts.create_if_missing(true);
+        let db = DB::open(&opts, path.as_ref()).map_err(|e| {
+            anyhow!(
+                "failed to open rocksdb at {}: {}",
+                path.as_ref().display(),
+                e
+            )
+        })?;
+        Ok(Self { db })
+    }
+}
+
+impl KeyValueStore for RocksDbStore {
+    fn get(&self, key: &[u8]) -> Result<Option<Vec<u8>>> {
+        self.db
+            .get(key)
+            .map_err(|e| anyhow!("rocksdb get failed: {}", e))
+    }
+
+    fn write_batch(&self, batch: Vec<(Vec<u8>, Vec<u8>)>) -> Result<()> {
+        let mut write = WriteBatch::default();
+        for (k, v) in batch {
+            write.put(k, v);
+        }
+        self.db
+            .write(write)
+            .map_err(|e| anyhow!("rocksdb write failed: {}", e))
+    }
+
+    fn entries(&self) -> Result<Vec<(Vec<u8>, Vec<u8>)>> {
+        self.db
+            .iterator(IteratorMode::Start)
+            .map(|item| {
+                let (k, v) = item.map_err(|e| anyhow!("rocksdb iteration failed: {}", e))?;
+                Ok((k.to_vec(), v.to_vec()))
+            })
+            .collect()
+    }
+}
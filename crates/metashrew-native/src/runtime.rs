@@ -0,0 +1,134 @@
+use crate::store::KeyValueStore;
+use anyhow::Result;
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+
+// Per-thread replacement for the metashrew-core globals: a read-through
+// cache over the installed store, the keys written since the last flush and
+// the program input.
+//
+// State is thread-local so that two indexers on different threads never
+// observe each other's pending writes. An indexer must do all of its work
+// for a block on one thread.
+
+#[derive(Default)]
+struct Runtime {
+    store: Option<Arc<dyn KeyValueStore>>,
+    cache: HashMap<Arc<Vec<u8>>, Arc<Vec<u8>>>,
+    to_flush: Vec<Arc<Vec<u8>>>,
+    input: Vec<u8>,
+}
+
+thread_local! {
+    static RUNTIME: RefCell<Runtime> = RefCell::new(Runtime::default());
+}
+
+/// Installs `store` as the calling thread's backing store until the returned
+/// guard is dropped. Cached values and pending writes are discarded both on
+/// install and on drop, so nothing leaks between stores.
+pub fn install(store: Arc<dyn KeyValueStore>) -> StoreGuard {
+    let previous = RUNTIME.with(|r| {
+        let mut r = r.borrow_mut();
+        r.cache.clear();
+        r.to_flush.clear();
+        r.store.replace(store)
+    });
+    StoreGuard { previous }
+}
+
+pub struct StoreGuard {
+    previous: Option<Arc<dyn KeyValueStore>>,
+}
+
+impl Drop for StoreGuard {
+    fn drop(&mut self) {
+        let previous = self.previous.take();
+        RUNTIME.with(|r| {
+            let mut r = r.borrow_mut();
+            r.cache.clear();
+            r.to_flush.clear();
+            r.store = previous;
+        });
+    }
+}
+
+/// Reads `key`, from the cache if it was read or written since the last
+/// [`clear`], else from the installed store. Missing keys read as empty.
+pub fn get(key: Arc<Vec<u8>>) -> Arc<Vec<u8>> {
+    let (cached, store) = RUNTIME.with(|r| {
+        let r = r.borrow();
+        (r.cache.get(&key).cloned(), r.store.clone())
+    });
+    if let Some(value) = cached {
+        return value;
+    }
+    let value = Arc::new(match store {
+        Some(store) => store
+            .get(key.as_ref())
+            .expect("failed to read from the store")
+            .unwrap_or_default(),
+        None => vec![],
+    });
+    RUNTIME.with(|r| r.borrow_mut().cache.insert(key, value.clone()));
+    value
+}
+
+pub fn set(key: Arc<Vec<u8>>, value: Arc<Vec<u8>>) {
+    RUNTIME.with(|r| {
+        let mut r = r.borrow_mut();
+        r.cache.insert(key.clone(), value);
+        r.to_flush.push(key);
+    });
+}
+
+/// Writes every key set since the last flush to the installed store in one
+/// batch. Without a store the cache keeps the values and this only resets
+/// the pending list.
+pub fn flush() -> Result<()> {
+    let (batch, store) = RUNTIME.with(|r| {
+        let mut r = r.borrow_mut();
+        let keys = std::mem::take(&mut r.to_flush);
+        let batch: BTreeMap<Vec<u8>, Vec<u8>> = keys
+            .into_iter()
+            .filter_map(|k| {
+                r.cache
+                    .get(&k)
+                    .map(|v| (k.as_ref().clone(), v.as_ref().clone()))
+            })
+            .collect();
+        (batch, r.store.clone())
+    });
+    match store {
+        Some(store) if !batch.is_empty() => store.write_batch(batch.into_iter().collect()),
+        _ => Ok(()),
+    }
+}
+
+/// Number of distinct keys written since the last flush.
+pub fn pending() -> usize {
+    RUNTIME.with(|r| {
+        let r = r.borrow();
+        let mut keys = r.to_flush.iter().collect::<Vec<_>>();
+        keys.sort();
+        keys.dedup();
+        keys.len()
+    })
+}
+
+/// Drops the cache and any pending writes.
+pub fn clear() {
+    RUNTIME.with(|r| {
+        let mut r = r.borrow_mut();
+        r.cache.clear();
+        r.to_flush.clear();
+    });
+}
+
+pub fn set_input(input: Vec<u8>) {
+    RUNTIME.with(|r| r.borrow_mut().input = input);
+}
+
+pub fn input() -> Vec<u8> {
+    RUNTIME.with(|r| r.borrow().input.clone())
+}
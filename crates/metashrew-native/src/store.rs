@@ -0,0 +1,62 @@
+use anyhow::Result;
+use std::collections::BTreeMap;
+use std::sync::RwLock;
+
+/// A flat key-value store the native indexer persists into.
+///
+/// Keys are the full pointer keys the indexer writes (e.g.
+/// `/alkanes/<id>/storage/...`); a missing key reads as empty, as it does
+/// under METASHREW.
+pub trait KeyValueStore: Send + Sync {
+    fn get(&self, key: &[u8]) -> Result<Option<Vec<u8>>>;
+
+    /// Writes every pair atomically: either all of a block's writes land or
+    /// none do.
+    fn write_batch(&self, batch: Vec<(Vec<u8>, Vec<u8>)>) -> Result<()>;
+
+    /// Every stored pair in key order. Meant for tests and tooling; it reads
+    /// the whole store.
+    fn entries(&self) -> Result<Vec<(Vec<u8>, Vec<u8>)>>;
+}
+
+/// In-memory [`KeyValueStore`], for tests and short-lived indexers.
+#[derive(Debug, Default)]
+pub struct MemoryStore(RwLock<BTreeMap<Vec<u8>, Vec<u8>>>);
+
+impl MemoryStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.read().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl KeyValueStore for MemoryStore {
+    fn get(&self, key: &[u8]) -> Result<Option<Vec<u8>>> {
+        Ok(self.0.read().unwrap().get(key).cloned())
+    }
+
+    fn write_batch(&self, batch: Vec<(Vec<u8>, Vec<u8>)>) -> Result<()> {
+        let mut map = self.0.write().unwrap();
+        for (k, v) in batch {
+            map.insert(k, v);
+        }
+        Ok(())
+    }
+
+    fn entries(&self) -> Result<Vec<(Vec<u8>, Vec<u8>)>> {
+        Ok(self
+            .0
+            .read()
+            .unwrap()
+            .iter()
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect())
+    }
+}
@@ -1,5 +1,5 @@
 use anyhow::{anyhow, Result};
-use metashrew_core::index_pointer::{AtomicPointer, IndexPointer};
+use metashrew_native::index_pointer::{AtomicPointer, IndexPointer};
 use metashrew_support::index_pointer::KeyValuePointer;
 use protorune_support::balance_sheet::{BalanceSheet, BalanceSheetOperations, ProtoruneRuneId};
 use protorune_support::rune_transfer::{increase_balances_using_sheet, RuneTransfer};
@@ -29,12 +29,14 @@ use bitcoin::blockdata::block::Block;
 use bitcoin::hashes::Hash;
 use bitcoin::script::Instruction;
 use bitcoin::{opcodes, Network, OutPoint, ScriptBuf, Transaction, TxOut, Txid};
-use metashrew_core::index_pointer::{AtomicPointer, IndexPointer};
 #[allow(unused_imports)]
 use metashrew_core::{
-    flush, input, println,
+    println,
     stdio::{stdout, Write},
 };
+use metashrew_native::index_pointer::{AtomicPointer, IndexPointer};
+#[allow(unused_imports)]
+use metashrew_native::{flush, input};
 use metashrew_support::address::Payload;
 use metashrew_support::index_pointer::KeyValuePointer;
 use ordinals::{Artifact, RuneId, Runestone};
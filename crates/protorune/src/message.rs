@@ -1,7 +1,7 @@
 use crate::tables::RuneTable;
 use anyhow::Result;
 use bitcoin::{Block, OutPoint, Transaction};
-use metashrew_core::index_pointer::AtomicPointer;
+use metashrew_native::index_pointer::AtomicPointer;
 use metashrew_support::index_pointer::KeyValuePointer;
 use protorune_support::balance_sheet::{BalanceSheet, ProtoruneRuneId};
 use protorune_support::rune_transfer::RuneTransfer;
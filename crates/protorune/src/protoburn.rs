@@ -1,12 +1,12 @@
 use crate::tables::{RuneTable, RUNES};
 use anyhow::{anyhow, Result};
 use bitcoin::{OutPoint, Txid};
-use metashrew_core::index_pointer::AtomicPointer;
 #[allow(unused_imports)]
 use metashrew_core::{
     println,
     stdio::{stdout, Write},
 };
+use metashrew_native::index_pointer::AtomicPointer;
 use metashrew_support::index_pointer::KeyValuePointer;
 use std::{
     cmp::min,
@@ -233,7 +233,7 @@ mod tests {
     use super::*;
     use bitcoin::hashes::Hash;
     use bitcoin::OutPoint;
-    use metashrew_core::index_pointer::AtomicPointer;
+    use metashrew_native::index_pointer::AtomicPointer;
     use ordinals::RuneId;
     use protorune_support::balance_sheet::ProtoruneRuneId;
     use std::collections::BTreeMap;
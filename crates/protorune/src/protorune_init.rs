@@ -1,11 +1,13 @@
 use crate::message::MessageContext;
 use crate::tables::RuneTable;
-use metashrew_core::index_pointer::AtomicPointer;
 #[allow(unused_imports)]
 use metashrew_core::{
-    flush, input, println,
+    println,
     stdio::{stdout, Write},
 };
+use metashrew_native::index_pointer::AtomicPointer;
+#[allow(unused_imports)]
+use metashrew_native::{flush, input};
 use metashrew_support::index_pointer::KeyValuePointer;
 use protorune_support::balance_sheet::ProtoruneRuneId;
 use std::sync::Arc;
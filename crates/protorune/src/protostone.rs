@@ -5,7 +5,7 @@ use crate::{
 };
 use anyhow::{anyhow, Result};
 use bitcoin::{Block, Transaction, Txid};
-use metashrew_core::index_pointer::{AtomicPointer, IndexPointer};
+use metashrew_native::index_pointer::{AtomicPointer, IndexPointer};
 use ordinals::Runestone;
 use protorune_support::{
     balance_sheet::BalanceSheet,
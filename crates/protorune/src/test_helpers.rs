@@ -51,7 +51,7 @@ pub fn init_network() {
 }
 
 pub fn clear() {
-    metashrew_core::clear();
+    metashrew_native::clear();
     init_network();
 }
 
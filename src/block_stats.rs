@@ -0,0 +1,211 @@
+use crate::message::AlkaneMessageContext;
+use crate::tables::BLOCK_STATS;
+use crate::vm::fuel::total_fuel;
+use alkanes_support::proto::alkanes::{BlockStats, CallDepthCount, RevertCount};
+use alkanes_support::trace::{StateDiff, TraceEvent};
+use anyhow::Result;
+use bitcoin::Block;
+#[allow(unused_imports)]
+use metashrew_core::{
+    println,
+    stdio::{stdout, Write},
+};
+use metashrew_support::index_pointer::KeyValuePointer;
+use ordinals::{Artifact, Runestone};
+use prost::Message;
+use protorune::message::MessageContext;
+use protorune_support::protostone::Protostone;
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+// Per-block indexing workload, served by the `blockstats` view.
+//
+// Counters accumulate in a thread-local while `index_block` runs, fed by
+// `handle_message` once each message has committed or reverted, and are
+// written to `/stats/<height>` at the end of the block. Like the history
+// tables, recording is only active between `begin_block_stats` and the
+// guard's drop, so simulations and views leave no stats behind.
+
+/// Revert classes, from the message's error.
+pub const REVERT_OUT_OF_FUEL: &str = "out_of_fuel";
+pub const REVERT_BALANCE: &str = "balance";
+pub const REVERT_DEPLOYMENT: &str = "deployment";
+pub const REVERT_EXTCALL: &str = "extcall";
+pub const REVERT_OTHER: &str = "revert";
+
+#[derive(Default)]
+struct Counters {
+    messages: u64,
+    reverts: BTreeMap<&'static str, u64>,
+    fuel_consumed: u64,
+    storage_bytes: u64,
+    alkanes_created: u64,
+    call_depths: BTreeMap<u32, u64>,
+}
+
+thread_local! {
+    static COUNTERS: RefCell<Option<Counters>> = const { RefCell::new(None) };
+}
+
+/// Starts counting for the block about to be indexed. Anything counted is
+/// dropped with the guard unless `save_block_stats` took it first.
+pub fn begin_block_stats() -> BlockStatsGuard {
+    COUNTERS.with(|c| *c.borrow_mut() = Some(Counters::default()));
+    BlockStatsGuard
+}
+
+pub struct BlockStatsGuard;
+
+impl Drop for BlockStatsGuard {
+    fn drop(&mut self) {
+        COUNTERS.with(|c| *c.borrow_mut() = None);
+    }
+}
+
+/// Deepest call frame `events` entered; 1 for a message without extcalls.
+pub fn call_depth(events: &[TraceEvent]) -> u32 {
+    let (mut depth, mut deepest) = (0u32, 0u32);
+    for event in events {
+        match event {
+            TraceEvent::EnterCall(_)
+            | TraceEvent::EnterDelegatecall(_)
+            | TraceEvent::EnterStaticcall(_) => {
+                depth += 1;
+                deepest = deepest.max(depth);
+            }
+            TraceEvent::ReturnContext(_) | TraceEvent::RevertContext(_) => {
+                depth = depth.saturating_sub(1);
+            }
+            _ => {}
+        }
+    }
+    deepest
+}
+
+/// Class of the error a message reverted with.
+pub fn revert_class(error: &str) -> &'static str {
+    if error.contains("fuel") {
+        REVERT_OUT_OF_FUEL
+    } else if error.contains("balance underflow") || error.contains("balance overflow") {
+        REVERT_BALANCE
+    } else if error.contains("no binary found") || error.contains("CREATE") {
+        REVERT_DEPLOYMENT
+    } else if error.contains("Extcall failed") {
+        REVERT_EXTCALL
+    } else {
+        REVERT_OTHER
+    }
+}
+
+/// What one message adds to its block's counters.
+pub struct MessageStats {
+    fuel: u64,
+    storage_bytes: u64,
+    alkanes_created: u64,
+    call_depth: u32,
+    revert: Option<&'static str>,
+}
+
+impl MessageStats {
+    /// A message that committed after using `fuel_used`, with its trace and
+    /// state diff.
+    pub fn committed(events: &[TraceEvent], fuel_used: u64, diff: &StateDiff) -> Self {
+        MessageStats {
+            fuel: fuel_used,
+            storage_bytes: diff
+                .storage
+                .iter()
+                .flat_map(|s| s.writes.iter())
+                .map(|w| (w.key.len() + w.new_value.len()) as u64)
+                .sum(),
+            alkanes_created: diff.created.len() as u64,
+            call_depth: call_depth(events),
+            revert: None,
+        }
+    }
+
+    /// A message that reverted with `error`. Its allocation of `fuel` is
+    /// drained, so all of it counts as consumed.
+    pub fn reverted(events: &[TraceEvent], fuel: u64, error: &anyhow::Error) -> Self {
+        MessageStats {
+            fuel,
+            storage_bytes: 0,
+            alkanes_created: 0,
+            call_depth: call_depth(events),
+            revert: Some(revert_class(&error.to_string())),
+        }
+    }
+}
+
+/// Adds `stats` to the counters of the block being indexed, if any.
+pub fn record_message(stats: MessageStats) {
+    COUNTERS.with(|c| {
+        if let Some(c) = c.borrow_mut().as_mut() {
+            c.messages += 1;
+            c.fuel_consumed = c.fuel_consumed.saturating_add(stats.fuel);
+            c.storage_bytes += stats.storage_bytes;
+            c.alkanes_created += stats.alkanes_created;
+            *c.call_depths.entry(stats.call_depth).or_default() += 1;
+            if let Some(class) = stats.revert {
+                *c.reverts.entry(class).or_default() += 1;
+            }
+        }
+    });
+}
+
+/// Alkanes protostones in `block`, whether or not they carry a message.
+pub fn count_protostones(block: &Block) -> u64 {
+    block
+        .txdata
+        .iter()
+        .filter_map(|tx| match Runestone::decipher(tx) {
+            Some(Artifact::Runestone(runestone)) => Protostone::from_runestone(&runestone).ok(),
+            _ => None,
+        })
+        .flatten()
+        .filter(|p| p.protocol_tag == AlkaneMessageContext::protocol_tag())
+        .count() as u64
+}
+
+/// Writes the counters of the block at `height` to `/stats/<height>`.
+pub fn save_block_stats(block: &Block, height: u64) -> Result<()> {
+    let Some(counters) = COUNTERS.with(|c| c.borrow_mut().take()) else {
+        return Ok(());
+    };
+    let stats = BlockStats {
+        height,
+        protostones: count_protostones(block),
+        messages: counters.messages,
+        reverts: counters
+            .reverts
+            .into_iter()
+            .map(|(class, count)| RevertCount {
+                class: class.to_string(),
+                count,
+            })
+            .collect(),
+        fuel_consumed: counters.fuel_consumed,
+        block_fuel: total_fuel(height as u32),
+        storage_bytes: counters.storage_bytes,
+        alkanes_created: counters.alkanes_created,
+        call_depths: counters
+            .call_depths
+            .into_iter()
+            .map(|(depth, count)| CallDepthCount { depth, count })
+            .collect(),
+    };
+    BLOCK_STATS
+        .select_value::<u64>(height)
+        .set(Arc::new(stats.encode_to_vec()));
+    Ok(())
+}
+
+/// Stats of the block at `height`, if it has any.
+pub fn block_stats_at(height: u64) -> Result<Option<BlockStats>> {
+    let bytes = BLOCK_STATS.select_value::<u64>(height).get();
+    if bytes.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(BlockStats::decode(bytes.as_slice())?))
+}
@@ -0,0 +1,179 @@
+use crate::history::history_key;
+use alkanes_support::id::AlkaneId;
+use alkanes_support::proto::alkanes::{AlkaneEvent, Outpoint};
+use alkanes_support::trace::{Trace, TraceEvent};
+use anyhow::Result;
+use bitcoin::hashes::Hash;
+use bitcoin::OutPoint;
+#[allow(unused_imports)]
+use metashrew_core::{
+    println,
+    stdio::{stdout, Write},
+};
+use metashrew_native::index_pointer::{AtomicPointer, IndexPointer};
+use metashrew_support::index_pointer::KeyValuePointer;
+use prost::Message;
+use std::sync::Arc;
+
+// Contract event logs emitted through `__emit_event`.
+//
+// Every event of a block is stored once, in emission order, in the list at
+// `/events/byheight/<height>`. Each alkane additionally gets two reference
+// lists, one over all of its events and one per topic, whose entries are
+// `height (u64 LE) ++ index (u32 LE)` pointing back into the per-height
+// list. Reference lists are append-only in height, so `eventsbyalkane` can
+// binary-search its `from_height`.
+//
+// Events are written through the message's AtomicPointer, so a message that
+// is rolled back after the fact leaves no events behind.
+
+pub fn events_by_height_pointer<T: KeyValuePointer>(root: &T, height: u64) -> T {
+    root.keyword("/events/byheight/")
+        .select_value::<u64>(height)
+}
+
+pub fn events_by_alkane_pointer<T: KeyValuePointer>(root: &T, alkane: &AlkaneId) -> T {
+    root.keyword("/events/byalkane/")
+        .select(&alkane.clone().into())
+        .keyword("/all/")
+}
+
+/// Topics are arbitrary bytes, so they are length-prefixed like storage keys
+/// in the history tables.
+pub fn events_by_topic_pointer<T: KeyValuePointer>(root: &T, alkane: &AlkaneId, topic: &[u8]) -> T {
+    root.keyword("/events/byalkane/")
+        .select(&alkane.clone().into())
+        .keyword("/topic/")
+        .select(&history_key(topic))
+}
+
+/// Events of `events` that survive execution, in emission order.
+///
+/// The trace is a flat sequence of enter/exit markers, so this replays it as a
+/// stack of frames: events accumulate on the innermost frame, move to the
+/// parent when it returns, and are dropped when it reverts. Staticcall frames
+/// drop their events even on return, since their state changes are discarded
+/// too.
+pub fn committed_events(events: &[TraceEvent]) -> Vec<(AlkaneId, Vec<u8>, Vec<u8>)> {
+    let mut frames: Vec<(bool, Vec<(AlkaneId, Vec<u8>, Vec<u8>)>)> = vec![];
+    let mut committed = vec![];
+    for event in events {
+        match event {
+            TraceEvent::EnterCall(_) | TraceEvent::EnterDelegatecall(_) => {
+                frames.push((false, vec![]))
+            }
+            TraceEvent::EnterStaticcall(_) => frames.push((true, vec![])),
+            TraceEvent::EmitEvent {
+                alkane,
+                topic,
+                data,
+            } => {
+                if let Some((_, emitted)) = frames.last_mut() {
+                    emitted.push((alkane.clone(), topic.clone(), data.clone()));
+                }
+            }
+            TraceEvent::ReturnContext(_) => {
+                if let Some((is_static, emitted)) = frames.pop() {
+                    if !is_static {
+                        match frames.last_mut() {
+                            Some((_, parent)) => parent.extend(emitted),
+                            None => committed.extend(emitted),
+                        }
+                    }
+                }
+            }
+            TraceEvent::RevertContext(_) => {
+                frames.pop();
+            }
+            _ => {}
+        }
+    }
+    committed
+}
+
+/// Indexes the committed events of a message's `trace`, emitted by the
+/// protostone at `outpoint` in the block at `height`.
+pub fn index_events(
+    atomic: &mut AtomicPointer,
+    outpoint: &OutPoint,
+    height: u64,
+    trace: &Trace,
+) -> Result<()> {
+    let events = committed_events(&trace.0.lock().unwrap());
+    if events.is_empty() {
+        return Ok(());
+    }
+    let root = atomic.derive(&IndexPointer::default());
+    let mut by_height = events_by_height_pointer(&root, height);
+    for (alkane, topic, data) in events {
+        let index = by_height.length();
+        by_height.append(Arc::new(
+            AlkaneEvent {
+                height,
+                outpoint: Some(Outpoint {
+                    txid: outpoint.txid.as_byte_array().to_vec(),
+                    vout: outpoint.vout,
+                }),
+                alkane: Some(alkane.clone().into()),
+                topic: topic.clone(),
+                data,
+            }
+            .encode_to_vec(),
+        ));
+        let mut reference = height.to_le_bytes().to_vec();
+        reference.extend_from_slice(&index.to_le_bytes());
+        let reference = Arc::new(reference);
+        events_by_alkane_pointer(&root, &alkane).append(reference.clone());
+        events_by_topic_pointer(&root, &alkane, &topic).append(reference);
+    }
+    Ok(())
+}
+
+/// Every event indexed at `height`, in emission order.
+pub fn events_at_height(height: u64) -> Result<Vec<AlkaneEvent>> {
+    events_by_height_pointer(&IndexPointer::default(), height)
+        .get_list()
+        .into_iter()
+        .map(|v| Ok(AlkaneEvent::decode(v.as_slice())?))
+        .collect()
+}
+
+/// `(height, index)` of a reference list entry.
+pub fn decode_reference(entry: &[u8]) -> Option<(u64, u32)> {
+    if entry.len() < 12 {
+        return None;
+    }
+    Some((
+        u64::from_le_bytes(entry[0..8].try_into().unwrap()),
+        u32::from_le_bytes(entry[8..12].try_into().unwrap()),
+    ))
+}
+
+/// Resolves a reference list entry to the event it points at.
+pub fn resolve_reference(entry: &[u8]) -> Result<Option<AlkaneEvent>> {
+    let (height, index) = match decode_reference(entry) {
+        Some(v) => v,
+        None => return Ok(None),
+    };
+    let bytes = events_by_height_pointer(&IndexPointer::default(), height)
+        .select_index(index)
+        .get();
+    if bytes.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(AlkaneEvent::decode(bytes.as_slice())?))
+}
+
+/// First index of the reference list at `ptr` whose height is at least
+/// `height`.
+pub fn lower_bound<T: KeyValuePointer>(ptr: &T, height: u64) -> u32 {
+    let (mut lo, mut hi) = (0u32, ptr.length());
+    while lo < hi {
+        let mid = lo + (hi - lo) / 2;
+        match decode_reference(ptr.select_index(mid).get().as_ref()) {
+            Some((h, _)) if h < height => lo = mid + 1,
+            _ => hi = mid,
+        }
+    }
+    lo
+}
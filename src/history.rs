@@ -0,0 +1,280 @@
+use crate::message::AlkaneMessageContext;
+use alkanes_support::id::AlkaneId;
+use anyhow::Result;
+use bitcoin::{Block, OutPoint};
+#[allow(unused_imports)]
+use metashrew_core::{
+    println,
+    stdio::{stdout, Write},
+};
+use metashrew_native::index_pointer::{AtomicPointer, IndexPointer};
+use metashrew_support::index_pointer::KeyValuePointer;
+use metashrew_support::utils::consensus_encode;
+use prost::Message;
+use protorune::balance_sheet::load_sheet;
+use protorune::message::MessageContext;
+use protorune::tables::{RuneTable, RUNES};
+use protorune_support::balance_sheet::BalanceSheetOperations;
+use protorune_support::proto::protorune::BalanceSheet as BalanceSheetProto;
+use std::cell::Cell;
+use std::sync::Arc;
+
+// Height-versioned shadows of the alkane storage, balance and outpoint
+// tables, so `getstorageat` / `getinventory` / `protorunesbyoutpoint` can
+// answer "as of height N" instead of only "as of the tip".
+//
+// Storage and balance history is an append-only list per slot whose entries
+// are `height (u64 LE) ++ value`. Several writes to the same slot within one
+// block collapse into a single entry, so the list is strictly increasing in
+// height and lookups are a binary search. History writes go through the same
+// AtomicPointer as the write they shadow, so a reverted message leaves no
+// history behind.
+//
+// Recording is only active while `index_block` is running — the height is
+// published through a thread-local for the duration of the block, the same
+// way protorune publishes `CURRENT_PROTOSTONE_INDEX`. Views and simulations
+// never set it, so sandbox writes don't pollute the history.
+thread_local! {
+    static HISTORY_HEIGHT: Cell<Option<u64>> = const { Cell::new(None) };
+}
+
+/// Publishes `height` as the block currently being indexed. History is
+/// recorded until the returned guard is dropped.
+pub fn begin_block_history(height: u64) -> BlockHistoryGuard {
+    HISTORY_HEIGHT.with(|c| c.set(Some(height)));
+    BlockHistoryGuard
+}
+
+/// Height of the block currently being indexed, or `None` outside of
+/// `index_block`.
+pub fn history_height() -> Option<u64> {
+    HISTORY_HEIGHT.with(|c| c.get())
+}
+
+/// Clears the published height on drop, including on the early-return
+/// error paths of `index_block`.
+pub struct BlockHistoryGuard;
+
+impl Drop for BlockHistoryGuard {
+    fn drop(&mut self) {
+        HISTORY_HEIGHT.with(|c| c.set(None));
+    }
+}
+
+fn encode_entry(height: u64, value: &[u8]) -> Arc<Vec<u8>> {
+    let mut entry = Vec::with_capacity(8 + value.len());
+    entry.extend_from_slice(&height.to_le_bytes());
+    entry.extend_from_slice(value);
+    Arc::new(entry)
+}
+
+fn entry_height(entry: &[u8]) -> Option<u64> {
+    if entry.len() < 8 {
+        return None;
+    }
+    Some(u64::from_le_bytes(entry[0..8].try_into().unwrap()))
+}
+
+/// Appends `(height, value)` to the history list at `ptr`, overwriting the
+/// last entry instead when it was written at the same height.
+pub fn record<T: KeyValuePointer>(ptr: &mut T, height: u64, value: &[u8]) {
+    let entry = encode_entry(height, value);
+    let length = ptr.length();
+    if length > 0 {
+        let mut last = ptr.select_index(length - 1);
+        if entry_height(last.get().as_ref()) == Some(height) {
+            last.set(entry);
+            return;
+        }
+    }
+    ptr.append(entry);
+}
+
+/// Last `(height, value)` entry of the history list at `ptr` written at or
+/// below `height`.
+///
+/// The outer `None` means the list has never been written, i.e. the slot
+/// predates history tracking and the caller should fall back to the live
+/// table. `Some(None)` means the slot existed in the history but had not
+/// been written yet at `height`.
+pub fn entry_at<T: KeyValuePointer>(ptr: &T, height: u64) -> Option<Option<(u64, Vec<u8>)>> {
+    let length = ptr.length();
+    if length == 0 {
+        return None;
+    }
+    // first index whose entry is strictly above `height`
+    let (mut lo, mut hi) = (0u32, length);
+    while lo < hi {
+        let mid = lo + (hi - lo) / 2;
+        match entry_height(ptr.select_index(mid).get().as_ref()) {
+            Some(h) if h <= height => lo = mid + 1,
+            _ => hi = mid,
+        }
+    }
+    if lo == 0 {
+        return Some(None);
+    }
+    let entry = ptr.select_index(lo - 1).get();
+    Some(entry_height(entry.as_ref()).map(|h| (h, entry[8..].to_vec())))
+}
+
+/// Value of the history list at `ptr` as of `height`, with the same
+/// fallback semantics as `entry_at`. A slot not yet written at `height`
+/// reads as empty, like a missing key in the live table.
+pub fn value_at<T: KeyValuePointer>(ptr: &T, height: u64) -> Option<Vec<u8>> {
+    entry_at(ptr, height).map(|entry| entry.map(|(_, value)| value).unwrap_or_default())
+}
+
+/// Storage keys are arbitrary bytes, so they are length-prefixed before being
+/// used as a path segment. Otherwise the list entries of key `k` (stored at
+/// `k/<index>`) could alias the history of another key that happens to start
+/// with `k/`.
+pub fn history_key(key: &[u8]) -> Vec<u8> {
+    let mut result = (key.len() as u32).to_le_bytes().to_vec();
+    result.extend_from_slice(key);
+    result
+}
+
+/// History list for storage slot `key` of the alkane rooted at `pointer`
+/// (`/alkanes/<id>`).
+pub fn storage_history_pointer<T: KeyValuePointer>(pointer: &T, key: &[u8]) -> T {
+    pointer
+        .keyword("/storagehistory/")
+        .select(&history_key(key))
+}
+
+/// Every storage key the alkane rooted at `pointer` has written, in order of
+/// first write, each listed once.
+pub fn storage_keys_pointer<T: KeyValuePointer>(pointer: &T) -> T {
+    pointer.keyword("/storagekeys/")
+}
+
+/// Mirrors a storage write of the alkane rooted at `pointer` into its storage
+/// history, registering `key` in the key list on its first write.
+pub fn record_storage<T: KeyValuePointer>(pointer: &T, key: &[u8], value: &[u8], height: u64) {
+    let mut history = storage_history_pointer(pointer, key);
+    if history.length() == 0 {
+        storage_keys_pointer(pointer).append(Arc::new(key.to_vec()));
+    }
+    record(&mut history, height, value);
+}
+
+pub fn balance_history_pointer<T: KeyValuePointer>(root: &T, who: &AlkaneId, what: &AlkaneId) -> T {
+    root.keyword("/alkanes/")
+        .select(&what.clone().into())
+        .keyword("/balancehistory/")
+        .select(&who.clone().into())
+}
+
+/// Every alkane `who` has ever held a balance of, each listed once. Unlike
+/// `/inventory/` this list is written on the first credit, not the second,
+/// and never holds duplicates.
+pub fn inventory_history_pointer<T: KeyValuePointer>(root: &T, who: &AlkaneId) -> T {
+    root.keyword("/alkanes/")
+        .select(&who.clone().into())
+        .keyword("/inventoryhistory/")
+}
+
+pub fn outpoint_history_pointer(outpoint: &OutPoint) -> Result<IndexPointer> {
+    Ok(IndexPointer::from_keyword("/outpointhistory/").select(&consensus_encode(outpoint)?))
+}
+
+/// Mirrors a balance write into the balance history. No-op outside of
+/// `index_block`.
+pub fn record_balance(atomic: &mut AtomicPointer, who: &AlkaneId, what: &AlkaneId, value: u128) {
+    let height = match history_height() {
+        Some(h) => h,
+        None => return,
+    };
+    let root = atomic.derive(&IndexPointer::default());
+    let mut ptr = balance_history_pointer(&root, who, what);
+    if ptr.length() == 0 {
+        inventory_history_pointer(&root, who).append(Arc::new(what.clone().into()));
+    }
+    record(&mut ptr, height, &value.to_le_bytes());
+}
+
+/// Balance of `what` held by `who` as of `height`, `None` if the pair has no
+/// history.
+pub fn balance_at(who: &AlkaneId, what: &AlkaneId, height: u64) -> Option<u128> {
+    value_at(
+        &balance_history_pointer(&IndexPointer::default(), who, what),
+        height,
+    )
+    .map(|v| {
+        if v.len() < 16 {
+            0
+        } else {
+            u128::from_le_bytes(v[0..16].try_into().unwrap())
+        }
+    })
+}
+
+fn alkanes_outpoint_table() -> RuneTable {
+    RuneTable::for_protocol(AlkaneMessageContext::protocol_tag())
+}
+
+/// Balance sheets of every alkane-carrying outpoint spent by `block`, taken
+/// before the block is indexed. Paired with `record_spent_outpoints` after
+/// the block to preserve the pre-spend sheet, which `clear_balances` zeroes.
+pub fn snapshot_spent_outpoints(block: &Block) -> Result<Vec<(OutPoint, BalanceSheetProto)>> {
+    let table = alkanes_outpoint_table();
+    let mut result = vec![];
+    for tx in &block.txdata {
+        if tx.is_coinbase() {
+            continue;
+        }
+        for input in &tx.input {
+            let sheet = load_sheet(
+                &table
+                    .OUTPOINT_TO_RUNES
+                    .select(&consensus_encode(&input.previous_output)?),
+            );
+            if sheet.balances().values().any(|v| *v != 0) {
+                result.push((input.previous_output, sheet.into()));
+            }
+        }
+    }
+    Ok(result)
+}
+
+/// Records `(height, pre-spend sheet)` for each snapshotted outpoint whose
+/// sheet was cleared while indexing the block at `height`.
+pub fn record_spent_outpoints(
+    snapshot: Vec<(OutPoint, BalanceSheetProto)>,
+    height: u64,
+) -> Result<()> {
+    let table = alkanes_outpoint_table();
+    for (outpoint, before) in snapshot {
+        let after: BalanceSheetProto = load_sheet(
+            &table
+                .OUTPOINT_TO_RUNES
+                .select(&consensus_encode(&outpoint)?),
+        )
+        .into();
+        if after == before {
+            continue;
+        }
+        let mut entry = height.to_le_bytes().to_vec();
+        entry.extend(before.encode_to_vec());
+        outpoint_history_pointer(&outpoint)?.set(Arc::new(entry));
+    }
+    Ok(())
+}
+
+/// Alkane balance sheet held by `outpoint` as of `height`, or `None` when the
+/// live sheet is already the answer.
+pub fn outpoint_sheet_at(outpoint: &OutPoint, height: u64) -> Result<Option<BalanceSheetProto>> {
+    let outpoint_bytes = consensus_encode(outpoint)?;
+    let created = RUNES.OUTPOINT_TO_HEIGHT.select(&outpoint_bytes);
+    if created.get().is_empty() || height < created.get_value::<u64>() {
+        return Ok(Some(BalanceSheetProto::default()));
+    }
+    let spent = outpoint_history_pointer(outpoint)?.get();
+    match entry_height(spent.as_ref()) {
+        Some(spent_height) if height < spent_height => {
+            Ok(Some(BalanceSheetProto::decode(&spent[8..])?))
+        }
+        _ => Ok(None),
+    }
+}
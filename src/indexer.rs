@@ -1,3 +1,5 @@
+use crate::block_stats;
+use crate::history;
 use crate::message::AlkaneMessageContext;
 use crate::network::{genesis, is_active, is_genesis, setup_diesel, setup_frbtc, setup_frsigil};
 use crate::unwrap;
@@ -85,6 +87,8 @@ use std::sync::Arc;
 pub fn index_block(block: &Block, height: u32) -> Result<()> {
     configure_network();
     clear_diesel_mints_cache();
+    let _history = history::begin_block_history(height.into());
+    let _stats = block_stats::begin_block_stats();
     let really_is_genesis = is_genesis(height.into());
     if really_is_genesis {
         genesis().unwrap();
@@ -95,12 +99,15 @@ pub fn index_block(block: &Block, height: u32) -> Result<()> {
         setup_frsigil(block)?;
         FuelTank::initialize(&block, height);
     }
+    let spent_outpoints = history::snapshot_spent_outpoints(block)?;
     // Get the set of updated addresses from the indexing process
     let _updated_addresses =
         Protorune::index_block::<AlkaneMessageContext>(block.clone(), height.into())?;
+    history::record_spent_outpoints(spent_outpoints, height.into())?;
 
     if is_active(height.into()) {
         unwrap::update_last_block(height as u128)?;
+        block_stats::save_block_stats(block, height.into())?;
     }
 
     #[cfg(feature = "cache")]
@@ -7,10 +7,12 @@ use alkanes_support::proto;
 use bitcoin::{Block, OutPoint};
 #[allow(unused_imports)]
 use metashrew_core::{
-    flush, input, println,
+    println,
     stdio::{stdout, Write},
 };
 #[allow(unused_imports)]
+use metashrew_native::{flush, input};
+#[allow(unused_imports)]
 use metashrew_support::block::AuxpowBlock;
 use metashrew_support::compat::export_bytes;
 #[allow(unused_imports)]
@@ -20,11 +22,16 @@ use prost::Message;
 use std::io::Cursor;
 use view::parcels_from_protobuf;
 pub mod block;
+pub mod block_stats;
 pub mod etl;
+pub mod events;
 #[cfg(any(test, feature = "test-utils"))]
 pub mod fuel_probe;
+pub mod history;
 pub mod indexer;
 pub mod message;
+#[cfg(feature = "native")]
+pub mod native;
 pub mod network;
 // precompile_diesel is always compiled into production builds (the
 // `tests::diesel_shadow` and `tests::diesel_sidebyside` paths need
@@ -48,11 +55,13 @@ pub mod network;
 // until that gap is reproduced in `tests::diesel_shadow` and fixed.
 pub mod precompile_diesel;
 pub mod precompiled;
+pub mod state_diff;
 pub mod tables;
 #[cfg(any(test, feature = "test-utils"))]
 pub mod tests;
 pub mod trace;
 pub mod unwrap;
+pub mod upgrades;
 pub mod utils;
 pub mod view;
 pub mod vm;
@@ -133,6 +142,31 @@ pub fn simulate() -> i32 {
     export_bytes(result.encode_to_vec())
 }
 
+#[cfg(not(test))]
+#[no_mangle]
+pub fn simulateprofile() -> i32 {
+    configure_network();
+    let data = input();
+    let _height = u32::from_le_bytes((&data[0..4]).try_into().unwrap());
+    let reader = &data[4..];
+    let mut result: proto::alkanes::SimulateResponse = proto::alkanes::SimulateResponse::default();
+    let (simulated, profile) = view::simulate_profiled(
+        &parcel_from_protobuf(proto::alkanes::MessageContextParcel::decode(reader).unwrap()),
+        u64::MAX,
+    );
+    match simulated {
+        Ok((response, gas_used)) => {
+            result.execution = Some(response.into());
+            result.gas_used = gas_used;
+        }
+        Err(e) => {
+            result.error = e.to_string();
+        }
+    }
+    result.profile = profile.map(Into::into);
+    export_bytes(result.encode_to_vec())
+}
+
 #[cfg(not(test))]
 #[no_mangle]
 pub fn sequence() -> i32 {
@@ -203,6 +237,44 @@ pub fn simulateblock() -> i32 {
     }
 }
 
+#[cfg(not(test))]
+#[no_mangle]
+pub fn simulatechain() -> i32 {
+    configure_network();
+    let data = input();
+    let _height = u32::from_le_bytes((&data[0..4]).try_into().unwrap());
+    let reader = &data[4..];
+    match view::simulate_chain_proto(reader) {
+        Ok(bytes) => export_bytes(bytes),
+        Err(e) => {
+            let resp = proto::alkanes::SimulateChainResponse {
+                error: e.to_string(),
+                ..Default::default()
+            };
+            export_bytes(resp.encode_to_vec())
+        }
+    }
+}
+
+#[cfg(not(test))]
+#[no_mangle]
+pub fn estimatefuel() -> i32 {
+    configure_network();
+    let data = input();
+    let _height = u32::from_le_bytes((&data[0..4]).try_into().unwrap());
+    let reader = &data[4..];
+    match view::estimate_fuel_proto(reader) {
+        Ok(bytes) => export_bytes(bytes),
+        Err(e) => {
+            let resp = proto::alkanes::EstimateFuelResponse {
+                error: e.to_string(),
+                ..Default::default()
+            };
+            export_bytes(resp.encode_to_vec())
+        }
+    }
+}
+
 #[cfg(not(test))]
 #[no_mangle]
 pub fn meta() -> i32 {
@@ -358,6 +430,20 @@ pub fn trace() -> i32 {
     export_bytes(view::trace(&outpoint).unwrap())
 }
 
+#[cfg(not(test))]
+#[no_mangle]
+pub fn tracediff() -> i32 {
+    configure_network();
+    let mut data: Cursor<Vec<u8>> = Cursor::new(input());
+    let _height = consume_sized_int::<u32>(&mut data).unwrap();
+    let outpoint: OutPoint =
+        protorune_support::proto::protorune::Outpoint::decode(&*consume_to_end(&mut data).unwrap())
+            .unwrap()
+            .try_into()
+            .unwrap();
+    export_bytes(view::tracediff(&outpoint).unwrap())
+}
+
 #[cfg(not(test))]
 #[no_mangle]
 pub fn getbytecode() -> i32 {
@@ -372,9 +458,9 @@ pub fn getbytecode() -> i32 {
 pub fn protorunesbyoutpoint() -> i32 {
     configure_network();
     let mut data: Cursor<Vec<u8>> = Cursor::new(input());
-    let _height = consume_sized_int::<u32>(&mut data).unwrap();
+    let height = consume_sized_int::<u32>(&mut data).unwrap();
     let result: protorune_support::proto::protorune::OutpointResponse =
-        view::protorunes_by_outpoint(&consume_to_end(&mut data).unwrap())
+        view::protorunes_by_outpoint_at(&consume_to_end(&mut data).unwrap(), height.into())
             .unwrap_or_else(|_| protorune_support::proto::protorune::OutpointResponse::default());
 
     export_bytes(result.encode_to_vec())
@@ -396,13 +482,15 @@ pub fn runesbyheight() -> i32 {
 #[cfg(not(test))]
 #[no_mangle]
 pub fn getinventory() -> i32 {
+    configure_network();
     let data = input();
-    let _height = u32::from_le_bytes((&data[0..4]).try_into().unwrap());
+    let height = u32::from_le_bytes((&data[0..4]).try_into().unwrap());
     let reader = &data[4..];
-    let result = view::getinventory(
+    let result = view::getinventory_at(
         &proto::alkanes::AlkaneInventoryRequest::decode(reader)
             .unwrap()
             .into(),
+        height.into(),
     )
     .unwrap();
     export_bytes(result.encode_to_vec())
@@ -413,17 +501,102 @@ pub fn getinventory() -> i32 {
 pub fn getstorageat() -> i32 {
     configure_network();
     let data = input();
-    let _height = u32::from_le_bytes((&data[0..4]).try_into().unwrap());
+    let height = u32::from_le_bytes((&data[0..4]).try_into().unwrap());
     let reader = &data[4..];
-    let result = view::getstorageat(
+    let result = view::getstorageat_at(
         &proto::alkanes::AlkaneStorageRequest::decode(reader)
             .unwrap()
             .into(),
+        height.into(),
     )
     .unwrap();
     export_bytes(result.encode_to_vec())
 }
 
+#[cfg(not(test))]
+#[no_mangle]
+pub fn getstoragekeys() -> i32 {
+    configure_network();
+    let data = input();
+    let height = u32::from_le_bytes((&data[0..4]).try_into().unwrap());
+    let reader = &data[4..];
+    let result = view::getstoragekeys(
+        &proto::alkanes::AlkaneStorageKeysRequest::decode(reader).unwrap(),
+        height.into(),
+    )
+    .unwrap_or_else(|_| proto::alkanes::AlkaneStorageKeysResponse::default());
+    export_bytes(result.encode_to_vec())
+}
+
+#[cfg(not(test))]
+#[no_mangle]
+pub fn holdersofalkane() -> i32 {
+    configure_network();
+    let data = input();
+    let height = u32::from_le_bytes((&data[0..4]).try_into().unwrap());
+    let reader = &data[4..];
+    let result = view::holders_of_alkane(
+        &proto::alkanes::HoldersOfAlkaneRequest::decode(reader).unwrap(),
+        height.into(),
+    )
+    .unwrap_or_else(|_| proto::alkanes::HoldersOfAlkaneResponse::default());
+    export_bytes(result.encode_to_vec())
+}
+
+#[cfg(not(test))]
+#[no_mangle]
+pub fn resolveimplementation() -> i32 {
+    configure_network();
+    let data = input();
+    let height = u32::from_le_bytes((&data[0..4]).try_into().unwrap());
+    let reader = &data[4..];
+    let result = view::resolve_implementation(
+        &proto::alkanes::ResolveImplementationRequest::decode(reader).unwrap(),
+        height.into(),
+    )
+    .unwrap_or_else(|_| proto::alkanes::ResolveImplementationResponse::default());
+    export_bytes(result.encode_to_vec())
+}
+
+#[cfg(not(test))]
+#[no_mangle]
+pub fn eventsbyheight() -> i32 {
+    configure_network();
+    let data = input();
+    let _height = u32::from_le_bytes((&data[0..4]).try_into().unwrap());
+    let reader = &data[4..];
+    let result =
+        view::events_by_height(&proto::alkanes::EventsByHeightRequest::decode(reader).unwrap())
+            .unwrap_or_else(|_| proto::alkanes::AlkaneEventsResponse::default());
+    export_bytes(result.encode_to_vec())
+}
+
+#[cfg(not(test))]
+#[no_mangle]
+pub fn blockstats() -> i32 {
+    configure_network();
+    let data = input();
+    let _height = u32::from_le_bytes((&data[0..4]).try_into().unwrap());
+    let reader = &data[4..];
+    let result =
+        view::block_stats(&proto::alkanes::BlockStatsRequest::decode(reader).unwrap())
+            .unwrap_or_else(|_| proto::alkanes::BlockStatsResponse::default());
+    export_bytes(result.encode_to_vec())
+}
+
+#[cfg(not(test))]
+#[no_mangle]
+pub fn eventsbyalkane() -> i32 {
+    configure_network();
+    let data = input();
+    let _height = u32::from_le_bytes((&data[0..4]).try_into().unwrap());
+    let reader = &data[4..];
+    let result =
+        view::events_by_alkane(&proto::alkanes::EventsByAlkaneRequest::decode(reader).unwrap())
+            .unwrap_or_else(|_| proto::alkanes::AlkaneEventsResponse::default());
+    export_bytes(result.encode_to_vec())
+}
+
 #[cfg(all(target_arch = "wasm32", not(test)))]
 #[no_mangle]
 pub fn _start() {
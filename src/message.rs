@@ -1,5 +1,9 @@
-use crate::network::{genesis::GENESIS_BLOCK, is_active};
+use crate::block_stats;
+use crate::events::index_events;
+use crate::network::{genesis::GENESIS_BLOCK, is_active, is_events_active};
+use crate::state_diff;
 use crate::trace::save_trace;
+use crate::upgrades;
 use crate::utils::{
     balance_pointer, credit_balances, debit_balances, pipe_storagemap_to, record_touched_storage,
 };
@@ -9,6 +13,7 @@ use crate::vm::{
     utils::{prepare_context, run_after_special, run_special_cellpacks},
 };
 use alkanes_support::id::AlkaneId;
+use alkanes_support::parcel::AlkaneTransfer;
 use alkanes_support::{
     cellpack::Cellpack,
     response::ExtendedCallResponse,
@@ -16,12 +21,12 @@ use alkanes_support::{
 };
 use anyhow::{anyhow, Result};
 use bitcoin::OutPoint;
-use metashrew_core::index_pointer::{AtomicPointer, IndexPointer};
 #[allow(unused_imports)]
 use metashrew_core::{
     println,
     stdio::{stdout, Write},
 };
+use metashrew_native::index_pointer::{AtomicPointer, IndexPointer};
 use metashrew_support::index_pointer::KeyValuePointer;
 use protorune::balance_sheet::MintableDebit;
 use protorune::message::{MessageContext, MessageContextParcel};
@@ -70,6 +75,7 @@ pub fn handle_message(
         parcel, &cellpack,
     )));
     let mut atomic = parcel.atomic.derive(&IndexPointer::default());
+    let _diff_guard = state_diff::begin_message_diff();
     let (caller, myself, binary) = run_special_cellpacks(context.clone(), &cellpack)?;
 
     #[cfg(feature = "debug-log")]
@@ -135,14 +141,35 @@ pub fn handle_message(
                 inner: response.into(),
                 fuel_used: gas_used,
             }));
-            save_trace(
-                &OutPoint {
-                    txid: parcel.transaction.compute_txid(),
-                    vout: parcel.vout,
-                },
-                parcel.height,
-                trace.clone(),
-            )?;
+            let outpoint = OutPoint {
+                txid: parcel.transaction.compute_txid(),
+                vout: parcel.vout,
+            };
+            save_trace(&outpoint, parcel.height, trace.clone())?;
+            let incoming: Vec<AlkaneTransfer> = parcel
+                .runes
+                .iter()
+                .map(|rune| AlkaneTransfer {
+                    id: rune.id.into(),
+                    value: rune.value,
+                })
+                .collect();
+            let diff = state_diff::message_diff(
+                &atomic,
+                &trace.0.lock().unwrap(),
+                parcel.vout,
+                &incoming,
+                parcel.pointer,
+                &response_alkanes,
+            );
+            upgrades::record_upgrades(&mut atomic, &diff, parcel.height);
+            let stats =
+                block_stats::MessageStats::committed(&trace.0.lock().unwrap(), gas_used, &diff);
+            state_diff::save_state_diff(&mut atomic, &outpoint, diff)?;
+            if is_events_active(parcel.height) {
+                index_events(&mut atomic, &outpoint, parcel.height, &trace)?;
+            }
+            block_stats::record_message(stats);
 
             Ok((response_alkanes.into(), combined))
         })
@@ -180,6 +207,11 @@ pub fn handle_message(
             }
 
             FuelTank::drain_fuel();
+            block_stats::record_message(block_stats::MessageStats::reverted(
+                &trace.0.lock().unwrap(),
+                fuel,
+                &e,
+            ));
             let mut response = ExtendedCallResponse::default();
 
             response.data = vec![0x08, 0xc3, 0x79, 0xa0];
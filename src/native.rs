@@ -0,0 +1,75 @@
+use crate::etl;
+use crate::indexer::{configure_network, index_block};
+use crate::network::clear_view_mode;
+use anyhow::{anyhow, Result};
+use bitcoin::Block;
+use metashrew_native::index_pointer::IndexPointer;
+use metashrew_native::{runtime, KeyValueStore};
+use metashrew_support::index_pointer::KeyValuePointer;
+use std::sync::Arc;
+
+// Embeddable indexer: the alkanes indexer linked into a host-target Rust
+// service (`--features native`, plus `rocksdb` for the RocksDB store).
+//
+// Each `index_block` installs the store for the calling thread, runs the same
+// `indexer::index_block` the wasm `_start` runs, and writes the block's
+// writes to the store in one batch. A block that fails to index writes
+// nothing. Views run the typed functions in `crate::view` against the store;
+// anything they write (simulations) is discarded.
+//
+// The indexer keeps process-wide state (network parameters, the fuel tank,
+// view mode), so a process should drive a single `NativeIndexer`, one call
+// at a time.
+
+/// Height of the last block written by `NativeIndexer::index_block`.
+pub const TIP_KEY: &str = "/native/tip";
+
+pub struct NativeIndexer {
+    store: Arc<dyn KeyValueStore>,
+}
+
+impl NativeIndexer {
+    pub fn new(store: Arc<dyn KeyValueStore>) -> Self {
+        NativeIndexer { store }
+    }
+
+    pub fn store(&self) -> &Arc<dyn KeyValueStore> {
+        &self.store
+    }
+
+    /// Height of the last indexed block, `None` before the first one.
+    pub fn tip(&self) -> Result<Option<u32>> {
+        Ok(match self.store.get(TIP_KEY.as_bytes())? {
+            Some(v) if v.len() == 4 => Some(u32::from_le_bytes(v.try_into().unwrap())),
+            _ => None,
+        })
+    }
+
+    /// Indexes `block` at `height`, which must follow the current tip.
+    pub fn index_block(&self, block: &Block, height: u32) -> Result<()> {
+        if let Some(tip) = self.tip()? {
+            if height != tip + 1 {
+                return Err(anyhow!(
+                    "cannot index block {}: indexer tip is {}",
+                    height,
+                    tip
+                ));
+            }
+        }
+        let _store = runtime::install(self.store.clone());
+        index_block(block, height)?;
+        etl::index_extensions(height, block);
+        IndexPointer::from_keyword(TIP_KEY).set_value::<u32>(height);
+        runtime::flush()
+    }
+
+    /// Runs `f` against the indexed state, e.g.
+    /// `indexer.view(|| view::getstorageat(&req))`.
+    pub fn view<T>(&self, f: impl FnOnce() -> Result<T>) -> Result<T> {
+        let _store = runtime::install(self.store.clone());
+        configure_network();
+        let result = f();
+        clear_view_mode();
+        result
+    }
+}
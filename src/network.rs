@@ -22,7 +22,7 @@ use alkanes_support::id::AlkaneId;
 use alkanes_support::parcel::AlkaneTransferParcel;
 use anyhow::Result;
 use bitcoin::{Block, OutPoint, Transaction};
-use metashrew_core::index_pointer::{AtomicPointer, IndexPointer};
+use metashrew_native::index_pointer::{AtomicPointer, IndexPointer};
 use metashrew_support::index_pointer::KeyValuePointer;
 use protorune::balance_sheet::PersistentRecord;
 use protorune::message::{MessageContext, MessageContextParcel};
@@ -224,6 +224,9 @@ pub mod genesis {
     /// from genesis here.
     pub const FRBTC_V130_FORK_HEIGHT: u32 = 0;
     pub const FRBTC_V131_FORK_HEIGHT: u32 = 0;
+    /// Contract event logs: links the `__emit_event` host function and indexes
+    /// emitted events. Genesis-coincident on regtest.
+    pub const EVENTS_FORK_HEIGHT: u32 = 0;
 }
 
 #[cfg(feature = "mainnet")]
@@ -242,6 +245,10 @@ pub mod genesis {
     /// v2.2.1-alpha.3) before this height or they diverge at 32:0.
     pub const FRBTC_V130_FORK_HEIGHT: u32 = 957_000;
     pub const FRBTC_V131_FORK_HEIGHT: u32 = 960_000;
+    /// Contract event logs: links the `__emit_event` host function. Not yet
+    /// scheduled — until a height is set here a contract importing it fails
+    /// to instantiate, exactly as it does today.
+    pub const EVENTS_FORK_HEIGHT: u32 = u32::MAX;
 }
 
 #[cfg(feature = "fractal")]
@@ -258,6 +265,8 @@ pub mod genesis {
     /// from genesis here.
     pub const FRBTC_V130_FORK_HEIGHT: u32 = 0;
     pub const FRBTC_V131_FORK_HEIGHT: u32 = 0;
+    /// Contract event logs; not yet scheduled on this chain.
+    pub const EVENTS_FORK_HEIGHT: u32 = u32::MAX;
 }
 
 #[cfg(feature = "dogecoin")]
@@ -274,6 +283,8 @@ pub mod genesis {
     /// from genesis here.
     pub const FRBTC_V130_FORK_HEIGHT: u32 = 0;
     pub const FRBTC_V131_FORK_HEIGHT: u32 = 0;
+    /// Contract event logs; not yet scheduled on this chain.
+    pub const EVENTS_FORK_HEIGHT: u32 = u32::MAX;
 }
 
 #[cfg(feature = "luckycoin")]
@@ -290,6 +301,8 @@ pub mod genesis {
     /// from genesis here.
     pub const FRBTC_V130_FORK_HEIGHT: u32 = 0;
     pub const FRBTC_V131_FORK_HEIGHT: u32 = 0;
+    /// Contract event logs; not yet scheduled on this chain.
+    pub const EVENTS_FORK_HEIGHT: u32 = u32::MAX;
 }
 
 #[cfg(feature = "bellscoin")]
@@ -306,12 +319,18 @@ pub mod genesis {
     /// from genesis here.
     pub const FRBTC_V130_FORK_HEIGHT: u32 = 0;
     pub const FRBTC_V131_FORK_HEIGHT: u32 = 0;
+    /// Contract event logs; not yet scheduled on this chain.
+    pub const EVENTS_FORK_HEIGHT: u32 = u32::MAX;
 }
 
 pub fn is_active(height: u64) -> bool {
     height >= genesis::GENESIS_BLOCK
 }
 
+pub fn is_events_active(height: u64) -> bool {
+    height >= genesis::EVENTS_FORK_HEIGHT as u64
+}
+
 static mut _VIEW: bool = false;
 
 pub fn set_view_mode() {
@@ -324,6 +343,14 @@ pub fn get_view_mode() -> bool {
     unsafe { _VIEW }
 }
 
+/// Leaves view mode. A wasm instance never needs this since each view runs
+/// in a fresh one; the native indexer does between a view and the next block.
+pub fn clear_view_mode() {
+    unsafe {
+        _VIEW = false;
+    }
+}
+
 pub fn is_genesis(height: u64) -> bool {
     let mut init_ptr = IndexPointer::from_keyword("/seen-genesis");
     let has_not_seen_genesis = init_ptr.get().len() == 0;
@@ -34,7 +34,7 @@ use alkanes_support::{
 use std::sync::Mutex as StdMutex;
 use anyhow::{anyhow, Result};
 use bitcoin::hashes::Hash;
-use metashrew_core::index_pointer::IndexPointer;
+use metashrew_native::index_pointer::IndexPointer;
 use metashrew_support::index_pointer::KeyValuePointer;
 use std::sync::{Arc, Mutex};
 
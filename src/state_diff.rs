@@ -0,0 +1,271 @@
+use crate::tables::TRACE_DIFFS;
+use alkanes_support::id::AlkaneId;
+use alkanes_support::parcel::{AlkaneTransfer, AlkaneTransferParcel};
+use alkanes_support::proto;
+use alkanes_support::trace::{
+    BalanceChange, InventoryDiff, OutputDiff, StateDiff, StorageDiff, StorageWrite, TraceEvent,
+};
+use anyhow::Result;
+use bitcoin::OutPoint;
+#[allow(unused_imports)]
+use metashrew_core::{
+    println,
+    stdio::{stdout, Write},
+};
+use metashrew_native::index_pointer::{AtomicPointer, IndexPointer};
+use metashrew_support::index_pointer::KeyValuePointer;
+use metashrew_support::utils::consensus_encode;
+use prost::Message;
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+// Per-outpoint state diffs, served by the `tracediff` view.
+//
+// New storage values and created alkanes come from the message's trace,
+// replayed frame by frame like `events::committed_events`. What the trace
+// does not carry is the value each slot and contract balance had before the
+// message, so while a message runs, the first write to every storage slot
+// (`pipe_storagemap_to`) and the first touch of every contract balance
+// (`balance_pointer`) records the value it finds. Recording is only active
+// between `begin_message_diff` and the guard's drop, so writes outside a
+// message (genesis setup, views) pay a single thread-local peek.
+//
+// Diffs are written through the message's AtomicPointer next to the trace,
+// so a message that is rolled back after the fact leaves no diff behind.
+
+/// Final value of every storage slot a message wrote, by alkane.
+pub type CommittedStorage = BTreeMap<AlkaneId, BTreeMap<Vec<u8>, Vec<u8>>>;
+
+#[derive(Default)]
+struct Priors {
+    // Full storage slot key → value before the message.
+    storage: BTreeMap<Vec<u8>, Arc<Vec<u8>>>,
+    // (holder, alkane) → balance before the message.
+    balances: BTreeMap<(AlkaneId, AlkaneId), u128>,
+}
+
+thread_local! {
+    static PRIORS: RefCell<Option<Priors>> = const { RefCell::new(None) };
+}
+
+/// Starts recording prior values for the message about to run. Recording
+/// stops, and anything recorded is dropped, when the guard is dropped.
+pub fn begin_message_diff() -> MessageDiffGuard {
+    PRIORS.with(|p| *p.borrow_mut() = Some(Priors::default()));
+    MessageDiffGuard
+}
+
+pub struct MessageDiffGuard;
+
+impl Drop for MessageDiffGuard {
+    fn drop(&mut self) {
+        PRIORS.with(|p| *p.borrow_mut() = None);
+    }
+}
+
+/// Records the value of the storage slot at `slot` unless this message
+/// already wrote it. Call before writing the slot.
+pub fn record_storage_prior<T: KeyValuePointer>(slot: &T) {
+    PRIORS.with(|p| {
+        if let Some(priors) = p.borrow_mut().as_mut() {
+            priors
+                .storage
+                .entry(slot.unwrap().as_ref().clone())
+                .or_insert_with(|| slot.get());
+        }
+    });
+}
+
+/// Records `current`, the stored balance of `what` held by `who`, unless
+/// this message already touched it.
+pub fn record_balance_prior(who: &AlkaneId, what: &AlkaneId, current: &[u8]) {
+    PRIORS.with(|p| {
+        if let Some(priors) = p.borrow_mut().as_mut() {
+            priors
+                .balances
+                .entry((*who, *what))
+                .or_insert_with(|| decode_balance(current));
+        }
+    });
+}
+
+fn decode_balance(v: &[u8]) -> u128 {
+    v.get(..16)
+        .map_or(0, |b| u128::from_le_bytes(b.try_into().unwrap()))
+}
+
+fn storage_slot<T: KeyValuePointer>(root: &T, alkane: &AlkaneId, key: &[u8]) -> T {
+    root.keyword("/alkanes/")
+        .select(&(*alkane).into())
+        .keyword("/storage/")
+        .select(&key.to_vec())
+}
+
+/// Storage writes and created alkanes of `events` that survive execution.
+///
+/// A frame's storage map is written to the frame's `myself` when it returns,
+/// after its children's, so later writes to a slot win. Writes and creations
+/// move to the parent frame on return and are dropped on revert; staticcall
+/// frames drop theirs even on return.
+pub fn committed_writes(events: &[TraceEvent]) -> (CommittedStorage, Vec<AlkaneId>) {
+    struct Frame {
+        is_static: bool,
+        myself: AlkaneId,
+        writes: CommittedStorage,
+        created: Vec<AlkaneId>,
+    }
+    let mut frames: Vec<Frame> = vec![];
+    let mut committed = (BTreeMap::new(), vec![]);
+    for event in events {
+        match event {
+            TraceEvent::EnterCall(ctx)
+            | TraceEvent::EnterDelegatecall(ctx)
+            | TraceEvent::EnterStaticcall(ctx) => frames.push(Frame {
+                is_static: matches!(event, TraceEvent::EnterStaticcall(_)),
+                myself: ctx.inner.myself,
+                writes: BTreeMap::new(),
+                created: vec![],
+            }),
+            TraceEvent::CreateAlkane(id) => {
+                if let Some(frame) = frames.last_mut() {
+                    frame.created.push(*id);
+                }
+            }
+            TraceEvent::ReturnContext(response) => {
+                let Some(mut frame) = frames.pop() else {
+                    continue;
+                };
+                if frame.is_static {
+                    continue;
+                }
+                let own = frame.writes.entry(frame.myself).or_default();
+                for (k, v) in response.inner.storage.0.iter() {
+                    own.insert(k.clone(), v.clone());
+                }
+                let (writes, created) = match frames.last_mut() {
+                    Some(parent) => (&mut parent.writes, &mut parent.created),
+                    None => (&mut committed.0, &mut committed.1),
+                };
+                for (alkane, slots) in frame.writes {
+                    writes.entry(alkane).or_default().extend(slots);
+                }
+                created.extend(frame.created);
+            }
+            TraceEvent::RevertContext(_) => {
+                frames.pop();
+            }
+            _ => {}
+        }
+    }
+    committed
+}
+
+/// The state diff of the message that produced `events`, read against
+/// `atomic` once the message has run. `incoming` is what the message took
+/// from its shadow vout `vout`; `outgoing` is what it sent to `pointer`.
+/// Consumes the prior values recorded since `begin_message_diff`.
+pub fn message_diff(
+    atomic: &AtomicPointer,
+    events: &[TraceEvent],
+    vout: u32,
+    incoming: &[AlkaneTransfer],
+    pointer: u32,
+    outgoing: &AlkaneTransferParcel,
+) -> StateDiff {
+    let priors = PRIORS.with(|p| p.borrow_mut().take()).unwrap_or_default();
+    let root = atomic.derive(&IndexPointer::default());
+    let (writes, created) = committed_writes(events);
+
+    let storage = writes
+        .into_iter()
+        .filter_map(|(alkane, slots)| {
+            let writes: Vec<StorageWrite> = slots
+                .into_iter()
+                .filter_map(|(key, new_value)| {
+                    let slot = storage_slot(&IndexPointer::default(), &alkane, &key).unwrap();
+                    let old_value = priors
+                        .storage
+                        .get(slot.as_ref())
+                        .map_or_else(Vec::new, |v| v.as_ref().clone());
+                    (old_value != new_value).then_some(StorageWrite {
+                        key,
+                        old_value,
+                        new_value,
+                    })
+                })
+                .collect();
+            (!writes.is_empty()).then_some(StorageDiff { alkane, writes })
+        })
+        .collect();
+
+    let mut inventories: BTreeMap<AlkaneId, Vec<BalanceChange>> = BTreeMap::new();
+    for ((holder, alkane), old_balance) in priors.balances {
+        let new_balance = decode_balance(
+            &root
+                .keyword("/alkanes/")
+                .select(&alkane.into())
+                .keyword("/balances/")
+                .select(&holder.into())
+                .get(),
+        );
+        if new_balance != old_balance {
+            inventories.entry(holder).or_default().push(BalanceChange {
+                alkane,
+                old_balance,
+                new_balance,
+            });
+        }
+    }
+
+    let mut outputs: BTreeMap<u32, OutputDiff> = BTreeMap::new();
+    let nonzero = |t: &&AlkaneTransfer| t.value != 0;
+    for transfer in incoming.iter().filter(nonzero) {
+        outputs
+            .entry(vout)
+            .or_insert_with(|| OutputDiff {
+                vout,
+                ..Default::default()
+            })
+            .debited
+            .push(*transfer);
+    }
+    for transfer in outgoing.0.iter().filter(nonzero) {
+        outputs
+            .entry(pointer)
+            .or_insert_with(|| OutputDiff {
+                vout: pointer,
+                ..Default::default()
+            })
+            .credited
+            .push(*transfer);
+    }
+
+    StateDiff {
+        storage,
+        inventories: inventories
+            .into_iter()
+            .map(|(holder, changes)| InventoryDiff { holder, changes })
+            .collect(),
+        outputs: outputs.into_values().collect(),
+        created,
+    }
+}
+
+/// Stores `diff` for the protostone at `outpoint`. Empty diffs are not
+/// stored; `tracediff` answers them with an empty `StateDiff` either way.
+pub fn save_state_diff(
+    atomic: &mut AtomicPointer,
+    outpoint: &OutPoint,
+    diff: StateDiff,
+) -> Result<()> {
+    if diff.is_empty() {
+        return Ok(());
+    }
+    atomic
+        .derive(&TRACE_DIFFS.select(&consensus_encode(outpoint)?))
+        .set(Arc::new(
+            <StateDiff as Into<proto::alkanes::StateDiff>>::into(diff).encode_to_vec(),
+        ));
+    Ok(())
+}
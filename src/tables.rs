@@ -1,4 +1,4 @@
-use metashrew_core::index_pointer::IndexPointer;
+use metashrew_native::index_pointer::IndexPointer;
 use metashrew_support::index_pointer::KeyValuePointer;
 use once_cell::sync::Lazy;
 
@@ -6,3 +6,10 @@ pub static TRACES: Lazy<IndexPointer> = Lazy::new(|| IndexPointer::from_keyword(
 
 pub static TRACES_BY_HEIGHT: Lazy<IndexPointer> =
     Lazy::new(|| IndexPointer::from_keyword("/trace/"));
+
+/// Per-outpoint `StateDiff`s, next to the traces; see `state_diff`.
+pub static TRACE_DIFFS: Lazy<IndexPointer> =
+    Lazy::new(|| IndexPointer::from_keyword("/tracediff/"));
+
+/// Per-block `BlockStats`, by height; see `block_stats`.
+pub static BLOCK_STATS: Lazy<IndexPointer> = Lazy::new(|| IndexPointer::from_keyword("/stats/"));
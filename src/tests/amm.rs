@@ -0,0 +1,560 @@
+use crate::index_block;
+use crate::message::AlkaneMessageContext;
+use crate::tests::helpers::{self as alkane_helpers, clear, BinaryAndCellpack};
+use crate::tests::std::{
+    alkanes_std_amm_factory_build, alkanes_std_amm_pool_build, alkanes_std_auth_token_build,
+    alkanes_std_beacon_proxy_build, alkanes_std_owned_token_build,
+    alkanes_std_upgradeable_beacon_build,
+};
+use alkanes_cli_common::alkanes::amm::FACTORY_OPCODE_GET_ALL_POOLS;
+use alkanes_cli_common::alkanes::amm_cli::{
+    init_pool_calldata, swap_calldata, swap_exact_out_calldata,
+};
+use alkanes_cli_common::alkanes::parsing::parse_protostones;
+use alkanes_cli_common::alkanes::types::AlkaneId as CliAlkaneId;
+use alkanes_support::cellpack::Cellpack;
+use alkanes_support::constants::{AMM_FACTORY_ID, AUTH_TOKEN_FACTORY_ID};
+use alkanes_support::id::AlkaneId;
+use anyhow::Result;
+use bitcoin::OutPoint;
+use metashrew_support::{
+    index_pointer::KeyValuePointer,
+    utils::{consensus_encode, consume_exact, consume_sized_int},
+};
+use protorune::test_helpers::create_block_with_coinbase_tx;
+use protorune::{balance_sheet::load_sheet, message::MessageContext, tables::RuneTable};
+use protorune_support::balance_sheet::BalanceSheetOperations;
+use std::io::Cursor;
+use wasm_bindgen_test::wasm_bindgen_test;
+
+const TOKEN_A: AlkaneId = AlkaneId { block: 2, tx: 1 };
+const TOKEN_B: AlkaneId = AlkaneId { block: 2, tx: 3 };
+const TOKEN_C: AlkaneId = AlkaneId { block: 2, tx: 5 };
+const FACTORY: AlkaneId = AlkaneId { block: 2, tx: 10 };
+// the A/B pool created in setup, and the B/C pool some tests add
+const POOL_AB: AlkaneId = AlkaneId { block: 2, tx: 11 };
+const POOL_BC: AlkaneId = AlkaneId { block: 2, tx: 12 };
+
+const ADD_LIQUIDITY: u128 = 1;
+const REMOVE_LIQUIDITY: u128 = 2;
+const SWAP: u128 = 3;
+const SIMULATE_SWAP: u128 = 4;
+const SIMULATE_SWAP_EXACT_OUT: u128 = 5;
+const GET_RESERVES: u128 = 97;
+const GET_PRICE: u128 = 98;
+const GET_NAME: u128 = 99;
+const GET_TOTAL_SUPPLY: u128 = 101;
+const POOL_DETAILS: u128 = 999;
+const FIND_EXISTING_POOL_ID: u128 = 2;
+
+/// The pool state [`POOL_DETAILS`] returns.
+struct PoolDetails {
+    token_a: AlkaneId,
+    token_b: AlkaneId,
+    reserve_a: u128,
+    reserve_b: u128,
+    total_supply: u128,
+    pool_name: String,
+}
+
+fn cli_id(id: AlkaneId) -> CliAlkaneId {
+    CliAlkaneId {
+        block: id.block as u64,
+        tx: id.tx as u64,
+    }
+}
+
+/// The cellpack of the protostone `alkanes-cli-common` builds for
+/// `calldata`, parsed the way `init_pool` and `execute_swap` parse it.
+fn protostone(calldata: String) -> Cellpack {
+    parse_protostones(&calldata)
+        .unwrap()
+        .remove(0)
+        .cellpack
+        .unwrap_or_else(|| panic!("no cellpack in {}", calldata))
+}
+
+fn create_pool(token_0: AlkaneId, token_1: AlkaneId, amount_0: u128, amount_1: u128) -> Cellpack {
+    protostone(init_pool_calldata(
+        &cli_id(FACTORY),
+        &cli_id(token_0),
+        &cli_id(token_1),
+        amount_0,
+        amount_1,
+    ))
+}
+
+fn swap(path: &[AlkaneId], amount_in: u128, min_out: u128, expires: u64) -> Cellpack {
+    let path = path.iter().map(|id| cli_id(*id)).collect::<Vec<_>>();
+    protostone(swap_calldata(
+        &cli_id(FACTORY),
+        &path,
+        amount_in,
+        min_out,
+        expires,
+    ))
+}
+
+fn swap_exact_out(path: &[AlkaneId], amount_out: u128, max_in: u128, expires: u64) -> Cellpack {
+    let path = path.iter().map(|id| cli_id(*id)).collect::<Vec<_>>();
+    protostone(swap_exact_out_calldata(
+        &cli_id(FACTORY),
+        &path,
+        amount_out,
+        max_in,
+        expires,
+    ))
+}
+
+/// Deploys three owned tokens with 1M units each, the pool implementation
+/// behind an upgradeable beacon, the beacon proxy template pools are cloned
+/// from and the factory, then creates the A/B pool with 100k A and 400k B.
+/// Returns the wallet output, which holds the tokens, their auth tokens and
+/// the LP tokens.
+fn setup() -> Result<OutPoint> {
+    clear();
+    index_block(&create_block_with_coinbase_tx(0), 0)?;
+    let owned_token = || {
+        BinaryAndCellpack::new(
+            alkanes_std_owned_token_build::get_bytes(),
+            Cellpack {
+                target: AlkaneId { block: 1, tx: 0 },
+                inputs: vec![0, 1, 1_000_000],
+            },
+        )
+    };
+    let block = alkane_helpers::init_with_cellpack_pairs(vec![
+        BinaryAndCellpack::new(
+            alkanes_std_auth_token_build::get_bytes(),
+            Cellpack {
+                target: AlkaneId {
+                    block: 3,
+                    tx: AUTH_TOKEN_FACTORY_ID,
+                },
+                inputs: vec![100],
+            },
+        ),
+        owned_token(),
+        owned_token(),
+        owned_token(),
+        // the implementation lands on 2:7
+        BinaryAndCellpack::new(
+            alkanes_std_amm_pool_build::get_bytes(),
+            Cellpack {
+                target: AlkaneId { block: 1, tx: 0 },
+                inputs: vec![50],
+            },
+        ),
+        BinaryAndCellpack::new(
+            alkanes_std_beacon_proxy_build::get_bytes(),
+            Cellpack {
+                target: AlkaneId {
+                    block: 3,
+                    tx: AMM_FACTORY_ID,
+                },
+                inputs: vec![0x8fff],
+            },
+        ),
+        // the beacon lands on 2:8 with its auth token on 2:9
+        BinaryAndCellpack::new(
+            alkanes_std_upgradeable_beacon_build::get_bytes(),
+            Cellpack {
+                target: AlkaneId { block: 1, tx: 0 },
+                inputs: vec![0x7fff, 2, 7, 1],
+            },
+        ),
+        BinaryAndCellpack::new(
+            alkanes_std_amm_factory_build::get_bytes(),
+            Cellpack {
+                target: AlkaneId { block: 1, tx: 0 },
+                inputs: vec![0, AMM_FACTORY_ID, 2, 8],
+            },
+        ),
+        BinaryAndCellpack::cellpack_only(create_pool(TOKEN_B, TOKEN_A, 400_000, 100_000)),
+    ]);
+    index_block(&block, 1)?;
+    Ok(OutPoint {
+        txid: block.txdata.last().unwrap().compute_txid(),
+        vout: 0,
+    })
+}
+
+/// Runs `cellpack` spending the wallet output and returns the new one.
+fn call(wallet: OutPoint, cellpack: Cellpack, height: u32) -> Result<OutPoint> {
+    let block = alkane_helpers::init_with_cellpack_pairs_w_input(
+        vec![BinaryAndCellpack::cellpack_only(cellpack)],
+        wallet,
+    );
+    index_block(&block, height)?;
+    Ok(OutPoint {
+        txid: block.txdata.last().unwrap().compute_txid(),
+        vout: 0,
+    })
+}
+
+/// Runs `cellpack` with nothing incoming and returns the traced outpoint.
+fn call_empty(cellpack: Cellpack, height: u32) -> Result<OutPoint> {
+    let block =
+        alkane_helpers::init_with_cellpack_pairs(vec![BinaryAndCellpack::cellpack_only(cellpack)]);
+    index_block(&block, height)?;
+    Ok(OutPoint {
+        txid: block.txdata.last().unwrap().compute_txid(),
+        vout: 3,
+    })
+}
+
+fn trace_of(wallet: &OutPoint) -> OutPoint {
+    OutPoint {
+        txid: wallet.txid,
+        vout: 3,
+    }
+}
+
+fn view(target: AlkaneId, inputs: Vec<u128>, height: u32) -> Result<Vec<u8>> {
+    let outpoint = call_empty(Cellpack { target, inputs }, height)?;
+    alkane_helpers::assert_return_context(&outpoint, |trace_response| {
+        Ok(trace_response.inner.data.clone())
+    })
+}
+
+fn view_u128(target: AlkaneId, inputs: Vec<u128>, height: u32) -> Result<u128> {
+    let data = view(target, inputs, height)?;
+    Ok(u128::from_le_bytes(data[0..16].try_into()?))
+}
+
+fn pool_details(pool: AlkaneId, height: u32) -> Result<PoolDetails> {
+    let mut cursor = Cursor::new(view(pool, vec![POOL_DETAILS], height)?);
+    let token_a = AlkaneId::parse(&mut cursor)?;
+    let token_b = AlkaneId::parse(&mut cursor)?;
+    let reserve_a = consume_sized_int::<u128>(&mut cursor)?;
+    let reserve_b = consume_sized_int::<u128>(&mut cursor)?;
+    let total_supply = consume_sized_int::<u128>(&mut cursor)?;
+    let name_len = consume_sized_int::<u32>(&mut cursor)?;
+    let pool_name = String::from_utf8(consume_exact(&mut cursor, name_len as usize)?)?;
+    Ok(PoolDetails {
+        token_a,
+        token_b,
+        reserve_a,
+        reserve_b,
+        total_supply,
+        pool_name,
+    })
+}
+
+fn all_pools(height: u32) -> Result<Vec<AlkaneId>> {
+    let mut cursor = Cursor::new(view(
+        FACTORY,
+        vec![FACTORY_OPCODE_GET_ALL_POOLS as u128],
+        height,
+    )?);
+    let count = consume_sized_int::<u128>(&mut cursor)?;
+    (0..count).map(|_| AlkaneId::parse(&mut cursor)).collect()
+}
+
+fn wallet_balance(wallet: &OutPoint, id: AlkaneId) -> Result<u128> {
+    let sheet = load_sheet(
+        &RuneTable::for_protocol(AlkaneMessageContext::protocol_tag())
+            .OUTPOINT_TO_RUNES
+            .select(&consensus_encode(wallet)?),
+    );
+    Ok(sheet.get_cached(&id.into()))
+}
+
+/// Constant-product output with the 0.3% fee taken from the input.
+fn expected_out(amount_in: u128, reserve_in: u128, reserve_out: u128) -> u128 {
+    let amount_in_with_fee = amount_in * 9970;
+    amount_in_with_fee * reserve_out / (reserve_in * 10_000 + amount_in_with_fee)
+}
+
+#[wasm_bindgen_test]
+fn test_amm_create_pool() -> Result<()> {
+    let wallet = setup();
+    // sqrt(100k * 400k) less the locked minimum
+    assert_eq!(wallet_balance(&wallet, POOL_AB)?, 199_000);
+    assert_eq!(wallet_balance(&wallet, TOKEN_A)?, 900_000);
+    assert_eq!(wallet_balance(&wallet, TOKEN_B)?, 600_000);
+
+    // the pool holds its pair in sorted order whatever order it was created in
+    let details = pool_details(POOL_AB, 2)?;
+    assert_eq!((details.token_a, details.token_b), (TOKEN_A, TOKEN_B));
+    assert_eq!(details.reserve_a, 100_000);
+    assert_eq!(details.reserve_b, 400_000);
+    assert_eq!(details.total_supply, 200_000);
+    assert_eq!(details.pool_name, "OWNED / OWNED LP");
+
+    for (height, (first, second)) in [(3, (TOKEN_A, TOKEN_B)), (4, (TOKEN_B, TOKEN_A))] {
+        let found = view(
+            FACTORY,
+            vec![
+                FIND_EXISTING_POOL_ID,
+                first.block,
+                first.tx,
+                second.block,
+                second.tx,
+            ],
+            height,
+        )?;
+        assert_eq!(found, Vec::<u8>::from(POOL_AB));
+    }
+    assert_eq!(all_pools(5)?, vec![POOL_AB]);
+
+    // a second pool for the pair is refused and the deposit refunded
+    let wallet = call(wallet, create_pool(TOKEN_A, TOKEN_B, 1000, 1000), 6)?;
+    alkane_helpers::assert_revert_context(&trace_of(&wallet), "AMMFactory: pool already exists")?;
+    assert_eq!(wallet_balance(&wallet, TOKEN_A)?, 900_000);
+    assert_eq!(wallet_balance(&wallet, TOKEN_B)?, 600_000);
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_amm_create_pool_requires_amounts() -> Result<()> {
+    setup()?;
+    let outpoint = call_empty(create_pool(TOKEN_A, TOKEN_C, 1000, 1000), 2)?;
+    alkane_helpers::assert_revert_context(&outpoint, "AMMFactory: insufficient")?;
+    let outpoint = call_empty(
+        Cellpack {
+            target: FACTORY,
+            inputs: vec![
+                FIND_EXISTING_POOL_ID,
+                TOKEN_A.block,
+                TOKEN_A.tx,
+                TOKEN_C.block,
+                TOKEN_C.tx,
+            ],
+        },
+        3,
+    )?;
+    alkane_helpers::assert_revert_context(&outpoint, "AMMFactory: pool not found")?;
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_amm_add_and_remove_liquidity() -> Result<()> {
+    let wallet = setup()?;
+    // the whole wallet goes in: all 600k B fits against 150k of the 900k A
+    let wallet = call(
+        wallet,
+        Cellpack {
+            target: POOL_AB,
+            inputs: vec![ADD_LIQUIDITY],
+        },
+        2,
+    )?;
+    assert_eq!(wallet_balance(&wallet, POOL_AB)?, 499_000);
+    assert_eq!(wallet_balance(&wallet, TOKEN_A)?, 750_000);
+    assert_eq!(wallet_balance(&wallet, TOKEN_B)?, 0);
+    assert_eq!(view_u128(POOL_AB, vec![GET_TOTAL_SUPPLY], 3)?, 500_000);
+
+    let wallet = call(
+        wallet,
+        Cellpack {
+            target: POOL_AB,
+            inputs: vec![REMOVE_LIQUIDITY],
+        },
+        4,
+    )?;
+    assert_eq!(wallet_balance(&wallet, POOL_AB)?, 0);
+    assert_eq!(wallet_balance(&wallet, TOKEN_A)?, 999_500);
+    assert_eq!(wallet_balance(&wallet, TOKEN_B)?, 998_000);
+
+    // only the locked minimum is left behind
+    let details = pool_details(POOL_AB, 5)?;
+    assert_eq!(details.total_supply, 1000);
+    assert_eq!((details.reserve_a, details.reserve_b), (500, 2000));
+
+    let wallet = call(
+        wallet,
+        Cellpack {
+            target: POOL_AB,
+            inputs: vec![REMOVE_LIQUIDITY],
+        },
+        6,
+    )?;
+    alkane_helpers::assert_revert_context(&trace_of(&wallet), "AMM: no liquidity tokens to burn")?;
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_amm_swap_exact_in() -> Result<()> {
+    let wallet = setup()?;
+    let out = expected_out(10_000, 100_000, 400_000);
+    assert_eq!(
+        view_u128(
+            POOL_AB,
+            vec![SIMULATE_SWAP, TOKEN_A.block, TOKEN_A.tx, 10_000],
+            2
+        )?,
+        out
+    );
+
+    let wallet = call(wallet, swap(&[TOKEN_A, TOKEN_B], 10_000, out + 1, 0), 3)?;
+    alkane_helpers::assert_revert_context(
+        &trace_of(&wallet),
+        "AMMFactory: insufficient output amount",
+    )?;
+    let wallet = call(wallet, swap(&[TOKEN_A, TOKEN_B], 10_000, out, 3), 4)?;
+    alkane_helpers::assert_revert_context(&trace_of(&wallet), "AMMFactory: expired deadline")?;
+    assert_eq!(wallet_balance(&wallet, TOKEN_A)?, 900_000);
+
+    let wallet = call(wallet, swap(&[TOKEN_A, TOKEN_B], 10_000, out, 5), 5)?;
+    assert_eq!(wallet_balance(&wallet, TOKEN_A)?, 890_000);
+    assert_eq!(wallet_balance(&wallet, TOKEN_B)?, 600_000 + out);
+    let details = pool_details(POOL_AB, 6)?;
+    assert_eq!(
+        (details.reserve_a, details.reserve_b),
+        (110_000, 400_000 - out)
+    );
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_amm_swap_exact_out() -> Result<()> {
+    let wallet = setup();
+    // buying 10k A needs 44579 B once the fee and rounding are paid
+    let amount_in = view_u128(
+        POOL_AB,
+        vec![SIMULATE_SWAP_EXACT_OUT, TOKEN_A.block, TOKEN_A.tx, 10_000],
+        2,
+    )?;
+    assert_eq!(amount_in, 44_579);
+    assert_eq!(expected_out(amount_in, 400_000, 100_000), 10_000);
+
+    let wallet = call(
+        wallet,
+        swap_exact_out(&[TOKEN_B, TOKEN_A], 10_000, amount_in - 1, 0),
+        3,
+    )?;
+    alkane_helpers::assert_revert_context(
+        &trace_of(&wallet),
+        "AMMFactory: excessive input amount",
+    )?;
+
+    // the difference to the maximum comes back
+    let wallet = call(
+        wallet,
+        swap_exact_out(&[TOKEN_B, TOKEN_A], 10_000, 50_000, 0),
+        4,
+    )?;
+    assert_eq!(wallet_balance(&wallet, TOKEN_A)?, 910_000);
+    assert_eq!(wallet_balance(&wallet, TOKEN_B)?, 600_000 - amount_in);
+    let details = pool_details(POOL_AB, 5)?;
+    assert_eq!(
+        (details.reserve_a, details.reserve_b),
+        (90_000, 400_000 + amount_in)
+    );
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_amm_swap_multi_hop() -> Result<()> {
+    let wallet = setup()?;
+    let wallet = call(wallet, create_pool(TOKEN_B, TOKEN_C, 200_000, 200_000), 2)?;
+    assert_eq!(wallet_balance(&wallet, POOL_BC)?, 199_000);
+    assert_eq!(all_pools(3)?, vec![POOL_AB, POOL_BC]);
+
+    let out_b = expected_out(10_000, 100_000, 400_000);
+    let out_c = expected_out(out_b, 200_000, 200_000);
+    let wallet = call(
+        wallet,
+        swap(&[TOKEN_A, TOKEN_B, TOKEN_C], 10_000, out_c, 0),
+        4,
+    )?;
+    assert_eq!(wallet_balance(&wallet, TOKEN_A)?, 890_000);
+    // the B in between never leaves the pools
+    assert_eq!(wallet_balance(&wallet, TOKEN_B)?, 400_000);
+    assert_eq!(wallet_balance(&wallet, TOKEN_C)?, 800_000 + out_c);
+    let details = pool_details(POOL_BC, 5)?;
+    assert_eq!(
+        (details.reserve_a, details.reserve_b),
+        (200_000 + out_b, 200_000 - out_c)
+    );
+
+    let wallet = call(wallet, swap(&[TOKEN_A, TOKEN_C], 10_000, 0, 0), 6)?;
+    alkane_helpers::assert_revert_context(&trace_of(&wallet), "AMMFactory: pool not found")?;
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_amm_reserve_and_price_views() -> Result<()> {
+    setup()?;
+    let reserves = view(POOL_AB, vec![GET_RESERVES], 2)?;
+    assert_eq!(u128::from_le_bytes(reserves[0..16].try_into()?), 100_000);
+    assert_eq!(u128::from_le_bytes(reserves[16..32].try_into()?), 400_000);
+
+    // 1 A buys 4 B and 1 B buys 0.25 A, with 18 decimals
+    let price = view(POOL_AB, vec![GET_PRICE], 3)?;
+    assert_eq!(
+        u128::from_le_bytes(price[0..16].try_into()?),
+        4_000_000_000_000_000_000
+    );
+    assert_eq!(
+        u128::from_le_bytes(price[16..32].try_into()?),
+        250_000_000_000_000_000
+    );
+
+    assert_eq!(
+        view(POOL_AB, vec![GET_NAME], 4)?,
+        b"OWNED / OWNED LP".to_vec()
+    );
+    let outpoint = call_empty(
+        Cellpack {
+            target: POOL_AB,
+            inputs: vec![SIMULATE_SWAP, TOKEN_C.block, TOKEN_C.tx, 10_000],
+        },
+        5,
+    )?;
+    alkane_helpers::assert_revert_context(&outpoint, "is not in the pool")?;
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_amm_pool_swap_invariant() -> Result<()> {
+    let wallet = setup()?;
+    let outpoint = call_empty(
+        Cellpack {
+            target: POOL_AB,
+            inputs: vec![SWAP, 0, 1000],
+        },
+        2,
+    )?;
+    alkane_helpers::assert_revert_context(&outpoint, "AMM: insufficient input amount")?;
+
+    // the whole wallet's 900k A pays for at most this much B
+    let out = expected_out(900_000, 100_000, 400_000);
+    let wallet = call(
+        wallet,
+        Cellpack {
+            target: POOL_AB,
+            inputs: vec![SWAP, 0, 399_999],
+        },
+        3,
+    )?;
+    alkane_helpers::assert_revert_context(&trace_of(&wallet), "AMM: invariant violated")?;
+    let wallet = call(
+        wallet,
+        Cellpack {
+            target: POOL_AB,
+            inputs: vec![SWAP, 0, 400_000],
+        },
+        4,
+    )?;
+    alkane_helpers::assert_revert_context(&trace_of(&wallet), "AMM: insufficient liquidity")?;
+
+    let wallet = call(
+        wallet,
+        Cellpack {
+            target: POOL_AB,
+            inputs: vec![SWAP, 0, out],
+        },
+        5,
+    )?;
+    assert_eq!(wallet_balance(&wallet, TOKEN_A)?, 0);
+    assert_eq!(wallet_balance(&wallet, TOKEN_B)?, 600_000 + out);
+    assert_eq!(wallet_balance(&wallet, POOL_AB)?, 199_000);
+    let details = pool_details(POOL_AB, 6)?;
+    assert_eq!(
+        (details.reserve_a, details.reserve_b),
+        (1_000_000, 400_000 - out)
+    );
+    Ok(())
+}
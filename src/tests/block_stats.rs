@@ -0,0 +1,84 @@
+use crate::block_stats::{call_depth, revert_class, REVERT_OTHER, REVERT_OUT_OF_FUEL};
+use crate::index_block;
+use crate::tests::helpers::{self as alkane_helpers, clear};
+use crate::tests::std::alkanes_std_test_build;
+use crate::view;
+use crate::vm::fuel::total_fuel;
+use alkanes_support::cellpack::Cellpack;
+use alkanes_support::id::AlkaneId;
+use alkanes_support::proto::alkanes::{BlockStats, BlockStatsRequest};
+use anyhow::Result;
+use protorune::test_helpers::create_block_with_coinbase_tx;
+use wasm_bindgen_test::wasm_bindgen_test;
+
+const TEST: AlkaneId = AlkaneId { block: 2, tx: 1 };
+
+fn stats(start_height: u64, end_height: u64) -> Result<Vec<BlockStats>> {
+    Ok(view::block_stats(&BlockStatsRequest {
+        start_height,
+        end_height,
+    })?
+    .blocks)
+}
+
+#[wasm_bindgen_test]
+fn test_block_stats_count_messages_reverts_and_depth() -> Result<()> {
+    clear();
+    index_block(&create_block_with_coinbase_tx(0), 0)?;
+    // deploy, a revert, and an extcall back into the deployed alkane
+    let block = alkane_helpers::init_with_multiple_cellpacks_with_tx(
+        vec![alkanes_std_test_build::get_bytes(), vec![], vec![]],
+        vec![
+            Cellpack {
+                target: AlkaneId { block: 1, tx: 0 },
+                inputs: vec![0],
+            },
+            Cellpack {
+                target: TEST,
+                inputs: vec![100],
+            },
+            Cellpack {
+                target: TEST,
+                inputs: vec![31, TEST.block, TEST.tx, 5],
+            },
+        ],
+    );
+    index_block(&block, 1)?;
+
+    let blocks = stats(1, 0)?;
+    assert_eq!(blocks.len(), 1);
+    let stats = &blocks[0];
+    assert_eq!(stats.height, 1);
+    assert_eq!(stats.protostones, 3);
+    assert_eq!(stats.messages, 3);
+    assert_eq!(stats.alkanes_created, 1);
+    assert_eq!(stats.reverts.iter().map(|r| r.count).sum::<u64>(), 1);
+    assert!(stats.storage_bytes > 0);
+    assert!(stats.fuel_consumed > 0);
+    assert_eq!(stats.block_fuel, total_fuel(1));
+    assert!(stats
+        .call_depths
+        .iter()
+        .any(|d| d.depth == 2 && d.count == 1));
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_block_stats_range_is_capped() -> Result<()> {
+    clear();
+    index_block(&create_block_with_coinbase_tx(0), 0)?;
+    index_block(&create_block_with_coinbase_tx(1), 1)?;
+    assert_eq!(stats(0, u64::MAX)?.len(), 2);
+    assert!(stats(view::BLOCK_STATS_MAX_RANGE, u64::MAX)?.is_empty());
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_revert_class_and_call_depth() {
+    assert_eq!(
+        revert_class("ALKANES: revert: all fuel consumed by WebAssembly"),
+        REVERT_OUT_OF_FUEL
+    );
+    assert_eq!(revert_class("ALKANES: revert: Error: nope"), REVERT_OTHER);
+    assert_eq!(call_depth(&[]), 0);
+}
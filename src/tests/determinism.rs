@@ -3,11 +3,12 @@ use alkanes_support::cellpack::Cellpack;
 use alkanes_support::id::AlkaneId;
 use alkanes_support::trace::{Trace, TraceEvent};
 use anyhow::Result;
-use bitcoin::{OutPoint, ScriptBuf, Sequence, TxIn, Witness};
+use bitcoin::{Block, OutPoint, ScriptBuf, Sequence, TxIn, Witness};
 use protorune_support::protostone::ProtostoneEdict;
 
 use crate::index_block;
 use crate::tests::helpers::{self as alkane_helpers, get_sheet_for_runtime};
+use crate::vm::module_cache;
 use alkane_helpers::clear;
 use alkanes::view;
 #[allow(unused_imports)]
@@ -15,6 +16,7 @@ use metashrew_core::{
     println,
     stdio::{stdout, Write},
 };
+use protorune::test_helpers::create_block_with_coinbase_tx;
 use protorune_support::balance_sheet::ProtoruneRuneId;
 use wasm_bindgen_test::wasm_bindgen_test;
 
@@ -80,3 +82,114 @@ fn test_incoming_alkanes_ordered() -> Result<()> {
 
     Ok(())
 }
+
+/// Deploys the test alkane (`2:1`) at height 1, then at height 2 calls it
+/// directly, through nested extcalls (the same module instantiated again
+/// within one call), and into a revert.
+fn module_cache_fixture() -> Vec<Block> {
+    let test_alkane = AlkaneId { block: 2, tx: 1 };
+    let deploy = alkane_helpers::init_with_multiple_cellpacks_with_tx(
+        vec![alkanes_std_test_build::get_bytes()],
+        vec![Cellpack {
+            target: AlkaneId { block: 1, tx: 0 },
+            inputs: vec![0],
+        }],
+    );
+    let calls = vec![
+        vec![104, 10],
+        vec![31, 2, 1, 2, 104, 20],
+        vec![34, 2, 1, 1, 105, 2, 1, 1, 103],
+        vec![33, 2, 1, 1, 103],
+        vec![100],
+        vec![105],
+    ];
+    let cellpacks = calls
+        .into_iter()
+        .map(|inputs| Cellpack {
+            target: test_alkane,
+            inputs,
+        })
+        .collect::<Vec<_>>();
+    let call = alkane_helpers::init_with_multiple_cellpacks_with_tx_w_input(
+        vec![vec![]; cellpacks.len()],
+        cellpacks,
+        Some(OutPoint {
+            txid: deploy.txdata.last().unwrap().compute_txid(),
+            vout: 0,
+        }),
+    );
+    vec![create_block_with_coinbase_tx(0), deploy, call]
+}
+
+/// The encoded trace of every protostone in `blocks`, which carries each
+/// frame's fuel and return data.
+fn fixture_traces(blocks: &[Block]) -> Result<Vec<Vec<u8>>> {
+    let mut traces = vec![];
+    for tx in blocks.iter().flat_map(|block| block.txdata.iter().skip(1)) {
+        traces.push(view::trace(&OutPoint {
+            txid: tx.compute_txid(),
+            vout: tx.output.len() as u32 + 1,
+        })?);
+    }
+    Ok(traces)
+}
+
+fn index_fixture_traces(cache: bool) -> Result<Vec<Vec<u8>>> {
+    clear();
+    module_cache::set_enabled(cache);
+    let blocks = module_cache_fixture();
+    let indexed = blocks
+        .iter()
+        .enumerate()
+        .try_for_each(|(height, block)| index_block(block, height as u32));
+    module_cache::set_enabled(true);
+    indexed?;
+    fixture_traces(&blocks)
+}
+
+#[wasm_bindgen_test]
+fn test_module_cache_keeps_traces_and_fuel() -> Result<()> {
+    let uncached = index_fixture_traces(false)?;
+    assert!(uncached.iter().all(|trace| !trace.is_empty()));
+    let cached = index_fixture_traces(true)?;
+    assert!(module_cache::len() > 0);
+    assert_eq!(cached, uncached);
+    // A second pass runs every call from an already warm cache.
+    assert_eq!(index_fixture_traces(true)?, uncached);
+    Ok(())
+}
+
+#[cfg(feature = "native")]
+#[wasm_bindgen_test]
+fn test_module_cache_keeps_state_root() -> Result<()> {
+    use crate::native::NativeIndexer;
+    use metashrew_native::{KeyValueStore, MemoryStore};
+    use sha2::{Digest, Sha256};
+    use std::sync::Arc;
+
+    // Digest of every stored pair, standing in for the state root.
+    let state_root = |cache: bool| -> Result<Vec<u8>> {
+        module_cache::set_enabled(cache);
+        let store = Arc::new(MemoryStore::new());
+        let indexer = NativeIndexer::new(store.clone());
+        let blocks = module_cache_fixture();
+        let indexed = blocks
+            .iter()
+            .enumerate()
+            .try_for_each(|(height, block)| indexer.index_block(block, height as u32));
+        module_cache::set_enabled(true);
+        indexed?;
+        let mut hasher = Sha256::new();
+        for (key, value) in store.entries()? {
+            hasher.update((key.len() as u32).to_le_bytes());
+            hasher.update(&key);
+            hasher.update((value.len() as u32).to_le_bytes());
+            hasher.update(&value);
+        }
+        Ok(hasher.finalize().to_vec())
+    };
+    let uncached = state_root(false)?;
+    assert_eq!(state_root(true)?, uncached);
+    assert_eq!(state_root(true)?, uncached);
+    Ok(())
+}
@@ -0,0 +1,200 @@
+use crate::index_block;
+use crate::tests::helpers::{
+    self as alkane_helpers, clear, deploy_test_alkane, test_alkane_call, TEST_ALKANE,
+};
+use crate::tests::std::{
+    alkanes_std_amm_factory_build, alkanes_std_amm_pool_build, alkanes_std_test_2_build,
+    alkanes_std_test_build, alkanes_std_vault_token_build,
+};
+use crate::view;
+use crate::vm::instance::AlkanesInstance;
+use crate::vm::runtime::AlkanesRuntimeContext;
+use alkanes_support::id::AlkaneId;
+use alkanes_support::proto::alkanes::{
+    AlkaneEventsResponse, EventsByAlkaneRequest, EventsByHeightRequest,
+};
+use anyhow::Result;
+use protorune::test_helpers::create_block_with_coinbase_tx;
+use std::sync::{Arc, Mutex};
+use wasm_bindgen_test::wasm_bindgen_test;
+
+fn topic_word(topic: &str) -> u128 {
+    let mut bytes = [0u8; 16];
+    bytes[..topic.len()].copy_from_slice(topic.as_bytes());
+    u128::from_le_bytes(bytes)
+}
+
+/// Deploys the test alkane at height 1, then runs each of `inputs` against it
+/// in its own transaction at height 2.
+fn index_calls(inputs: Vec<Vec<u128>>) -> Result<()> {
+    clear();
+    index_block(&create_block_with_coinbase_tx(0), 0)?;
+    index_block(&deploy_test_alkane(vec![]), 1)?;
+    let calls = alkane_helpers::init_with_cellpack_pairs(
+        inputs.into_iter().map(test_alkane_call).collect(),
+    );
+    index_block(&calls, 2)?;
+    Ok(())
+}
+
+fn events_at(height: u64, topic: &str) -> Result<AlkaneEventsResponse> {
+    view::events_by_height(&EventsByHeightRequest {
+        height,
+        topic: topic.as_bytes().to_vec(),
+    })
+}
+
+fn events_of(topic: &str, cursor: u64, limit: u32) -> Result<AlkaneEventsResponse> {
+    view::events_by_alkane(&EventsByAlkaneRequest {
+        id: Some(TEST_ALKANE.into()),
+        topic: topic.as_bytes().to_vec(),
+        from_height: 0,
+        to_height: 0,
+        cursor,
+        limit,
+    })
+}
+
+fn values(response: &AlkaneEventsResponse) -> Vec<u128> {
+    response
+        .events
+        .iter()
+        .map(|event| u128::from_le_bytes(event.data[..16].try_into().unwrap()))
+        .collect()
+}
+
+#[wasm_bindgen_test]
+fn test_emitted_events_are_indexed() -> Result<()> {
+    index_calls(vec![
+        vec![120, topic_word("Swap"), 7],
+        vec![120, topic_word("Mint"), 8],
+    ])?;
+
+    let at_height = events_at(2, "")?;
+    assert_eq!(values(&at_height), vec![7, 8]);
+    let event = &at_height.events[0];
+    assert_eq!(event.height, 2);
+    assert_eq!(AlkaneId::from(event.alkane.clone().unwrap()), TEST_ALKANE);
+    assert_eq!(event.topic, b"Swap".to_vec());
+    assert!(event.outpoint.is_some());
+
+    assert_eq!(values(&events_at(2, "Mint")?), vec![8]);
+    assert!(events_at(1, "")?.events.is_empty());
+
+    assert_eq!(values(&events_of("", 0, 0)?), vec![7, 8]);
+    assert_eq!(values(&events_of("Swap", 0, 0)?), vec![7]);
+    assert!(events_of("Burn", 0, 0)?.events.is_empty());
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_reverted_and_static_events_are_dropped() -> Result<()> {
+    index_calls(vec![
+        // reverts at the top level
+        vec![121, topic_word("Swap"), 1],
+        // first child reverts, second commits; the parent allows both to fail
+        vec![
+            34,
+            TEST_ALKANE.block,
+            TEST_ALKANE.tx,
+            3,
+            121,
+            topic_word("Swap"),
+            2,
+            TEST_ALKANE.block,
+            TEST_ALKANE.tx,
+            3,
+            120,
+            topic_word("Swap"),
+            3,
+        ],
+        // emitted inside a staticcall
+        vec![
+            33,
+            TEST_ALKANE.block,
+            TEST_ALKANE.tx,
+            3,
+            120,
+            topic_word("Swap"),
+            4,
+        ],
+    ])?;
+
+    assert_eq!(values(&events_at(2, "")?), vec![3]);
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_eventsbyalkane_pagination() -> Result<()> {
+    index_calls(vec![
+        vec![120, topic_word("Swap"), 1],
+        vec![120, topic_word("Swap"), 2],
+        vec![120, topic_word("Swap"), 3],
+    ])?;
+
+    let mut paged = vec![];
+    let mut cursor = 0;
+    loop {
+        let page = events_of("Swap", cursor, 2)?;
+        assert!(page.events.len() <= 2);
+        paged.extend(values(&page));
+        if page.next_cursor == 0 {
+            break;
+        }
+        cursor = page.next_cursor;
+    }
+    assert_eq!(paged, vec![1, 2, 3]);
+
+    let bounded = view::events_by_alkane(&EventsByAlkaneRequest {
+        id: Some(TEST_ALKANE.into()),
+        topic: vec![],
+        from_height: 3,
+        to_height: 0,
+        cursor: 0,
+        limit: 0,
+    })?;
+    assert!(bounded.events.is_empty());
+    assert_eq!(bounded.next_cursor, 0);
+    Ok(())
+}
+
+/// The `__meta` of `binary` instantiated with the host functions of a
+/// height before the events fork.
+fn meta_before_events_fork(binary: Vec<u8>) -> Result<String> {
+    let mut instance = AlkanesInstance::with_host_functions(
+        Arc::new(Mutex::new(AlkanesRuntimeContext::default())),
+        Arc::new(binary),
+        100_000_000,
+        false,
+    )?;
+    Ok(String::from_utf8(instance.call_meta()?)?)
+}
+
+#[wasm_bindgen_test]
+fn test_access_control_and_pausable_instantiate_before_events_fork() -> Result<()> {
+    // the test alkane emits events itself, so it only links from the fork on
+    assert!(meta_before_events_fork(alkanes_std_test_build::get_bytes()).is_err());
+
+    let meta = meta_before_events_fork(alkanes_std_test_2_build::get_bytes())?;
+    for method in ["grant_role", "renounce_role", "pause", "unpause"] {
+        let name = format!("\"name\": \"{}\"", method);
+        assert!(meta.contains(&name), "{} missing from {}", method, meta);
+    }
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_vault_token_instantiates_before_events_fork() -> Result<()> {
+    let meta = meta_before_events_fork(alkanes_std_vault_token_build::get_bytes())?;
+    assert!(meta.contains("\"name\": \"transfer_from\""), "{}", meta);
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_amm_instantiates_before_events_fork() -> Result<()> {
+    let meta = meta_before_events_fork(alkanes_std_amm_pool_build::get_bytes())?;
+    assert!(meta.contains("\"name\": \"swap\""), "{}", meta);
+    let meta = meta_before_events_fork(alkanes_std_amm_factory_build::get_bytes())?;
+    assert!(meta.contains("\"name\": \"create_new_pool\""), "{}", meta);
+    Ok(())
+}
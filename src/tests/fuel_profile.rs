@@ -0,0 +1,118 @@
+use crate::index_block;
+use crate::tests::helpers::{self as alkane_helpers, clear, BinaryAndCellpack};
+use crate::tests::std::alkanes_std_test_build;
+use crate::view;
+use crate::vm::fuel::{fuel_per_store_byte, FUEL_EXTCALL};
+use crate::vm::profiler::{self, FrameKind, FuelFrame};
+use alkanes_support::cellpack::Cellpack;
+use alkanes_support::id::AlkaneId;
+use anyhow::Result;
+use protorune::message::MessageContextParcel;
+use protorune::test_helpers::create_block_with_coinbase_tx;
+use wasm_bindgen_test::wasm_bindgen_test;
+
+const TEST_ALKANE: AlkaneId = AlkaneId { block: 2, tx: 1 };
+const FEES_KEY: &[u8] = b"/claimablefees";
+
+fn deploy() -> Result<()> {
+    clear();
+    index_block(&create_block_with_coinbase_tx(0), 0)?;
+    let deploy = alkane_helpers::init_with_cellpack_pairs(vec![BinaryAndCellpack::new(
+        alkanes_std_test_build::get_bytes(),
+        Cellpack {
+            target: AlkaneId { block: 1, tx: 0 },
+            inputs: vec![0],
+        },
+    )]);
+    index_block(&deploy, 1)?;
+    Ok(())
+}
+
+fn parcel(inputs: Vec<u128>) -> MessageContextParcel {
+    MessageContextParcel {
+        block: create_block_with_coinbase_tx(2),
+        height: 2,
+        calldata: Cellpack {
+            target: TEST_ALKANE,
+            inputs,
+        }
+        .encipher(),
+        ..Default::default()
+    }
+}
+
+/// Calls the test alkane, which extcalls itself with `inputs`.
+fn profile_self_call(inputs: Vec<u128>) -> (Result<u64>, FuelFrame) {
+    let mut calldata = vec![31, TEST_ALKANE.block, TEST_ALKANE.tx, inputs.len() as u128];
+    calldata.extend(inputs);
+    let (result, profile) = view::simulate_profiled(&parcel(calldata), u64::MAX);
+    (
+        result.map(|(_, gas_used)| gas_used),
+        profile.expect("profile recorded"),
+    )
+}
+
+#[wasm_bindgen_test]
+fn test_profile_attributes_storage_and_extcall_fuel() -> Result<()> {
+    deploy()?;
+    let (gas_used, root) = profile_self_call(vec![104, 5]);
+    let gas_used = gas_used?;
+
+    assert_eq!(root.alkane, TEST_ALKANE);
+    assert_eq!(root.opcode, 31);
+    assert!(!root.reverted);
+    assert_eq!(root.fuel_used, gas_used);
+    assert!(root.instruction_fuel > 0);
+    assert_eq!(root.children.len(), 1);
+
+    let child = &root.children[0];
+    assert_eq!(child.kind, FrameKind::Call);
+    assert_eq!(child.opcode, 104);
+    assert!(child.extcall_fuel >= FUEL_EXTCALL);
+    assert!(root.fuel_used >= child.fuel_used + child.extcall_fuel + root.instruction_fuel);
+    let write = child
+        .storage_writes
+        .iter()
+        .find(|w| w.key == FEES_KEY)
+        .expect("claimable fees write");
+    assert_eq!(write.bytes, (8 + FEES_KEY.len() + 16) as u64);
+    assert_eq!(write.fuel, write.bytes * fuel_per_store_byte(2));
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_profile_records_storage_reads() -> Result<()> {
+    deploy()?;
+    let (gas_used, root) = profile_self_call(vec![105]);
+    gas_used?;
+    let child = &root.children[0];
+    let read = child
+        .storage_reads
+        .iter()
+        .find(|r| r.key == FEES_KEY)
+        .expect("claimable fees read");
+    assert!(read.fuel > 0);
+    assert!(child.storage_writes.iter().any(|w| w.key == FEES_KEY));
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_profile_marks_reverted_frames() -> Result<()> {
+    deploy()?;
+    let (gas_used, root) = profile_self_call(vec![100]);
+    assert!(gas_used.is_err());
+    assert!(root.reverted);
+    assert_eq!(root.children.len(), 1);
+    assert!(root.children[0].reverted);
+    assert!(root.children[0].storage_writes.is_empty());
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_profiler_is_off_by_default() -> Result<()> {
+    deploy()?;
+    view::simulate_safe(&parcel(vec![105]), u64::MAX)?;
+    assert!(!profiler::is_enabled());
+    assert!(profiler::drain().is_empty());
+    Ok(())
+}
@@ -16,7 +16,15 @@ mod tests {
     };
     use alkanes_support::cellpack::Cellpack;
     use alkanes_support::gz::compress;
+    use alkanes_support::proto::alkanes::AlkaneInventoryRequest;
+    use bitcoin::hashes::Hash;
+    use bitcoin::{Block, OutPoint};
+    use metashrew_core::index_pointer::IndexPointer;
     use protorune::test_helpers::create_block_with_coinbase_tx;
+    use protorune_support::balance_sheet::BalanceSheet;
+    use protorune_support::proto::protorune::OutpointWithProtocol;
+
+    use crate::tests::helpers::{call_test_alkane_w_input, deploy_test_alkane, TEST_ALKANE};
 
     /// Test that getstorageat works when called with configure_network().
     /// This mirrors the production flow where all other view functions call
@@ -164,4 +172,107 @@ mod tests {
 
         Ok(())
     }
+
+    fn claimable_fees_request() -> AlkaneStorageRequest {
+        AlkaneStorageRequest {
+            id: Some(TEST_ALKANE.into()),
+            path: "/claimablefees".as_bytes().to_vec(),
+        }
+    }
+
+    fn last_outpoint(block: &Block) -> OutPoint {
+        OutPoint {
+            txid: block.txdata.last().unwrap().compute_txid(),
+            vout: 0,
+        }
+    }
+
+    fn outpoint_balance_at(outpoint: &OutPoint, height: u64) -> Result<u128> {
+        let request = OutpointWithProtocol {
+            txid: outpoint.txid.as_byte_array().to_vec(),
+            vout: outpoint.vout,
+            protocol: Some(1u128.into()),
+        };
+        let response = view::protorunes_by_outpoint_at(&request.encode_to_vec(), height)?;
+        let sheet: BalanceSheet<IndexPointer> = response.balances.unwrap_or_default().into();
+        Ok(sheet.get_cached(&TEST_ALKANE.into()))
+    }
+
+    fn inventory_at(height: u64) -> Result<Vec<(AlkaneId, u128)>> {
+        let response = view::getinventory_at(
+            &AlkaneInventoryRequest {
+                id: Some(TEST_ALKANE.into()),
+            },
+            height,
+        )?;
+        Ok(response
+            .alkanes
+            .into_iter()
+            .map(|transfer| (transfer.id.unwrap().into(), transfer.value.unwrap().into()))
+            .collect())
+    }
+
+    /// Deploys the test alkane at height 1 with `/claimablefees` = 10, sets it
+    /// to 20 at height 2 and leaves height 3 empty. Each height must answer
+    /// with the value current as of that block, while the tip answer stays
+    /// the latest write.
+    #[wasm_bindgen_test]
+    fn test_getstorageat_historical() -> Result<()> {
+        clear();
+        index_block(&create_block_with_coinbase_tx(0), 0)?;
+
+        let deploy = deploy_test_alkane(vec![vec![104, 10]]);
+        index_block(&deploy, 1)?;
+
+        let update = call_test_alkane_w_input(vec![104, 20], &deploy);
+        index_block(&update, 2)?;
+        index_block(&create_block_with_coinbase_tx(3), 3)?;
+
+        let req = claimable_fees_request();
+        let current = view::getstorageat(&req)?.value;
+        assert_eq!(current, 20u128.to_le_bytes().to_vec());
+
+        assert!(view::getstorageat_at(&req, 0)?.value.is_empty());
+        assert_eq!(
+            view::getstorageat_at(&req, 1)?.value,
+            10u128.to_le_bytes().to_vec()
+        );
+        assert_eq!(view::getstorageat_at(&req, 2)?.value, current);
+        assert_eq!(view::getstorageat_at(&req, 3)?.value, current);
+        assert_eq!(view::getstorageat_at(&req, u32::MAX.into())?.value, current);
+
+        Ok(())
+    }
+
+    /// Mints 1000 of the test alkane into an outpoint at height 1 and donates
+    /// that outpoint to the alkane itself at height 2. The outpoint must hold
+    /// the tokens only as of height 1, and the alkane's inventory must hold
+    /// them only from height 2 on.
+    #[wasm_bindgen_test]
+    fn test_outpoint_and_inventory_historical() -> Result<()> {
+        clear();
+        index_block(&create_block_with_coinbase_tx(0), 0)?;
+
+        let mint = deploy_test_alkane(vec![vec![22, 1000]]);
+        index_block(&mint, 1)?;
+        let minted = last_outpoint(&mint);
+
+        let donate = call_test_alkane_w_input(vec![7], &mint);
+        index_block(&donate, 2)?;
+
+        assert_eq!(outpoint_balance_at(&minted, 0)?, 0);
+        assert_eq!(outpoint_balance_at(&minted, 1)?, 1000);
+        assert_eq!(outpoint_balance_at(&minted, 2)?, 0);
+        assert_eq!(
+            outpoint_balance_at(&minted, u32::MAX.into())?,
+            outpoint_balance_at(&minted, 2)?
+        );
+
+        assert!(inventory_at(0)?.is_empty());
+        assert!(inventory_at(1)?.is_empty());
+        assert_eq!(inventory_at(2)?, vec![(TEST_ALKANE, 1000)]);
+        assert_eq!(inventory_at(u32::MAX.into())?, inventory_at(2)?);
+
+        Ok(())
+    }
 }
@@ -0,0 +1,94 @@
+use crate::index_block;
+use crate::tests::helpers::{call_test_alkane_w_input, clear, deploy_test_alkane, TEST_ALKANE};
+use crate::view;
+use alkanes_support::proto::alkanes::{AlkaneStorageKeysRequest, AlkaneStorageKeysResponse};
+use anyhow::Result;
+use protorune::test_helpers::create_block_with_coinbase_tx;
+use wasm_bindgen_test::wasm_bindgen_test;
+
+/// Deploys the test alkane at height 1, which writes `/initialized` and sets
+/// `/claimablefees` to 10, then sets `/claimablefees` to 20 at height 2.
+fn index_storage_writes() -> Result<()> {
+    clear();
+    index_block(&create_block_with_coinbase_tx(0), 0)?;
+
+    let deploy = deploy_test_alkane(vec![vec![104, 10]]);
+    index_block(&deploy, 1)?;
+
+    let update = call_test_alkane_w_input(vec![104, 20], &deploy);
+    index_block(&update, 2)?;
+    Ok(())
+}
+
+fn storage_keys(
+    prefix: &str,
+    cursor: u64,
+    limit: u32,
+    height: u64,
+) -> Result<AlkaneStorageKeysResponse> {
+    view::getstoragekeys(
+        &AlkaneStorageKeysRequest {
+            id: Some(TEST_ALKANE.into()),
+            prefix: prefix.as_bytes().to_vec(),
+            cursor,
+            limit,
+        },
+        height,
+    )
+}
+
+#[wasm_bindgen_test]
+fn test_getstoragekeys_lists_written_keys() -> Result<()> {
+    index_storage_writes()?;
+
+    let response = storage_keys("", 0, 0, u32::MAX.into())?;
+    let keys = response
+        .entries
+        .iter()
+        .map(|entry| String::from_utf8(entry.key.clone()).unwrap())
+        .collect::<Vec<String>>();
+    assert_eq!(keys, vec!["/initialized", "/claimablefees"]);
+    assert_eq!(response.next_cursor, 0);
+
+    let fees = &response.entries[1];
+    assert_eq!(fees.value, 20u128.to_le_bytes().to_vec());
+    assert_eq!(fees.last_modified_height, 2);
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_getstoragekeys_prefix_and_height() -> Result<()> {
+    index_storage_writes()?;
+
+    let response = storage_keys("/claimable", 0, 0, 1)?;
+    assert_eq!(response.entries.len(), 1);
+    assert_eq!(response.entries[0].key, b"/claimablefees".to_vec());
+    assert_eq!(response.entries[0].value, 10u128.to_le_bytes().to_vec());
+    assert_eq!(response.entries[0].last_modified_height, 1);
+
+    assert!(storage_keys("", 0, 0, 0)?.entries.is_empty());
+    assert!(storage_keys("/nothing", 0, 0, u32::MAX.into())?
+        .entries
+        .is_empty());
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_getstoragekeys_pagination() -> Result<()> {
+    index_storage_writes()?;
+
+    let full = storage_keys("", 0, 0, u32::MAX.into())?;
+    let mut paged = vec![];
+    let mut cursor = 0;
+    loop {
+        let page = storage_keys("", cursor, 1, u32::MAX.into())?;
+        assert!(page.entries.len() <= 1);
+        paged.extend(page.entries);
+        if page.next_cursor == 0 {
+            break;
+        }
+        cursor = page.next_cursor;
+    }
+    assert_eq!(paged, full.entries);
+    Ok(())
+}
@@ -2,6 +2,7 @@ use crate::message::AlkaneMessageContext;
 use crate::view;
 use alkanes_support::cellpack::Cellpack;
 use alkanes_support::envelope::RawEnvelope;
+use alkanes_support::error::StructuredError;
 use alkanes_support::gz::compress;
 use alkanes_support::id::AlkaneId;
 use alkanes_support::trace::{Trace, TraceEvent, TraceResponse};
@@ -11,12 +12,12 @@ use bitcoin::{
     address::NetworkChecked, Address, Amount, OutPoint, ScriptBuf, Sequence, TxIn, TxOut, Witness,
 };
 use bitcoin::{Block, Network, Transaction};
-use metashrew_core::index_pointer::IndexPointer;
 #[allow(unused_imports)]
 use metashrew_core::{
-    clear as clear_base, println,
+    println,
     stdio::{stdout, Write},
 };
+use metashrew_native::index_pointer::IndexPointer;
 use metashrew_support::index_pointer::KeyValuePointer;
 use metashrew_support::utils::consensus_encode;
 use ordinals::{Etching, Rune, Runestone};
@@ -90,7 +91,7 @@ pub fn configure_network() {
 }
 
 pub fn clear() {
-    clear_base();
+    metashrew_native::clear();
     configure_network();
 }
 
@@ -112,6 +113,49 @@ pub fn init_test_with_cellpack(cellpack: Cellpack) -> Block {
     test_block
 }
 
+/// Where the first alkane deployed with `[1, 0]` on a cleared chain lands,
+/// which is where [`deploy_test_alkane`] puts the test alkane.
+#[cfg(test)]
+pub const TEST_ALKANE: AlkaneId = AlkaneId { block: 2, tx: 1 };
+
+/// A call to [`TEST_ALKANE`] with `inputs`, e.g. `[104, n]` to set
+/// `/claimablefees` to `n`.
+#[cfg(test)]
+pub fn test_alkane_call(inputs: Vec<u128>) -> BinaryAndCellpack {
+    BinaryAndCellpack::cellpack_only(Cellpack {
+        target: TEST_ALKANE,
+        inputs,
+    })
+}
+
+/// Deploys the test alkane to [`TEST_ALKANE`], followed by a call for each
+/// of `calls`.
+#[cfg(test)]
+pub fn deploy_test_alkane(calls: Vec<Vec<u128>>) -> Block {
+    let mut pairs = vec![BinaryAndCellpack::new(
+        alkanes_std_test_build::get_bytes(),
+        Cellpack {
+            target: AlkaneId { block: 1, tx: 0 },
+            inputs: vec![0],
+        },
+    )];
+    pairs.extend(calls.into_iter().map(test_alkane_call));
+    init_with_cellpack_pairs(pairs)
+}
+
+/// Calls [`TEST_ALKANE`] with `inputs`, spending the first output of the last
+/// transaction in `previous`.
+#[cfg(test)]
+pub fn call_test_alkane_w_input(inputs: Vec<u128>, previous: &Block) -> Block {
+    init_with_cellpack_pairs_w_input(
+        vec![test_alkane_call(inputs)],
+        OutPoint {
+            txid: previous.txdata.last().unwrap().compute_txid(),
+            vout: 0,
+        },
+    )
+}
+
 /// A struct that combines a binary and its corresponding cellpack for cleaner initialization
 #[derive(Debug, Clone)]
 pub struct BinaryAndCellpack {
@@ -676,6 +720,28 @@ pub fn assert_revert_context(outpoint: &OutPoint, expected_error_message: &str)
     Ok(())
 }
 
+/// Asserts the last event reverted with a structured error of `code`, and
+/// returns the decoded error so its params can be checked.
+pub fn assert_revert_error(outpoint: &OutPoint, code: u32) -> Result<StructuredError> {
+    match get_trace_event_at_index(outpoint, None)? {
+        TraceEvent::RevertContext(trace_response) => {
+            let error = StructuredError::decode_revert_data(&trace_response.inner.data)
+                .unwrap_or_else(|| {
+                    panic!(
+                        "Expected a structured error, got: '{}'",
+                        String::from_utf8_lossy(&trace_response.inner.data)
+                    )
+                });
+            assert_eq!(error.code, code, "Unexpected error code in {:?}", error);
+            Ok(error)
+        }
+        event => panic!(
+            "Expected RevertContext variant, but got a different variant: {:?}",
+            event
+        ),
+    }
+}
+
 pub fn assert_revert_context_at_index(
     outpoint: &OutPoint,
     expected_error_message: &str,
@@ -0,0 +1,103 @@
+use crate::index_block;
+use crate::tests::helpers::{self as alkane_helpers, clear};
+use crate::tests::std::alkanes_std_test_build;
+use crate::utils::alkane_holders_pointer;
+use crate::view;
+use alkanes_support::cellpack::Cellpack;
+use alkanes_support::id::AlkaneId;
+use alkanes_support::proto::alkanes::{HoldersOfAlkaneRequest, HoldersOfAlkaneResponse};
+use anyhow::Result;
+use bitcoin::OutPoint;
+use metashrew_native::index_pointer::IndexPointer;
+use metashrew_support::index_pointer::KeyValuePointer;
+use protorune::test_helpers::create_block_with_coinbase_tx;
+use wasm_bindgen_test::wasm_bindgen_test;
+
+const TOKEN: AlkaneId = AlkaneId { block: 2, tx: 1 };
+const VAULT: AlkaneId = AlkaneId { block: 2, tx: 2 };
+
+/// Deploys two test alkanes at height 1, the first minting 1000 of itself
+/// to an output the second deploy spends and forwards. At height 2 that
+/// output is spent into the second alkane's `donate`, which keeps it.
+fn index_donation() -> Result<()> {
+    clear();
+    index_block(&create_block_with_coinbase_tx(0), 0)?;
+    let deploy = alkane_helpers::init_with_multiple_cellpacks_with_tx(
+        vec![
+            alkanes_std_test_build::get_bytes(),
+            alkanes_std_test_build::get_bytes(),
+        ],
+        vec![
+            Cellpack {
+                target: AlkaneId { block: 1, tx: 0 },
+                inputs: vec![22, 1000],
+            },
+            Cellpack {
+                target: AlkaneId { block: 1, tx: 0 },
+                inputs: vec![0],
+            },
+        ],
+    );
+    index_block(&deploy, 1)?;
+    let donate = alkane_helpers::init_with_multiple_cellpacks_with_tx_w_input(
+        vec![vec![]],
+        vec![Cellpack {
+            target: VAULT,
+            inputs: vec![7],
+        }],
+        Some(OutPoint {
+            txid: deploy.txdata.last().unwrap().compute_txid(),
+            vout: 0,
+        }),
+    );
+    index_block(&donate, 2)?;
+    Ok(())
+}
+
+fn holders(id: AlkaneId, cursor: u64, limit: u32, height: u64) -> Result<HoldersOfAlkaneResponse> {
+    view::holders_of_alkane(
+        &HoldersOfAlkaneRequest {
+            id: Some(id.into()),
+            cursor,
+            limit,
+        },
+        height,
+    )
+}
+
+fn listed(response: &HoldersOfAlkaneResponse) -> Vec<(AlkaneId, u128)> {
+    response
+        .holders
+        .iter()
+        .map(|h| {
+            (
+                h.holder.clone().unwrap().into(),
+                h.balance.clone().unwrap().into(),
+            )
+        })
+        .collect()
+}
+
+#[wasm_bindgen_test]
+fn test_holders_of_alkane_lists_contract_balances() -> Result<()> {
+    index_donation()?;
+
+    let at_tip = holders(TOKEN, 0, 0, 2)?;
+    assert_eq!(listed(&at_tip), vec![(VAULT, 1000)]);
+    assert_eq!(at_tip.next_cursor, 0);
+
+    // the vault was credited the token while being deployed at height 1,
+    // but forwarded it on
+    assert!(holders(TOKEN, 0, 0, 1)?.holders.is_empty());
+    assert!(holders(VAULT, 0, 0, 2)?.holders.is_empty());
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_holders_are_listed_once() -> Result<()> {
+    index_donation()?;
+    let listed = alkane_holders_pointer(&IndexPointer::default(), &TOKEN);
+    assert_eq!(listed.length(), 1);
+    assert_eq!(holders(TOKEN, 0, 1, 2)?.next_cursor, 0);
+    Ok(())
+}
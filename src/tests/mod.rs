@@ -62,6 +62,28 @@ pub mod view;
 #[cfg(test)]
 pub mod getstorageat;
 #[cfg(test)]
+pub mod getstoragekeys;
+#[cfg(test)]
+pub mod events;
+#[cfg(test)]
+pub mod trace_diff;
+#[cfg(test)]
+pub mod holders;
+#[cfg(test)]
+pub mod block_stats;
+#[cfg(test)]
+pub mod structured_errors;
+#[cfg(test)]
+pub mod reentrancy;
+#[cfg(test)]
+pub mod vault_token;
+#[cfg(test)]
+pub mod amm;
+#[cfg(test)]
+pub mod fuel_profile;
+#[cfg(test)]
+pub mod native_parity;
+#[cfg(test)]
 pub mod simulatetransaction;
 #[cfg(test)]
 pub mod freeze_poc;
@@ -0,0 +1,140 @@
+//! Indexes the same fixture blocks through both storage backends. The module
+//! is built with and without the `native` feature: without it the indexer
+//! stores through `metashrew_core`, as `alkanes.wasm` does, and with it
+//! through `metashrew_native`. Both builds assert against the same
+//! [`expected`] state, so the backends cannot drift apart unnoticed.
+
+use crate::index_block;
+#[cfg(feature = "native")]
+use crate::native::NativeIndexer;
+use crate::tests::helpers::{call_test_alkane_w_input, clear, deploy_test_alkane, TEST_ALKANE};
+use crate::tests::std::alkanes_std_test_build;
+use crate::view;
+use alkanes_support::proto::alkanes::{
+    AlkaneStorageKeyEntry, AlkaneStorageKeysRequest, AlkaneStorageKeysResponse, BytecodeRequest,
+};
+use anyhow::Result;
+use bitcoin::Block;
+#[cfg(feature = "native")]
+use metashrew_native::{KeyValueStore, MemoryStore};
+use prost::Message;
+use protorune::test_helpers::create_block_with_coinbase_tx;
+#[cfg(feature = "native")]
+use std::sync::Arc;
+use wasm_bindgen_test::wasm_bindgen_test;
+
+/// Deploys the test alkane at height 1 and rewrites its storage at height 2.
+fn fixture_blocks() -> Vec<Block> {
+    let deploy = deploy_test_alkane(vec![vec![104, 10]]);
+    let update = call_test_alkane_w_input(vec![104, 20], &deploy);
+    vec![create_block_with_coinbase_tx(0), deploy, update]
+}
+
+/// What the parity test compares across backends.
+fn observe() -> Result<(AlkaneStorageKeysResponse, Vec<u8>)> {
+    let keys = view::getstoragekeys(
+        &AlkaneStorageKeysRequest {
+            id: Some(TEST_ALKANE.into()),
+            prefix: vec![],
+            cursor: 0,
+            limit: 0,
+        },
+        u32::MAX.into(),
+    )?;
+    let bytecode = view::getbytecode(
+        &BytecodeRequest {
+            id: Some(TEST_ALKANE.into()),
+        }
+        .encode_to_vec(),
+        2,
+    )?;
+    Ok((keys, bytecode))
+}
+
+/// What [`observe`] must return after indexing [`fixture_blocks`], whichever
+/// backend did the indexing.
+fn expected() -> (AlkaneStorageKeysResponse, Vec<u8>) {
+    let entry = |key: &str, value: Vec<u8>, height: u64| AlkaneStorageKeyEntry {
+        key: key.as_bytes().to_vec(),
+        value,
+        last_modified_height: height,
+    };
+    let keys = AlkaneStorageKeysResponse {
+        entries: vec![
+            entry("/initialized", vec![1], 1),
+            entry("/claimablefees", 20u128.to_le_bytes().to_vec(), 2),
+        ],
+        next_cursor: 0,
+    };
+    (keys, alkanes_std_test_build::get_bytes())
+}
+
+#[wasm_bindgen_test]
+fn test_cache_indexing_matches_expected_state() -> Result<()> {
+    clear();
+    for (height, block) in fixture_blocks().iter().enumerate() {
+        index_block(block, height as u32)?;
+    }
+    assert_eq!(observe()?, expected());
+    Ok(())
+}
+
+#[cfg(feature = "native")]
+fn index_native(store: Arc<dyn KeyValueStore>, blocks: &[Block]) -> Result<NativeIndexer> {
+    let indexer = NativeIndexer::new(store);
+    for (height, block) in blocks.iter().enumerate() {
+        indexer.index_block(block, height as u32)?;
+    }
+    Ok(indexer)
+}
+
+#[cfg(feature = "native")]
+#[wasm_bindgen_test]
+fn test_native_memory_store_matches_expected_state() -> Result<()> {
+    let expected = expected();
+    let store = Arc::new(MemoryStore::new());
+    let indexer = index_native(store.clone(), &fixture_blocks())?;
+    assert_eq!(indexer.tip()?, Some(2));
+    assert!(!store.is_empty());
+    assert_eq!(indexer.view(observe)?, expected);
+
+    // A fresh indexer over the same store sees the same state.
+    let reopened = NativeIndexer::new(store);
+    assert_eq!(reopened.view(observe)?, expected);
+    Ok(())
+}
+
+#[cfg(feature = "native")]
+#[wasm_bindgen_test]
+fn test_native_indexer_rejects_out_of_order_blocks() -> Result<()> {
+    let blocks = fixture_blocks();
+    let store = Arc::new(MemoryStore::new());
+    let indexer = index_native(store.clone(), &blocks[..2])?;
+    let before = store.entries()?;
+    assert!(indexer.index_block(&blocks[2], 3).is_err());
+    assert!(indexer.index_block(&blocks[1], 1).is_err());
+    assert_eq!(store.entries()?, before);
+    Ok(())
+}
+
+#[cfg(feature = "rocksdb")]
+#[wasm_bindgen_test]
+fn test_native_rocksdb_store_matches_memory_store() -> Result<()> {
+    use metashrew_native::RocksDbStore;
+
+    let blocks = fixture_blocks();
+    let memory = Arc::new(MemoryStore::new());
+    let expected = index_native(memory.clone(), &blocks)?.view(observe)?;
+
+    let path = std::env::temp_dir().join(format!("alkanes-native-parity-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&path);
+    let result = (|| -> Result<()> {
+        let rocks = Arc::new(RocksDbStore::open(&path)?);
+        let indexer = index_native(rocks.clone(), &blocks)?;
+        assert_eq!(indexer.view(observe)?, expected);
+        assert_eq!(rocks.entries()?, memory.entries()?);
+        Ok(())
+    })();
+    let _ = std::fs::remove_dir_all(&path);
+    result
+}
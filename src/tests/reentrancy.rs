@@ -0,0 +1,85 @@
+use crate::index_block;
+use crate::tests::helpers::{self as alkane_helpers, clear, BinaryAndCellpack};
+use crate::tests::std::alkanes_std_test_build;
+use alkanes_support::cellpack::Cellpack;
+use alkanes_support::id::AlkaneId;
+use anyhow::Result;
+use bitcoin::OutPoint;
+use protorune::test_helpers::create_block_with_coinbase_tx;
+use wasm_bindgen_test::wasm_bindgen_test;
+
+const TEST_ALKANE: AlkaneId = AlkaneId { block: 2, tx: 1 };
+
+// opcode 123 is `#[nonreentrant]` and calls `target` with `inputs`; opcode
+// 31 makes the same call unguarded
+const GUARDED_EXT_CALL: u128 = 123;
+const EXT_CALL: u128 = 31;
+
+fn deploy() -> Result<()> {
+    clear();
+    index_block(&create_block_with_coinbase_tx(0), 0)?;
+    let deploy = alkane_helpers::init_with_cellpack_pairs(vec![BinaryAndCellpack::new(
+        alkanes_std_test_build::get_bytes(),
+        Cellpack {
+            target: AlkaneId { block: 1, tx: 0 },
+            inputs: vec![0],
+        },
+    )]);
+    index_block(&deploy, 1)?;
+    Ok(())
+}
+
+/// Inputs for `opcode` making the test alkane call itself with `inputs`.
+fn call_inputs(opcode: u128, inputs: Vec<u128>) -> Vec<u128> {
+    let mut encoded = vec![opcode, TEST_ALKANE.block, TEST_ALKANE.tx, inputs.len() as u128];
+    encoded.extend(inputs);
+    encoded
+}
+
+fn call(inputs: Vec<u128>, height: u32) -> Result<OutPoint> {
+    let block = alkane_helpers::init_with_cellpack_pairs(vec![BinaryAndCellpack::cellpack_only(
+        Cellpack {
+            target: TEST_ALKANE,
+            inputs,
+        },
+    )]);
+    index_block(&block, height)?;
+    Ok(OutPoint {
+        txid: block.txdata.last().unwrap().compute_txid(),
+        vout: 3,
+    })
+}
+
+#[wasm_bindgen_test]
+fn test_nonreentrant_blocks_direct_reentry() -> Result<()> {
+    deploy()?;
+    let inner = call_inputs(GUARDED_EXT_CALL, vec![]);
+    let outpoint = call(call_inputs(GUARDED_EXT_CALL, inner), 2)?;
+    alkane_helpers::assert_revert_context(&outpoint, "ReentrancyGuard: reentrant call")?;
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_nonreentrant_blocks_reentry_through_another_frame() -> Result<()> {
+    deploy()?;
+    // guarded -> unguarded -> guarded, all on the same alkane
+    let innermost = call_inputs(GUARDED_EXT_CALL, vec![]);
+    let middle = call_inputs(EXT_CALL, innermost);
+    let outpoint = call(call_inputs(GUARDED_EXT_CALL, middle), 2)?;
+    alkane_helpers::assert_revert_context(&outpoint, "ReentrancyGuard: reentrant call")?;
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_nonreentrant_allows_unguarded_reentry_and_releases_lock() -> Result<()> {
+    deploy()?;
+    // opcode 99 returns fixed data and isn't guarded
+    for height in 2..4 {
+        let outpoint = call(call_inputs(GUARDED_EXT_CALL, vec![99]), height)?;
+        alkane_helpers::assert_return_context(&outpoint, |trace_response| {
+            assert_eq!(trace_response.inner.data, vec![0x01, 0x02, 0x03, 0x04]);
+            Ok(())
+        })?;
+    }
+    Ok(())
+}
@@ -298,6 +298,7 @@ mod tests {
             transaction_bytes: None,
             block_bytes: None,
             storage_overrides: vec![],
+            balance_overrides: vec![],
         })?;
 
         // Step 4: assertions on response shape.
@@ -461,6 +462,7 @@ mod tests {
             height: 1,
             block_bytes,
             storage_overrides: vec![],
+            balance_overrides: vec![],
         })?;
 
         assert!(response.error.is_none(), "simulate_block error: {:?}", response.error);
@@ -542,6 +544,7 @@ mod tests {
             transaction_bytes: None,
             block_bytes: None,
             storage_overrides: overrides,
+            balance_overrides: vec![],
         })?;
 
         assert!(
@@ -552,4 +555,272 @@ mod tests {
         assert!(!response.protostones.is_empty());
         Ok(())
     }
+
+    /// `simulate_chain`: three pending txs, each spending the previous
+    /// one's output 0, starting from an output that holds 1000 of the
+    /// test alkane. Every tx forwards what it spends, so the balance must
+    /// travel down the chain through the shared sandbox; simulated alone,
+    /// the last tx's input holds nothing.
+    #[wasm_bindgen_test]
+    #[cfg_attr(not(target_arch = "wasm32"), test)]
+    fn simulate_chain_carries_balances_between_pending_txs() -> Result<()> {
+        use crate::view::{simulate_chain, SimulateChainInput};
+        use protorune_support::balance_sheet::ProtoruneRuneId;
+
+        clear();
+
+        // Deploy the test alkane (2:0), self-minting 1000 to output 0.
+        let setup_block: Block = alkane_helpers::init_with_multiple_cellpacks(
+            alkanes_std_test_build::get_bytes(),
+            vec![Cellpack {
+                target: AlkaneId { block: 1, tx: 0 },
+                inputs: vec![22, 1000],
+            }],
+        );
+        index_block(&setup_block, 0)?;
+        let test_alkane = AlkaneId { block: 2, tx: 0 };
+        let minted = ProtoruneRuneId { block: 2, tx: 0 };
+
+        let mut prev = OutPoint {
+            txid: setup_block.txdata[1].compute_txid(),
+            vout: 0,
+        };
+        let mut pending = vec![];
+        for _ in 0..3 {
+            let tx = build_invoke_tx(test_alkane, 99, prev);
+            prev = OutPoint {
+                txid: tx.compute_txid(),
+                vout: 0,
+            };
+            pending.push(tx);
+        }
+
+        let table = RuneTable::for_protocol(AlkaneMessageContext::protocol_tag());
+        let pending_key = consensus_encode(&OutPoint {
+            txid: pending[0].compute_txid(),
+            vout: 0,
+        })?;
+        let pre_state = table.OUTPOINT_TO_RUNES.select(&pending_key).get();
+
+        let response = simulate_chain(SimulateChainInput {
+            height: 1,
+            transactions: pending.iter().map(serialize).collect(),
+            storage_overrides: vec![],
+            balance_overrides: vec![],
+        })?;
+
+        assert!(response.error.is_none(), "simulate_chain error: {:?}", response.error);
+        assert_eq!(response.txs.len(), 3);
+        let mut prev_block: Option<Block> = None;
+        for (i, (tx, simulated)) in pending.iter().zip(&response.txs).enumerate() {
+            assert!(simulated.error.is_none(), "tx {} failed: {:?}", i, simulated.error);
+            assert_eq!(simulated.txid, tx.compute_txid().to_string());
+            assert_eq!(simulated.height, 1 + i as u64);
+            let vout0 = simulated
+                .final_balances_by_vout
+                .iter()
+                .find(|vb| vb.vout == 0)
+                .expect("tx should route its balance to vout 0");
+            assert_eq!(vout0.runes, vec![(minted.clone(), 1000)], "tx {}", i);
+
+            // Each faux block chains off the previous one.
+            let block: Block = bitcoin::consensus::deserialize(&simulated.used_block_bytes)?;
+            assert_eq!(&block.txdata[1], tx);
+            if let Some(prev_block) = &prev_block {
+                assert_eq!(block.header.prev_blockhash, prev_block.block_hash());
+            }
+            prev_block = Some(block);
+        }
+        let summed: u64 = response.txs.iter().map(|t| t.total_fuel_used).sum();
+        assert_eq!(response.total_fuel_used, summed);
+
+        // Without its parents the last tx spends an empty outpoint.
+        let alone = simulate_transaction(&hex::encode(serialize(&pending[2])), 1)?;
+        assert!(alone
+            .final_balances_by_vout
+            .iter()
+            .all(|vb| vb.runes.iter().all(|(_, amount)| *amount == 0)));
+
+        // Zero on-disk side effects.
+        assert_eq!(table.OUTPOINT_TO_RUNES.select(&pending_key).get(), pre_state);
+        Ok(())
+    }
+
+    /// A balance override gives an outpoint the tx spends exactly the
+    /// listed alkanes: an empty outpoint gets funded, and a funded one is
+    /// replaced rather than topped up. Nothing reaches the live index.
+    #[wasm_bindgen_test]
+    #[cfg_attr(not(target_arch = "wasm32"), test)]
+    fn simulate_transaction_applies_balance_overrides() -> Result<()> {
+        use crate::view::simulate_transaction_with_overrides;
+        use alkanes_support::parcel::AlkaneTransfer;
+        use bitcoin::hashes::Hash;
+        use protorune::balance_sheet::load_sheet;
+        use protorune_support::balance_sheet::{BalanceSheetOperations, ProtoruneRuneId};
+
+        clear();
+
+        let setup_block: Block = alkane_helpers::init_with_multiple_cellpacks(
+            alkanes_std_test_build::get_bytes(),
+            vec![Cellpack {
+                target: AlkaneId { block: 1, tx: 0 },
+                inputs: vec![22, 1000],
+            }],
+        );
+        index_block(&setup_block, 0)?;
+        let test_alkane = AlkaneId { block: 2, tx: 0 };
+        let minted = ProtoruneRuneId { block: 2, tx: 0 };
+        let vout0_balance = |r: &crate::view::SimulateTransactionResponseNative| {
+            r.final_balances_by_vout
+                .iter()
+                .find(|vb| vb.vout == 0)
+                .map(|vb| vb.runes.clone())
+                .unwrap_or_default()
+        };
+
+        // An outpoint nothing was ever sent to.
+        let empty = OutPoint {
+            txid: bitcoin::Txid::from_byte_array([0xab; 32]),
+            vout: 3,
+        };
+        let tx = build_invoke_tx(test_alkane, 99, empty);
+        let funded = simulate_transaction_with_overrides(
+            &hex::encode(serialize(&tx)),
+            1,
+            vec![],
+            vec![(
+                empty,
+                vec![AlkaneTransfer {
+                    id: test_alkane,
+                    value: 1000,
+                }],
+            )],
+        )?;
+        assert!(funded.error.is_none(), "{:?}", funded.error);
+        assert_eq!(vout0_balance(&funded), vec![(minted.clone(), 1000)]);
+
+        // The deploy output really holds 1000; the override says 5.
+        let minted_at = OutPoint {
+            txid: setup_block.txdata[1].compute_txid(),
+            vout: 0,
+        };
+        let tx = build_invoke_tx(test_alkane, 99, minted_at);
+        let replaced = simulate_transaction_with_overrides(
+            &hex::encode(serialize(&tx)),
+            1,
+            vec![],
+            vec![(
+                minted_at,
+                vec![AlkaneTransfer {
+                    id: test_alkane,
+                    value: 5,
+                }],
+            )],
+        )?;
+        assert!(replaced.error.is_none(), "{:?}", replaced.error);
+        assert_eq!(vout0_balance(&replaced), vec![(minted.clone(), 5)]);
+
+        let table = RuneTable::for_protocol(AlkaneMessageContext::protocol_tag());
+        let live = load_sheet(&table.OUTPOINT_TO_RUNES.select(&consensus_encode(&minted_at)?));
+        assert_eq!(live.get(&minted), 1000);
+        let live = load_sheet(&table.OUTPOINT_TO_RUNES.select(&consensus_encode(&empty)?));
+        assert_eq!(live.get(&minted), 0);
+        Ok(())
+    }
+
+    /// `estimatefuel` reports the fuel the top-level call used, what the
+    /// tx is allocated next to the previous block, and that the minimum
+    /// allocation is enough for a no-op call.
+    #[wasm_bindgen_test]
+    #[cfg_attr(not(target_arch = "wasm32"), test)]
+    fn estimate_fuel_reports_fuel_used_against_allocation() -> Result<()> {
+        use crate::view::estimate_fuel;
+        use crate::vm::fuel::{minimum_fuel, minimum_vfsize_for, total_fuel, VirtualFuelBytes};
+        use alkanes_support::proto::alkanes::EstimateFuelRequest;
+
+        clear();
+        let setup_block: Block = alkane_helpers::init_with_multiple_cellpacks(
+            alkanes_std_test_build::get_bytes(),
+            vec![Cellpack {
+                target: AlkaneId { block: 1, tx: 0 },
+                inputs: vec![78],
+            }],
+        );
+        index_block(&setup_block, 0)?;
+        let tx = build_invoke_tx(
+            AlkaneId { block: 2, tx: 0 },
+            0,
+            OutPoint {
+                txid: setup_block.txdata[1].compute_txid(),
+                vout: 0,
+            },
+        );
+
+        let estimate = estimate_fuel(&EstimateFuelRequest {
+            height: 1,
+            transaction: serialize(&tx),
+            ..Default::default()
+        })?;
+        assert!(estimate.error.is_empty(), "{}", estimate.error);
+        assert_eq!(estimate.protostones.len(), 1);
+        assert!(!estimate.protostones[0].reverted);
+        assert!(estimate.fuel_used > 0);
+        assert_eq!(estimate.fuel_used, estimate.protostones[0].fuel_used);
+        assert_eq!(
+            estimate.fuel_used,
+            simulate_transaction(&hex::encode(serialize(&tx)), 1)?.total_fuel_used
+        );
+        assert_eq!(estimate.block_vfsize, setup_block.vfsize());
+        assert_eq!(estimate.transaction_vfsize, tx.vfsize());
+        assert_eq!(estimate.minimum_fuel, minimum_fuel(1));
+        assert_eq!(estimate.block_fuel, total_fuel(1));
+        assert_eq!(estimate.allocated_fuel, minimum_fuel(1));
+        assert!(estimate.satisfiable);
+        assert_eq!(estimate.minimum_vfsize, 0);
+
+        // Regtest always allocates the minimum, so nothing above it fits.
+        assert_eq!(minimum_vfsize_for(minimum_fuel(1) + 1, 0, 1), None);
+        Ok(())
+    }
+
+    /// A reverted protostone's fuel is not traced, so `estimatefuel` fails
+    /// rather than report it as free.
+    #[wasm_bindgen_test]
+    #[cfg_attr(not(target_arch = "wasm32"), test)]
+    fn estimate_fuel_fails_on_revert() -> Result<()> {
+        use crate::view::estimate_fuel;
+        use alkanes_support::proto::alkanes::EstimateFuelRequest;
+
+        clear();
+        let setup_block: Block = alkane_helpers::init_with_multiple_cellpacks(
+            alkanes_std_test_build::get_bytes(),
+            vec![Cellpack {
+                target: AlkaneId { block: 1, tx: 0 },
+                inputs: vec![78],
+            }],
+        );
+        index_block(&setup_block, 0)?;
+        let tx = build_invoke_tx(
+            AlkaneId { block: 2, tx: 0 },
+            100,
+            OutPoint {
+                txid: setup_block.txdata[1].compute_txid(),
+                vout: 0,
+            },
+        );
+
+        let estimate = estimate_fuel(&EstimateFuelRequest {
+            height: 1,
+            transaction: serialize(&tx),
+            ..Default::default()
+        })?;
+        assert!(estimate.protostones[0].reverted);
+        assert!(
+            estimate.error.starts_with("protostone 0 reverted"),
+            "{}",
+            estimate.error
+        );
+        assert!(estimate.error.contains("Revert"), "{}", estimate.error);
+        Ok(())
+    }
 }
@@ -0,0 +1,72 @@
+use crate::index_block;
+use crate::tests::helpers::{self as alkane_helpers, clear, BinaryAndCellpack};
+use crate::tests::std::alkanes_std_test_build;
+use crate::vm::instance::AlkanesInstance;
+use crate::vm::runtime::AlkanesRuntimeContext;
+use alkanes_support::cellpack::Cellpack;
+use alkanes_support::error::ErrorValue;
+use alkanes_support::id::AlkaneId;
+use anyhow::Result;
+use bitcoin::OutPoint;
+use protorune::test_helpers::create_block_with_coinbase_tx;
+use serde_json::Value;
+use std::sync::{Arc, Mutex};
+use wasm_bindgen_test::wasm_bindgen_test;
+
+const TEST_ALKANE: AlkaneId = AlkaneId { block: 2, tx: 1 };
+
+#[wasm_bindgen_test]
+fn test_structured_revert_is_decoded_from_trace() -> Result<()> {
+    clear();
+    index_block(&create_block_with_coinbase_tx(0), 0)?;
+    let deploy = alkane_helpers::init_with_cellpack_pairs(vec![BinaryAndCellpack::new(
+        alkanes_std_test_build::get_bytes(),
+        Cellpack {
+            target: AlkaneId { block: 1, tx: 0 },
+            inputs: vec![0],
+        },
+    )]);
+    index_block(&deploy, 1)?;
+    let call = alkane_helpers::init_with_cellpack_pairs(vec![BinaryAndCellpack::cellpack_only(
+        Cellpack {
+            target: TEST_ALKANE,
+            inputs: vec![122, 100, 3],
+        },
+    )]);
+    index_block(&call, 2)?;
+
+    let outpoint = OutPoint {
+        txid: call.txdata.last().unwrap().compute_txid(),
+        vout: 3,
+    };
+    let error = alkane_helpers::assert_revert_error(&outpoint, 4097)?;
+    assert_eq!(error.name, "InsufficientLiquidity");
+    assert_eq!(error.param("needed"), Some(&ErrorValue::Uint(100)));
+    assert_eq!(error.param("have"), Some(&ErrorValue::Uint(3)));
+    // the message still reads as a string revert
+    alkane_helpers::assert_revert_context(
+        &outpoint,
+        "InsufficientLiquidity { needed: 100, have: 3 }",
+    )?;
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_errors_are_exported_in_meta() -> Result<()> {
+    clear();
+    let context = Arc::new(Mutex::new(AlkanesRuntimeContext::default()));
+    let mut instance = AlkanesInstance::from_alkane(
+        context,
+        Arc::new(alkanes_std_test_build::get_bytes()),
+        100000000,
+    )?;
+    let abi: Value = serde_json::from_slice(&instance.call_meta()?)?;
+    assert_eq!(abi["contract"], "LoggerAlkane");
+    assert!(abi["methods"].is_array());
+    let error = &abi["errors"][0];
+    assert_eq!(error["name"], "InsufficientLiquidity");
+    assert_eq!(error["code"], 4097);
+    assert_eq!(error["params"][0]["name"], "needed");
+    assert_eq!(error["params"][0]["type"], "u128");
+    Ok(())
+}
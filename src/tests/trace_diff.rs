@@ -0,0 +1,108 @@
+use crate::index_block;
+use crate::tests::helpers::{self as alkane_helpers, clear};
+use crate::tests::std::alkanes_std_test_build;
+use crate::view;
+use alkanes_support::cellpack::Cellpack;
+use alkanes_support::id::AlkaneId;
+use alkanes_support::parcel::AlkaneTransfer;
+use alkanes_support::trace::{OutputDiff, StateDiff, StorageDiff, StorageWrite};
+use anyhow::Result;
+use bitcoin::{Block, OutPoint, Transaction};
+use protorune::test_helpers::create_block_with_coinbase_tx;
+use wasm_bindgen_test::wasm_bindgen_test;
+
+const TEST_ALKANE: AlkaneId = AlkaneId { block: 2, tx: 1 };
+
+fn diff_of(tx: &Transaction) -> Result<StateDiff> {
+    StateDiff::try_from(view::tracediff(&OutPoint {
+        txid: tx.compute_txid(),
+        vout: tx.output.len() as u32 + 1,
+    })?)
+}
+
+/// Deploys the test alkane at height 1, minting 1000 of it to the deploy's
+/// first output, then runs `inputs` against it at height 2 spending that
+/// output.
+fn index_call(inputs: Vec<u128>) -> Result<(Block, Block)> {
+    clear();
+    index_block(&create_block_with_coinbase_tx(0), 0)?;
+    let deploy = alkane_helpers::init_with_multiple_cellpacks_with_tx(
+        vec![alkanes_std_test_build::get_bytes()],
+        vec![Cellpack {
+            target: AlkaneId { block: 1, tx: 0 },
+            inputs: vec![22, 1000],
+        }],
+    );
+    index_block(&deploy, 1)?;
+    let call = alkane_helpers::init_with_multiple_cellpacks_with_tx_w_input(
+        vec![vec![]],
+        vec![Cellpack {
+            target: TEST_ALKANE,
+            inputs,
+        }],
+        Some(OutPoint {
+            txid: deploy.txdata.last().unwrap().compute_txid(),
+            vout: 0,
+        }),
+    );
+    index_block(&call, 2)?;
+    Ok((deploy, call))
+}
+
+#[wasm_bindgen_test]
+fn test_trace_diff_records_creation_storage_and_outputs() -> Result<()> {
+    let (deploy, call) = index_call(vec![104, 20])?;
+
+    let deployed = diff_of(deploy.txdata.last().unwrap())?;
+    assert_eq!(deployed.created, vec![TEST_ALKANE]);
+    assert!(deployed.outputs.contains(&OutputDiff {
+        vout: 0,
+        credited: vec![AlkaneTransfer {
+            id: TEST_ALKANE,
+            value: 1000,
+        }],
+        debited: vec![],
+    }));
+
+    let called = diff_of(call.txdata.last().unwrap())?;
+    assert!(called.created.is_empty());
+    assert_eq!(
+        called.storage,
+        vec![StorageDiff {
+            alkane: TEST_ALKANE,
+            writes: vec![StorageWrite {
+                key: b"/claimablefees".to_vec(),
+                old_value: vec![],
+                new_value: 20u128.to_le_bytes().to_vec(),
+            }],
+        }]
+    );
+    let tx = call.txdata.last().unwrap();
+    let transfer = vec![AlkaneTransfer {
+        id: TEST_ALKANE,
+        value: 1000,
+    }];
+    assert_eq!(
+        called.outputs,
+        vec![
+            OutputDiff {
+                vout: 0,
+                credited: transfer.clone(),
+                debited: vec![],
+            },
+            OutputDiff {
+                vout: tx.output.len() as u32 + 1,
+                credited: vec![],
+                debited: transfer,
+            },
+        ]
+    );
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_trace_diff_is_empty_for_reverted_message() -> Result<()> {
+    let (_, call) = index_call(vec![100])?;
+    assert!(diff_of(call.txdata.last().unwrap())?.is_empty());
+    Ok(())
+}
@@ -4,6 +4,7 @@ use crate::tests::std::{
     alkanes_std_auth_token_build, alkanes_std_beacon_proxy_build, alkanes_std_test_2_build,
     alkanes_std_test_build, alkanes_std_upgradeable_beacon_build, alkanes_std_upgradeable_build,
 };
+use crate::upgrades::{self, ProxyKind, Upgrade};
 use alkane_helpers::clear;
 use alkanes::view;
 use alkanes::vm::utils::sequence_pointer;
@@ -24,6 +25,7 @@ use metashrew_support::index_pointer::KeyValuePointer;
 use protorune::test_helpers::{create_block_with_coinbase_tx, create_coinbase_transaction};
 use protorune_support::balance_sheet::ProtoruneRuneId;
 use protorune_support::utils::consensus_decode;
+use sha2::{Digest, Sha256};
 use wasm_bindgen_test::wasm_bindgen_test;
 
 pub const BEACON_ID: u128 = 0xbeac0;
@@ -377,3 +379,81 @@ fn test_beacon_proxy() -> Result<()> {
     check_after_upgrade(0, proxy_sequence_1)?;
     check_after_upgrade(0, proxy_sequence_2)
 }
+
+#[wasm_bindgen_test]
+fn test_resolve_upgraded_proxy() -> Result<()> {
+    setup_env()?;
+    let (init_block, proxy_sequence) = deploy_upgradeable_proxy(
+        alkanes_std_upgradeable_build::get_bytes(),
+        0,
+        AlkaneId { block: 2, tx: 1 },
+    )?;
+    let proxy = AlkaneId {
+        block: 2,
+        tx: proxy_sequence,
+    };
+    let before = upgrades::resolve(&proxy, 0)?;
+    assert_eq!(before.chain, vec![(proxy, ProxyKind::Upgradeable)]);
+    assert_eq!(before.implementation, AlkaneId { block: 2, tx: 1 });
+    assert!(before.upgrades.is_empty());
+
+    upgrade_implementation(
+        1,
+        OutPoint {
+            txid: init_block.txdata[init_block.txdata.len() - 1].compute_txid(),
+            vout: 0,
+        },
+        proxy,
+    )?;
+    let after = upgrades::resolve(&proxy, 1)?;
+    assert_eq!(after.implementation, AlkaneId { block: 2, tx: 2 });
+    assert_eq!(
+        after.bytecode_hash,
+        <[u8; 32]>::from(Sha256::digest(alkanes_std_test_2_build::get_bytes()))
+    );
+    assert_eq!(
+        after.upgrades,
+        vec![Upgrade {
+            alkane: proxy,
+            height: 1,
+            old_implementation: AlkaneId { block: 2, tx: 1 },
+            new_implementation: AlkaneId { block: 2, tx: 2 },
+        }]
+    );
+    // as of the deploy height the upgrade hasn't happened yet
+    let at_deploy = upgrades::resolve(&proxy, 0)?;
+    assert_eq!(at_deploy.implementation, AlkaneId { block: 2, tx: 1 });
+    assert!(at_deploy.upgrades.is_empty());
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_resolve_beacon_proxy() -> Result<()> {
+    setup_env()?;
+    deploy_upgradeable_beacon()?;
+    let beacon = AlkaneId {
+        block: 4,
+        tx: BEACON_ID,
+    };
+    let (_, proxy_sequence) =
+        deploy_upgradeable_proxy(alkanes_std_beacon_proxy_build::get_bytes(), 0, beacon)?;
+    let proxy = AlkaneId {
+        block: 2,
+        tx: proxy_sequence,
+    };
+    let resolved = upgrades::resolve(&proxy, 0)?;
+    assert_eq!(
+        resolved.chain,
+        vec![
+            (proxy, ProxyKind::BeaconProxy),
+            (beacon, ProxyKind::UpgradeableBeacon)
+        ]
+    );
+    assert_eq!(resolved.implementation, AlkaneId { block: 2, tx: 1 });
+
+    // calling the beacon itself runs the beacon's code
+    let direct = upgrades::resolve(&beacon, 0)?;
+    assert!(direct.chain.is_empty());
+    assert_eq!(direct.implementation, beacon);
+    Ok(())
+}
@@ -0,0 +1,294 @@
+use crate::index_block;
+use crate::message::AlkaneMessageContext;
+use crate::tests::helpers::{self as alkane_helpers, clear, BinaryAndCellpack};
+use crate::tests::std::{
+    alkanes_std_auth_token_build, alkanes_std_owned_token_build, alkanes_std_vault_token_build,
+};
+use alkanes_support::cellpack::Cellpack;
+use alkanes_support::constants::AUTH_TOKEN_FACTORY_ID;
+use alkanes_support::id::AlkaneId;
+use anyhow::Result;
+use bitcoin::OutPoint;
+use metashrew_support::{index_pointer::KeyValuePointer, utils::consensus_encode};
+use protorune::test_helpers::create_block_with_coinbase_tx;
+use protorune::{balance_sheet::load_sheet, message::MessageContext, tables::RuneTable};
+use protorune_support::balance_sheet::BalanceSheetOperations;
+use wasm_bindgen_test::wasm_bindgen_test;
+
+// the underlying token and the auth tokens standing for the two accounts
+const UNDERLYING: AlkaneId = AlkaneId { block: 2, tx: 1 };
+const ALICE: AlkaneId = AlkaneId { block: 2, tx: 2 };
+const BOB: AlkaneId = AlkaneId { block: 2, tx: 4 };
+const VAULT: AlkaneId = AlkaneId { block: 2, tx: 5 };
+
+const DEPOSIT: u128 = 1;
+const WITHDRAW: u128 = 2;
+const APPROVE: u128 = 3;
+const TRANSFER: u128 = 4;
+const TRANSFER_FROM: u128 = 5;
+const GET_TOTAL_SUPPLY: u128 = 101;
+const GET_UNDERLYING: u128 = 102;
+const BALANCE_OF: u128 = 103;
+const ALLOWANCE: u128 = 104;
+
+/// Deploys the underlying, a second owned token for Bob's auth token and the
+/// vault, then deposits all 1000 underlying units for Alice. Returns the
+/// wallet output, which holds both auth tokens.
+fn setup() -> Result<OutPoint> {
+    clear();
+    index_block(&create_block_with_coinbase_tx(0), 0)?;
+    let block = alkane_helpers::init_with_cellpack_pairs(vec![
+        BinaryAndCellpack::new(
+            alkanes_std_auth_token_build::get_bytes(),
+            Cellpack {
+                target: AlkaneId {
+                    block: 3,
+                    tx: AUTH_TOKEN_FACTORY_ID,
+                },
+                inputs: vec![100],
+            },
+        ),
+        BinaryAndCellpack::new(
+            alkanes_std_owned_token_build::get_bytes(),
+            Cellpack {
+                target: AlkaneId { block: 1, tx: 0 },
+                inputs: vec![0, 1, 1000],
+            },
+        ),
+        BinaryAndCellpack::new(
+            alkanes_std_owned_token_build::get_bytes(),
+            Cellpack {
+                target: AlkaneId { block: 1, tx: 0 },
+                inputs: vec![0, 1, 1],
+            },
+        ),
+        BinaryAndCellpack::new(
+            alkanes_std_vault_token_build::get_bytes(),
+            Cellpack {
+                target: AlkaneId { block: 1, tx: 0 },
+                inputs: vec![0, UNDERLYING.block, UNDERLYING.tx],
+            },
+        ),
+        BinaryAndCellpack::cellpack_only(Cellpack {
+            target: VAULT,
+            inputs: vec![DEPOSIT, ALICE.block, ALICE.tx],
+        }),
+    ]);
+    index_block(&block, 1)?;
+    Ok(OutPoint {
+        txid: block.txdata.last().unwrap().compute_txid(),
+        vout: 0,
+    })
+}
+
+/// Calls the vault spending the wallet output and returns the new one.
+fn call(wallet: OutPoint, inputs: Vec<u128>, height: u32) -> Result<OutPoint> {
+    let block = alkane_helpers::init_with_cellpack_pairs_w_input(
+        vec![BinaryAndCellpack::cellpack_only(Cellpack {
+            target: VAULT,
+            inputs,
+        })],
+        wallet,
+    );
+    index_block(&block, height)?;
+    Ok(OutPoint {
+        txid: block.txdata.last().unwrap().compute_txid(),
+        vout: 0,
+    })
+}
+
+/// Calls the vault with nothing incoming and returns the traced outpoint.
+fn call_empty(inputs: Vec<u128>, height: u32) -> Result<OutPoint> {
+    let block = alkane_helpers::init_with_cellpack_pairs(vec![BinaryAndCellpack::cellpack_only(
+        Cellpack {
+            target: VAULT,
+            inputs,
+        },
+    )]);
+    index_block(&block, height)?;
+    Ok(OutPoint {
+        txid: block.txdata.last().unwrap().compute_txid(),
+        vout: 3,
+    })
+}
+
+fn trace_of(wallet: &OutPoint) -> OutPoint {
+    OutPoint {
+        txid: wallet.txid,
+        vout: 3,
+    }
+}
+
+fn view_u128(inputs: Vec<u128>, height: u32) -> Result<u128> {
+    let outpoint = call_empty(inputs, height)?;
+    alkane_helpers::assert_return_context(&outpoint, |trace_response| {
+        Ok(u128::from_le_bytes(trace_response.inner.data[0..16].try_into()?))
+    })
+}
+
+fn balance_of(owner: AlkaneId, height: u32) -> Result<u128> {
+    view_u128(vec![BALANCE_OF, owner.block, owner.tx], height)
+}
+
+fn wallet_balance(wallet: &OutPoint, id: AlkaneId) -> Result<u128> {
+    let sheet = load_sheet(
+        &RuneTable::for_protocol(AlkaneMessageContext::protocol_tag())
+            .OUTPOINT_TO_RUNES
+            .select(&consensus_encode(wallet)?),
+    );
+    Ok(sheet.get_cached(&id.into()))
+}
+
+#[wasm_bindgen_test]
+fn test_vault_token_deposit_and_withdraw() -> Result<()> {
+    let wallet = setup()?;
+    // the vault keeps the underlying and forwards the auth tokens
+    assert_eq!(wallet_balance(&wallet, UNDERLYING)?, 0);
+    assert_eq!(wallet_balance(&wallet, ALICE)?, 1);
+    assert_eq!(wallet_balance(&wallet, BOB)?, 1);
+    assert_eq!(balance_of(ALICE, 2)?, 1000);
+    assert_eq!(view_u128(vec![GET_TOTAL_SUPPLY], 3)?, 1000);
+
+    let outpoint = call_empty(vec![GET_UNDERLYING], 4)?;
+    alkane_helpers::assert_return_context(&outpoint, |trace_response| {
+        assert_eq!(trace_response.inner.data, Vec::<u8>::from(UNDERLYING));
+        Ok(())
+    })?;
+
+    let wallet = call(wallet, vec![WITHDRAW, ALICE.block, ALICE.tx, 400], 5)?;
+    assert_eq!(wallet_balance(&wallet, UNDERLYING)?, 400);
+    assert_eq!(balance_of(ALICE, 6)?, 600);
+    assert_eq!(view_u128(vec![GET_TOTAL_SUPPLY], 7)?, 600);
+
+    // depositing it back for Bob
+    let wallet = call(wallet, vec![DEPOSIT, BOB.block, BOB.tx], 8)?;
+    assert_eq!(wallet_balance(&wallet, UNDERLYING)?, 0);
+    assert_eq!(wallet_balance(&wallet, ALICE)?, 1);
+    assert_eq!(balance_of(BOB, 9)?, 400);
+    assert_eq!(view_u128(vec![GET_TOTAL_SUPPLY], 10)?, 1000);
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_vault_token_withdraw_requires_owner() -> Result<()> {
+    setup()?;
+    let outpoint = call_empty(vec![WITHDRAW, ALICE.block, ALICE.tx, 1], 2)?;
+    alkane_helpers::assert_revert_context(&outpoint, "VaultToken: caller does not act for")?;
+    assert_eq!(balance_of(ALICE, 3)?, 1000);
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_vault_token_withdraw_over_balance() -> Result<()> {
+    let wallet = setup()?;
+    let wallet = call(wallet, vec![WITHDRAW, ALICE.block, ALICE.tx, 1001], 2)?;
+    alkane_helpers::assert_revert_context(&trace_of(&wallet), "VaultToken: insufficient balance")?;
+    // the refund keeps the auth tokens in the wallet
+    assert_eq!(wallet_balance(&wallet, ALICE)?, 1);
+    assert_eq!(wallet_balance(&wallet, UNDERLYING)?, 0);
+    assert_eq!(balance_of(ALICE, 3)?, 1000);
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_vault_token_deposit_requires_underlying() -> Result<()> {
+    let wallet = setup()?;
+    let outpoint = call_empty(vec![DEPOSIT, ALICE.block, ALICE.tx], 2)?;
+    alkane_helpers::assert_revert_context(&outpoint, "VaultToken: nothing to deposit")?;
+    // auth tokens alone aren't a deposit either
+    let wallet = call(wallet, vec![DEPOSIT, BOB.block, BOB.tx], 3)?;
+    alkane_helpers::assert_revert_context(&trace_of(&wallet), "VaultToken: nothing to deposit")?;
+    assert_eq!(balance_of(BOB, 4)?, 0);
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_vault_token_transfer() -> Result<()> {
+    let wallet = setup()?;
+    let wallet = call(wallet, vec![TRANSFER, ALICE.block, ALICE.tx, BOB.block, BOB.tx, 250], 2)?;
+    assert_eq!(balance_of(ALICE, 3)?, 750);
+    assert_eq!(balance_of(BOB, 4)?, 250);
+
+    let wallet = call(wallet, vec![TRANSFER, BOB.block, BOB.tx, ALICE.block, ALICE.tx, 251], 5)?;
+    alkane_helpers::assert_revert_context(&trace_of(&wallet), "VaultToken: insufficient balance")?;
+
+    let outpoint = call_empty(vec![TRANSFER, ALICE.block, ALICE.tx, BOB.block, BOB.tx, 1], 6)?;
+    alkane_helpers::assert_revert_context(&outpoint, "VaultToken: caller does not act for")?;
+
+    // Bob withdraws what he was sent
+    let wallet = call(wallet, vec![WITHDRAW, BOB.block, BOB.tx, 250], 7)?;
+    assert_eq!(wallet_balance(&wallet, UNDERLYING)?, 250);
+    assert_eq!(balance_of(BOB, 8)?, 0);
+    assert_eq!(view_u128(vec![GET_TOTAL_SUPPLY], 9)?, 750);
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_vault_token_approve_and_transfer_from() -> Result<()> {
+    let wallet = setup()?;
+    let allowance = vec![ALLOWANCE, ALICE.block, ALICE.tx, BOB.block, BOB.tx];
+    let outpoint = call_empty(vec![APPROVE, ALICE.block, ALICE.tx, BOB.block, BOB.tx, 300], 2)?;
+    alkane_helpers::assert_revert_context(&outpoint, "VaultToken: caller does not act for")?;
+
+    let wallet = call(wallet, vec![APPROVE, ALICE.block, ALICE.tx, BOB.block, BOB.tx, 300], 3)?;
+    assert_eq!(view_u128(allowance.clone(), 4)?, 300);
+
+    // Bob moves 200 of Alice's balance to himself
+    let transfer_from = |amount: u128| {
+        vec![
+            TRANSFER_FROM,
+            BOB.block,
+            BOB.tx,
+            ALICE.block,
+            ALICE.tx,
+            BOB.block,
+            BOB.tx,
+            amount,
+        ]
+    };
+    let outpoint = call_empty(transfer_from(200), 5)?;
+    alkane_helpers::assert_revert_context(&outpoint, "VaultToken: caller does not act for")?;
+    let wallet = call(wallet, transfer_from(200), 6)?;
+    assert_eq!(balance_of(ALICE, 7)?, 800);
+    assert_eq!(balance_of(BOB, 8)?, 200);
+    assert_eq!(view_u128(allowance.clone(), 9)?, 100);
+
+    let wallet = call(wallet, transfer_from(101), 10)?;
+    alkane_helpers::assert_revert_context(&trace_of(&wallet), "VaultToken: insufficient allowance")?;
+    assert_eq!(balance_of(ALICE, 11)?, 800);
+
+    // approving zero revokes the rest
+    let wallet = call(wallet, vec![APPROVE, ALICE.block, ALICE.tx, BOB.block, BOB.tx, 0], 12)?;
+    assert_eq!(view_u128(allowance, 13)?, 0);
+    let wallet = call(wallet, transfer_from(1), 14)?;
+    alkane_helpers::assert_revert_context(&trace_of(&wallet), "VaultToken: insufficient allowance")?;
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_vault_token_unlimited_allowance() -> Result<()> {
+    let wallet = setup()?;
+    let wallet = call(
+        wallet,
+        vec![APPROVE, ALICE.block, ALICE.tx, BOB.block, BOB.tx, u128::MAX],
+        2,
+    )?;
+    let wallet = call(
+        wallet,
+        vec![TRANSFER_FROM, BOB.block, BOB.tx, ALICE.block, ALICE.tx, BOB.block, BOB.tx, 1000],
+        3,
+    )?;
+    assert_eq!(balance_of(BOB, 4)?, 1000);
+    assert_eq!(
+        view_u128(vec![ALLOWANCE, ALICE.block, ALICE.tx, BOB.block, BOB.tx], 5)?,
+        u128::MAX
+    );
+    // Alice's balance is gone, so spending more fails on the balance
+    let wallet = call(
+        wallet,
+        vec![TRANSFER_FROM, BOB.block, BOB.tx, ALICE.block, ALICE.tx, BOB.block, BOB.tx, 1],
+        6,
+    )?;
+    alkane_helpers::assert_revert_context(&trace_of(&wallet), "VaultToken: insufficient balance")?;
+    Ok(())
+}
@@ -49,6 +49,7 @@ fn test_simulate_huge_pointer_no_oom() -> Result<()> {
         transaction_bytes: None, // synth-tx path -> allocates max_pointer+1 outputs
         block_bytes: None,
         storage_overrides: vec![],
+        balance_overrides: vec![],
     });
 
     // INVARIANT: a view request must never trigger an unbounded allocation.
@@ -132,6 +132,7 @@ fn test_view_flag_leak_corrupts_indexer() -> Result<()> {
         transaction_bytes: None, // force the synth-tx path (seeds input balances)
         block_bytes: None,
         storage_overrides: vec![],
+        balance_overrides: vec![],
     });
     println!("[leak] simulate_protostones returned err = {}", sim.is_err());
     assert!(
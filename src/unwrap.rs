@@ -5,8 +5,8 @@ use alkanes_support::{
 use anyhow::Result;
 use bitcoin::hashes::Hash;
 use bitcoin::{OutPoint, TxOut};
-use metashrew_core::index_pointer::IndexPointer;
 use metashrew_core::{get_cache, println, stdio::stdout};
+use metashrew_native::index_pointer::IndexPointer;
 use metashrew_support::index_pointer::KeyValuePointer;
 use metashrew_support::utils::{consensus_decode, consensus_encode, is_empty};
 use protorune::tables::OUTPOINT_SPENDABLE_BY;
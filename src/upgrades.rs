@@ -0,0 +1,186 @@
+use crate::history;
+use crate::vm::utils::get_alkane_binary;
+use alkanes_support::id::AlkaneId;
+use alkanes_support::trace::StateDiff;
+use anyhow::{anyhow, Result};
+use metashrew_native::index_pointer::{AtomicPointer, IndexPointer};
+use metashrew_support::index_pointer::KeyValuePointer;
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+
+// Proxy resolution and upgrade history for the standard proxy layouts.
+//
+// `alkanes-std-upgradeable` keeps its implementation in `/implementation`,
+// `alkanes-std-beacon-proxy` keeps the beacon it asks in `/beacon`, and both
+// mark themselves with `/proxy_initialized`. `alkanes-std-upgradeable-beacon`
+// keeps the implementation it serves in `/implementation` too, but is
+// initialized with `/initialized` like any other alkane.
+//
+// Upgrades are read off the message's state diff: a committed write to
+// `/implementation` that replaces one alkane id with another is recorded as
+// `height (u64 LE) ++ old ++ new` in the alkane's `/upgrades/` list, through
+// the message's AtomicPointer.
+
+pub const IMPLEMENTATION_SLOT: &[u8] = b"/implementation";
+pub const BEACON_SLOT: &[u8] = b"/beacon";
+pub const PROXY_INITIALIZED_SLOT: &[u8] = b"/proxy_initialized";
+
+/// Longest chain of proxies and beacons `resolve` follows.
+pub const MAX_PROXY_DEPTH: usize = 8;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProxyKind {
+    Upgradeable,
+    BeaconProxy,
+    UpgradeableBeacon,
+}
+
+/// One implementation change of an alkane.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Upgrade {
+    pub alkane: AlkaneId,
+    pub height: u64,
+    pub old_implementation: AlkaneId,
+    pub new_implementation: AlkaneId,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Resolution {
+    pub chain: Vec<(AlkaneId, ProxyKind)>,
+    pub implementation: AlkaneId,
+    pub bytecode_hash: [u8; 32],
+    pub upgrades: Vec<Upgrade>,
+}
+
+pub fn upgrades_pointer<T: KeyValuePointer>(root: &T, alkane: &AlkaneId) -> T {
+    root.keyword("/alkanes/")
+        .select(&(*alkane).into())
+        .keyword("/upgrades/")
+}
+
+/// Records every implementation change committed in `diff`.
+pub fn record_upgrades(atomic: &mut AtomicPointer, diff: &StateDiff, height: u64) {
+    let root = atomic.derive(&IndexPointer::default());
+    for storage in &diff.storage {
+        for write in &storage.writes {
+            if write.key != IMPLEMENTATION_SLOT {
+                continue;
+            }
+            let (Ok(old), Ok(new)) = (
+                AlkaneId::try_from(write.old_value.clone()),
+                AlkaneId::try_from(write.new_value.clone()),
+            ) else {
+                continue;
+            };
+            let mut entry = height.to_le_bytes().to_vec();
+            entry.extend(Vec::<u8>::from(old));
+            entry.extend(Vec::<u8>::from(new));
+            upgrades_pointer(&root, &storage.alkane).append(Arc::new(entry));
+        }
+    }
+}
+
+/// Upgrades of `alkane` at or below `height`, oldest first.
+pub fn upgrades_of(alkane: &AlkaneId, height: u64) -> Result<Vec<Upgrade>> {
+    let mut result = vec![];
+    for entry in upgrades_pointer(&IndexPointer::default(), alkane).get_list() {
+        if entry.len() != 72 {
+            return Err(anyhow!("malformed upgrade entry for {:?}", alkane));
+        }
+        let at = u64::from_le_bytes(entry[0..8].try_into().unwrap());
+        if at > height {
+            break;
+        }
+        result.push(Upgrade {
+            alkane: *alkane,
+            height: at,
+            old_implementation: AlkaneId::try_from(entry[8..40].to_vec())?,
+            new_implementation: AlkaneId::try_from(entry[40..72].to_vec())?,
+        });
+    }
+    Ok(result)
+}
+
+/// Storage slot `key` of `alkane` as of `height`, falling back to the live
+/// table for slots written before history tracking.
+fn slot_at(alkane: &AlkaneId, key: &[u8], height: u64) -> Vec<u8> {
+    let pointer = IndexPointer::from_keyword("/alkanes/").select(&(*alkane).into());
+    history::value_at(&history::storage_history_pointer(&pointer, key), height).unwrap_or_else(
+        || {
+            pointer
+                .keyword("/storage/")
+                .select(&key.to_vec())
+                .get()
+                .as_ref()
+                .clone()
+        },
+    )
+}
+
+fn id_at(alkane: &AlkaneId, key: &[u8], height: u64) -> Option<AlkaneId> {
+    AlkaneId::try_from(slot_at(alkane, key, height)).ok()
+}
+
+/// Which standard layout `alkane` follows as of `height`, and the alkane it
+/// defers to.
+pub fn proxy_layout(alkane: &AlkaneId, height: u64) -> Option<(ProxyKind, AlkaneId)> {
+    if !slot_at(alkane, PROXY_INITIALIZED_SLOT, height).is_empty() {
+        if let Some(implementation) = id_at(alkane, IMPLEMENTATION_SLOT, height) {
+            return Some((ProxyKind::Upgradeable, implementation));
+        }
+        return id_at(alkane, BEACON_SLOT, height).map(|beacon| (ProxyKind::BeaconProxy, beacon));
+    }
+    id_at(alkane, IMPLEMENTATION_SLOT, height)
+        .map(|implementation| (ProxyKind::UpgradeableBeacon, implementation))
+}
+
+/// Follows `alkane` through upgradeable proxies, and beacon proxies to their
+/// beacon's implementation, as of `height`. A beacon queried directly is
+/// not a proxy: its own code answers calls.
+pub fn resolve(alkane: &AlkaneId, height: u64) -> Result<Resolution> {
+    let mut chain = vec![];
+    let mut current = *alkane;
+    loop {
+        let layout = match proxy_layout(&current, height) {
+            Some((ProxyKind::UpgradeableBeacon, _))
+                if !matches!(chain.last(), Some((_, ProxyKind::BeaconProxy))) =>
+            {
+                None
+            }
+            layout => layout,
+        };
+        let Some((kind, next)) = layout else {
+            break;
+        };
+        if chain.iter().any(|(id, _)| *id == current) {
+            return Err(anyhow!("proxy cycle through {:?}", current));
+        }
+        if chain.len() == MAX_PROXY_DEPTH {
+            return Err(anyhow!("proxy chain longer than {}", MAX_PROXY_DEPTH));
+        }
+        chain.push((current, kind));
+        current = next;
+    }
+    let binary = get_alkane_binary(
+        IndexPointer::from_keyword("/alkanes/"),
+        &current,
+        height as u32,
+    )?;
+    if binary.is_empty() {
+        return Err(anyhow!("no bytecode for implementation {:?}", current));
+    }
+    let mut upgrades = vec![];
+    for (id, _) in &chain {
+        upgrades.extend(upgrades_of(id, height)?);
+    }
+    if chain.is_empty() {
+        upgrades.extend(upgrades_of(alkane, height)?);
+    }
+    upgrades.sort_by_key(|upgrade| upgrade.height);
+    Ok(Resolution {
+        chain,
+        implementation: current,
+        bytecode_hash: Sha256::digest(binary.as_ref()).into(),
+        upgrades,
+    })
+}
@@ -1,14 +1,16 @@
+use crate::history;
+use crate::state_diff;
 use alkanes_support::parcel::AlkaneTransferParcel;
 use alkanes_support::storage::StorageMap;
 use alkanes_support::{id::AlkaneId, parcel::AlkaneTransfer};
 use anyhow::{anyhow, Result};
 use bitcoin::OutPoint;
-use metashrew_core::index_pointer::{AtomicPointer, IndexPointer};
 #[allow(unused_imports)]
 use metashrew_core::{
     println,
     stdio::{stdout, Write},
 };
+use metashrew_native::index_pointer::{AtomicPointer, IndexPointer};
 use metashrew_support::index_pointer::KeyValuePointer;
 use protorune_support::rune_transfer::RuneTransfer;
 use protorune_support::utils::consensus_decode;
@@ -37,9 +39,11 @@ pub fn balance_pointer(
         .select(&what_bytes)
         .keyword("/balances/")
         .select(&who_bytes);
-    if ptr.get().len() != 0 {
+    let current = ptr.get();
+    if current.len() != 0 {
         alkane_inventory_pointer(who).append(Arc::new(what_bytes));
     }
+    state_diff::record_balance_prior(who, what, &current);
     ptr
 }
 
@@ -51,6 +55,31 @@ pub fn alkane_inventory_pointer(who: &AlkaneId) -> IndexPointer {
     ptr
 }
 
+/// Every alkane that has been credited a balance of `what`, each listed
+/// once, in order of first credit. The reverse of `/inventory/`; holders
+/// whose balance has since dropped to zero stay listed. Credits from
+/// before the list was introduced are not backfilled.
+pub fn alkane_holders_pointer<T: KeyValuePointer>(root: &T, what: &AlkaneId) -> T {
+    root.keyword("/alkanes/")
+        .select(&what.clone().into())
+        .keyword("/holders/")
+}
+
+/// Lists `who` among the holders of `what` unless it already is. Written
+/// through `atomic` so a reverted credit leaves no entry behind.
+pub fn record_holder(atomic: &mut AtomicPointer, who: &AlkaneId, what: &AlkaneId) {
+    let root = atomic.derive(&IndexPointer::default());
+    let mut listed = root
+        .keyword("/alkanes/")
+        .select(&what.clone().into())
+        .keyword("/isholder/")
+        .select(&who.clone().into());
+    if listed.get().len() == 0 {
+        listed.set_value::<u8>(1);
+        alkane_holders_pointer(&root, what).append(Arc::new(who.clone().into()));
+    }
+}
+
 pub fn alkane_id_to_outpoint(alkane_id: &AlkaneId) -> Result<OutPoint> {
     let alkane_id_bytes: Vec<u8> = alkane_id.clone().into();
     let outpoint_bytes = IndexPointer::from_keyword("/alkanes_id_to_outpoint/")
@@ -72,12 +101,16 @@ pub fn credit_balances(
 ) -> Result<()> {
     for rune in runes.clone() {
         let mut ptr = balance_pointer(atomic, to, &rune.id.clone().into());
-        ptr.set_value::<u128>(
-            rune.value
-                .checked_add(ptr.get_value::<u128>())
-                .ok_or("")
-                .map_err(|_| anyhow!("balance overflow during credit_balances"))?,
-        );
+        let balance = rune
+            .value
+            .checked_add(ptr.get_value::<u128>())
+            .ok_or("")
+            .map_err(|_| anyhow!("balance overflow during credit_balances"))?;
+        ptr.set_value::<u128>(balance);
+        history::record_balance(atomic, to, &rune.id.clone().into(), balance);
+        if rune.value != 0 {
+            record_holder(atomic, to, &rune.id.clone().into());
+        }
     }
     Ok(())
 }
@@ -112,7 +145,9 @@ pub fn debit_balances(
     for transfer in &runes.0 {
         let mut pointer = balance_pointer(atomic, to, &transfer.id.clone().into());
         let pointer_value = pointer.get_value::<u128>();
-        pointer.set_value::<u128>(checked_debit_with_minting(transfer, to, pointer_value)?);
+        let balance = checked_debit_with_minting(transfer, to, pointer_value)?;
+        pointer.set_value::<u128>(balance);
+        history::record_balance(atomic, to, &transfer.id, balance);
     }
     Ok(())
 }
@@ -132,28 +167,36 @@ pub fn transfer_from(
         let mut from_pointer =
             balance_pointer(atomic, &from.clone().into(), &transfer.id.clone().into());
         let balance = from_pointer.get_value::<u128>();
-        from_pointer.set_value::<u128>(checked_debit_with_minting(transfer, from, balance)?);
+        let from_balance = checked_debit_with_minting(transfer, from, balance)?;
+        from_pointer.set_value::<u128>(from_balance);
+        history::record_balance(atomic, from, &transfer.id, from_balance);
         let mut to_pointer =
             balance_pointer(atomic, &to.clone().into(), &transfer.id.clone().into());
         // INVARIANT: a stored balance must never exceed u128::MAX. Credit with a
         // checked add (matching `credit_balances`) so an overflow becomes a
         // graceful per-tx revert instead of a panic (which wedges the block in a
         // checked build) or a silent wrap (which corrupts the ledger in release).
-        let to_balance = to_pointer.get_value::<u128>();
-        to_pointer.set_value::<u128>(
-            to_balance
-                .checked_add(transfer.value)
-                .ok_or_else(|| anyhow!("balance overflow during transfer_from"))?,
-        );
+        let to_balance = to_pointer
+            .get_value::<u128>()
+            .checked_add(transfer.value)
+            .ok_or_else(|| anyhow!("balance overflow during transfer_from"))?;
+        to_pointer.set_value::<u128>(to_balance);
+        history::record_balance(atomic, to, &transfer.id, to_balance);
+        if transfer.value != 0 {
+            record_holder(atomic, to, &transfer.id);
+        }
     }
     Ok(())
 }
 pub fn pipe_storagemap_to<T: KeyValuePointer>(map: &StorageMap, pointer: &mut T) {
+    let height = history::history_height();
     map.0.iter().for_each(|(k, v)| {
-        pointer
-            .keyword("/storage/")
-            .select(k)
-            .set(Arc::new(v.clone()));
+        let mut slot = pointer.keyword("/storage/").select(k);
+        state_diff::record_storage_prior(&slot);
+        slot.set(Arc::new(v.clone()));
+        if let Some(height) = height {
+            history::record_storage(pointer, k, v, height);
+        }
     });
 }
 
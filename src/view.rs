@@ -1,13 +1,19 @@
+use crate::block_stats;
+use crate::events;
+use crate::history;
 use crate::message::AlkaneMessageContext;
 use crate::network::set_view_mode;
-use crate::tables::{TRACES, TRACES_BY_HEIGHT};
+use crate::tables::{TRACES, TRACES_BY_HEIGHT, TRACE_DIFFS};
 use crate::unwrap as unwrap_view;
+use crate::upgrades::{self, ProxyKind};
 use crate::utils::{
-    alkane_id_to_outpoint, alkane_inventory_pointer, balance_pointer, credit_balances,
-    debit_balances, disable_touched_storage_collector, drain_touched_storage,
+    alkane_holders_pointer, alkane_id_to_outpoint, alkane_inventory_pointer, balance_pointer,
+    credit_balances, debit_balances, disable_touched_storage_collector, drain_touched_storage,
     enable_touched_storage_collector, pipe_storagemap_to,
 };
+use crate::vm::fuel::{self, VirtualFuelBytes};
 use crate::vm::instance::AlkanesInstance;
+use crate::vm::profiler::{self, FuelFrame};
 use crate::vm::runtime::AlkanesRuntimeContext;
 use crate::vm::utils::{
     get_alkane_binary, prepare_context, run_after_special, run_special_cellpacks, sequence_pointer,
@@ -17,8 +23,12 @@ use alkanes_support::id::AlkaneId;
 use alkanes_support::parcel::AlkaneTransfer;
 use alkanes_support::proto;
 use alkanes_support::proto::alkanes::{
-    AlkaneIdToOutpointRequest, AlkaneIdToOutpointResponse, AlkaneInventoryRequest,
-    AlkaneInventoryResponse, AlkaneStorageRequest, AlkaneStorageResponse,
+    AlkaneEventsResponse, AlkaneHolder, AlkaneIdToOutpointRequest, AlkaneIdToOutpointResponse,
+    AlkaneInventoryRequest, AlkaneInventoryResponse, AlkaneStorageKeyEntry,
+    AlkaneStorageKeysRequest, AlkaneStorageKeysResponse, AlkaneStorageRequest,
+    AlkaneStorageResponse, BlockStatsRequest, BlockStatsResponse, EventsByAlkaneRequest,
+    EventsByHeightRequest, HoldersOfAlkaneRequest, HoldersOfAlkaneResponse, ProxyHop,
+    ResolveImplementationRequest, ResolveImplementationResponse, UpgradeRecord,
 };
 use alkanes_support::response::ExtendedCallResponse;
 use anyhow::{anyhow, Result};
@@ -29,9 +39,9 @@ use bitcoin::{
     blockdata::block::Header, Amount, Block, BlockHash, CompactTarget, OutPoint, ScriptBuf,
     Sequence, Transaction, TxIn, TxMerkleNode, TxOut, Witness,
 };
-use metashrew_core::index_pointer::{AtomicPointer, IndexPointer};
 #[allow(unused_imports)]
 use metashrew_core::{println, stdio::stdout};
+use metashrew_native::index_pointer::{AtomicPointer, IndexPointer};
 use metashrew_support::{index_pointer::KeyValuePointer, utils::consensus_encode};
 use prost::Message;
 use protorune::balance_sheet::MintableDebit;
@@ -271,6 +281,31 @@ pub fn protorunes_by_outpoint(
     })
 }
 
+/// `protorunes_by_outpoint` as of `height`. Alkane sheets come from the
+/// outpoint history when the outpoint had not been created yet or was
+/// spent after `height`; every other case is answered from the live table.
+pub fn protorunes_by_outpoint_at(
+    input: &Vec<u8>,
+    height: u64,
+) -> Result<protorune_support::proto::protorune::OutpointResponse> {
+    let request = protorune_support::proto::protorune::OutpointWithProtocol::decode(&**input)?;
+    let mut response = protorunes_by_outpoint(input)?;
+    if into_u128(request.protocol.unwrap_or_else(|| {
+        <u128 as Into<protorune_support::proto::protorune::Uint128>>::into(1u128)
+    })) != AlkaneMessageContext::protocol_tag()
+    {
+        return Ok(response);
+    }
+    let outpoint = OutPoint {
+        txid: bitcoin::Txid::from_byte_array(request.txid.as_slice().try_into()?),
+        vout: request.vout,
+    };
+    if let Some(sheet) = history::outpoint_sheet_at(&outpoint, height)? {
+        response.balances = Some(to_alkanes_balances(sheet));
+    }
+    Ok(response)
+}
+
 pub fn to_alkanes_outpoints(
     v: Vec<protorune_support::proto::protorune::OutpointResponse>,
 ) -> Vec<protorune_support::proto::protorune::OutpointResponse> {
@@ -401,6 +436,130 @@ pub fn getinventory(req: &AlkaneInventoryRequest) -> Result<AlkaneInventoryRespo
     Ok(result)
 }
 
+/// `getinventory` as of `height`. Alkanes with a zero balance at `height`
+/// are left out. Holders whose balances predate history tracking fall back
+/// to the live inventory.
+pub fn getinventory_at(
+    req: &AlkaneInventoryRequest,
+    height: u64,
+) -> Result<AlkaneInventoryResponse> {
+    let who: AlkaneId = req
+        .id
+        .clone()
+        .ok_or_else(|| anyhow!("missing alkane id"))?
+        .into();
+    let held = history::inventory_history_pointer(&IndexPointer::default(), &who).get_list();
+    if held.is_empty() {
+        return getinventory(req);
+    }
+    let mut result = AlkaneInventoryResponse::default();
+    for what in held {
+        let id = AlkaneId::parse(&mut Cursor::new(what.as_ref().clone()))?;
+        let value = history::balance_at(&who, &id, height).unwrap_or(0);
+        if value != 0 {
+            result.alkanes.push(AlkaneTransfer { id, value }.into());
+        }
+    }
+    Ok(result)
+}
+
+/// Page size used by `holdersofalkane` when the request leaves `limit` unset.
+pub const HOLDERS_DEFAULT_LIMIT: u32 = 100;
+/// Upper bound on the `holdersofalkane` page size.
+pub const HOLDERS_MAX_LIMIT: u32 = 1000;
+/// Upper bound on the holders one `holdersofalkane` call walks, for the same
+/// reason as `STORAGE_KEYS_MAX_SCAN`.
+pub const HOLDERS_MAX_SCAN: u64 = 10_000;
+
+/// Alkanes holding a nonzero balance of `req.id` as of `height`, in order of
+/// first credit, each with its balance. Holders whose balance predates
+/// history tracking are answered from the live table.
+///
+/// Only alkanes credited since the holder list was introduced are listed:
+/// there is no backfill, so an alkane that held `req.id` before then and
+/// has not been credited it again is missing from the response.
+pub fn holders_of_alkane(
+    req: &HoldersOfAlkaneRequest,
+    height: u64,
+) -> Result<HoldersOfAlkaneResponse> {
+    let what: AlkaneId = req
+        .id
+        .clone()
+        .ok_or_else(|| anyhow!("missing alkane id"))?
+        .into();
+    let holders = alkane_holders_pointer(&IndexPointer::default(), &what);
+    let length = holders.length() as u64;
+    let limit = match req.limit {
+        0 => HOLDERS_DEFAULT_LIMIT,
+        v => v.min(HOLDERS_MAX_LIMIT),
+    } as usize;
+    let scan_end = length.min(req.cursor.saturating_add(HOLDERS_MAX_SCAN));
+    let mut result = HoldersOfAlkaneResponse::default();
+    let mut index = req.cursor;
+    while index < scan_end && result.holders.len() < limit {
+        let holder = AlkaneId::parse(&mut Cursor::new(
+            holders.select_index(index as u32).get().as_ref().clone(),
+        ))?;
+        index += 1;
+        let balance = history::balance_at(&holder, &what, height).unwrap_or_else(|| {
+            IndexPointer::from_keyword("/alkanes/")
+                .select(&what.into())
+                .keyword("/balances/")
+                .select(&holder.into())
+                .get_value::<u128>()
+        });
+        if balance != 0 {
+            result.holders.push(AlkaneHolder {
+                holder: Some(holder.into()),
+                balance: Some(balance.into()),
+            });
+        }
+    }
+    result.next_cursor = if index < length { index } else { 0 };
+    Ok(result)
+}
+
+/// The alkane whose code runs when `req.id` is called as of `height`,
+/// following the standard upgradeable and beacon proxy layouts, with the
+/// hash of its bytecode and the upgrade history of every proxy on the way.
+pub fn resolve_implementation(
+    req: &ResolveImplementationRequest,
+    height: u64,
+) -> Result<ResolveImplementationResponse> {
+    let alkane: AlkaneId = req
+        .id
+        .clone()
+        .ok_or_else(|| anyhow!("missing alkane id"))?
+        .into();
+    let resolution = upgrades::resolve(&alkane, height)?;
+    Ok(ResolveImplementationResponse {
+        chain: resolution
+            .chain
+            .into_iter()
+            .map(|(alkane, kind)| ProxyHop {
+                alkane: Some(alkane.into()),
+                kind: match kind {
+                    ProxyKind::Upgradeable => proto::alkanes::ProxyKind::Upgradeable,
+                    ProxyKind::BeaconProxy => proto::alkanes::ProxyKind::BeaconProxy,
+                    ProxyKind::UpgradeableBeacon => proto::alkanes::ProxyKind::UpgradeableBeacon,
+                } as i32,
+            })
+            .collect(),
+        implementation: Some(resolution.implementation.into()),
+        bytecode_hash: resolution.bytecode_hash.to_vec(),
+        upgrades: resolution
+            .upgrades
+            .into_iter()
+            .map(|upgrade| UpgradeRecord {
+                alkane: Some(upgrade.alkane.into()),
+                height: upgrade.height,
+                old_implementation: Some(upgrade.old_implementation.into()),
+                new_implementation: Some(upgrade.new_implementation.into()),
+            })
+            .collect(),
+    })
+}
+
 pub fn getstorageat(req: &AlkaneStorageRequest) -> Result<AlkaneStorageResponse> {
     let mut result: AlkaneStorageResponse = AlkaneStorageResponse::default();
     let alkane_storage_pointer = IndexPointer::from_keyword("/alkanes/")
@@ -411,6 +570,72 @@ pub fn getstorageat(req: &AlkaneStorageRequest) -> Result<AlkaneStorageResponse>
     Ok(result)
 }
 
+/// `getstorageat` as of `height`. Slots written before history tracking
+/// began are answered from the live table.
+pub fn getstorageat_at(req: &AlkaneStorageRequest, height: u64) -> Result<AlkaneStorageResponse> {
+    let alkane_pointer = IndexPointer::from_keyword("/alkanes/").select(
+        &crate::utils::from_protobuf(req.id.clone().ok_or_else(|| anyhow!("missing alkane id"))?)
+            .into(),
+    );
+    match history::value_at(
+        &history::storage_history_pointer(&alkane_pointer, &req.path),
+        height,
+    ) {
+        Some(value) => Ok(AlkaneStorageResponse { value }),
+        None => getstorageat(req),
+    }
+}
+
+/// Page size used by `getstoragekeys` when the request leaves `limit` unset.
+pub const STORAGE_KEYS_DEFAULT_LIMIT: u32 = 100;
+/// Upper bound on the `getstoragekeys` page size.
+pub const STORAGE_KEYS_MAX_LIMIT: u32 = 1000;
+/// Upper bound on the keys one `getstoragekeys` call walks, so a selective
+/// prefix over a large key list can't exhaust the view's fuel. A page cut
+/// short by this bound still returns a `next_cursor` to resume from.
+pub const STORAGE_KEYS_MAX_SCAN: u64 = 10_000;
+
+/// Storage keys written by an alkane as of `height`, in order of first
+/// write, each with its value and the height it was last written at. Keys
+/// first written above `height` or outside `req.prefix` are skipped.
+pub fn getstoragekeys(
+    req: &AlkaneStorageKeysRequest,
+    height: u64,
+) -> Result<AlkaneStorageKeysResponse> {
+    let alkane_pointer = IndexPointer::from_keyword("/alkanes/").select(
+        &crate::utils::from_protobuf(req.id.clone().ok_or_else(|| anyhow!("missing alkane id"))?)
+            .into(),
+    );
+    let keys = history::storage_keys_pointer(&alkane_pointer);
+    let length = keys.length() as u64;
+    let limit = match req.limit {
+        0 => STORAGE_KEYS_DEFAULT_LIMIT,
+        v => v.min(STORAGE_KEYS_MAX_LIMIT),
+    } as usize;
+    let scan_end = length.min(req.cursor.saturating_add(STORAGE_KEYS_MAX_SCAN));
+    let mut result = AlkaneStorageKeysResponse::default();
+    let mut index = req.cursor;
+    while index < scan_end && result.entries.len() < limit {
+        let key = keys.select_index(index as u32).get();
+        index += 1;
+        if !key.starts_with(&req.prefix) {
+            continue;
+        }
+        if let Some(Some((last_modified_height, value))) = history::entry_at(
+            &history::storage_history_pointer(&alkane_pointer, &key),
+            height,
+        ) {
+            result.entries.push(AlkaneStorageKeyEntry {
+                key: key.as_ref().clone(),
+                value,
+                last_modified_height,
+            });
+        }
+    }
+    result.next_cursor = if index < length { index } else { 0 };
+    Ok(result)
+}
+
 pub fn traceblock(height: u32) -> Result<Vec<u8>> {
     let mut block_events: Vec<proto::alkanes::AlkanesBlockEvent> = vec![];
     for outpoint in TRACES_BY_HEIGHT.select_value(height as u64).get_list() {
@@ -438,6 +663,85 @@ pub fn traceblock(height: u32) -> Result<Vec<u8>> {
     Ok(result.encode_to_vec())
 }
 
+/// Events indexed in the block at `req.height`, in emission order, optionally
+/// restricted to one topic.
+pub fn events_by_height(req: &EventsByHeightRequest) -> Result<AlkaneEventsResponse> {
+    Ok(AlkaneEventsResponse {
+        events: events::events_at_height(req.height)?
+            .into_iter()
+            .filter(|event| req.topic.is_empty() || event.topic == req.topic)
+            .collect(),
+        next_cursor: 0,
+    })
+}
+
+/// Page size used by `eventsbyalkane` when the request leaves `limit` unset.
+pub const EVENTS_DEFAULT_LIMIT: u32 = 100;
+/// Upper bound on the `eventsbyalkane` page size.
+pub const EVENTS_MAX_LIMIT: u32 = 1000;
+
+/// Events emitted by `req.id` between `req.from_height` and `req.to_height`,
+/// oldest first. A non-empty `req.topic` reads the per-topic list instead of
+/// the alkane's full list, so cursors are only meaningful for the same topic.
+pub fn events_by_alkane(req: &EventsByAlkaneRequest) -> Result<AlkaneEventsResponse> {
+    let alkane: AlkaneId = req
+        .id
+        .clone()
+        .ok_or_else(|| anyhow!("eventsbyalkane: missing alkane id"))?
+        .into();
+    let root = IndexPointer::default();
+    let list = if req.topic.is_empty() {
+        events::events_by_alkane_pointer(&root, &alkane)
+    } else {
+        events::events_by_topic_pointer(&root, &alkane, &req.topic)
+    };
+    let length = list.length();
+    let limit = match req.limit {
+        0 => EVENTS_DEFAULT_LIMIT,
+        v => v.min(EVENTS_MAX_LIMIT),
+    };
+    let in_range = |height: u64| req.to_height == 0 || height <= req.to_height;
+    let mut index =
+        events::lower_bound(&list, req.from_height).max(req.cursor.min(length as u64) as u32);
+    let mut result = AlkaneEventsResponse::default();
+    while index < length && (result.events.len() as u32) < limit {
+        let entry = list.select_index(index).get();
+        match events::decode_reference(entry.as_ref()) {
+            Some((height, _)) if !in_range(height) => break,
+            _ => {}
+        }
+        if let Some(event) = events::resolve_reference(entry.as_ref())? {
+            result.events.push(event);
+        }
+        index += 1;
+    }
+    result.next_cursor = match events::decode_reference(list.select_index(index).get().as_ref()) {
+        Some((height, _)) if index < length && in_range(height) => index as u64,
+        _ => 0,
+    };
+    Ok(result)
+}
+
+/// Upper bound on the number of heights one `blockstats` call covers.
+pub const BLOCK_STATS_MAX_RANGE: u64 = 1000;
+
+/// Stats of the blocks from `req.start_height` to `req.end_height`, skipping
+/// blocks without any. Ranges longer than `BLOCK_STATS_MAX_RANGE` are cut
+/// short; callers continue from the last height returned.
+pub fn block_stats(req: &BlockStatsRequest) -> Result<BlockStatsResponse> {
+    let end = req
+        .end_height
+        .max(req.start_height)
+        .min(req.start_height.saturating_add(BLOCK_STATS_MAX_RANGE - 1));
+    let mut blocks = vec![];
+    for height in req.start_height..=end {
+        if let Some(stats) = block_stats::block_stats_at(height)? {
+            blocks.push(stats);
+        }
+    }
+    Ok(BlockStatsResponse { blocks })
+}
+
 pub fn trace(outpoint: &OutPoint) -> Result<Vec<u8>> {
     Ok(TRACES
         .select(&consensus_encode::<OutPoint>(&outpoint)?)
@@ -446,6 +750,16 @@ pub fn trace(outpoint: &OutPoint) -> Result<Vec<u8>> {
         .clone())
 }
 
+/// Encoded `StateDiff` of the message at a protostone outpoint; empty when
+/// the message changed nothing, reverted, or never ran.
+pub fn tracediff(outpoint: &OutPoint) -> Result<Vec<u8>> {
+    Ok(TRACE_DIFFS
+        .select(&consensus_encode::<OutPoint>(&outpoint)?)
+        .get()
+        .as_ref()
+        .clone())
+}
+
 pub fn simulate_safe(
     parcel: &MessageContextParcel,
     fuel: u64,
@@ -454,6 +768,18 @@ pub fn simulate_safe(
     simulate_parcel(parcel, fuel)
 }
 
+/// `simulate_safe` with the fuel profiler enabled. The profile is returned
+/// even when the simulation fails, since that is when it is most useful.
+pub fn simulate_profiled(
+    parcel: &MessageContextParcel,
+    fuel: u64,
+) -> (Result<(ExtendedCallResponse, u64)>, Option<FuelFrame>) {
+    let _profiler = profiler::enable();
+    let result = simulate_safe(parcel, fuel);
+    let frame = profiler::drain().into_iter().next().map(|(_, frame)| frame);
+    (result, frame)
+}
+
 pub fn meta_safe(parcel: &MessageContextParcel) -> Result<Vec<u8>> {
     set_view_mode();
     let list = decode_varint_list(&mut Cursor::new(parcel.calldata.clone()))?;
@@ -559,6 +885,8 @@ pub fn multi_simulate_safe(
 //      writes (from both `handle_message` and the extcall `Saveable::save`
 //      path), bucketed using the per-iteration protostone index set by
 //      protorune.
+//   6. Fuel profiler — opt-in per request (`profile`). Records a fuel tree
+//      per protostone; see `vm::profiler`.
 
 /// Native Rust response shape. The proto encoding in `simulate_protostones_proto`
 /// / `simulate_transaction_proto` mirrors this. Mirrors the user's spec:
@@ -602,6 +930,9 @@ pub struct ProtostoneExecution {
     /// (alkane_id, key) pair across all `handle_message` + extcall
     /// returns that fired inside this protostone's processing.
     pub touched_storage: Vec<(AlkaneId, Vec<(Vec<u8>, Vec<u8>)>)>,
+    /// Fuel tree of the protostone's message, when the fuel profiler was
+    /// enabled for the request.
+    pub profile: Option<FuelFrame>,
 }
 
 #[derive(Debug, Clone)]
@@ -627,6 +958,9 @@ pub struct SimulateProtostonesInput {
     pub transaction_bytes: Option<Vec<u8>>,
     pub block_bytes: Option<Vec<u8>>,
     pub storage_overrides: Vec<(AlkaneId, Vec<(Vec<u8>, Vec<u8>)>)>,
+    /// Outpoints whose balance sheet is replaced before execution; see
+    /// `apply_balance_overrides`.
+    pub balance_overrides: Vec<(OutPoint, Vec<AlkaneTransfer>)>,
 }
 
 /// Decode either a PSBT-hex or raw-tx-hex string into a `bitcoin::Transaction`.
@@ -885,6 +1219,26 @@ fn seed_input_balances(
     Ok(())
 }
 
+/// Replace the balance sheet at each outpoint with exactly the given
+/// alkanes, in the sandbox atomic only, as if the outpoint held them
+/// on-chain. The live entries are zeroed first: `seed_input_balances`
+/// appends, so seeding on top of them alone would leave alkanes the
+/// override doesn't mention in place.
+fn apply_balance_overrides(
+    atomic: &mut AtomicPointer,
+    table: &protorune::tables::RuneTable,
+    overrides: &[(OutPoint, Vec<AlkaneTransfer>)],
+) -> Result<()> {
+    for (outpoint, balances) in overrides {
+        let key = consensus_encode(outpoint)?;
+        protorune::balance_sheet::clear_balances(
+            &atomic.derive(&table.OUTPOINT_TO_RUNES.select(&key)),
+        );
+        seed_input_balances(atomic, table, outpoint, balances)?;
+    }
+    Ok(())
+}
+
 /// Decode the enciphered protostones bytes — the same bytes the
 /// runestone's protocol field would carry — back into a `Vec<u128>`
 /// suitable for `Runestone { protocol: Some(values) }`. Empty input
@@ -917,6 +1271,12 @@ fn touched_storage_for_protostone(
         .collect()
 }
 
+/// Removes and returns the fuel profile recorded for protostone `index`.
+fn take_profile(profiles: &mut Vec<(usize, FuelFrame)>, index: usize) -> Option<FuelFrame> {
+    let position = profiles.iter().position(|(i, _)| *i == index)?;
+    Some(profiles.remove(position).1)
+}
+
 /// RAII guard for the four process-global view-mode collectors. Disabling on
 /// `Drop` guarantees they are turned off on EVERY exit from
 /// `simulate_protostones` — the happy path, an early `?` return (e.g. a failing
@@ -938,6 +1298,15 @@ impl Drop for ViewCollectorGuard {
 /// protostones directly. See the module doc above for the full design.
 pub fn simulate_protostones(
     input: SimulateProtostonesInput,
+) -> Result<SimulateTransactionResponseNative> {
+    simulate_protostones_fueled(input, false)
+}
+
+/// `simulate_protostones`, optionally with the whole block's fuel allocated
+/// to the transaction instead of its vfsize share.
+fn simulate_protostones_fueled(
+    input: SimulateProtostonesInput,
+    unmetered: bool,
 ) -> Result<SimulateTransactionResponseNative> {
     set_view_mode();
 
@@ -948,6 +1317,7 @@ pub fn simulate_protostones(
         transaction_bytes,
         block_bytes,
         storage_overrides,
+        balance_overrides,
     } = input;
 
     let protostones_values = decode_protostones_bytes(&protostones_bytes)?;
@@ -1036,6 +1406,10 @@ pub fn simulate_protostones(
 
     use crate::vm::fuel::FuelTank;
     FuelTank::initialize(&block, height as u32);
+    if unmetered {
+        // `index_protostones` below runs the tx as txindex 0.
+        FuelTank::fuel_transaction_unmetered(0, height as u32);
+    }
 
     // Activate the view-mode collectors. The guard disables them on ANY exit
     // (early `?` return / panic / normal), so they can never leak into the
@@ -1070,6 +1444,7 @@ pub fn simulate_protostones(
             &alkane_inputs,
         )?;
     }
+    apply_balance_overrides(&mut sandbox_atomic, &table, &balance_overrides)?;
 
     let mut balances_by_output: BTreeMap<u32, BalanceSheet<AtomicPointer>> = BTreeMap::new();
 
@@ -1089,6 +1464,7 @@ pub fn simulate_protostones(
     let collected_traces = crate::trace::drain_view_traces();
     let collected_balances = protorune::drain_final_balances();
     let touched_buckets = drain_touched_storage();
+    let mut profiles = profiler::drain();
     crate::trace::disable_view_trace_collector();
     protorune::disable_skip_protostone_persistence();
     protorune::disable_final_balances_sink();
@@ -1143,6 +1519,7 @@ pub fn simulate_protostones(
                 trace: tr,
                 fuel_used: fuel,
                 touched_storage: touched,
+                profile: take_profile(&mut profiles, i),
             }
         })
         .collect();
@@ -1179,13 +1556,29 @@ pub fn simulate_transaction(
     input_hex: &str,
     height: u64,
 ) -> Result<SimulateTransactionResponseNative> {
-    simulate_transaction_with_overrides(input_hex, height, Vec::new())
+    simulate_transaction_with_overrides(input_hex, height, Vec::new(), Vec::new())
 }
 
+/// `simulate_transaction` with storage overrides and outpoint balance
+/// overrides seeded into the sandbox first. A balance override on an
+/// outpoint the tx spends replaces that input's live balance.
 pub fn simulate_transaction_with_overrides(
     input_hex: &str,
     height: u64,
     storage_overrides: Vec<(AlkaneId, Vec<(Vec<u8>, Vec<u8>)>)>,
+    balance_overrides: Vec<(OutPoint, Vec<AlkaneTransfer>)>,
+) -> Result<SimulateTransactionResponseNative> {
+    simulate_transaction_fueled(input_hex, height, storage_overrides, balance_overrides, false)
+}
+
+/// `simulate_transaction_with_overrides`; see `simulate_protostones_fueled`
+/// for `unmetered`.
+fn simulate_transaction_fueled(
+    input_hex: &str,
+    height: u64,
+    storage_overrides: Vec<(AlkaneId, Vec<(Vec<u8>, Vec<u8>)>)>,
+    balance_overrides: Vec<(OutPoint, Vec<AlkaneTransfer>)>,
+    unmetered: bool,
 ) -> Result<SimulateTransactionResponseNative> {
     set_view_mode();
 
@@ -1254,14 +1647,18 @@ pub fn simulate_transaction_with_overrides(
     let faux_block = synthesize_faux_block_for(tx.clone(), height);
     let faux_block_bytes = serialize(&faux_block);
 
-    let mut response = simulate_protostones(SimulateProtostonesInput {
-        height,
-        alkane_inputs: combined,
-        protostones_bytes,
-        transaction_bytes: Some(tx_bytes.clone()),
-        block_bytes: Some(faux_block_bytes.clone()),
-        storage_overrides,
-    })?;
+    let mut response = simulate_protostones_fueled(
+        SimulateProtostonesInput {
+            height,
+            alkane_inputs: combined,
+            protostones_bytes,
+            transaction_bytes: Some(tx_bytes.clone()),
+            block_bytes: Some(faux_block_bytes.clone()),
+            storage_overrides,
+            balance_overrides,
+        },
+        unmetered,
+    )?;
 
     // Overwrite txid/used_bytes (just to be defensive — they should
     // already match since we passed them through).
@@ -1319,6 +1716,7 @@ pub struct SimulateBlockInput {
     pub height: u64,
     pub block_bytes: Vec<u8>,
     pub storage_overrides: Vec<(AlkaneId, Vec<(Vec<u8>, Vec<u8>)>)>,
+    pub balance_overrides: Vec<(OutPoint, Vec<AlkaneTransfer>)>,
 }
 
 /// Build an empty-shape per-tx response for txs we skip (coinbase /
@@ -1337,6 +1735,159 @@ fn empty_tx_response(tx: &Transaction, height: u64, reason: &str) -> SimulateTra
     }
 }
 
+/// Runs `tx` (at `txindex` in `block`) through `index_protostones` on the
+/// shared sandbox, in its own checkpoint: merged into the sandbox on
+/// success so later txs see its outputs, discarded on failure. Coinbase /
+/// no-runestone txs come back as empty-shape entries.
+fn simulate_tx_in_sandbox(
+    sandbox_atomic: &mut AtomicPointer,
+    tx: &Transaction,
+    txindex: usize,
+    block: &Block,
+    height: u64,
+) -> SimulateTransactionResponseNative {
+    use ordinals::{Artifact, Runestone};
+    // Skip coinbase outright — it never carries a runestone and the
+    // alkanes code path doesn't run for it.
+    if txindex == 0 && tx.is_coinbase() {
+        return empty_tx_response(tx, height, "coinbase");
+    }
+
+    let runestone = match Runestone::decipher(tx) {
+        Some(Artifact::Runestone(r)) => r,
+        _ => return empty_tx_response(tx, height, "no_runestone"),
+    };
+    let runestone_output_index = match protorune::Protorune::get_runestone_output_index(tx) {
+        std::result::Result::Ok(i) => i,
+        std::result::Result::Err(_) => {
+            return empty_tx_response(tx, height, "runestone_output_index_unknown");
+        }
+    };
+
+    // Per-tx checkpoint on the shared sandbox: any writes during this
+    // tx's index_protostones land in a temporary layer that gets
+    // merged into the sandbox layer on success (visible to the next
+    // tx) or discarded on failure.
+    sandbox_atomic.checkpoint(); // depth=3: tx layer
+
+    // Activate the per-protostone view-mode collectors fresh for
+    // this tx. drain afterwards so they don't bleed across txs.
+    crate::trace::enable_view_trace_collector();
+    protorune::enable_final_balances_sink();
+    enable_touched_storage_collector();
+    // Persistence flag is OFF in the shared sandbox so save_balances +
+    // clear_balances fire (through the atomic) and the next tx sees the
+    // right outpoint state.
+    protorune::disable_skip_protostone_persistence();
+
+    let mut balances_by_output: BTreeMap<u32, BalanceSheet<AtomicPointer>> = BTreeMap::new();
+    let outcome = protorune::Protorune::index_protostones::<AlkaneMessageContext>(
+        sandbox_atomic,
+        tx,
+        txindex as u32,
+        block,
+        height,
+        &runestone,
+        runestone_output_index,
+        &mut balances_by_output,
+        protorune::default_output(tx),
+    );
+
+    let collected_traces = crate::trace::drain_view_traces();
+    let collected_balances = protorune::drain_final_balances();
+    let touched_buckets = drain_touched_storage();
+    let mut profiles = profiler::drain();
+    crate::trace::disable_view_trace_collector();
+    protorune::disable_final_balances_sink();
+    disable_touched_storage_collector();
+
+    match outcome {
+        std::result::Result::Ok(_) => {
+            // Merge this tx's writes into the sandbox layer so the
+            // next tx in the block sees them.
+            sandbox_atomic.commit(); // depth=2
+        }
+        std::result::Result::Err(e) => {
+            // Discard this tx's partial writes; the next tx sees
+            // pre-failure state.
+            sandbox_atomic.rollback(); // depth=2
+            return SimulateTransactionResponseNative {
+                txid: tx.compute_txid().to_string(),
+                height,
+                protostones: vec![],
+                final_balances_by_vout: vec![],
+                total_fuel_used: 0,
+                used_transaction_bytes: serialize(tx),
+                used_block_bytes: Vec::new(),
+                error: Some(format!("index_protostones failed: {}", e)),
+            };
+        }
+    }
+
+    // Build the per-tx response. Same shape simulate_transaction
+    // returns.
+    use alkanes_support::trace::TraceEvent;
+    let sum_fuel = |tr: &alkanes_support::trace::Trace| -> u64 {
+        let mut acc: u64 = 0;
+        for ev in tr.0.lock().unwrap().iter() {
+            match ev {
+                TraceEvent::ReturnContext(r) | TraceEvent::RevertContext(r) => {
+                    if r.fuel_used != u64::MAX {
+                        acc = acc.saturating_add(r.fuel_used);
+                    }
+                }
+                _ => {}
+            }
+        }
+        acc
+    };
+
+    let mut tx_fuel: u64 = 0;
+    let protostones: Vec<ProtostoneExecution> = collected_traces
+        .into_iter()
+        .enumerate()
+        .map(|(i, (op, tr))| {
+            let fuel = sum_fuel(&tr);
+            tx_fuel = tx_fuel.saturating_add(fuel);
+            let touched = touched_buckets
+                .get(i)
+                .map(touched_storage_for_protostone)
+                .unwrap_or_default();
+            ProtostoneExecution {
+                index: i,
+                outpoint: op,
+                trace: tr,
+                fuel_used: fuel,
+                touched_storage: touched,
+                profile: take_profile(&mut profiles, i),
+            }
+        })
+        .collect();
+
+    let final_balances_by_vout: Vec<VoutBalances> = collected_balances
+        .into_iter()
+        .map(|(vout, sheet)| VoutBalances {
+            vout,
+            runes: sheet
+                .balances()
+                .iter()
+                .map(|(id, amt)| (id.clone(), *amt))
+                .collect(),
+        })
+        .collect();
+
+    SimulateTransactionResponseNative {
+        txid: tx.compute_txid().to_string(),
+        height,
+        protostones,
+        final_balances_by_vout,
+        total_fuel_used: tx_fuel,
+        used_transaction_bytes: serialize(tx),
+        used_block_bytes: Vec::new(),
+        error: None,
+    }
+}
+
 pub fn simulate_block(input: SimulateBlockInput) -> Result<SimulateBlockResponseNative> {
     set_view_mode();
 
@@ -1355,162 +1906,22 @@ pub fn simulate_block(input: SimulateBlockInput) -> Result<SimulateBlockResponse
     let mut sandbox_atomic = AtomicPointer::default();
     sandbox_atomic.checkpoint(); // depth=2: sandbox layer
 
-    // Apply pre-execution storage overrides into the sandbox layer.
+    // Apply pre-execution storage + balance overrides into the sandbox layer.
     apply_storage_overrides(&mut sandbox_atomic, &input.storage_overrides);
 
     let table = protorune::tables::RuneTable::for_protocol(
         <AlkaneMessageContext as MessageContext>::protocol_tag(),
     );
+    apply_balance_overrides(&mut sandbox_atomic, &table, &input.balance_overrides)?;
 
     let mut txs: Vec<SimulateTransactionResponseNative> = Vec::with_capacity(block.txdata.len());
     let mut total_fuel_used: u64 = 0;
 
-    use ordinals::{Artifact, Runestone};
     for (txindex, tx) in block.txdata.iter().enumerate() {
-        // Skip coinbase outright — it never carries a runestone and the
-        // alkanes code path doesn't run for it.
-        if txindex == 0 && tx.is_coinbase() {
-            txs.push(empty_tx_response(tx, input.height, "coinbase"));
-            continue;
-        }
-
-        let runestone = match Runestone::decipher(tx) {
-            Some(Artifact::Runestone(r)) => r,
-            _ => {
-                txs.push(empty_tx_response(tx, input.height, "no_runestone"));
-                continue;
-            }
-        };
-        let runestone_output_index = match protorune::Protorune::get_runestone_output_index(tx) {
-            std::result::Result::Ok(i) => i,
-            std::result::Result::Err(_) => {
-                txs.push(empty_tx_response(tx, input.height, "runestone_output_index_unknown"));
-                continue;
-            }
-        };
-
-        // Per-tx checkpoint on the shared sandbox: any writes during this
-        // tx's index_protostones land in a temporary layer that gets
-        // merged into the sandbox layer on success (visible to the next
-        // tx) or discarded on failure.
-        sandbox_atomic.checkpoint(); // depth=3: tx layer
-
-        // Activate the per-protostone view-mode collectors fresh for
-        // this tx. drain afterwards so they don't bleed across txs.
-        crate::trace::enable_view_trace_collector();
-        protorune::enable_final_balances_sink();
-        enable_touched_storage_collector();
-        // Persistence flag is OFF for simulateblock so save_balances +
-        // clear_balances fire (through the atomic) and the next tx in
-        // the block sees the right outpoint state.
-        protorune::disable_skip_protostone_persistence();
-
-        let mut balances_by_output: BTreeMap<u32, BalanceSheet<AtomicPointer>> = BTreeMap::new();
-        let outcome = protorune::Protorune::index_protostones::<AlkaneMessageContext>(
-            &mut sandbox_atomic,
-            tx,
-            txindex as u32,
-            &block,
-            input.height,
-            &runestone,
-            runestone_output_index,
-            &mut balances_by_output,
-            protorune::default_output(tx),
-        );
-
-        let collected_traces = crate::trace::drain_view_traces();
-        let collected_balances = protorune::drain_final_balances();
-        let touched_buckets = drain_touched_storage();
-        crate::trace::disable_view_trace_collector();
-        protorune::disable_final_balances_sink();
-        disable_touched_storage_collector();
-
-        match outcome {
-            std::result::Result::Ok(_) => {
-                // Merge this tx's writes into the sandbox layer so the
-                // next tx in the block sees them.
-                sandbox_atomic.commit(); // depth=2
-            }
-            std::result::Result::Err(e) => {
-                // Discard this tx's partial writes; the next tx sees
-                // pre-failure state.
-                sandbox_atomic.rollback(); // depth=2
-                txs.push(SimulateTransactionResponseNative {
-                    txid: tx.compute_txid().to_string(),
-                    height: input.height,
-                    protostones: vec![],
-                    final_balances_by_vout: vec![],
-                    total_fuel_used: 0,
-                    used_transaction_bytes: serialize(tx),
-                    used_block_bytes: Vec::new(),
-                    error: Some(format!("index_protostones failed: {}", e)),
-                });
-                continue;
-            }
-        }
-
-        // Build the per-tx response. Same shape simulate_transaction
-        // returns.
-        use alkanes_support::trace::TraceEvent;
-        let sum_fuel = |tr: &alkanes_support::trace::Trace| -> u64 {
-            let mut acc: u64 = 0;
-            for ev in tr.0.lock().unwrap().iter() {
-                match ev {
-                    TraceEvent::ReturnContext(r) | TraceEvent::RevertContext(r) => {
-                        if r.fuel_used != u64::MAX {
-                            acc = acc.saturating_add(r.fuel_used);
-                        }
-                    }
-                    _ => {}
-                }
-            }
-            acc
-        };
-
-        let mut tx_fuel: u64 = 0;
-        let protostones: Vec<ProtostoneExecution> = collected_traces
-            .into_iter()
-            .enumerate()
-            .map(|(i, (op, tr))| {
-                let fuel = sum_fuel(&tr);
-                tx_fuel = tx_fuel.saturating_add(fuel);
-                let touched = touched_buckets
-                    .get(i)
-                    .map(touched_storage_for_protostone)
-                    .unwrap_or_default();
-                ProtostoneExecution {
-                    index: i,
-                    outpoint: op,
-                    trace: tr,
-                    fuel_used: fuel,
-                    touched_storage: touched,
-                }
-            })
-            .collect();
-
-        let final_balances_by_vout: Vec<VoutBalances> = collected_balances
-            .into_iter()
-            .map(|(vout, sheet)| VoutBalances {
-                vout,
-                runes: sheet
-                    .balances()
-                    .iter()
-                    .map(|(id, amt)| (id.clone(), *amt))
-                    .collect(),
-            })
-            .collect();
-
-        total_fuel_used = total_fuel_used.saturating_add(tx_fuel);
-        txs.push(SimulateTransactionResponseNative {
-            txid: tx.compute_txid().to_string(),
-            height: input.height,
-            protostones,
-            final_balances_by_vout,
-            total_fuel_used: tx_fuel,
-            used_transaction_bytes: serialize(tx),
-            used_block_bytes: Vec::new(),
-            error: None,
-        });
+        let response =
+            simulate_tx_in_sandbox(&mut sandbox_atomic, tx, txindex, &block, input.height);
+        total_fuel_used = total_fuel_used.saturating_add(response.total_fuel_used);
+        txs.push(response);
     }
 
     // Reference `table` to silence any unused-binding lint if no tx with
@@ -1531,6 +1942,103 @@ pub fn simulate_block(input: SimulateBlockInput) -> Result<SimulateBlockResponse
     })
 }
 
+// ---------------------------------------------------------------------------
+// simulate_chain — dependent unconfirmed txs, one faux block each.
+// ---------------------------------------------------------------------------
+//
+// Like simulate_block, but for a caller's own ordered txs that are not in
+// any block yet (wrap → swap → add-liquidity, each spending the last).
+// Tx i runs in its own faux block at `height + i`: the first chains off
+// the previous indexed block as in simulate_transaction, each later one
+// off the faux block before it. All of them share one sandbox, so a tx
+// spending an earlier tx's output sees the alkanes that tx routed there;
+// a tx that fails is rolled back and later txs see the state before it.
+
+#[derive(Debug, Clone)]
+pub struct SimulateChainInput {
+    /// Height of the first faux block.
+    pub height: u64,
+    /// Raw txs or PSBTs, in the order they would be mined.
+    pub transactions: Vec<Vec<u8>>,
+    pub storage_overrides: Vec<(AlkaneId, Vec<(Vec<u8>, Vec<u8>)>)>,
+    pub balance_overrides: Vec<(OutPoint, Vec<AlkaneTransfer>)>,
+}
+
+#[derive(Debug, Clone)]
+pub struct SimulateChainResponseNative {
+    pub height: u64,
+    /// One entry per input tx, in input order; `used_block_bytes` is that
+    /// tx's faux block.
+    pub txs: Vec<SimulateTransactionResponseNative>,
+    pub total_fuel_used: u64,
+    pub error: Option<String>,
+}
+
+/// A faux block for `tx` at `height`, chained off `prev` when given (a
+/// faux block of the same simulation) rather than the indexed chain.
+fn synthesize_chained_faux_block_for(
+    tx: Transaction,
+    height: u64,
+    prev: Option<&Block>,
+) -> Block {
+    let mut block = synthesize_faux_block_for(tx, height);
+    if let Some(prev) = prev {
+        block.header.prev_blockhash = prev.block_hash();
+        block.header.time = prev.header.time.saturating_add(600);
+        block.header.bits = prev.header.bits;
+    }
+    block
+}
+
+pub fn simulate_chain(input: SimulateChainInput) -> Result<SimulateChainResponseNative> {
+    set_view_mode();
+
+    let txs = input
+        .transactions
+        .iter()
+        .enumerate()
+        .map(|(i, bytes)| {
+            decode_tx_or_psbt_bytes(bytes).map_err(|e| anyhow!("simulatechain: tx {}: {}", i, e))
+        })
+        .collect::<Result<Vec<Transaction>>>()?;
+
+    // Same checkpoint discipline as simulate_block: a sandbox layer that
+    // is never committed, with a per-tx layer inside it.
+    let mut sandbox_atomic = AtomicPointer::default();
+    sandbox_atomic.checkpoint(); // depth=2: sandbox layer
+    apply_storage_overrides(&mut sandbox_atomic, &input.storage_overrides);
+    apply_balance_overrides(
+        &mut sandbox_atomic,
+        &protorune::tables::RuneTable::for_protocol(
+            <AlkaneMessageContext as MessageContext>::protocol_tag(),
+        ),
+        &input.balance_overrides,
+    )?;
+
+    use crate::vm::fuel::FuelTank;
+    let mut responses: Vec<SimulateTransactionResponseNative> = Vec::with_capacity(txs.len());
+    let mut total_fuel_used: u64 = 0;
+    let mut prev: Option<Block> = None;
+    for (i, tx) in txs.into_iter().enumerate() {
+        let height = input.height + i as u64;
+        let block = synthesize_chained_faux_block_for(tx, height, prev.as_ref());
+        FuelTank::initialize(&block, height as u32);
+        let mut response =
+            simulate_tx_in_sandbox(&mut sandbox_atomic, &block.txdata[1], 1, &block, height);
+        response.used_block_bytes = serialize(&block);
+        total_fuel_used = total_fuel_used.saturating_add(response.total_fuel_used);
+        responses.push(response);
+        prev = Some(block);
+    }
+
+    Ok(SimulateChainResponseNative {
+        height: input.height,
+        txs: responses,
+        total_fuel_used,
+        error: None,
+    })
+}
+
 // ---------------------------------------------------------------------------
 // proto-encoded entry points — what `lib.rs` calls from the no_mangle
 // wasm exports `simulateprotostones()` and `simulatetransaction()`.
@@ -1597,6 +2105,26 @@ fn overrides_from_proto(
         .collect()
 }
 
+fn balance_overrides_from_proto(
+    v: &[proto::alkanes::BalanceOverride],
+) -> Result<Vec<(OutPoint, Vec<AlkaneTransfer>)>> {
+    v.iter()
+        .map(|o| {
+            let outpoint = o
+                .outpoint
+                .as_ref()
+                .ok_or_else(|| anyhow!("balance override without an outpoint"))?;
+            Ok((
+                OutPoint {
+                    txid: bitcoin::Txid::from_byte_array(outpoint.txid.as_slice().try_into()?),
+                    vout: outpoint.vout,
+                },
+                o.balances.iter().map(alkane_transfer_from_proto).collect(),
+            ))
+        })
+        .collect()
+}
+
 fn response_to_proto(r: SimulateTransactionResponseNative) -> proto::alkanes::SimulateTransactionResponse {
     proto::alkanes::SimulateTransactionResponse {
         txid: r.txid,
@@ -1623,6 +2151,7 @@ fn response_to_proto(r: SimulateTransactionResponseNative) -> proto::alkanes::Si
                             .collect(),
                     })
                     .collect(),
+                profile: p.profile.map(Into::into),
             })
             .collect(),
         final_balances_by_vout: r
@@ -1657,6 +2186,7 @@ fn response_to_proto(r: SimulateTransactionResponseNative) -> proto::alkanes::Si
 pub fn simulate_protostones_proto(input: &[u8]) -> Result<Vec<u8>> {
     let req = proto::alkanes::SimulateProtostonesRequest::decode(input)
         .map_err(|e| anyhow!("decode SimulateProtostonesRequest: {}", e))?;
+    let _profiler = req.profile.then(profiler::enable);
     let native = simulate_protostones(SimulateProtostonesInput {
         height: req.height,
         alkane_inputs: req
@@ -1676,6 +2206,7 @@ pub fn simulate_protostones_proto(input: &[u8]) -> Result<Vec<u8>> {
             Some(req.block)
         },
         storage_overrides: overrides_from_proto(&req.storage_overrides),
+        balance_overrides: vec![],
     })?;
     Ok(response_to_proto(native).encode_to_vec())
 }
@@ -1684,6 +2215,7 @@ pub fn simulate_protostones_proto(input: &[u8]) -> Result<Vec<u8>> {
 pub fn simulate_transaction_proto(input: &[u8]) -> Result<Vec<u8>> {
     let req = proto::alkanes::SimulateTransactionRequest::decode(input)
         .map_err(|e| anyhow!("decode SimulateTransactionRequest: {}", e))?;
+    let _profiler = req.profile.then(profiler::enable);
     // Accept either raw tx bytes or PSBT — decode_tx_or_psbt_bytes
     // handles both. We re-hex-encode here just to satisfy the existing
     // `simulate_transaction(&str, u64)` signature without adding a
@@ -1693,6 +2225,7 @@ pub fn simulate_transaction_proto(input: &[u8]) -> Result<Vec<u8>> {
         &hex_input,
         req.height,
         overrides_from_proto(&req.storage_overrides),
+        balance_overrides_from_proto(&req.balance_overrides)?,
     )?;
     Ok(response_to_proto(native).encode_to_vec())
 }
@@ -1701,10 +2234,12 @@ pub fn simulate_transaction_proto(input: &[u8]) -> Result<Vec<u8>> {
 pub fn simulate_block_proto(input: &[u8]) -> Result<Vec<u8>> {
     let req = proto::alkanes::SimulateBlockRequest::decode(input)
         .map_err(|e| anyhow!("decode SimulateBlockRequest: {}", e))?;
+    let _profiler = req.profile.then(profiler::enable);
     let native = simulate_block(SimulateBlockInput {
         height: req.height,
         block_bytes: req.block,
         storage_overrides: overrides_from_proto(&req.storage_overrides),
+        balance_overrides: balance_overrides_from_proto(&req.balance_overrides)?,
     })?;
     let resp = proto::alkanes::SimulateBlockResponse {
         block_hash: native.block_hash,
@@ -1717,6 +2252,116 @@ pub fn simulate_block_proto(input: &[u8]) -> Result<Vec<u8>> {
     Ok(resp.encode_to_vec())
 }
 
+/// Entry point for the `simulatechain()` wasm export.
+pub fn simulate_chain_proto(input: &[u8]) -> Result<Vec<u8>> {
+    let req = proto::alkanes::SimulateChainRequest::decode(input)
+        .map_err(|e| anyhow!("decode SimulateChainRequest: {}", e))?;
+    let _profiler = req.profile.then(profiler::enable);
+    let native = simulate_chain(SimulateChainInput {
+        height: req.height,
+        transactions: req.transactions,
+        storage_overrides: overrides_from_proto(&req.storage_overrides),
+        balance_overrides: balance_overrides_from_proto(&req.balance_overrides)?,
+    })?;
+    let resp = proto::alkanes::SimulateChainResponse {
+        height: native.height,
+        txs: native.txs.into_iter().map(response_to_proto).collect(),
+        total_fuel_used: native.total_fuel_used,
+        error: native.error.unwrap_or_default(),
+    };
+    Ok(resp.encode_to_vec())
+}
+
+/// Fuel the transaction is charged for one protostone's message: the
+/// `fuel_used` of its top-level call, which already covers its extcalls.
+/// A reverted call traces no fuel, so it reports 0 and `estimate_fuel`
+/// fails the estimate.
+fn protostone_fuel(trace: &alkanes_support::trace::Trace) -> proto::alkanes::ProtostoneFuel {
+    use alkanes_support::trace::TraceEvent;
+    match trace.0.lock().unwrap().last() {
+        Some(TraceEvent::ReturnContext(r)) => proto::alkanes::ProtostoneFuel {
+            fuel_used: r.fuel_used,
+            ..Default::default()
+        },
+        Some(TraceEvent::RevertContext(r)) => proto::alkanes::ProtostoneFuel {
+            fuel_used: 0,
+            reverted: true,
+            revert_data: r.inner.data.clone(),
+        },
+        _ => proto::alkanes::ProtostoneFuel::default(),
+    }
+}
+
+/// Simulates `req.transaction` with the whole block's fuel available and
+/// compares the fuel its protostones used against what `FuelTank` would
+/// allocate it, assuming the rest of its block adds up to `block_vfsize`.
+pub fn estimate_fuel(
+    req: &proto::alkanes::EstimateFuelRequest,
+) -> Result<proto::alkanes::EstimateFuelResponse> {
+    let height = req.height as u32;
+    let tx = decode_tx_or_psbt_bytes(&req.transaction)?;
+    let simulated = simulate_transaction_fueled(
+        &hex::encode(&req.transaction),
+        req.height,
+        overrides_from_proto(&req.storage_overrides),
+        balance_overrides_from_proto(&req.balance_overrides)?,
+        true,
+    )?;
+    let block_vfsize = if req.block_vfsize != 0 {
+        req.block_vfsize
+    } else {
+        height
+            .checked_sub(1)
+            .and_then(|previous| crate::etl::get_block(previous).ok())
+            .map(|block| block.vfsize())
+            .unwrap_or(0)
+    };
+    let transaction_vfsize = tx.vfsize();
+    let protostones: Vec<proto::alkanes::ProtostoneFuel> = simulated
+        .protostones
+        .iter()
+        .map(|p| protostone_fuel(&p.trace))
+        .collect();
+    let fuel_used = protostones
+        .iter()
+        .fold(0u64, |acc, p| acc.saturating_add(p.fuel_used));
+    // The fuel a reverted call burnt before reverting is not traced, so
+    // `fuel_used` would undercount it.
+    let error = match protostones.iter().position(|p| p.reverted) {
+        Some(index) if simulated.error.is_none() => format!(
+            "protostone {} reverted, its fuel cannot be estimated: {}",
+            index,
+            String::from_utf8_lossy(protostones[index].revert_data.get(4..).unwrap_or_default())
+        ),
+        _ => simulated.error.unwrap_or_default(),
+    };
+    let minimum_vfsize = fuel::minimum_vfsize_for(fuel_used, block_vfsize, height);
+    Ok(proto::alkanes::EstimateFuelResponse {
+        height: req.height,
+        protostones,
+        fuel_used,
+        minimum_fuel: fuel::minimum_fuel(height),
+        block_fuel: fuel::total_fuel(height),
+        block_vfsize,
+        transaction_vfsize,
+        allocated_fuel: fuel::transaction_fuel_for(
+            transaction_vfsize,
+            block_vfsize.saturating_add(transaction_vfsize),
+            height,
+        ),
+        minimum_vfsize: minimum_vfsize.unwrap_or(0),
+        satisfiable: minimum_vfsize.is_some(),
+        error,
+    })
+}
+
+/// Entry point for the `estimatefuel()` wasm export.
+pub fn estimate_fuel_proto(input: &[u8]) -> Result<Vec<u8>> {
+    let req = proto::alkanes::EstimateFuelRequest::decode(input)
+        .map_err(|e| anyhow!("decode EstimateFuelRequest: {}", e))?;
+    Ok(estimate_fuel(&req)?.encode_to_vec())
+}
+
 pub fn getbytecode(input: &Vec<u8>, height: u32) -> Result<Vec<u8>> {
     let request = alkanes_support::proto::alkanes::BytecodeRequest::decode(&**input)?;
     let alkane_id = request.id.clone().unwrap();
@@ -1725,7 +2370,7 @@ pub fn getbytecode(input: &Vec<u8>, height: u32) -> Result<Vec<u8>> {
     // Get the bytecode from storage; precompiled built-ins resolve from the
     // static in-binary version maps at `height`.
     let bytecode = get_alkane_binary(
-        metashrew_core::index_pointer::IndexPointer::from_keyword("/alkanes/"),
+        metashrew_native::index_pointer::IndexPointer::from_keyword("/alkanes/"),
         &alkane_id,
         height,
     )?;
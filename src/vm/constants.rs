@@ -1,5 +1,10 @@
 pub(super) const MEMORY_LIMIT: usize = 43554432;
 
+// Size limits for a single `__emit_event` call. Exceeding either aborts the
+// emitting frame.
+pub const MAX_EVENT_TOPIC_SIZE: usize = 64;
+pub const MAX_EVENT_DATA_SIZE: usize = 4096;
+
 // Maximum checkpoint (extcall) depth before an extcall is rejected as
 // possible infinite recursion. This is consensus-critical: changing it
 // alters which call chains revert, so it must match deployed indexers.
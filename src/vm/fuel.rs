@@ -234,6 +234,18 @@ impl FuelTank {
         }
     }
 
+    /// Allocates the transaction at `txindex` the whole block's fuel ahead of
+    /// its first message, which then skips `fuel_transaction`. Used by the
+    /// `estimatefuel` view so the simulation measures what the protostones
+    /// need rather than what the synthetic block grants them.
+    pub fn fuel_transaction_unmetered(txindex: u32, height: u32) {
+        let mut tank = _FUEL_TANK.write().unwrap();
+        let tank = tank.as_mut().unwrap();
+        tank.current_txindex = txindex;
+        tank.transaction_fuel = total_fuel(height);
+        tank.block_metered_fuel = 0;
+    }
+
     pub fn refuel_block() {
         let mut tank = _FUEL_TANK.write().unwrap();
         let tank = tank.as_mut().unwrap();
@@ -324,6 +336,73 @@ pub const fn minimum_fuel(height: u32) -> u64 {
         MINIMUM_FUEL_START
     }
 }
+
+/// Fuel `FuelTank::fuel_transaction` allocates a transaction of `txsize`
+/// vfsize that is first in a block of `block_size` vfsize (itself included).
+#[cfg(not(any(
+    feature = "mainnet",
+    feature = "dogecoin",
+    feature = "bellscoin",
+    feature = "fractal",
+    feature = "luckycoin"
+)))]
+pub fn transaction_fuel_for(_txsize: u64, _block_size: u64, height: u32) -> u64 {
+    minimum_fuel(height)
+}
+
+#[cfg(any(
+    feature = "mainnet",
+    feature = "dogecoin",
+    feature = "bellscoin",
+    feature = "fractal",
+    feature = "luckycoin"
+))]
+pub fn transaction_fuel_for(txsize: u64, block_size: u64, height: u32) -> u64 {
+    let metered = if block_size == 0 {
+        total_fuel(height)
+    } else {
+        (total_fuel(height) as u128 * txsize as u128 / block_size as u128) as u64
+    };
+    std::cmp::max(minimum_fuel(height), metered)
+}
+
+/// Smallest vfsize for which `transaction_fuel_for` covers `fuel` when the
+/// rest of the block adds up to `other_size` vfsize. `Some(0)` means the
+/// minimum allocation already covers it; `None` means no size does.
+#[cfg(not(any(
+    feature = "mainnet",
+    feature = "dogecoin",
+    feature = "bellscoin",
+    feature = "fractal",
+    feature = "luckycoin"
+)))]
+pub fn minimum_vfsize_for(fuel: u64, _other_size: u64, height: u32) -> Option<u64> {
+    (fuel <= minimum_fuel(height)).then_some(0)
+}
+
+#[cfg(any(
+    feature = "mainnet",
+    feature = "dogecoin",
+    feature = "bellscoin",
+    feature = "fractal",
+    feature = "luckycoin"
+))]
+pub fn minimum_vfsize_for(fuel: u64, other_size: u64, height: u32) -> Option<u64> {
+    if fuel <= minimum_fuel(height) {
+        return Some(0);
+    }
+    // total * x / (other + x) >= fuel  <=>  x * (total - fuel) >= fuel * other
+    let total = total_fuel(height);
+    if fuel > total || (fuel == total && other_size > 0) {
+        return None;
+    }
+    let headroom = (total - fuel) as u128;
+    if headroom == 0 {
+        return Some(1);
+    }
+    let needed = (fuel as u128 * other_size as u128).div_ceil(headroom);
+    u64::try_from(std::cmp::max(needed, 1)).ok()
+}
 //use if regtest
 #[cfg(not(any(
     feature = "mainnet",
@@ -361,6 +440,7 @@ pub const FUEL_FUEL: u64 = 5;
 pub const FUEL_EXTCALL: u64 = 500;
 pub const FUEL_HEIGHT: u64 = 10;
 pub const FUEL_BALANCE: u64 = 10;
+pub const FUEL_EMIT_EVENT: u64 = 100; // Fixed cost per event, plus store-byte cost of topic + data
 pub const FUEL_EXTCALL_DEPLOY_START: u64 = 10_000;
 pub const FUEL_EXTCALL_DEPLOY_CHANGE1: u64 = 100_000;
 pub const fn fuel_extcall_deploy(height: u32) -> u64 {
@@ -385,6 +465,7 @@ impl<'a> Fuelable for Caller<'_, AlkanesState> {
             self.set_fuel(remaining)
                 .map_err(|e| anyhow!("failed to set fuel: {}", e))?;
         }
+        super::profiler::charge(n);
         Ok(())
     }
 }
@@ -399,6 +480,7 @@ impl Fuelable for AlkanesInstance {
                 .set_fuel(remaining)
                 .map_err(|e| anyhow!("failed to set fuel: {}", e))?;
         }
+        super::profiler::charge(n);
         Ok(())
     }
 }
@@ -1,5 +1,6 @@
-use super::constants::max_checkpoint_depth;
+use super::constants::{max_checkpoint_depth, MAX_EVENT_DATA_SIZE, MAX_EVENT_TOPIC_SIZE};
 use super::fuel::compute_extcall_fuel;
+use super::profiler::{self, FrameKind};
 use super::{
     get_memory, read_arraybuffer, send_to_arraybuffer, sequence_pointer, AlkanesState, Extcall,
     Saveable, SaveableExtendedCallResponse,
@@ -18,12 +19,12 @@ use alkanes_support::{
 #[allow(unused_imports)]
 use anyhow::{anyhow, Result};
 use bitcoin::{BlockHash, Transaction};
-use metashrew_core::index_pointer::IndexPointer;
 #[allow(unused_imports)]
 use metashrew_core::{
     print, println,
     stdio::{stdout, Write},
 };
+use metashrew_native::index_pointer::IndexPointer;
 use metashrew_support::index_pointer::KeyValuePointer;
 use num::traits::ToBytes;
 use ordinals::Artifact;
@@ -31,9 +32,9 @@ use ordinals::Runestone;
 use protorune_support::protostone::Protostone;
 
 use crate::vm::fuel::{
-    consume_fuel, fuel_extcall_deploy, fuel_per_store_byte, Fuelable, FUEL_BALANCE, FUEL_EXTCALL,
-    FUEL_FUEL, FUEL_HEIGHT, FUEL_LOAD_BLOCK, FUEL_LOAD_TRANSACTION, FUEL_PER_LOAD_BYTE,
-    FUEL_PER_REQUEST_BYTE, FUEL_SEQUENCE,
+    consume_fuel, fuel_extcall_deploy, fuel_per_store_byte, Fuelable, FUEL_BALANCE,
+    FUEL_EMIT_EVENT, FUEL_EXTCALL, FUEL_FUEL, FUEL_HEIGHT, FUEL_LOAD_BLOCK, FUEL_LOAD_TRANSACTION,
+    FUEL_PER_LOAD_BYTE, FUEL_PER_REQUEST_BYTE, FUEL_SEQUENCE,
 };
 use protorune_support::utils::{consensus_encode, decode_varint_list};
 use std::collections::BTreeMap;
@@ -99,7 +100,7 @@ impl AlkanesHostFunctionsImpl {
         caller: &mut Caller<'_, AlkanesState>,
         k: i32,
     ) -> Result<i32> {
-        let (bytes_processed, result) = {
+        let (bytes_processed, result, key) = {
             let mem = get_memory(caller)?;
             let key = {
                 let data = mem.data(&caller);
@@ -120,7 +121,7 @@ impl AlkanesHostFunctionsImpl {
                 .get()
                 .len()
                 .try_into()?;
-            ((result as u64) + (key.len() as u64), result)
+            ((result as u64) + (key.len() as u64), result, key)
         };
 
         let fuel_cost =
@@ -136,6 +137,7 @@ impl AlkanesHostFunctionsImpl {
         }
 
         consume_fuel(caller, fuel_cost)?;
+        profiler::storage_read(&key, bytes_processed, fuel_cost);
         Ok(result)
     }
     pub(super) fn load_storage<'a>(
@@ -143,7 +145,7 @@ impl AlkanesHostFunctionsImpl {
         k: i32,
         v: i32,
     ) -> Result<i32> {
-        let (bytes_processed, value) = {
+        let (bytes_processed, value, key) = {
             let mem = get_memory(caller)?;
             let key = {
                 let data = mem.data(&caller);
@@ -159,7 +161,7 @@ impl AlkanesHostFunctionsImpl {
                     .select(&key)
                     .get()
             };
-            (key.len() + value.len(), value)
+            (key.len() + value.len(), value, key)
         };
 
         let fuel_cost = overflow_error((bytes_processed as u64).checked_mul(FUEL_PER_LOAD_BYTE))?;
@@ -172,6 +174,7 @@ impl AlkanesHostFunctionsImpl {
         }
 
         consume_fuel(caller, fuel_cost)?;
+        profiler::storage_read(&key, bytes_processed as u64, fuel_cost);
         send_to_arraybuffer(caller, v.try_into()?, value.as_ref())
     }
     pub(super) fn request_context(caller: &mut Caller<'_, AlkanesState>) -> Result<i32> {
@@ -824,6 +827,16 @@ impl AlkanesHostFunctionsImpl {
         }
 
         consume_fuel(caller, total_fuel)?;
+        let deploy_fuel = if cellpack.target.is_deployment() {
+            fuel_extcall_deploy(height)
+        } else {
+            0
+        };
+        profiler::extcall(
+            FrameKind::of::<T>(),
+            total_fuel.saturating_add(deploy_fuel),
+            storage_map_len,
+        );
 
         let mut trace_context: TraceContext = subcontext.flat().into();
         let start_fuel: u64 = caller.get_fuel()?;
@@ -909,6 +922,62 @@ impl AlkanesHostFunctionsImpl {
         print!("{}", String::from_utf8(message)?);
         Ok(())
     }
+    pub(super) fn emit_event<'a>(
+        caller: &mut Caller<'_, AlkanesState>,
+        topic_ptr: i32,
+        data_ptr: i32,
+    ) -> Result<()> {
+        let (topic, data) = {
+            let mem = get_memory(caller)?;
+            let mem_data = mem.data(&caller);
+            (
+                read_arraybuffer(mem_data, topic_ptr)?,
+                read_arraybuffer(mem_data, data_ptr)?,
+            )
+        };
+        if topic.len() > MAX_EVENT_TOPIC_SIZE {
+            return Err(anyhow!(
+                "event topic of {} bytes exceeds the {} byte limit",
+                topic.len(),
+                MAX_EVENT_TOPIC_SIZE
+            ));
+        }
+        if data.len() > MAX_EVENT_DATA_SIZE {
+            return Err(anyhow!(
+                "event data of {} bytes exceeds the {} byte limit",
+                data.len(),
+                MAX_EVENT_DATA_SIZE
+            ));
+        }
+        // Events are persisted by the indexer, so they are priced like a
+        // storage write of the same size.
+        let height = caller.data_mut().context.lock().unwrap().message.height as u32;
+        let fuel_cost = overflow_error(
+            ((topic.len() + data.len()) as u64)
+                .checked_mul(fuel_per_store_byte(height))
+                .and_then(|v| v.checked_add(FUEL_EMIT_EVENT)),
+        )?;
+
+        #[cfg(feature = "debug-log")]
+        {
+            println!(
+                "emit_event: topic_size={} bytes, data_size={} bytes, fuel_cost={}",
+                topic.len(),
+                data.len(),
+                fuel_cost
+            );
+        }
+
+        consume_fuel(caller, fuel_cost)?;
+        let context_guard = caller.data_mut().context.lock().unwrap();
+        let alkane = context_guard.myself.clone();
+        context_guard.trace.clock(TraceEvent::EmitEvent {
+            alkane,
+            topic,
+            data,
+        });
+        Ok(())
+    }
 }
 
 // Implementation of the safe wrapper
@@ -1019,6 +1088,16 @@ impl SafeAlkanesHostFunctionsImpl {
         Self::with_context_safety(caller, |c| AlkanesHostFunctionsImpl::height(c, output))
     }
 
+    pub(super) fn emit_event(
+        caller: &mut Caller<'_, AlkanesState>,
+        topic: i32,
+        data: i32,
+    ) -> Result<()> {
+        Self::with_context_safety(caller, |c| {
+            AlkanesHostFunctionsImpl::emit_event(c, topic, data)
+        })
+    }
+
     pub(super) fn handle_extcall<'a, T: Extcall>(
         caller: &mut Caller<'a, AlkanesState>,
         cellpack_ptr: i32,
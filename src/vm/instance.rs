@@ -1,7 +1,9 @@
+use super::module_cache::{self, Loaded};
 use super::{
     extcall::*, read_arraybuffer, AlkanesExportsImpl, AlkanesRuntimeContext, AlkanesState,
     SafeAlkanesHostFunctionsImpl, MEMORY_LIMIT,
 };
+use crate::network::is_events_active;
 use alkanes_support::{
     response::{CallResponse, ExtendedCallResponse},
     trace::{TraceEvent, TraceResponse},
@@ -80,9 +82,22 @@ impl AlkanesInstance {
             .select(&context.myself.clone().into())
             .get();
             */
-        let mut config = Config::default();
-        config.consume_fuel(true);
-        let engine = Engine::new(&config);
+        let height = context.lock().unwrap().message.height;
+        Self::with_host_functions(context, binary, start_fuel, is_events_active(height))
+    }
+    /// Instantiates `binary` against the host functions of a height before
+    /// the events fork, or from it on when `events` is set.
+    pub fn with_host_functions(
+        context: Arc<Mutex<AlkanesRuntimeContext>>,
+        binary: Arc<Vec<u8>>,
+        start_fuel: u64,
+        events: bool,
+    ) -> Result<Self> {
+        let Loaded {
+            engine,
+            module,
+            linker,
+        } = module_cache::load(&binary, events)?;
         let mut store = Store::<AlkanesState>::new(
             &engine,
             AlkanesState {
@@ -93,242 +108,6 @@ impl AlkanesInstance {
         );
         store.limiter(|state| &mut state.limiter);
         Store::<AlkanesState>::set_fuel(&mut store, start_fuel)?; // TODO: implement gas limits
-        let module = Module::new(&engine, &mut &binary[..])?;
-        let mut linker: Linker<AlkanesState> = Linker::<AlkanesState>::new(&engine);
-        linker.func_wrap("env", "abort", SafeAlkanesHostFunctionsImpl::abort)?;
-        linker.func_wrap(
-            "env",
-            "__load_storage",
-            |mut caller: Caller<'_, AlkanesState>, k: i32, v: i32| {
-                match SafeAlkanesHostFunctionsImpl::load_storage(&mut caller, k, v) {
-                    Ok(v) => v,
-                    Err(_e) => {
-                        SafeAlkanesHostFunctionsImpl::_abort(caller);
-                        -1
-                    }
-                }
-            },
-        )?;
-        linker.func_wrap(
-            "env",
-            "__request_storage",
-            |mut caller: Caller<'_, AlkanesState>, k: i32| {
-                match SafeAlkanesHostFunctionsImpl::request_storage(&mut caller, k) {
-                    Ok(v) => v,
-                    Err(_e) => {
-                        SafeAlkanesHostFunctionsImpl::_abort(caller);
-                        -1
-                    }
-                }
-            },
-        )?;
-        linker.func_wrap(
-            "env",
-            "__log",
-            |mut caller: Caller<'_, AlkanesState>, v: i32| {
-                if let Err(_e) = SafeAlkanesHostFunctionsImpl::log(&mut caller, v) {
-                    SafeAlkanesHostFunctionsImpl::_abort(caller);
-                }
-            },
-        )?;
-        linker.func_wrap(
-            "env",
-            "__balance",
-            |mut caller: Caller<'_, AlkanesState>, who: i32, what: i32, output: i32| {
-                if let Err(_e) =
-                    SafeAlkanesHostFunctionsImpl::balance(&mut caller, who, what, output)
-                {
-                    SafeAlkanesHostFunctionsImpl::_abort(caller);
-                }
-            },
-        )?;
-        linker.func_wrap(
-            "env",
-            "__request_context",
-            |mut caller: Caller<'_, AlkanesState>| -> i32 {
-                match SafeAlkanesHostFunctionsImpl::request_context(&mut caller) {
-                    Ok(v) => v,
-                    Err(_e) => {
-                        SafeAlkanesHostFunctionsImpl::_abort(caller);
-                        -1
-                    }
-                }
-            },
-        )?;
-        linker.func_wrap(
-            "env",
-            "__load_context",
-            |mut caller: Caller<'_, AlkanesState>, output: i32| {
-                match SafeAlkanesHostFunctionsImpl::load_context(&mut caller, output) {
-                    Ok(v) => v,
-                    Err(_e) => {
-                        SafeAlkanesHostFunctionsImpl::_abort(caller);
-                        -1
-                    }
-                }
-            },
-        )?;
-        linker.func_wrap(
-            "env",
-            "__sequence",
-            |mut caller: Caller<'_, AlkanesState>, output: i32| {
-                if let Err(_e) = SafeAlkanesHostFunctionsImpl::sequence(&mut caller, output) {
-                    SafeAlkanesHostFunctionsImpl::_abort(caller);
-                }
-            },
-        )?;
-        linker.func_wrap(
-            "env",
-            "__fuel",
-            |mut caller: Caller<'_, AlkanesState>, output: i32| {
-                if let Err(_e) = SafeAlkanesHostFunctionsImpl::fuel(&mut caller, output) {
-                    SafeAlkanesHostFunctionsImpl::_abort(caller);
-                }
-            },
-        )?;
-        linker.func_wrap(
-            "env",
-            "__height",
-            |mut caller: Caller<'_, AlkanesState>, output: i32| {
-                if let Err(_e) = SafeAlkanesHostFunctionsImpl::height(&mut caller, output) {
-                    SafeAlkanesHostFunctionsImpl::_abort(caller);
-                }
-            },
-        )?;
-
-        linker.func_wrap(
-            "env",
-            "__returndatacopy",
-            |mut caller: Caller<'_, AlkanesState>, output: i32| {
-                if let Err(_e) = SafeAlkanesHostFunctionsImpl::returndatacopy(&mut caller, output) {
-                    SafeAlkanesHostFunctionsImpl::_abort(caller);
-                }
-            },
-        )?;
-        linker.func_wrap(
-            "env",
-            "__request_transaction",
-            |mut caller: Caller<'_, AlkanesState>| -> i32 {
-                match SafeAlkanesHostFunctionsImpl::request_transaction(&mut caller) {
-                    Ok(v) => v,
-                    Err(_e) => {
-                        SafeAlkanesHostFunctionsImpl::_abort(caller);
-                        -1
-                    }
-                }
-            },
-        )?;
-        linker.func_wrap(
-            "env",
-            "__load_transaction",
-            |mut caller: Caller<'_, AlkanesState>, output: i32| {
-                if let Err(_e) = SafeAlkanesHostFunctionsImpl::load_transaction(&mut caller, output)
-                {
-                    SafeAlkanesHostFunctionsImpl::_abort(caller);
-                }
-            },
-        )?;
-        /* removed below to prevent redundancy / requirement for archived chaindata */
-        /*
-        linker.func_wrap(
-            "env",
-            "__request_output",
-            |mut caller: Caller<'_, AlkanesState>, outpoint: i32| -> i32 {
-                match SafeAlkanesHostFunctionsImpl::request_output(&mut caller, outpoint) {
-                  Err(_e) => {
-                    SafeAlkanesHostFunctionsImpl::_abort(caller);
-                    -1
-                  }
-                  Ok(v) => v
-                }
-            },
-        )?;
-        linker.func_wrap(
-            "env",
-            "__load_output",
-            |mut caller: Caller<'_, AlkanesState>, outpoint: i32, output: i32| {
-                if let Err(_e) = SafeAlkanesHostFunctionsImpl::load_output(&mut caller, outpoint, output) {
-                    SafeAlkanesHostFunctionsImpl::_abort(caller);
-                }
-            },
-        )?;
-        */
-        linker.func_wrap(
-            "env",
-            "__request_block",
-            |mut caller: Caller<'_, AlkanesState>| match SafeAlkanesHostFunctionsImpl::request_block(
-                &mut caller,
-            ) {
-                Ok(v) => v,
-                Err(_e) => {
-                    SafeAlkanesHostFunctionsImpl::_abort(caller);
-                    -1
-                }
-            },
-        )?;
-        linker.func_wrap(
-            "env",
-            "__load_block",
-            |mut caller: Caller<'_, AlkanesState>, output: i32| {
-                if let Err(_e) = SafeAlkanesHostFunctionsImpl::load_block(&mut caller, output) {
-                    SafeAlkanesHostFunctionsImpl::_abort(caller);
-                }
-            },
-        )?;
-        linker.func_wrap(
-            "env",
-            "__call",
-            |mut caller: Caller<'_, AlkanesState>,
-             cellpack_ptr: i32,
-             incoming_alkanes_ptr: i32,
-             checkpoint_ptr: i32,
-             start_fuel: u64|
-             -> i32 {
-                SafeAlkanesHostFunctionsImpl::handle_extcall::<Call>(
-                    &mut caller,
-                    cellpack_ptr,
-                    incoming_alkanes_ptr,
-                    checkpoint_ptr,
-                    start_fuel,
-                )
-            },
-        )?;
-        linker.func_wrap(
-            "env",
-            "__delegatecall",
-            |mut caller: Caller<'_, AlkanesState>,
-             cellpack_ptr: i32,
-             incoming_alkanes_ptr: i32,
-             checkpoint_ptr: i32,
-             start_fuel: u64|
-             -> i32 {
-                SafeAlkanesHostFunctionsImpl::handle_extcall::<Delegatecall>(
-                    &mut caller,
-                    cellpack_ptr,
-                    incoming_alkanes_ptr,
-                    checkpoint_ptr,
-                    start_fuel,
-                )
-            },
-        )?;
-        linker.func_wrap(
-            "env",
-            "__staticcall",
-            |mut caller: Caller<'_, AlkanesState>,
-             cellpack_ptr: i32,
-             incoming_alkanes_ptr: i32,
-             checkpoint_ptr: i32,
-             start_fuel: u64|
-             -> i32 {
-                SafeAlkanesHostFunctionsImpl::handle_extcall::<Staticcall>(
-                    &mut caller,
-                    cellpack_ptr,
-                    incoming_alkanes_ptr,
-                    checkpoint_ptr,
-                    start_fuel,
-                )
-            },
-        )?;
         let mut alkanes_instance = AlkanesInstance {
             instance: linker
                 .instantiate(&mut store, &module)?
@@ -396,3 +175,255 @@ impl AlkanesInstance {
         AlkanesExportsImpl::call_meta(self)
     }
 }
+
+/// The host functions alkanes import, for modules compiled by `engine`.
+/// `events` links `__emit_event`.
+pub(crate) fn host_linker(engine: &Engine, events: bool) -> Result<Linker<AlkanesState>> {
+    let mut linker: Linker<AlkanesState> = Linker::<AlkanesState>::new(engine);
+    linker.func_wrap("env", "abort", SafeAlkanesHostFunctionsImpl::abort)?;
+    linker.func_wrap(
+        "env",
+        "__load_storage",
+        |mut caller: Caller<'_, AlkanesState>, k: i32, v: i32| {
+            match SafeAlkanesHostFunctionsImpl::load_storage(&mut caller, k, v) {
+                Ok(v) => v,
+                Err(_e) => {
+                    SafeAlkanesHostFunctionsImpl::_abort(caller);
+                    -1
+                }
+            }
+        },
+    )?;
+    linker.func_wrap(
+        "env",
+        "__request_storage",
+        |mut caller: Caller<'_, AlkanesState>, k: i32| {
+            match SafeAlkanesHostFunctionsImpl::request_storage(&mut caller, k) {
+                Ok(v) => v,
+                Err(_e) => {
+                    SafeAlkanesHostFunctionsImpl::_abort(caller);
+                    -1
+                }
+            }
+        },
+    )?;
+    linker.func_wrap(
+        "env",
+        "__log",
+        |mut caller: Caller<'_, AlkanesState>, v: i32| {
+            if let Err(_e) = SafeAlkanesHostFunctionsImpl::log(&mut caller, v) {
+                SafeAlkanesHostFunctionsImpl::_abort(caller);
+            }
+        },
+    )?;
+    linker.func_wrap(
+        "env",
+        "__balance",
+        |mut caller: Caller<'_, AlkanesState>, who: i32, what: i32, output: i32| {
+            if let Err(_e) = SafeAlkanesHostFunctionsImpl::balance(&mut caller, who, what, output) {
+                SafeAlkanesHostFunctionsImpl::_abort(caller);
+            }
+        },
+    )?;
+    linker.func_wrap(
+        "env",
+        "__request_context",
+        |mut caller: Caller<'_, AlkanesState>| -> i32 {
+            match SafeAlkanesHostFunctionsImpl::request_context(&mut caller) {
+                Ok(v) => v,
+                Err(_e) => {
+                    SafeAlkanesHostFunctionsImpl::_abort(caller);
+                    -1
+                }
+            }
+        },
+    )?;
+    linker.func_wrap(
+        "env",
+        "__load_context",
+        |mut caller: Caller<'_, AlkanesState>, output: i32| {
+            match SafeAlkanesHostFunctionsImpl::load_context(&mut caller, output) {
+                Ok(v) => v,
+                Err(_e) => {
+                    SafeAlkanesHostFunctionsImpl::_abort(caller);
+                    -1
+                }
+            }
+        },
+    )?;
+    linker.func_wrap(
+        "env",
+        "__sequence",
+        |mut caller: Caller<'_, AlkanesState>, output: i32| {
+            if let Err(_e) = SafeAlkanesHostFunctionsImpl::sequence(&mut caller, output) {
+                SafeAlkanesHostFunctionsImpl::_abort(caller);
+            }
+        },
+    )?;
+    linker.func_wrap(
+        "env",
+        "__fuel",
+        |mut caller: Caller<'_, AlkanesState>, output: i32| {
+            if let Err(_e) = SafeAlkanesHostFunctionsImpl::fuel(&mut caller, output) {
+                SafeAlkanesHostFunctionsImpl::_abort(caller);
+            }
+        },
+    )?;
+    linker.func_wrap(
+        "env",
+        "__height",
+        |mut caller: Caller<'_, AlkanesState>, output: i32| {
+            if let Err(_e) = SafeAlkanesHostFunctionsImpl::height(&mut caller, output) {
+                SafeAlkanesHostFunctionsImpl::_abort(caller);
+            }
+        },
+    )?;
+    // Only linked from the events fork on: before it, a module importing
+    // `__emit_event` must keep failing to instantiate.
+    if events {
+        linker.func_wrap(
+            "env",
+            "__emit_event",
+            |mut caller: Caller<'_, AlkanesState>, topic: i32, data: i32| {
+                if let Err(_e) = SafeAlkanesHostFunctionsImpl::emit_event(&mut caller, topic, data)
+                {
+                    SafeAlkanesHostFunctionsImpl::_abort(caller);
+                }
+            },
+        )?;
+    }
+
+    linker.func_wrap(
+        "env",
+        "__returndatacopy",
+        |mut caller: Caller<'_, AlkanesState>, output: i32| {
+            if let Err(_e) = SafeAlkanesHostFunctionsImpl::returndatacopy(&mut caller, output) {
+                SafeAlkanesHostFunctionsImpl::_abort(caller);
+            }
+        },
+    )?;
+    linker.func_wrap(
+        "env",
+        "__request_transaction",
+        |mut caller: Caller<'_, AlkanesState>| -> i32 {
+            match SafeAlkanesHostFunctionsImpl::request_transaction(&mut caller) {
+                Ok(v) => v,
+                Err(_e) => {
+                    SafeAlkanesHostFunctionsImpl::_abort(caller);
+                    -1
+                }
+            }
+        },
+    )?;
+    linker.func_wrap(
+        "env",
+        "__load_transaction",
+        |mut caller: Caller<'_, AlkanesState>, output: i32| {
+            if let Err(_e) = SafeAlkanesHostFunctionsImpl::load_transaction(&mut caller, output) {
+                SafeAlkanesHostFunctionsImpl::_abort(caller);
+            }
+        },
+    )?;
+    /* removed below to prevent redundancy / requirement for archived chaindata */
+    /*
+    linker.func_wrap(
+        "env",
+        "__request_output",
+        |mut caller: Caller<'_, AlkanesState>, outpoint: i32| -> i32 {
+            match SafeAlkanesHostFunctionsImpl::request_output(&mut caller, outpoint) {
+              Err(_e) => {
+                SafeAlkanesHostFunctionsImpl::_abort(caller);
+                -1
+              }
+              Ok(v) => v
+            }
+        },
+    )?;
+    linker.func_wrap(
+        "env",
+        "__load_output",
+        |mut caller: Caller<'_, AlkanesState>, outpoint: i32, output: i32| {
+            if let Err(_e) = SafeAlkanesHostFunctionsImpl::load_output(&mut caller, outpoint, output) {
+                SafeAlkanesHostFunctionsImpl::_abort(caller);
+            }
+        },
+    )?;
+    */
+    linker.func_wrap(
+        "env",
+        "__request_block",
+        |mut caller: Caller<'_, AlkanesState>| match SafeAlkanesHostFunctionsImpl::request_block(
+            &mut caller,
+        ) {
+            Ok(v) => v,
+            Err(_e) => {
+                SafeAlkanesHostFunctionsImpl::_abort(caller);
+                -1
+            }
+        },
+    )?;
+    linker.func_wrap(
+        "env",
+        "__load_block",
+        |mut caller: Caller<'_, AlkanesState>, output: i32| {
+            if let Err(_e) = SafeAlkanesHostFunctionsImpl::load_block(&mut caller, output) {
+                SafeAlkanesHostFunctionsImpl::_abort(caller);
+            }
+        },
+    )?;
+    linker.func_wrap(
+        "env",
+        "__call",
+        |mut caller: Caller<'_, AlkanesState>,
+         cellpack_ptr: i32,
+         incoming_alkanes_ptr: i32,
+         checkpoint_ptr: i32,
+         start_fuel: u64|
+         -> i32 {
+            SafeAlkanesHostFunctionsImpl::handle_extcall::<Call>(
+                &mut caller,
+                cellpack_ptr,
+                incoming_alkanes_ptr,
+                checkpoint_ptr,
+                start_fuel,
+            )
+        },
+    )?;
+    linker.func_wrap(
+        "env",
+        "__delegatecall",
+        |mut caller: Caller<'_, AlkanesState>,
+         cellpack_ptr: i32,
+         incoming_alkanes_ptr: i32,
+         checkpoint_ptr: i32,
+         start_fuel: u64|
+         -> i32 {
+            SafeAlkanesHostFunctionsImpl::handle_extcall::<Delegatecall>(
+                &mut caller,
+                cellpack_ptr,
+                incoming_alkanes_ptr,
+                checkpoint_ptr,
+                start_fuel,
+            )
+        },
+    )?;
+    linker.func_wrap(
+        "env",
+        "__staticcall",
+        |mut caller: Caller<'_, AlkanesState>,
+         cellpack_ptr: i32,
+         incoming_alkanes_ptr: i32,
+         checkpoint_ptr: i32,
+         start_fuel: u64|
+         -> i32 {
+            SafeAlkanesHostFunctionsImpl::handle_extcall::<Staticcall>(
+                &mut caller,
+                cellpack_ptr,
+                incoming_alkanes_ptr,
+                checkpoint_ptr,
+                start_fuel,
+            )
+        },
+    )?;
+    Ok(linker)
+}
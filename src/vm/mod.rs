@@ -4,6 +4,8 @@ pub mod extcall;
 pub mod fuel;
 pub mod host_functions;
 pub mod instance;
+pub mod module_cache;
+pub mod profiler;
 pub mod runtime;
 pub mod state;
 pub mod utils;
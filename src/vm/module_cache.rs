@@ -0,0 +1,136 @@
+//! Validated modules by code hash, with the `Engine` they were compiled by
+//! and the host `Linker`s that instantiate them.
+//!
+//! Only compilation is shared. Every [`AlkanesInstance`](super::AlkanesInstance)
+//! still gets its own `Store` (fuel, memory limiter, context) and instance,
+//! and the engine compiles eagerly, so translating a module never draws on a
+//! store's fuel: a call burns the same fuel whether its module was cached or
+//! not.
+//!
+//! Thread-local like the other indexer caches; wasm32 is single-threaded so
+//! this is effectively a static.
+
+use super::instance::host_linker;
+use super::AlkanesState;
+use anyhow::Result;
+use sha2::{Digest, Sha256};
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::rc::Rc;
+use wasmi::{CompilationMode, Config, Engine, Linker, Module};
+
+/// Modules held before the cache starts over. Compiled code lives as long
+/// as its engine, so the whole engine is dropped rather than single modules.
+pub const MODULE_CACHE_CAPACITY: usize = 64;
+
+/// The engine configuration every alkane runs under, cached or not.
+pub fn engine() -> Engine {
+    let mut config = Config::default();
+    config.consume_fuel(true);
+    // Lazy translation would charge a store for compiling each function on
+    // first use, so fuel would depend on what earlier calls compiled.
+    config.compilation_mode(CompilationMode::Eager);
+    Engine::new(&config)
+}
+
+/// What [`load`] hands to `AlkanesInstance::from_alkane`.
+pub struct Loaded {
+    pub engine: Engine,
+    pub module: Module,
+    pub linker: Rc<Linker<AlkanesState>>,
+}
+
+struct ModuleCache {
+    engine: Engine,
+    modules: HashMap<[u8; 32], Module>,
+    // Indexed by whether `__emit_event` is linked.
+    linkers: [Option<Rc<Linker<AlkanesState>>>; 2],
+}
+
+impl ModuleCache {
+    fn new() -> Self {
+        ModuleCache {
+            engine: engine(),
+            modules: HashMap::new(),
+            linkers: [None, None],
+        }
+    }
+
+    fn linker(&mut self, events: bool) -> Result<Rc<Linker<AlkanesState>>> {
+        let slot = &mut self.linkers[events as usize];
+        if let Some(linker) = slot {
+            return Ok(linker.clone());
+        }
+        let linker = Rc::new(host_linker(&self.engine, events)?);
+        *slot = Some(linker.clone());
+        Ok(linker)
+    }
+
+    fn module(&mut self, binary: &[u8]) -> Result<Module> {
+        let hash: [u8; 32] = Sha256::digest(binary).into();
+        if let Some(module) = self.modules.get(&hash) {
+            return Ok(module.clone());
+        }
+        if self.modules.len() >= MODULE_CACHE_CAPACITY {
+            *self = ModuleCache::new();
+        }
+        // Only modules that validate are kept; a bad binary fails the same
+        // way on every call.
+        let module = Module::new(&self.engine, &mut &binary[..])?;
+        self.modules.insert(hash, module.clone());
+        Ok(module)
+    }
+}
+
+thread_local! {
+    static MODULE_CACHE: RefCell<Option<ModuleCache>> = const { RefCell::new(None) };
+    static ENABLED: Cell<bool> = const { Cell::new(true) };
+}
+
+/// Turns the cache on or off (it starts on); turning it off drops it.
+pub fn set_enabled(enabled: bool) {
+    ENABLED.with(|e| e.set(enabled));
+    if !enabled {
+        clear();
+    }
+}
+
+pub fn is_enabled() -> bool {
+    ENABLED.with(|e| e.get())
+}
+
+/// Drops every cached module, and the engine and linkers with them.
+pub fn clear() {
+    MODULE_CACHE.with(|c| *c.borrow_mut() = None);
+}
+
+/// Number of cached modules.
+pub fn len() -> usize {
+    MODULE_CACHE.with(|c| c.borrow().as_ref().map_or(0, |cache| cache.modules.len()))
+}
+
+/// The compiled `binary` and a linker with the host functions for
+/// `events`, from the cache when it is on, otherwise built fresh.
+pub fn load(binary: &[u8], events: bool) -> Result<Loaded> {
+    if !is_enabled() {
+        let engine = engine();
+        let module = Module::new(&engine, &mut &binary[..])?;
+        let linker = Rc::new(host_linker(&engine, events)?);
+        return Ok(Loaded {
+            engine,
+            module,
+            linker,
+        });
+    }
+    MODULE_CACHE.with(|c| {
+        let mut cache = c.borrow_mut();
+        let cache = cache.get_or_insert_with(ModuleCache::new);
+        let module = cache.module(binary)?;
+        let linker = cache.linker(events)?;
+        Ok(Loaded {
+            engine: cache.engine.clone(),
+            module,
+            linker,
+        })
+    })
+}
@@ -0,0 +1,282 @@
+use super::extcall::Extcall;
+use alkanes_support::id::AlkaneId;
+use alkanes_support::proto;
+use alkanes_support::storage::StorageMap;
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+
+// Opt-in fuel profiler for the simulate views.
+//
+// While enabled, every frame that runs through `run_after_special` — the
+// top-level message and each extcall below it — is recorded as a node of a
+// fuel tree. Host functions attribute what they charge to the innermost open
+// frame; whatever a frame consumed beyond its host charges and its children
+// is wasm instruction fuel. Default-off like the touched-storage collector,
+// so the indexer hot path only pays a thread-local peek per hook.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrameKind {
+    Call,
+    Delegatecall,
+    Staticcall,
+}
+
+impl FrameKind {
+    pub fn of<T: Extcall>() -> Self {
+        if T::isstatic() {
+            FrameKind::Staticcall
+        } else if T::isdelegate() {
+            FrameKind::Delegatecall
+        } else {
+            FrameKind::Call
+        }
+    }
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FrameKind::Call => "call",
+            FrameKind::Delegatecall => "delegatecall",
+            FrameKind::Staticcall => "staticcall",
+        }
+    }
+}
+
+/// Fuel attributed to one storage key within a frame.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct KeyFuel {
+    pub key: Vec<u8>,
+    pub bytes: u64,
+    pub fuel: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct FuelFrame {
+    pub alkane: AlkaneId,
+    pub kind: FrameKind,
+    pub opcode: u128,
+    pub start_fuel: u64,
+    /// Everything the frame consumed, including storage writes and children.
+    pub fuel_used: u64,
+    pub instruction_fuel: u64,
+    /// Host-call fuel other than storage reads and extcall overhead.
+    pub host_fuel: u64,
+    pub storage_reads: Vec<KeyFuel>,
+    pub storage_writes: Vec<KeyFuel>,
+    /// Overhead the caller paid to enter this frame.
+    pub extcall_fuel: u64,
+    /// Bytes of the caller's pending storage flushed at the call boundary.
+    pub transferred_bytes: u64,
+    pub reverted: bool,
+    pub children: Vec<FuelFrame>,
+}
+
+impl FuelFrame {
+    /// Fuel this frame cost its caller. A reverted extcall consumes the
+    /// whole allowance it was started with.
+    fn charged(&self) -> u64 {
+        if self.reverted {
+            self.start_fuel
+        } else {
+            self.fuel_used
+        }
+    }
+}
+
+struct OpenFrame {
+    frame: FuelFrame,
+    host_total: u64,
+    read_total: u64,
+    extcall_total: u64,
+    reads: BTreeMap<Vec<u8>, KeyFuel>,
+    protostone: usize,
+}
+
+#[derive(Default)]
+struct Profiler {
+    stack: Vec<OpenFrame>,
+    pending: Option<(FrameKind, u64, u64)>,
+    roots: Vec<(usize, FuelFrame)>,
+}
+
+thread_local! {
+    static PROFILER: RefCell<Option<Profiler>> = const { RefCell::new(None) };
+}
+
+fn with_profiler(f: impl FnOnce(&mut Profiler)) {
+    PROFILER.with(|p| {
+        if let Some(profiler) = p.borrow_mut().as_mut() {
+            f(profiler);
+        }
+    });
+}
+
+/// Starts recording. Recording stops, and anything not drained is
+/// discarded, when the returned guard is dropped.
+pub fn enable() -> ProfilerGuard {
+    PROFILER.with(|p| *p.borrow_mut() = Some(Profiler::default()));
+    ProfilerGuard
+}
+
+pub fn is_enabled() -> bool {
+    PROFILER.with(|p| p.borrow().is_some())
+}
+
+pub struct ProfilerGuard;
+
+impl Drop for ProfilerGuard {
+    fn drop(&mut self) {
+        PROFILER.with(|p| *p.borrow_mut() = None);
+    }
+}
+
+/// Takes the completed top-level frames recorded so far, each tagged with
+/// the protostone index that was current when it started (`usize::MAX`
+/// outside of `index_protostones`).
+pub fn drain() -> Vec<(usize, FuelFrame)> {
+    let mut roots = vec![];
+    with_profiler(|p| roots = std::mem::take(&mut p.roots));
+    roots
+}
+
+pub fn enter(alkane: &AlkaneId, opcode: u128, start_fuel: u64) {
+    with_profiler(|p| {
+        let (kind, extcall_fuel, transferred_bytes) =
+            p.pending.take().unwrap_or((FrameKind::Call, 0, 0));
+        p.stack.push(OpenFrame {
+            frame: FuelFrame {
+                alkane: alkane.clone(),
+                kind,
+                opcode,
+                start_fuel,
+                fuel_used: 0,
+                instruction_fuel: 0,
+                host_fuel: 0,
+                storage_reads: vec![],
+                storage_writes: vec![],
+                extcall_fuel,
+                transferred_bytes,
+                reverted: false,
+                children: vec![],
+            },
+            host_total: 0,
+            read_total: 0,
+            extcall_total: 0,
+            reads: BTreeMap::new(),
+            protostone: protorune::current_protostone_index(),
+        });
+    });
+}
+
+/// Records fuel charged by a host function to the running frame.
+pub fn charge(fuel: u64) {
+    with_profiler(|p| {
+        if let Some(open) = p.stack.last_mut() {
+            open.host_total = open.host_total.saturating_add(fuel);
+        }
+    });
+}
+
+/// Attributes part of a host charge to a storage read of `key`.
+pub fn storage_read(key: &[u8], bytes: u64, fuel: u64) {
+    with_profiler(|p| {
+        if let Some(open) = p.stack.last_mut() {
+            open.read_total = open.read_total.saturating_add(fuel);
+            let entry = open.reads.entry(key.to_vec()).or_insert_with(|| KeyFuel {
+                key: key.to_vec(),
+                ..Default::default()
+            });
+            entry.bytes = entry.bytes.saturating_add(bytes);
+            entry.fuel = entry.fuel.saturating_add(fuel);
+        }
+    });
+}
+
+/// Attributes part of a host charge to extcall overhead. The next frame
+/// entered is the callee.
+pub fn extcall(kind: FrameKind, fuel: u64, transferred_bytes: u64) {
+    with_profiler(|p| {
+        if let Some(open) = p.stack.last_mut() {
+            open.extcall_total = open.extcall_total.saturating_add(fuel);
+        }
+        p.pending = Some((kind, fuel, transferred_bytes));
+    });
+}
+
+/// Closes the running frame. `wasm_fuel` is what the instance's fuel meter
+/// dropped by; on success `storage` is the frame's returned storage map,
+/// priced at `fuel_per_store_byte` the way `run_after_special` does.
+pub fn exit(wasm_fuel: u64, storage: &StorageMap, fuel_per_store_byte: u64, reverted: bool) {
+    with_profiler(|p| {
+        let open = match p.stack.pop() {
+            Some(open) => open,
+            None => return,
+        };
+        let mut frame = open.frame;
+        let children_fuel = frame
+            .children
+            .iter()
+            .fold(0u64, |acc, child| acc.saturating_add(child.charged()));
+        frame.instruction_fuel = wasm_fuel
+            .saturating_sub(open.host_total)
+            .saturating_sub(children_fuel);
+        frame.host_fuel = open
+            .host_total
+            .saturating_sub(open.read_total)
+            .saturating_sub(open.extcall_total);
+        frame.storage_reads = open.reads.into_values().collect();
+        frame.reverted = reverted;
+        frame.fuel_used = wasm_fuel;
+        if !reverted {
+            frame.storage_writes = storage
+                .0
+                .iter()
+                .map(|(k, v)| {
+                    // u32 length prefixes on both key and value, as in
+                    // `StorageMap::serialize`.
+                    let bytes = (8 + k.len() + v.len()) as u64;
+                    KeyFuel {
+                        key: k.clone(),
+                        bytes,
+                        fuel: bytes.saturating_mul(fuel_per_store_byte),
+                    }
+                })
+                .collect();
+            let storage_len = storage.serialize().len() as u64;
+            frame.fuel_used =
+                wasm_fuel.saturating_add(storage_len.saturating_mul(fuel_per_store_byte));
+        }
+        match p.stack.last_mut() {
+            Some(parent) => parent.frame.children.push(frame),
+            None => p.roots.push((open.protostone, frame)),
+        }
+    });
+}
+
+impl From<KeyFuel> for proto::alkanes::FuelStorageAccess {
+    fn from(v: KeyFuel) -> Self {
+        proto::alkanes::FuelStorageAccess {
+            key: v.key,
+            bytes: v.bytes,
+            fuel: v.fuel,
+        }
+    }
+}
+
+impl From<FuelFrame> for proto::alkanes::FuelProfileFrame {
+    fn from(v: FuelFrame) -> Self {
+        proto::alkanes::FuelProfileFrame {
+            alkane: Some(v.alkane.into()),
+            kind: v.kind.as_str().to_string(),
+            opcode: Some(v.opcode.into()),
+            start_fuel: v.start_fuel,
+            fuel_used: v.fuel_used,
+            instruction_fuel: v.instruction_fuel,
+            host_fuel: v.host_fuel,
+            storage_reads: v.storage_reads.into_iter().map(Into::into).collect(),
+            storage_writes: v.storage_writes.into_iter().map(Into::into).collect(),
+            extcall_fuel: v.extcall_fuel,
+            transferred_bytes: v.transferred_bytes,
+            reverted: v.reverted,
+            children: v.children.into_iter().map(Into::into).collect(),
+        }
+    }
+}
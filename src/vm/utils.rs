@@ -1,6 +1,7 @@
 use super::{AlkanesInstance, AlkanesRuntimeContext, AlkanesState};
 use crate::utils::{pipe_storagemap_to, record_touched_storage, transfer_from};
 use crate::vm::fuel::fuel_per_store_byte;
+use crate::vm::profiler;
 use alkanes_support::trace::TraceEvent;
 use alkanes_support::{
     cellpack::Cellpack, gz::decompress, id::AlkaneId, parcel::AlkaneTransferParcel,
@@ -9,12 +10,12 @@ use alkanes_support::{
 };
 use anyhow::{anyhow, Result};
 use bitcoin::OutPoint;
-use metashrew_core::index_pointer::{AtomicPointer, IndexPointer};
 #[allow(unused_imports)]
 use metashrew_core::{
     clear as clear_base, println,
     stdio::{stdout, Write},
 };
+use metashrew_native::index_pointer::{AtomicPointer, IndexPointer};
 use metashrew_support::index_pointer::KeyValuePointer;
 use protorune_support::utils::consensus_encode;
 use std::sync::{Arc, Mutex};
@@ -286,6 +287,15 @@ pub fn run_after_special(
         );
     }
 
+    if profiler::is_enabled() {
+        let ctx = context.lock().unwrap();
+        profiler::enter(
+            &ctx.myself,
+            ctx.inputs.first().copied().unwrap_or(0),
+            start_fuel,
+        );
+    }
+
     // DIESEL precompile: bypass wasmi for the hot-path mint and view
     // opcodes when the tx is a single-mint-protostone (the common case).
     //
@@ -345,11 +355,20 @@ pub fn run_after_special(
         let (resp, gas, _path) = crate::precompile_diesel::run_diesel_eoa(
             context.clone(),
             &crate::precompile_diesel::CHAIN_GAS,
-        )?;
+        )
+        .map_err(|e| {
+            profiler::exit(0, &StorageMap::default(), 0, true);
+            e
+        })?;
+        profiler::exit(gas, &StorageMap::default(), 0, false);
         return Ok((resp, gas));
     }
 
-    let mut instance = AlkanesInstance::from_alkane(context.clone(), binary.clone(), start_fuel)?;
+    let mut instance = AlkanesInstance::from_alkane(context.clone(), binary.clone(), start_fuel)
+        .map_err(|e| {
+            profiler::exit(0, &StorageMap::default(), 0, true);
+            e
+        })?;
     let exec_result = instance.execute();
 
     // Capture fuel consumption for ALL paths (success and revert) so the
@@ -371,9 +390,11 @@ pub fn run_after_special(
         crate::fuel_probe::record(target, opcode, height, probe_gas);
     }
 
+    let wasm_fuel = start_fuel.saturating_sub(remaining_fuel);
     let response = match exec_result {
         Ok(r) => r,
         Err(e) => {
+            profiler::exit(wasm_fuel, &StorageMap::default(), 0, true);
             // Shadow-compare the revert path before propagating.
             #[cfg(any(test, feature = "test-utils"))]
             {
@@ -385,6 +406,12 @@ pub fn run_after_special(
         }
     };
     let storage_len = response.storage.serialize().len() as u64;
+    profiler::exit(
+        wasm_fuel,
+        &response.storage,
+        fuel_per_store_byte(height),
+        false,
+    );
 
     #[cfg(feature = "debug-log")]
     {